@@ -1,6 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::Rng;
 use flywheel::math::matrix::Matrix;
+use flywheel::math::strategy::MatmulStrategy;
 
 fn bench_2x2_matmul(c: &mut Criterion) {
     let mut rng = rand::thread_rng();
@@ -50,5 +51,30 @@ fn bench_4x4_matmul(c: &mut Criterion) {
     c.bench_function("4x4 matrix multiplication", |bench| bench.iter(|| a.clone() * b.clone()));
 }
 
-criterion_group!(benches, bench_2x2_matmul, bench_3x3_matmul, bench_4x4_matmul);
+fn bench_64x64_strategies(c: &mut Criterion) {
+    let mut rng = rand::thread_rng();
+    let a_data = (0..64 * 64)
+        .map(|_| rng.gen_range(-1.0..1.0))
+        .collect::<Vec<f32>>();
+
+    let b_data = (0..64 * 64)
+        .map(|_| rng.gen_range(-1.0..1.0))
+        .collect::<Vec<f32>>();
+
+    let a = Matrix::from_vec(64, 64, a_data);
+    let b = Matrix::from_vec(64, 64, b_data);
+
+    let mut group = c.benchmark_group("64x64 matrix multiplication");
+    for strategy in [
+        MatmulStrategy::Naive,
+        MatmulStrategy::Blocked,
+        MatmulStrategy::Strassen { cutoff: 16 },
+        MatmulStrategy::Parallel,
+    ] {
+        group.bench_function(strategy.to_string(), |bench| bench.iter(|| a.mul_with(&b, strategy)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_2x2_matmul, bench_3x3_matmul, bench_4x4_matmul, bench_64x64_strategies);
 criterion_main!(benches);
\ No newline at end of file
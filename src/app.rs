@@ -0,0 +1,165 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::ecs::schedule::{Schedule, System};
+use crate::ecs::world::World;
+
+/// The clock resource an [`App`] keeps in its world, read by systems through [`Time`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Time {
+    /// the fixed step in seconds
+    pub step: f32,
+    /// the seconds since the last frame, or the step in headless mode
+    pub delta: f32,
+    /// how far between the last fixed step and the next the frame is, in [0, 1),
+    /// for interpolating what's drawn
+    pub alpha: f32,
+    /// the number of fixed steps run
+    pub ticks: u64,
+    /// the number of frames run
+    pub frames: u64,
+    /// the simulated seconds, the number of steps run times the step
+    pub elapsed: f64,
+}
+
+/// A resource that stops [`App::run`] at the end of the frame it's inserted in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Exit;
+
+/// How [`App::run`] drives the loop
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Runner {
+    /// frames paced by the wall clock until something inserts [`Exit`]
+    Realtime,
+    /// `ticks` fixed steps as fast as possible, each followed by one frame,
+    /// stopping early on [`Exit`]
+    Headless { ticks: u64 },
+}
+
+/// A world with a fixed-rate simulation schedule and a variable-rate one run once per frame.
+///
+/// each frame's time goes into an accumulator that the fixed schedule drains a step at a
+/// time. at most `max_steps` run per frame, with any whole steps left over dropped, so a
+/// slow frame slows the simulation down rather than falling further and further behind.
+pub struct App {
+    pub world: World,
+    fixed: Schedule,
+    update: Schedule,
+    step: f64,
+    max_steps: u32,
+    accumulator: f64,
+}
+
+impl Default for App {
+    fn default() -> App {
+        App::new()
+    }
+}
+
+impl App {
+    /// an app stepping at 60hz, with at most 5 steps per frame
+    pub fn new() -> App {
+        let mut world = World::new();
+        world.insert_resource(Time { step: 1.0 / 60.0, delta: 0.0, alpha: 0.0, ticks: 0, frames: 0, elapsed: 0.0 });
+        App { world, fixed: Schedule::new(), update: Schedule::new(), step: 1.0 / 60.0, max_steps: 5, accumulator: 0.0 }
+    }
+
+    /// step the simulation every `seconds`
+    pub fn with_step(mut self, seconds: f64) -> App {
+        assert!(seconds > 0.0, "the step must be positive");
+        self.step = seconds;
+        self.world.resource_mut::<Time>().unwrap().step = seconds as f32;
+        self
+    }
+
+    /// run at most `steps` fixed steps in one frame
+    pub fn with_max_steps(mut self, steps: u32) -> App {
+        assert!(steps > 0, "at least one step per frame is needed");
+        self.max_steps = steps;
+        self
+    }
+
+    #[inline(always)]
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// add a system run every fixed step
+    pub fn add_fixed_system(&mut self, system: System) -> &mut App {
+        self.fixed.add_system(system);
+        self
+    }
+
+    /// add a system run once per frame, after the fixed steps
+    pub fn add_system(&mut self, system: System) -> &mut App {
+        self.update.add_system(system);
+        self
+    }
+
+    #[inline(always)]
+    pub fn time(&self) -> Time {
+        *self.world.resource::<Time>().unwrap()
+    }
+
+    /// whether something has inserted [`Exit`]
+    #[inline(always)]
+    pub fn exiting(&self) -> bool {
+        self.world.contains_resource::<Exit>()
+    }
+
+    fn fixed_step(&mut self) {
+        self.fixed.run(&mut self.world);
+        let mut time = self.world.resource_mut::<Time>().unwrap();
+        time.ticks += 1;
+        time.elapsed = time.ticks as f64 * self.step;
+    }
+
+    /// advance by `seconds` of wall time, running the fixed steps that covers and then the
+    /// frame's systems. returns the number of fixed steps run
+    pub fn frame(&mut self, seconds: f64) -> u32 {
+        self.accumulator += seconds.max(0.0);
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.fixed_step();
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+        if self.accumulator >= self.step {
+            self.accumulator %= self.step;
+        }
+
+        let mut time = self.world.resource_mut::<Time>().unwrap();
+        time.delta = seconds as f32;
+        time.alpha = (self.accumulator / self.step) as f32;
+        drop(time);
+        self.update.run(&mut self.world);
+        self.world.resource_mut::<Time>().unwrap().frames += 1;
+        steps
+    }
+
+    /// run the loop until it's done, see [`Runner`]
+    pub fn run(&mut self, runner: Runner) {
+        match runner {
+            Runner::Headless { ticks } => {
+                for _ in 0..ticks {
+                    if self.exiting() {
+                        break
+                    }
+                    self.frame(self.step);
+                }
+            }
+            Runner::Realtime => {
+                let mut last = Instant::now();
+                while !self.exiting() {
+                    let now = Instant::now();
+                    self.frame((now - last).as_secs_f64());
+                    last = now;
+                    // nothing draws between steps here, so wait for the next one instead of spinning
+                    let idle = self.step - self.accumulator - last.elapsed().as_secs_f64();
+                    if idle > 0.0 {
+                        thread::sleep(Duration::from_secs_f64(idle));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::f32::consts::FRAC_PI_2;
+use crate::camera::Camera;
+use crate::math::curves::{lerp, smoothstep};
+use crate::math::quat::Quat;
+use crate::math::random::Pcg32;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// how close to straight up or down pitch is allowed to get, so the view doesn't flip over
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+/// the fraction of the way to move towards a target over `dt`, for smoothing at `rate` per second
+/// that comes out the same whatever the frame rate
+#[inline(always)]
+fn smoothing(rate: f32, dt: f32) -> f32 {
+    if rate.is_infinite() { 1.0 } else { 1.0 - (-rate * dt).exp() }
+}
+
+/// Circles a camera around a target, as in a model viewer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitController {
+    pub target: Vec3,
+    pub distance: f32,
+    /// radians about the y-axis, 0 looking down the negative z-axis
+    pub yaw: f32,
+    /// radians above the horizon the camera is looking up from
+    pub pitch: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+impl OrbitController {
+    pub fn new(target: Vec3, distance: f32) -> OrbitController {
+        OrbitController { target, distance, yaw: 0.0, pitch: 0.0, min_distance: 0.01, max_distance: f32::INFINITY }
+    }
+
+    pub fn with_distance_limits(self, min_distance: f32, max_distance: f32) -> OrbitController {
+        assert!(min_distance > 0.0 && max_distance >= min_distance);
+        let distance = self.distance.clamp(min_distance, max_distance);
+        OrbitController { min_distance, max_distance, distance, ..self }
+    }
+
+    /// turn around the target, keeping the pitch short of the poles
+    pub fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.yaw += yaw;
+        self.pitch = (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// multiply the distance by `factor`, so values below 1 move in
+    pub fn zoom(&mut self, factor: f32) {
+        self.distance = (self.distance * factor).clamp(self.min_distance, self.max_distance);
+    }
+
+    /// slide the target across the screen, by amounts relative to the distance so panning feels the same up close
+    pub fn pan(&mut self, right: f32, up: f32) {
+        let rotation = self.rotation();
+        self.target += (rotation * Vec3::X * right + rotation * Vec3::Y * up) * self.distance;
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(self.pitch)
+    }
+
+    /// where the camera sits
+    pub fn position(&self) -> Vec3 {
+        self.target - self.rotation() * Vec3::NEG_Z * self.distance
+    }
+
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.position = self.position();
+        camera.rotation = self.rotation();
+    }
+}
+
+/// Flies a camera around freely, steered by mouse look and movement keys
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlyController {
+    pub yaw: f32,
+    pub pitch: f32,
+    /// units per second
+    pub speed: f32,
+    /// radians per unit of look input
+    pub sensitivity: f32,
+}
+
+impl FlyController {
+    pub fn new(speed: f32, sensitivity: f32) -> FlyController {
+        FlyController { yaw: 0.0, pitch: 0.0, speed, sensitivity }
+    }
+
+    /// take over a camera where it's already looking, ignoring any roll
+    pub fn from_camera(camera: &Camera, speed: f32, sensitivity: f32) -> FlyController {
+        let forward = camera.forward();
+        let yaw = (-forward.x).atan2(-forward.z);
+        let pitch = forward.y.clamp(-1.0, 1.0).asin().clamp(-MAX_PITCH, MAX_PITCH);
+        FlyController { yaw, pitch, speed, sensitivity }
+    }
+
+    /// turn by `look`, with positive x to the right and positive y up, then move by `movement`
+    /// along the camera's right, up and forward directions, which is normalized so diagonals aren't faster
+    pub fn update(&mut self, camera: &mut Camera, look: Vec2, movement: Vec3, dt: f32) {
+        self.yaw -= look.x * self.sensitivity;
+        self.pitch = (self.pitch + look.y * self.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+        camera.rotation = Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(self.pitch);
+        let local = Vec3(movement.x, movement.y, -movement.z).try_normalize().unwrap_or(Vec3::ZERO);
+        camera.position += camera.rotation * local * (self.speed * dt);
+    }
+}
+
+/// Follows a target in the xy plane, letting it wander inside a dead zone before catching up smoothly
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Follow2d {
+    pub position: Vec2,
+    /// half the size of the box around the position the target can move in freely
+    pub dead_zone: Vec2,
+    /// how quickly the camera catches up, per second, with infinity snapping straight there
+    pub rate: f32,
+}
+
+impl Follow2d {
+    pub fn new(position: Vec2, dead_zone: Vec2, rate: f32) -> Follow2d {
+        assert!(dead_zone.x >= 0.0 && dead_zone.y >= 0.0 && rate >= 0.0);
+        Follow2d { position, dead_zone, rate }
+    }
+
+    /// move towards the nearest position that has `target` inside the dead zone
+    pub fn update(&mut self, target: Vec2, dt: f32) -> Vec2 {
+        let offset = target - self.position;
+        let goal = self.position + offset - offset.clamp(-self.dead_zone, self.dead_zone);
+        self.position = lerp(self.position, goal, smoothing(self.rate, dt));
+        self.position
+    }
+
+    /// put the camera over the position, leaving its distance along z alone
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.position.x = self.position.x;
+        camera.position.y = self.position.y;
+    }
+}
+
+/// Shakes a camera by an amount that builds up with trauma and wears off over time.
+///
+/// the shake goes with the square of the trauma, so small knocks stay subtle and big ones stand out,
+/// and it follows smooth noise rather than jumping around from frame to frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraShake {
+    /// from 0 to 1
+    pub trauma: f32,
+    /// how much trauma wears off per second
+    pub decay: f32,
+    /// the furthest the camera moves along each of its local axes
+    pub max_offset: Vec3,
+    /// the furthest the camera turns in yaw, pitch and roll, in radians
+    pub max_angle: Vec3,
+    /// how many times a second the noise changes direction
+    pub frequency: f32,
+    seed: u64,
+    time: f32,
+}
+
+/// smooth noise in `-1..=1`, a different curve for each seed and channel
+fn noise(seed: u64, channel: u64, x: f32) -> f32 {
+    let lattice = |i: f32| Pcg32::with_stream(seed ^ (i as i64 as u64), channel).next_f32() * 2.0 - 1.0;
+    let i = x.floor();
+    lerp(lattice(i), lattice(i + 1.0), smoothstep(0.0, 1.0, x - i))
+}
+
+impl CameraShake {
+    pub fn new(max_offset: Vec3, max_angle: Vec3, seed: u64) -> CameraShake {
+        CameraShake { trauma: 0.0, decay: 1.0, max_offset, max_angle, frequency: 15.0, seed, time: 0.0 }
+    }
+
+    pub fn with_decay(self, decay: f32) -> CameraShake {
+        CameraShake { decay, ..self }
+    }
+
+    pub fn with_frequency(self, frequency: f32) -> CameraShake {
+        CameraShake { frequency, ..self }
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// wear the trauma off and move the noise along
+    pub fn update(&mut self, dt: f32) {
+        self.trauma = (self.trauma - self.decay * dt).max(0.0);
+        self.time += dt;
+    }
+
+    /// the offset and rotation to shake by right now, in the camera's local space
+    pub fn shake(&self) -> (Vec3, Quat) {
+        let amount = self.trauma * self.trauma;
+        if amount == 0.0 {
+            return (Vec3::ZERO, Quat::IDENTITY)
+        }
+        let x = self.time * self.frequency;
+        let channel = |c| noise(self.seed, c, x) * amount;
+        let offset = Vec3(channel(0), channel(1), channel(2)) * self.max_offset;
+        let angle = Vec3(channel(3), channel(4), channel(5)) * self.max_angle;
+        let rotation = Quat::from_rotation_y(angle.x) * Quat::from_rotation_x(angle.y) * Quat::from_rotation_z(angle.z);
+        (offset, rotation)
+    }
+
+    /// a copy of `camera` with the shake added, leaving the camera itself steady for next frame
+    pub fn shaken(&self, camera: &Camera) -> Camera {
+        let (offset, rotation) = self.shake();
+        Camera {
+            position: camera.position + camera.rotation * offset,
+            rotation: camera.rotation * rotation,
+            ..*camera
+        }
+    }
+}
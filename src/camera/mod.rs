@@ -0,0 +1,172 @@
+use crate::geometry::frustum::Frustum;
+use crate::geometry::ray::Ray3;
+use crate::math::affine::Affine3;
+use crate::math::matrix::Matrix;
+use crate::math::quat::Quat;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+
+pub mod controller;
+
+pub use controller::{CameraShake, FlyController, Follow2d, OrbitController};
+
+/// How a camera flattens view space onto the screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// with a vertical field of view of `fov_y` radians
+    Perspective { fov_y: f32, near: f32, far: f32 },
+    /// showing `height` units of the world from top to bottom
+    Orthographic { height: f32, near: f32, far: f32 },
+}
+
+/// A camera looking down its local negative z-axis with y up, rendering into a viewport of pixels.
+///
+/// screen coordinates are pixels from the top left, and depth runs from 0 at the near plane to 1 at the far one,
+/// matching [`crate::render::Renderer`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub position: Vec3,
+    /// from the camera's local space to world space
+    pub rotation: Quat,
+    pub projection: Projection,
+    /// the width and height of the screen in pixels
+    pub viewport: Vec2,
+}
+
+impl Camera {
+    pub fn perspective(fov_y: f32, near: f32, far: f32, viewport: Vec2) -> Camera {
+        assert!(near > 0.0 && far > near);
+        Camera::new(Projection::Perspective { fov_y, near, far }, viewport)
+    }
+
+    pub fn orthographic(height: f32, near: f32, far: f32, viewport: Vec2) -> Camera {
+        assert!(height > 0.0 && far != near);
+        Camera::new(Projection::Orthographic { height, near, far }, viewport)
+    }
+
+    /// a camera at the origin looking down the negative z-axis
+    pub fn new(projection: Projection, viewport: Vec2) -> Camera {
+        assert!(viewport.x > 0.0 && viewport.y > 0.0, "viewport can't be empty");
+        Camera { position: Vec3::ZERO, rotation: Quat::IDENTITY, projection, viewport }
+    }
+
+    pub fn with_position(self, position: Vec3) -> Camera {
+        Camera { position, ..self }
+    }
+
+    pub fn with_rotation(self, rotation: Quat) -> Camera {
+        Camera { rotation, ..self }
+    }
+
+    /// turned to look at `target`, with `up` as close to the screen's up as it can be
+    pub fn looking_at(self, target: Vec3, up: Vec3) -> Camera {
+        Camera { rotation: look_rotation(target - self.position, up), ..self }
+    }
+
+    /// the width of the viewport over its height
+    #[inline(always)]
+    pub fn aspect(&self) -> f32 {
+        self.viewport.x / self.viewport.y
+    }
+
+    #[inline(always)]
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    #[inline(always)]
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    #[inline(always)]
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    /// from the camera's local space to world space
+    pub fn transform(&self) -> Affine3 {
+        Affine3::from_scale_rotation_translation(Vec3::ONE, self.rotation, self.position)
+    }
+
+    /// from world space to view space
+    pub fn view(&self) -> Affine3 {
+        self.transform().inverse_rigid()
+    }
+
+    pub fn view_matrix(&self) -> Matrix<f32> {
+        self.view().to_matrix()
+    }
+
+    pub fn projection_matrix(&self) -> Matrix<f32> {
+        match self.projection {
+            Projection::Perspective { fov_y, near, far } => Matrix::perspective(fov_y, self.aspect(), near, far),
+            Projection::Orthographic { height, near, far } => {
+                let (x, y) = (height * 0.5 * self.aspect(), height * 0.5);
+                Matrix::orthographic(-x, x, -y, y, near, far)
+            }
+        }
+    }
+
+    /// from world space to clip space
+    pub fn view_projection(&self) -> Matrix<f32> {
+        self.projection_matrix().mul_4x4(&self.view_matrix())
+    }
+
+    /// the volume the camera sees, in world space
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from_matrix(&self.view_projection())
+    }
+
+    /// where `point` lands on the screen, with its depth as z.
+    /// none if it's level with or behind a perspective camera
+    pub fn world_to_screen(&self, point: Vec3) -> Option<Vec3> {
+        let clip = &self.view_projection() * Vec4::from_point(point);
+        if clip.w <= 0.0 {
+            return None
+        }
+        let ndc = clip.xyz() / clip.w;
+        Some(Vec3(
+            (ndc.x * 0.5 + 0.5) * self.viewport.x,
+            (0.5 - ndc.y * 0.5) * self.viewport.y,
+            ndc.z,
+        ))
+    }
+
+    /// the point in world space at `pixel` and `depth`, undoing [`Camera::world_to_screen`]
+    pub fn screen_to_world(&self, pixel: Vec2, depth: f32) -> Vec3 {
+        let ndc = Vec2(pixel.x / self.viewport.x * 2.0 - 1.0, 1.0 - pixel.y / self.viewport.y * 2.0);
+        let local = match self.projection {
+            Projection::Perspective { fov_y, near, far } => {
+                // the view space z that the projection maps to `depth`
+                let z = near * far / (depth * (far - near) - far);
+                let tan = (fov_y * 0.5).tan();
+                Vec3(ndc.x * tan * self.aspect(), ndc.y * tan, -1.0) * -z
+            }
+            Projection::Orthographic { height, near, far } => {
+                let half = height * 0.5;
+                Vec3(ndc.x * half * self.aspect(), ndc.y * half, depth * (near - far) - near)
+            }
+        };
+        self.position + self.rotation * local
+    }
+
+    /// the ray from the near plane through `pixel`, for picking what's under the cursor
+    pub fn pick_ray(&self, pixel: Vec2) -> Ray3 {
+        let near = self.screen_to_world(pixel, 0.0);
+        Ray3::new(near, self.screen_to_world(pixel, 1.0) - near)
+    }
+}
+
+/// the rotation turning the negative z-axis to `forward`, keeping the y-axis as close to `up` as it can
+fn look_rotation(forward: Vec3, up: Vec3) -> Quat {
+    let back = -forward.normalize();
+    let right = up.cross(back).try_normalize().unwrap_or_else(|| back.any_orthonormal());
+    let up = back.cross(right);
+    Quat::from_matrix(&Matrix::from_vec(3, 3, vec![
+        right.x, up.x, back.x,
+        right.y, up.y, back.y,
+        right.z, up.z, back.z,
+    ]))
+}
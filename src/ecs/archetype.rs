@@ -0,0 +1,301 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::ecs::entity::Entity;
+
+/// Anything that can be stored on an entity, which is any thread safe `'static` type
+pub trait Component: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Component for T {}
+
+/// A `Vec<T>` of some component type
+trait AnyVec: Send + Sync {
+    fn len(&self) -> usize;
+    fn empty(&self) -> Box<dyn AnyVec>;
+    fn swap_remove_drop(&mut self, row: usize);
+    /// swap remove `row` and push it onto `other`, which must hold the same type
+    fn swap_remove_into(&mut self, row: usize, other: &mut dyn AnyVec);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Component> AnyVec for Vec<T> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn empty(&self) -> Box<dyn AnyVec> {
+        Box::new(Vec::<T>::new())
+    }
+
+    fn swap_remove_drop(&mut self, row: usize) {
+        self.swap_remove(row);
+    }
+
+    fn swap_remove_into(&mut self, row: usize, other: &mut dyn AnyVec) {
+        let value = self.swap_remove(row);
+        other.as_any_mut().downcast_mut::<Vec<T>>().unwrap().push(value);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The values of one component type in an archetype, with the tick each was added and last changed
+pub struct Column {
+    data: Box<dyn AnyVec>,
+    pub(crate) added: Vec<u32>,
+    pub(crate) changed: Vec<u32>,
+}
+
+impl Column {
+    pub(crate) fn new<T: Component>() -> Column {
+        Column { data: Box::new(Vec::<T>::new()), added: Vec::new(), changed: Vec::new() }
+    }
+
+    fn empty(&self) -> Column {
+        Column { data: self.data.empty(), added: Vec::new(), changed: Vec::new() }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
+    /// the values, which must be of type `T`
+    #[inline(always)]
+    pub fn values<T: Component>(&self) -> &[T] {
+        self.data.as_any().downcast_ref::<Vec<T>>().unwrap()
+    }
+
+    /// the values and their change ticks, which must be of type `T`
+    #[inline(always)]
+    pub(crate) fn values_mut<T: Component>(&mut self) -> (&mut Vec<T>, &mut [u32]) {
+        (self.data.as_any_mut().downcast_mut::<Vec<T>>().unwrap(), &mut self.changed)
+    }
+
+    /// write `value` at `row`, which is either an existing row or one past the end
+    pub(crate) fn write<T: Component>(&mut self, row: usize, value: T, tick: u32) {
+        let (values, _) = self.values_mut::<T>();
+        if row < values.len() {
+            values[row] = value;
+            self.changed[row] = tick;
+        } else {
+            values.push(value);
+            self.added.push(tick);
+            self.changed.push(tick);
+        }
+    }
+
+    fn swap_remove_drop(&mut self, row: usize) {
+        self.data.swap_remove_drop(row);
+        self.added.swap_remove(row);
+        self.changed.swap_remove(row);
+    }
+
+    fn swap_remove_into(&mut self, row: usize, other: &mut Column) {
+        self.data.swap_remove_into(row, &mut *other.data);
+        other.added.push(self.added.swap_remove(row));
+        other.changed.push(self.changed.swap_remove(row));
+    }
+
+    pub(crate) fn take<T: Component>(&mut self, row: usize) -> T {
+        self.added.swap_remove(row);
+        self.changed.swap_remove(row);
+        self.values_mut::<T>().0.swap_remove(row)
+    }
+}
+
+/// The storage for every entity with exactly the same set of component types.
+///
+/// each component type has a column, and each entity a row across all of them.
+pub struct Archetype {
+    /// sorted, parallel to `columns`
+    types: Vec<TypeId>,
+    columns: Vec<RwLock<Column>>,
+    entities: Vec<Entity>,
+    /// the archetype reached by inserting a bundle, keyed by the bundle's type,
+    /// or by removing one component type
+    pub(crate) add_edges: HashMap<TypeId, usize>,
+    pub(crate) remove_edges: HashMap<TypeId, usize>,
+}
+
+impl Archetype {
+    /// an archetype with the given columns, which must be sorted by type
+    pub(crate) fn new(columns: Vec<(TypeId, Column)>) -> Archetype {
+        debug_assert!(columns.windows(2).all(|w| w[0].0 < w[1].0));
+        let (types, columns) = columns.into_iter().map(|(t, c)| (t, RwLock::new(c))).unzip();
+        Archetype {
+            types,
+            columns,
+            entities: Vec::new(),
+            add_edges: HashMap::new(),
+            remove_edges: HashMap::new(),
+        }
+    }
+
+    /// the component types, sorted
+    #[inline(always)]
+    pub fn types(&self) -> &[TypeId] {
+        &self.types
+    }
+
+    #[inline(always)]
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn has(&self, ty: TypeId) -> bool {
+        self.types.binary_search(&ty).is_ok()
+    }
+
+    #[inline(always)]
+    fn column_index(&self, ty: TypeId) -> Option<usize> {
+        self.types.binary_search(&ty).ok()
+    }
+
+    /// read access to the column of `T`, none if the archetype doesn't have one.
+    ///
+    /// panics if the column is being written, since that's a conflicting borrow.
+    pub fn read<T: Component>(&self) -> Option<RwLockReadGuard<'_, Column>> {
+        let column = &self.columns[self.column_index(TypeId::of::<T>())?];
+        Some(column.try_read().unwrap_or_else(|_| panic!("{} is already borrowed mutably", type_name::<T>())))
+    }
+
+    /// write access to the column of `T`, none if the archetype doesn't have one.
+    ///
+    /// panics if the column is being read or written, since that's a conflicting borrow.
+    pub fn write<T: Component>(&self) -> Option<RwLockWriteGuard<'_, Column>> {
+        let column = &self.columns[self.column_index(TypeId::of::<T>())?];
+        Some(column.try_write().unwrap_or_else(|_| panic!("{} is already borrowed", type_name::<T>())))
+    }
+
+    /// the column of `T` without locking, through exclusive access to the archetype
+    pub(crate) fn column_mut<T: Component>(&mut self) -> Option<&mut Column> {
+        let index = self.column_index(TypeId::of::<T>())?;
+        Some(self.columns[index].get_mut().unwrap())
+    }
+
+    /// empty columns of the same types, for building a neighbouring archetype
+    pub(crate) fn empty_columns(&mut self) -> Vec<(TypeId, Column)> {
+        self.types.iter().zip(&mut self.columns).map(|(t, c)| (*t, c.get_mut().unwrap().empty())).collect()
+    }
+
+    pub(crate) fn push_entity(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    /// drop every component of the entity at `row`, returning the entity swapped into its place
+    pub(crate) fn swap_remove(&mut self, row: usize) -> Option<Entity> {
+        for column in &mut self.columns {
+            column.get_mut().unwrap().swap_remove_drop(row);
+        }
+        self.finish_removal(row)
+    }
+
+    /// move the entity at `row` into `other`, dropping the components it doesn't have.
+    /// returns the new row in `other` and the entity swapped into `row`.
+    ///
+    /// any columns in `other` missing here are left one short, for the caller to fill.
+    /// a column already taken from with [`Column::take`] can be skipped with `taken`.
+    pub(crate) fn move_to(&mut self, row: usize, other: &mut Archetype, taken: Option<TypeId>) -> (usize, Option<Entity>) {
+        for (ty, column) in self.types.iter().zip(&mut self.columns) {
+            if taken == Some(*ty) {
+                continue
+            }
+            let column = column.get_mut().unwrap();
+            match other.column_index(*ty) {
+                Some(index) => column.swap_remove_into(row, other.columns[index].get_mut().unwrap()),
+                None => column.swap_remove_drop(row),
+            }
+        }
+        let new_row = other.push_entity(self.entities[row]);
+        (new_row, self.finish_removal(row))
+    }
+
+    /// the entity at `row` has had its components removed, so remove it too
+    fn finish_removal(&mut self, row: usize) -> Option<Entity> {
+        self.entities.swap_remove(row);
+        self.entities.get(row).copied()
+    }
+}
+
+/// A set of components added to an entity together, implemented for tuples of components
+pub trait Bundle: Send + Sync + 'static {
+    /// the component types, sorted, which panics if any repeat
+    fn types() -> Vec<TypeId>;
+
+    /// an empty column for each component, in no particular order
+    fn columns() -> Vec<(TypeId, Column)>;
+
+    /// write each component at `row` of the archetype, which has a column for all of them
+    fn write(self, archetype: &mut Archetype, row: usize, tick: u32);
+}
+
+macro_rules! impl_bundle {
+    ($($name: ident),*) => {
+        impl<$($name: Component),*> Bundle for ($($name,)*) {
+            fn types() -> Vec<TypeId> {
+                let mut types = vec![$(TypeId::of::<$name>()),*];
+                let count = types.len();
+                types.sort_unstable();
+                types.dedup();
+                assert_eq!(types.len(), count, "a bundle can't hold the same component twice");
+                types
+            }
+
+            fn columns() -> Vec<(TypeId, Column)> {
+                vec![$((TypeId::of::<$name>(), Column::new::<$name>())),*]
+            }
+
+            #[allow(non_snake_case)]
+            fn write(self, archetype: &mut Archetype, row: usize, tick: u32) {
+                let ($($name,)*) = self;
+                $(archetype.column_mut::<$name>().unwrap().write(row, $name, tick);)*
+            }
+        }
+    };
+}
+
+impl Bundle for () {
+    fn types() -> Vec<TypeId> {
+        Vec::new()
+    }
+
+    fn columns() -> Vec<(TypeId, Column)> {
+        Vec::new()
+    }
+
+    fn write(self, _: &mut Archetype, _: usize, _: u32) {}
+}
+
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,62 @@
+use crate::ecs::archetype::{Bundle, Component};
+use crate::ecs::entity::Entity;
+use crate::ecs::world::World;
+
+type Command = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// Structural changes recorded while the world is only shared, to be applied later in order.
+///
+/// commands on entities that are gone by the time they're applied do nothing.
+#[derive(Default)]
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+}
+
+impl CommandBuffer {
+    pub fn new() -> CommandBuffer {
+        CommandBuffer::default()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) {
+        self.push(move |world| { world.spawn(bundle); });
+    }
+
+    pub fn despawn(&mut self, entity: Entity) {
+        self.push(move |world| { world.despawn(entity); });
+    }
+
+    pub fn insert<B: Bundle>(&mut self, entity: Entity, bundle: B) {
+        self.push(move |world| { world.insert(entity, bundle); });
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) {
+        self.push(move |world| { world.remove::<T>(entity); });
+    }
+
+    /// record any other change to the world
+    pub fn push<F: FnOnce(&mut World) + Send + Sync + 'static>(&mut self, command: F) {
+        self.commands.push(Box::new(command));
+    }
+
+    /// move the commands of `other` onto the end of this buffer
+    pub fn append(&mut self, other: &mut CommandBuffer) {
+        self.commands.append(&mut other.commands);
+    }
+
+    /// apply every command in the order they were recorded, leaving the buffer empty
+    pub fn apply(&mut self, world: &mut World) {
+        for command in self.commands.drain(..) {
+            command(world);
+        }
+    }
+}
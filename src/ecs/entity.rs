@@ -0,0 +1,94 @@
+use std::fmt;
+
+/// An entity in a [`World`](crate::ecs::world::World).
+///
+/// the index of a despawned entity is reused with a higher generation,
+/// so old ids never refer to the new entity.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    #[inline(always)]
+    pub fn index(self) -> u32 {
+        self.index
+    }
+
+    #[inline(always)]
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Debug for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+/// Where an entity's components live, the archetype and the row within it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Location {
+    pub archetype: usize,
+    pub row: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Slot {
+    generation: u32,
+    location: Option<Location>,
+}
+
+/// Hands out entity ids and tracks where each live entity is stored
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Entities {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl Entities {
+    pub fn alloc(&mut self, location: Location) -> Entity {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.generation += 1;
+                slot.location = Some(location);
+                Entity { index, generation: slot.generation }
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, location: Some(location) });
+                Entity { index: self.slots.len() as u32 - 1, generation: 0 }
+            }
+        }
+    }
+
+    /// where `entity` is, none if it's been despawned
+    #[inline(always)]
+    pub fn location(&self, entity: Entity) -> Option<Location> {
+        let slot = self.slots.get(entity.index as usize)?;
+        if slot.generation == entity.generation { slot.location } else { None }
+    }
+
+    /// move a live entity, which must not be stale
+    #[inline(always)]
+    pub fn set_location(&mut self, entity: Entity, location: Location) {
+        self.slots[entity.index as usize].location = Some(location);
+    }
+
+    pub fn free(&mut self, entity: Entity) -> Option<Location> {
+        let location = self.location(entity)?;
+        self.slots[entity.index as usize].location = None;
+        self.free.push(entity.index);
+        self.len -= 1;
+        Some(location)
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
@@ -0,0 +1,6 @@
+pub mod archetype;
+pub mod command;
+pub mod entity;
+pub mod query;
+pub mod schedule;
+pub mod world;
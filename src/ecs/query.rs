@@ -0,0 +1,392 @@
+use std::any::TypeId;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+use crate::ecs::archetype::{Archetype, Column, Component};
+use crate::ecs::entity::Entity;
+use crate::ecs::schedule::Access;
+use crate::ecs::world::World;
+
+/// What a query reads from each entity: [`Entity`], `&T`, `&mut T`, `Option<&T>`,
+/// or tuples of those
+pub trait Fetch {
+    /// the locks held on an archetype's columns while iterating it
+    type Guard<'w>;
+    type Item<'a>;
+
+    /// whether entities in `archetype` have everything this needs
+    fn matches(archetype: &Archetype) -> bool;
+
+    /// add the components this reads and writes to `access`
+    fn access(access: &mut Access);
+
+    /// lock the columns of a matching archetype, stamping writes with `tick`
+    fn lock(archetype: &Archetype, tick: u32) -> Self::Guard<'_>;
+
+    fn get<'a>(guard: &'a mut Self::Guard<'_>, row: usize) -> Self::Item<'a>;
+}
+
+impl Fetch for Entity {
+    type Guard<'w> = &'w [Entity];
+    type Item<'a> = Entity;
+
+    #[inline(always)]
+    fn matches(_: &Archetype) -> bool {
+        true
+    }
+
+    fn access(_: &mut Access) {}
+
+    #[inline(always)]
+    fn lock(archetype: &Archetype, _: u32) -> &[Entity] {
+        archetype.entities()
+    }
+
+    #[inline(always)]
+    fn get(guard: &mut &[Entity], row: usize) -> Entity {
+        guard[row]
+    }
+}
+
+impl<T: Component> Fetch for &T {
+    type Guard<'w> = RwLockReadGuard<'w, Column>;
+    type Item<'a> = &'a T;
+
+    #[inline(always)]
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    #[inline(always)]
+    fn lock(archetype: &Archetype, _: u32) -> RwLockReadGuard<'_, Column> {
+        archetype.read::<T>().unwrap()
+    }
+
+    #[inline(always)]
+    fn get<'a>(guard: &'a mut RwLockReadGuard<'_, Column>, row: usize) -> &'a T {
+        &guard.values::<T>()[row]
+    }
+}
+
+impl<T: Component> Fetch for &mut T {
+    type Guard<'w> = (RwLockWriteGuard<'w, Column>, u32);
+    type Item<'a> = Mut<'a, T>;
+
+    #[inline(always)]
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+
+    fn access(access: &mut Access) {
+        access.add_write::<T>();
+    }
+
+    #[inline(always)]
+    fn lock(archetype: &Archetype, tick: u32) -> (RwLockWriteGuard<'_, Column>, u32) {
+        (archetype.write::<T>().unwrap(), tick)
+    }
+
+    #[inline(always)]
+    fn get<'a>(guard: &'a mut (RwLockWriteGuard<'_, Column>, u32), row: usize) -> Mut<'a, T> {
+        let tick = guard.1;
+        let (values, changed) = guard.0.values_mut::<T>();
+        Mut { value: &mut values[row], changed: &mut changed[row], tick }
+    }
+}
+
+impl<T: Component> Fetch for Option<&T> {
+    type Guard<'w> = Option<RwLockReadGuard<'w, Column>>;
+    type Item<'a> = Option<&'a T>;
+
+    #[inline(always)]
+    fn matches(_: &Archetype) -> bool {
+        true
+    }
+
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    #[inline(always)]
+    fn lock(archetype: &Archetype, _: u32) -> Option<RwLockReadGuard<'_, Column>> {
+        archetype.read::<T>()
+    }
+
+    #[inline(always)]
+    fn get<'a>(guard: &'a mut Option<RwLockReadGuard<'_, Column>>, row: usize) -> Option<&'a T> {
+        guard.as_ref().map(|g| &g.values::<T>()[row])
+    }
+}
+
+macro_rules! impl_fetch {
+    ($($name: ident),*) => {
+        impl<$($name: Fetch),*> Fetch for ($($name,)*) {
+            type Guard<'w> = ($($name::Guard<'w>,)*);
+            type Item<'a> = ($($name::Item<'a>,)*);
+
+            #[inline(always)]
+            fn matches(archetype: &Archetype) -> bool {
+                $($name::matches(archetype))&&*
+            }
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            #[inline(always)]
+            fn lock(archetype: &Archetype, tick: u32) -> Self::Guard<'_> {
+                ($($name::lock(archetype, tick),)*)
+            }
+
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn get<'a>(guard: &'a mut Self::Guard<'_>, row: usize) -> Self::Item<'a> {
+                let ($($name,)*) = guard;
+                ($($name::get($name, row),)*)
+            }
+        }
+    };
+}
+
+impl_fetch!(A);
+impl_fetch!(A, B);
+impl_fetch!(A, B, C);
+impl_fetch!(A, B, C, D);
+impl_fetch!(A, B, C, D, E);
+impl_fetch!(A, B, C, D, E, F);
+impl_fetch!(A, B, C, D, E, F, G);
+impl_fetch!(A, B, C, D, E, F, G, H);
+
+/// A mutable reference to a component from a query, which marks it changed when written through
+pub struct Mut<'a, T> {
+    value: &'a mut T,
+    changed: &'a mut u32,
+    tick: u32,
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        *self.changed = self.tick;
+        self.value
+    }
+}
+
+/// Which entities a query visits beyond those [`Fetch`] matches: [`With`], [`Without`],
+/// [`Added`], [`Changed`], or tuples of those which must all pass
+pub trait Filter {
+    /// whether any row checks are needed, so queries without them skip the work
+    const ROWS: bool = false;
+
+    /// whether entities in `archetype` can pass
+    fn matches(archetype: &Archetype) -> bool;
+
+    /// keep the rows of a matching archetype that pass, given the tick the query last ran
+    fn retain(_archetype: &Archetype, _since: u32, _rows: &mut Vec<usize>) {}
+
+    /// add the components this reads to `access`
+    fn access(_access: &mut Access) {}
+}
+
+impl Filter for () {
+    #[inline(always)]
+    fn matches(_: &Archetype) -> bool {
+        true
+    }
+}
+
+/// Only entities that have a `T`
+pub struct With<T>(PhantomData<T>);
+
+/// Only entities that don't have a `T`
+pub struct Without<T>(PhantomData<T>);
+
+/// Only entities whose `T` was added since the query last ran
+pub struct Added<T>(PhantomData<T>);
+
+/// Only entities whose `T` was added or mutably accessed since the query last ran
+pub struct Changed<T>(PhantomData<T>);
+
+impl<T: Component> Filter for With<T> {
+    #[inline(always)]
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+}
+
+impl<T: Component> Filter for Without<T> {
+    #[inline(always)]
+    fn matches(archetype: &Archetype) -> bool {
+        !archetype.has(TypeId::of::<T>())
+    }
+}
+
+impl<T: Component> Filter for Added<T> {
+    const ROWS: bool = true;
+
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    #[inline(always)]
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+
+    fn retain(archetype: &Archetype, since: u32, rows: &mut Vec<usize>) {
+        let column = archetype.read::<T>().unwrap();
+        rows.retain(|row| column.added[*row] > since);
+    }
+}
+
+impl<T: Component> Filter for Changed<T> {
+    const ROWS: bool = true;
+
+    fn access(access: &mut Access) {
+        access.add_read::<T>();
+    }
+
+    #[inline(always)]
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+
+    fn retain(archetype: &Archetype, since: u32, rows: &mut Vec<usize>) {
+        let column = archetype.read::<T>().unwrap();
+        rows.retain(|row| column.changed[*row] > since);
+    }
+}
+
+macro_rules! impl_filter {
+    ($($name: ident),*) => {
+        impl<$($name: Filter),*> Filter for ($($name,)*) {
+            const ROWS: bool = $($name::ROWS)||*;
+
+            #[inline(always)]
+            fn matches(archetype: &Archetype) -> bool {
+                $($name::matches(archetype))&&*
+            }
+
+            fn retain(archetype: &Archetype, since: u32, rows: &mut Vec<usize>) {
+                $($name::retain(archetype, since, rows);)*
+            }
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+        }
+    };
+}
+
+impl_filter!(A);
+impl_filter!(A, B);
+impl_filter!(A, B, C);
+impl_filter!(A, B, C, D);
+
+/// The entities of a world matching `Q` and the filter `F`, see [`World::query`].
+///
+/// each archetype's columns are locked while it's visited, so fetching a component mutably
+/// alongside another fetch or filter of the same component panics. [`Added`] and [`Changed`]
+/// compare against the tick given to [`Query::since`], which defaults to counting everything.
+pub struct Query<'w, Q: Fetch, F: Filter = ()> {
+    world: &'w World,
+    since: u32,
+    marker: PhantomData<fn() -> (Q, F)>,
+}
+
+impl<'w, Q: Fetch, F: Filter> Query<'w, Q, F> {
+    pub(crate) fn new(world: &'w World) -> Query<'w, Q, F> {
+        Query { world, since: 0, marker: PhantomData }
+    }
+
+    /// only count additions and changes made after `tick`
+    #[inline(always)]
+    pub fn since(self, tick: u32) -> Query<'w, Q, F> {
+        Query { since: tick, ..self }
+    }
+
+    fn archetypes(&self) -> impl Iterator<Item = &'w Archetype> {
+        self.world.archetypes().iter().filter(|a| !a.is_empty() && Q::matches(a) && F::matches(a))
+    }
+
+    /// the rows of a matching archetype that pass the filter's row checks
+    fn rows(&self, archetype: &Archetype) -> Vec<usize> {
+        let mut rows = (0..archetype.len()).collect();
+        F::retain(archetype, self.since, &mut rows);
+        rows
+    }
+
+    /// call `f` with every matching entity's components, archetype by archetype
+    pub fn for_each<C>(&self, mut f: C)
+    where
+        C: for<'a> FnMut(Q::Item<'a>),
+    {
+        let tick = self.world.change_tick();
+        for archetype in self.archetypes() {
+            if F::ROWS {
+                let rows = self.rows(archetype);
+                let mut guard = Q::lock(archetype, tick);
+                for row in rows {
+                    f(Q::get(&mut guard, row));
+                }
+            } else {
+                let mut guard = Q::lock(archetype, tick);
+                for row in 0..archetype.len() {
+                    f(Q::get(&mut guard, row));
+                }
+            }
+        }
+    }
+
+    /// the number of matching entities
+    pub fn count(&self) -> usize {
+        self.archetypes().map(|a| if F::ROWS { self.rows(a).len() } else { a.len() }).sum()
+    }
+
+    /// the matching entities, in the order [`Query::for_each`] visits them
+    pub fn entities(&self) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        for archetype in self.archetypes() {
+            if F::ROWS {
+                entities.extend(self.rows(archetype).into_iter().map(|row| archetype.entities()[row]));
+            } else {
+                entities.extend_from_slice(archetype.entities());
+            }
+        }
+        entities
+    }
+
+    /// call `f` with one entity's components, none if it doesn't match
+    pub fn get<R, C>(&self, entity: Entity, f: C) -> Option<R>
+    where
+        C: for<'a> FnOnce(Q::Item<'a>) -> R,
+    {
+        let location = self.world.location(entity)?;
+        let archetype = &self.world.archetypes()[location.archetype];
+        if !Q::matches(archetype) || !F::matches(archetype) {
+            return None
+        }
+        if F::ROWS {
+            let mut rows = vec![location.row];
+            F::retain(archetype, self.since, &mut rows);
+            if rows.is_empty() {
+                return None
+            }
+        }
+        let mut guard = Q::lock(archetype, self.world.change_tick());
+        Some(f(Q::get(&mut guard, location.row)))
+    }
+}
@@ -0,0 +1,456 @@
+use std::any::{type_name, TypeId};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use rayon::prelude::*;
+use crate::ecs::archetype::Component;
+use crate::ecs::command::CommandBuffer;
+use crate::ecs::query::{Fetch, Filter, Query};
+use crate::ecs::world::{Res, ResMut, World};
+
+/// The components and resources a system reads and writes, with their type names for reporting
+#[derive(Clone, Debug, Default)]
+pub struct Access {
+    reads: Vec<(TypeId, &'static str)>,
+    writes: Vec<(TypeId, &'static str)>,
+    resource_reads: Vec<(TypeId, &'static str)>,
+    resource_writes: Vec<(TypeId, &'static str)>,
+}
+
+impl Access {
+    pub fn new() -> Access {
+        Access::default()
+    }
+
+    pub fn add_read<T: Component>(&mut self) {
+        push_unique(&mut self.reads, (TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    pub fn add_write<T: Component>(&mut self) {
+        push_unique(&mut self.writes, (TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    pub fn add_resource_read<R: Component>(&mut self) {
+        push_unique(&mut self.resource_reads, (TypeId::of::<R>(), type_name::<R>()));
+    }
+
+    pub fn add_resource_write<R: Component>(&mut self) {
+        push_unique(&mut self.resource_writes, (TypeId::of::<R>(), type_name::<R>()));
+    }
+
+    /// add everything a query over `Q` filtered by `F` touches
+    pub fn add_query<Q: Fetch, F: Filter>(&mut self) {
+        Q::access(self);
+        F::access(self);
+    }
+
+    /// add everything `other` touches
+    pub fn extend(&mut self, other: &Access) {
+        for (ours, theirs) in [
+            (&mut self.reads, &other.reads),
+            (&mut self.writes, &other.writes),
+            (&mut self.resource_reads, &other.resource_reads),
+            (&mut self.resource_writes, &other.resource_writes),
+        ] {
+            for item in theirs {
+                push_unique(ours, *item);
+            }
+        }
+    }
+
+    /// the names of the components and resources one of the two writes while the other
+    /// touches them at all, empty if both can run at once
+    pub fn conflicts(&self, other: &Access) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        let sets = [
+            (&self.writes, &other.writes),
+            (&self.writes, &other.reads),
+            (&other.writes, &self.reads),
+            (&self.resource_writes, &other.resource_writes),
+            (&self.resource_writes, &other.resource_reads),
+            (&other.resource_writes, &self.resource_reads),
+        ];
+        for (writes, touches) in sets {
+            for (ty, name) in writes {
+                if touches.iter().any(|(t, _)| t == ty) && !names.contains(name) {
+                    names.push(*name);
+                }
+            }
+        }
+        names
+    }
+}
+
+fn push_unique(items: &mut Vec<(TypeId, &'static str)>, item: (TypeId, &'static str)) {
+    if !items.iter().any(|(ty, _)| *ty == item.0) {
+        items.push(item);
+    }
+}
+
+/// What a running system sees: the world, its own command buffer, and when it last ran
+pub struct Context<'w> {
+    world: &'w World,
+    commands: &'w mut CommandBuffer,
+    last_run: u32,
+}
+
+impl<'w> Context<'w> {
+    #[inline(always)]
+    pub fn world(&self) -> &'w World {
+        self.world
+    }
+
+    /// structural changes, applied once every system in the stage has finished
+    #[inline(always)]
+    pub fn commands(&mut self) -> &mut CommandBuffer {
+        self.commands
+    }
+
+    /// the change tick the system last ran at, 0 before its first run
+    #[inline(always)]
+    pub fn last_run(&self) -> u32 {
+        self.last_run
+    }
+
+    /// every entity matching `Q`, counting additions and changes since the system last ran
+    #[inline(always)]
+    pub fn query<Q: Fetch>(&self) -> Query<'w, Q> {
+        self.world.query::<Q>().since(self.last_run)
+    }
+
+    /// like [`Context::query`] with the filter `F`
+    #[inline(always)]
+    pub fn query_filtered<Q: Fetch, F: Filter>(&self) -> Query<'w, Q, F> {
+        self.world.query_filtered::<Q, F>().since(self.last_run)
+    }
+
+    #[inline(always)]
+    pub fn resource<R: Component>(&self) -> Option<Res<'w, R>> {
+        self.world.resource::<R>()
+    }
+
+    #[inline(always)]
+    pub fn resource_mut<R: Component>(&self) -> Option<ResMut<'w, R>> {
+        self.world.resource_mut::<R>()
+    }
+}
+
+type SystemFn = Box<dyn FnMut(&mut Context<'_>) + Send + Sync>;
+
+/// A named function run over the world by a [`Schedule`], with the data it declares it touches.
+///
+/// a system touching anything it didn't declare may run alongside another that writes it,
+/// which panics on the conflicting borrow.
+pub struct System {
+    name: String,
+    run: SystemFn,
+    access: Access,
+    after: Vec<String>,
+    before: Vec<String>,
+    commands: CommandBuffer,
+    last_run: u32,
+}
+
+impl System {
+    pub fn new<F: FnMut(&mut Context<'_>) + Send + Sync + 'static>(name: &str, run: F) -> System {
+        System {
+            name: name.to_string(),
+            run: Box::new(run),
+            access: Access::new(),
+            after: Vec::new(),
+            before: Vec::new(),
+            commands: CommandBuffer::new(),
+            last_run: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline(always)]
+    pub fn access(&self) -> &Access {
+        &self.access
+    }
+
+    pub fn read<T: Component>(mut self) -> System {
+        self.access.add_read::<T>();
+        self
+    }
+
+    pub fn write<T: Component>(mut self) -> System {
+        self.access.add_write::<T>();
+        self
+    }
+
+    pub fn read_resource<R: Component>(mut self) -> System {
+        self.access.add_resource_read::<R>();
+        self
+    }
+
+    pub fn write_resource<R: Component>(mut self) -> System {
+        self.access.add_resource_write::<R>();
+        self
+    }
+
+    /// declare everything a query over `Q` filtered by `F` touches
+    pub fn query<Q: Fetch, F: Filter>(mut self) -> System {
+        self.access.add_query::<Q, F>();
+        self
+    }
+
+    pub fn with_access(mut self, access: &Access) -> System {
+        self.access.extend(access);
+        self
+    }
+
+    /// run after the system called `name`
+    pub fn after(mut self, name: &str) -> System {
+        self.after.push(name.to_string());
+        self
+    }
+
+    /// run before the system called `name`
+    pub fn before(mut self, name: &str) -> System {
+        self.before.push(name.to_string());
+        self
+    }
+
+    fn run(&mut self, world: &World, tick: u32) {
+        let mut context = Context { world, commands: &mut self.commands, last_run: self.last_run };
+        (self.run)(&mut context);
+        self.last_run = tick;
+    }
+}
+
+/// Why a [`Schedule`] couldn't be built
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// systems whose ordering constraints loop, each one running after the one before
+    Cycle(Vec<String>),
+    /// an ordering constraint naming a system that isn't in the schedule
+    UnknownSystem { system: String, dependency: String },
+    /// two systems with the same name
+    DuplicateName(String),
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::Cycle(names) => write!(f, "ordering cycle: {} -> {}", names.join(" -> "), names[0]),
+            ScheduleError::UnknownSystem { system, dependency } => {
+                write!(f, "{system:?} is ordered against unknown system {dependency:?}")
+            }
+            ScheduleError::DuplicateName(name) => write!(f, "duplicate system name: {name:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Two systems whose access conflicts with no ordering between them, so which runs first
+/// depends on how they happened to be staged
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ambiguity {
+    pub a: String,
+    pub b: String,
+    /// the names of the components and resources they conflict on
+    pub conflicts: Vec<&'static str>,
+}
+
+/// Systems grouped into stages that run one after another.
+///
+/// the systems in a stage have no conflicting access, so they run in parallel on rayon.
+/// a system goes in the first stage after everything it's ordered after that it doesn't
+/// conflict with, and systems are otherwise considered in the order they were added.
+/// commands recorded in a stage are applied in system order once the stage is done.
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<System>,
+    /// the stage of each system, none until built
+    stages: Option<Vec<usize>>,
+    ambiguities: Vec<Ambiguity>,
+}
+
+impl Schedule {
+    pub fn new() -> Schedule {
+        Schedule::default()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    pub fn add_system(&mut self, system: System) -> &mut Schedule {
+        self.systems.push(system);
+        self.stages = None;
+        self
+    }
+
+    /// work out the stages and ambiguities, which happens on the first run otherwise
+    pub fn build(&mut self) -> Result<(), ScheduleError> {
+        let n = self.systems.len();
+        let mut names = HashMap::new();
+        for (i, system) in self.systems.iter().enumerate() {
+            if names.insert(system.name.as_str(), i).is_some() {
+                return Err(ScheduleError::DuplicateName(system.name.clone()))
+            }
+        }
+
+        // edges from each system to the ones that must run after it
+        let mut successors = vec![Vec::new(); n];
+        let mut predecessors = vec![Vec::new(); n];
+        for (i, system) in self.systems.iter().enumerate() {
+            let lookup = |dependency: &String| names.get(dependency.as_str()).copied().ok_or_else(|| {
+                ScheduleError::UnknownSystem { system: system.name.clone(), dependency: dependency.clone() }
+            });
+            for dependency in &system.after {
+                let j = lookup(dependency)?;
+                successors[j].push(i);
+                predecessors[i].push(j);
+            }
+            for dependency in &system.before {
+                let j = lookup(dependency)?;
+                successors[i].push(j);
+                predecessors[j].push(i);
+            }
+        }
+
+        // topological order, taking the earliest added system whenever there's a choice
+        let mut remaining: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+        let mut ready: BTreeSet<usize> = (0..n).filter(|i| remaining[*i] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+        while let Some(i) = ready.pop_first() {
+            order.push(i);
+            for &j in &successors[i] {
+                remaining[j] -= 1;
+                if remaining[j] == 0 {
+                    ready.insert(j);
+                }
+            }
+        }
+        if order.len() < n {
+            return Err(ScheduleError::Cycle(self.find_cycle(&predecessors, &remaining)))
+        }
+
+        // the first stage after every predecessor without a conflict
+        let mut stage_of = vec![0; n];
+        let mut stages: Vec<Vec<usize>> = Vec::new();
+        for &i in &order {
+            let mut stage = predecessors[i].iter().map(|p| stage_of[*p] + 1).max().unwrap_or(0);
+            while stage < stages.len() && stages[stage].iter().any(|j| !self.conflicts(i, *j).is_empty()) {
+                stage += 1;
+            }
+            if stage == stages.len() {
+                stages.push(Vec::new());
+            }
+            stages[stage].push(i);
+            stage_of[i] = stage;
+        }
+
+        // everything each system is transitively ordered before
+        let mut reachable = vec![vec![false; n]; n];
+        for &i in order.iter().rev() {
+            for &j in &successors[i] {
+                let after = reachable[j].clone();
+                reachable[i][j] = true;
+                reachable[i].iter_mut().zip(after).for_each(|(a, b)| *a |= b);
+            }
+        }
+        self.ambiguities.clear();
+        for (i, before) in reachable.iter().enumerate() {
+            for (j, after) in reachable.iter().enumerate().skip(i + 1) {
+                let conflicts = self.conflicts(i, j);
+                if !conflicts.is_empty() && !before[j] && !after[i] {
+                    let (a, b) = (self.systems[i].name.clone(), self.systems[j].name.clone());
+                    self.ambiguities.push(Ambiguity { a, b, conflicts });
+                }
+            }
+        }
+
+        self.stages = Some(stage_of);
+        Ok(())
+    }
+
+    fn conflicts(&self, i: usize, j: usize) -> Vec<&'static str> {
+        self.systems[i].access.conflicts(&self.systems[j].access)
+    }
+
+    /// walk back through predecessors left over from sorting until one repeats,
+    /// starting the cycle at its earliest added system
+    fn find_cycle(&self, predecessors: &[Vec<usize>], remaining: &[usize]) -> Vec<String> {
+        let mut at = (0..remaining.len()).find(|i| remaining[*i] > 0).unwrap();
+        let mut path = Vec::new();
+        let start = loop {
+            if let Some(start) = path.iter().position(|i| *i == at) {
+                break start
+            }
+            path.push(at);
+            at = predecessors[at].iter().copied().find(|p| remaining[*p] > 0).unwrap();
+        };
+        let mut cycle: Vec<usize> = path[start..].iter().rev().copied().collect();
+        let first = cycle.iter().enumerate().min_by_key(|(_, i)| **i).unwrap().0;
+        cycle.rotate_left(first);
+        cycle.iter().map(|i| self.systems[*i].name.clone()).collect()
+    }
+
+    /// the names of the systems in each stage, empty until built
+    pub fn stages(&self) -> Vec<Vec<&str>> {
+        let Some(stage_of) = &self.stages else { return Vec::new() };
+        let mut stages = vec![Vec::new(); stage_of.iter().max().map_or(0, |s| s + 1)];
+        for (system, stage) in self.systems.iter().zip(stage_of) {
+            stages[*stage].push(system.name.as_str());
+        }
+        stages
+    }
+
+    /// the pairs of systems with conflicting access and no ordering, empty until built
+    #[inline(always)]
+    pub fn ambiguities(&self) -> &[Ambiguity] {
+        &self.ambiguities
+    }
+
+    /// run every system once, stage by stage, building first if needed.
+    ///
+    /// each stage runs at a new change tick, so systems see changes from earlier stages and
+    /// from anything that ran after them last time. panics if the schedule can't be built.
+    pub fn run(&mut self, world: &mut World) {
+        if self.stages.is_none() {
+            if let Err(error) = self.build() {
+                panic!("{error}");
+            }
+        }
+        let stage_of = self.stages.take().unwrap();
+        let count = stage_of.iter().max().map_or(0, |s| s + 1);
+
+        for stage in 0..count {
+            let mut systems: Vec<&mut System> = self.systems.iter_mut()
+                .zip(&stage_of)
+                .filter(|(_, s)| **s == stage)
+                .map(|(system, _)| system)
+                .collect();
+
+            let tick = world.increment_change_tick();
+            let shared = &*world;
+            if systems.len() == 1 {
+                systems[0].run(shared, tick);
+            } else {
+                systems.par_iter_mut().for_each(|system| system.run(shared, tick));
+            }
+
+            // commands land after the stage's tick, so they count as changes to every system in it
+            world.increment_change_tick();
+            for system in systems {
+                system.commands.apply(world);
+            }
+        }
+
+        self.stages = Some(stage_of);
+    }
+}
@@ -0,0 +1,321 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::ecs::archetype::{Archetype, Bundle, Column, Component};
+use crate::ecs::entity::{Entities, Entity, Location};
+use crate::ecs::query::{Fetch, Filter, Query};
+
+/// Every entity and its components, grouped into archetypes by which components they have.
+///
+/// structural changes, spawning, despawning and adding or removing components, need exclusive
+/// access. queries only need shared access, with each component column locked for the duration
+/// of a query, so queries over different components can run on different threads at once.
+pub struct World {
+    entities: Entities,
+    archetypes: Vec<Archetype>,
+    /// the archetype for each sorted set of component types
+    index: HashMap<Vec<TypeId>, usize>,
+    /// values that aren't attached to any entity, one of each type
+    resources: HashMap<TypeId, RwLock<Box<dyn Any + Send + Sync>>>,
+    tick: u32,
+}
+
+impl Default for World {
+    fn default() -> World {
+        World::new()
+    }
+}
+
+impl World {
+    pub fn new() -> World {
+        let mut index = HashMap::new();
+        index.insert(Vec::new(), 0);
+        World {
+            entities: Entities::default(),
+            archetypes: vec![Archetype::new(Vec::new())],
+            index,
+            resources: HashMap::new(),
+            tick: 1,
+        }
+    }
+
+    /// the number of live entities
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entities.len() == 0
+    }
+
+    /// whether `entity` is still alive
+    #[inline(always)]
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.location(entity).is_some()
+    }
+
+    #[inline(always)]
+    pub fn archetypes(&self) -> &[Archetype] {
+        &self.archetypes
+    }
+
+    /// the tick that components added or changed now are stamped with
+    #[inline(always)]
+    pub fn change_tick(&self) -> u32 {
+        self.tick
+    }
+
+    /// start a new tick, so later changes can be told apart from earlier ones.
+    /// returns the new tick
+    pub fn increment_change_tick(&mut self) -> u32 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// the archetype for `types`, which must be sorted, building it from `columns` if it's new
+    fn archetype_for<F>(&mut self, types: Vec<TypeId>, columns: F) -> usize
+    where
+        F: FnOnce(&mut [Archetype]) -> Vec<(TypeId, Column)>,
+    {
+        if let Some(&id) = self.index.get(&types) {
+            return id
+        }
+        let mut columns = columns(&mut self.archetypes);
+        columns.sort_unstable_by_key(|(ty, _)| *ty);
+        self.archetypes.push(Archetype::new(columns));
+        self.index.insert(types, self.archetypes.len() - 1);
+        self.archetypes.len() - 1
+    }
+
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let id = self.archetype_for(B::types(), |_| B::columns());
+        let archetype = &mut self.archetypes[id];
+        let entity = self.entities.alloc(Location { archetype: id, row: archetype.len() });
+        let row = archetype.push_entity(entity);
+        bundle.write(archetype, row, self.tick);
+        entity
+    }
+
+    /// remove `entity` and drop its components, returning false if it was already gone
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        let Some(location) = self.entities.free(entity) else { return false };
+        if let Some(moved) = self.archetypes[location.archetype].swap_remove(location.row) {
+            self.entities.set_location(moved, location);
+        }
+        true
+    }
+
+    /// add components to `entity`, replacing any it already has of the same type.
+    /// returns false if the entity is gone
+    pub fn insert<B: Bundle>(&mut self, entity: Entity, bundle: B) -> bool {
+        let Some(location) = self.entities.location(entity) else { return false };
+        let source = location.archetype;
+
+        let target = match self.archetypes[source].add_edges.get(&TypeId::of::<B>()) {
+            Some(&target) => target,
+            None => {
+                let mut types = self.archetypes[source].types().to_vec();
+                types.extend(B::types());
+                types.sort_unstable();
+                types.dedup();
+                let target = self.archetype_for(types, |archetypes| {
+                    let mut columns = archetypes[source].empty_columns();
+                    columns.extend(B::columns().into_iter().filter(|(ty, _)| !archetypes[source].has(*ty)));
+                    columns
+                });
+                self.archetypes[source].add_edges.insert(TypeId::of::<B>(), target);
+                target
+            }
+        };
+
+        let row = if target == source {
+            location.row
+        } else {
+            self.move_entity(entity, location, target, None)
+        };
+        bundle.write(&mut self.archetypes[target], row, self.tick);
+        true
+    }
+
+    /// take a component off `entity`, none if it doesn't have one or is gone
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        let location = self.entities.location(entity)?;
+        let source = location.archetype;
+        let ty = TypeId::of::<T>();
+        if !self.archetypes[source].has(ty) {
+            return None
+        }
+
+        let target = match self.archetypes[source].remove_edges.get(&ty) {
+            Some(&target) => target,
+            None => {
+                let types: Vec<TypeId> = self.archetypes[source].types().iter().copied().filter(|t| *t != ty).collect();
+                let target = self.archetype_for(types, |archetypes| {
+                    let mut columns = archetypes[source].empty_columns();
+                    columns.retain(|(t, _)| *t != ty);
+                    columns
+                });
+                self.archetypes[source].remove_edges.insert(ty, target);
+                target
+            }
+        };
+
+        let value = self.archetypes[source].column_mut::<T>().unwrap().take(location.row);
+        self.move_entity(entity, location, target, Some(ty));
+        Some(value)
+    }
+
+    /// move an entity between archetypes, returning its new row
+    fn move_entity(&mut self, entity: Entity, location: Location, target: usize, taken: Option<TypeId>) -> usize {
+        let (from, to) = pair_mut(&mut self.archetypes, location.archetype, target);
+        let (row, moved) = from.move_to(location.row, to, taken);
+        if let Some(moved) = moved {
+            self.entities.set_location(moved, location);
+        }
+        self.entities.set_location(entity, Location { archetype: target, row });
+        row
+    }
+
+    /// whether `entity` is alive and has a `T`
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.entities.location(entity).is_some_and(|l| self.archetypes[l.archetype].has(TypeId::of::<T>()))
+    }
+
+    /// the `T` of `entity`, none if it doesn't have one or is gone.
+    ///
+    /// the whole column stays locked for reading while the reference is held.
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<Ref<'_, T>> {
+        let location = self.entities.location(entity)?;
+        let guard = self.archetypes[location.archetype].read::<T>()?;
+        Some(Ref { guard, row: location.row, marker: PhantomData })
+    }
+
+    /// the `T` of `entity`, marked as changed, none if it doesn't have one or is gone
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        let location = self.entities.location(entity)?;
+        let column = self.archetypes[location.archetype].column_mut::<T>()?;
+        let (values, changed) = column.values_mut::<T>();
+        changed[location.row] = self.tick;
+        Some(&mut values[location.row])
+    }
+
+    /// every entity matching `Q`, see [`Query`]
+    #[inline(always)]
+    pub fn query<Q: Fetch>(&self) -> Query<'_, Q> {
+        Query::new(self)
+    }
+
+    /// every entity matching `Q` that also passes the filter `F`, see [`Query`]
+    #[inline(always)]
+    pub fn query_filtered<Q: Fetch, F: Filter>(&self) -> Query<'_, Q, F> {
+        Query::new(self)
+    }
+
+    /// add a resource, returning the one it replaced
+    pub fn insert_resource<R: Component>(&mut self, value: R) -> Option<R> {
+        let old = self.resources.insert(TypeId::of::<R>(), RwLock::new(Box::new(value)))?;
+        Some(*old.into_inner().unwrap().downcast::<R>().unwrap())
+    }
+
+    pub fn remove_resource<R: Component>(&mut self) -> Option<R> {
+        let old = self.resources.remove(&TypeId::of::<R>())?;
+        Some(*old.into_inner().unwrap().downcast::<R>().unwrap())
+    }
+
+    #[inline(always)]
+    pub fn contains_resource<R: Component>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    /// the resource of type `R`, none if there isn't one.
+    ///
+    /// panics if it's borrowed mutably, since that's a conflicting borrow.
+    pub fn resource<R: Component>(&self) -> Option<Res<'_, R>> {
+        let lock = self.resources.get(&TypeId::of::<R>())?;
+        let guard = lock.try_read().unwrap_or_else(|_| panic!("{} is already borrowed mutably", type_name::<R>()));
+        Some(Res { guard, marker: PhantomData })
+    }
+
+    /// the resource of type `R` for writing, none if there isn't one.
+    ///
+    /// this only needs shared access to the world, and panics if the resource is already borrowed.
+    pub fn resource_mut<R: Component>(&self) -> Option<ResMut<'_, R>> {
+        let lock = self.resources.get(&TypeId::of::<R>())?;
+        let guard = lock.try_write().unwrap_or_else(|_| panic!("{} is already borrowed", type_name::<R>()));
+        Some(ResMut { guard, marker: PhantomData })
+    }
+
+    #[inline(always)]
+    pub(crate) fn location(&self, entity: Entity) -> Option<Location> {
+        self.entities.location(entity)
+    }
+}
+
+/// two distinct elements of a slice mutably at once
+fn pair_mut<T>(items: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
+    assert_ne!(a, b);
+    if a < b {
+        let (low, high) = items.split_at_mut(b);
+        (&mut low[a], &mut high[0])
+    } else {
+        let (low, high) = items.split_at_mut(a);
+        (&mut high[0], &mut low[b])
+    }
+}
+
+/// A shared reference to a component, holding its column's read lock
+pub struct Ref<'w, T: Component> {
+    guard: RwLockReadGuard<'w, Column>,
+    row: usize,
+    marker: PhantomData<&'w T>,
+}
+
+impl<T: Component> Deref for Ref<'_, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.guard.values::<T>()[self.row]
+    }
+}
+
+/// A shared reference to a resource, holding its read lock
+pub struct Res<'w, R: Component> {
+    guard: RwLockReadGuard<'w, Box<dyn Any + Send + Sync>>,
+    marker: PhantomData<&'w R>,
+}
+
+impl<R: Component> Deref for Res<'_, R> {
+    type Target = R;
+
+    #[inline(always)]
+    fn deref(&self) -> &R {
+        self.guard.downcast_ref::<R>().unwrap()
+    }
+}
+
+/// A mutable reference to a resource, holding its write lock
+pub struct ResMut<'w, R: Component> {
+    guard: RwLockWriteGuard<'w, Box<dyn Any + Send + Sync>>,
+    marker: PhantomData<&'w mut R>,
+}
+
+impl<R: Component> Deref for ResMut<'_, R> {
+    type Target = R;
+
+    #[inline(always)]
+    fn deref(&self) -> &R {
+        self.guard.downcast_ref::<R>().unwrap()
+    }
+}
+
+impl<R: Component> DerefMut for ResMut<'_, R> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut R {
+        self.guard.downcast_mut::<R>().unwrap()
+    }
+}
@@ -0,0 +1,279 @@
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// An axis aligned bounding box in two dimensions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb2 {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+/// An axis aligned bounding box in three dimensions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb3 {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb2 {
+    /// a box containing nothing, which is the identity for [`Aabb2::union`]
+    pub const EMPTY: Aabb2 = Aabb2::new(Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY));
+
+    /// construct a new [`Aabb2`] from its corners
+    #[inline(always)]
+    pub const fn new(min: Vec2, max: Vec2) -> Aabb2 {
+        Aabb2 { min, max }
+    }
+
+    /// construct a new [`Aabb2`] from its center and half of its size
+    #[inline(always)]
+    pub fn from_center_half_extents(center: Vec2, half_extents: Vec2) -> Aabb2 {
+        Aabb2::new(center - half_extents, center + half_extents)
+    }
+
+    /// the smallest box containing all `points`, or [`Aabb2::EMPTY`] if there are none
+    pub fn from_points(points: &[Vec2]) -> Aabb2 {
+        points.iter().fold(Aabb2::EMPTY, |aabb, p| aabb.include(*p))
+    }
+
+    /// whether the box contains nothing, as [`Aabb2::EMPTY`] does
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y
+    }
+
+    #[inline(always)]
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    #[inline(always)]
+    pub fn half_extents(&self) -> Vec2 {
+        (self.max - self.min) * 0.5
+    }
+
+    #[inline(always)]
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    #[inline(always)]
+    pub fn area(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y
+    }
+
+    #[inline(always)]
+    pub fn perimeter(&self) -> f32 {
+        let size = self.size();
+        2.0 * (size.x + size.y)
+    }
+
+    /// the smallest box containing both boxes
+    #[inline(always)]
+    pub fn union(self, other: Aabb2) -> Aabb2 {
+        Aabb2::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// the smallest box containing both this box and `point`
+    #[inline(always)]
+    pub fn include(self, point: Vec2) -> Aabb2 {
+        Aabb2::new(self.min.min(point), self.max.max(point))
+    }
+
+    /// the box grown by `margin` on every side
+    #[inline(always)]
+    pub fn grow(self, margin: f32) -> Aabb2 {
+        Aabb2::new(self.min - Vec2::splat(margin), self.max + Vec2::splat(margin))
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    /// whether `other` is entirely inside this box
+    #[inline(always)]
+    pub fn contains_aabb(&self, other: &Aabb2) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// whether the boxes overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_aabb(&self, other: &Aabb2) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x
+            && self.min.y <= other.max.y && self.max.y >= other.min.y
+    }
+
+    /// the closest point in the box to `point`, which is `point` itself if it's inside
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        point.max(self.min).min(self.max)
+    }
+
+    #[inline(always)]
+    pub fn distance_squared_to_point(&self, point: Vec2) -> f32 {
+        (self.closest_point(point) - point).magnitude_squared()
+    }
+
+    /// the distance from `point` to the box, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        (self.closest_point(point) - point).magnitude()
+    }
+
+    /// the smallest box containing this box transformed by a 3x3 affine matrix,
+    /// an empty box stays empty
+    pub fn transform(&self, m: &Matrix<f32>) -> Aabb2 {
+        if self.is_empty() {
+            return Aabb2::EMPTY
+        }
+        let affine = Affine2::from_matrix(m);
+        let center = affine.transform_point2(self.center());
+        let half = self.half_extents();
+        let half = affine.x_axis.abs() * half.x + affine.y_axis.abs() * half.y;
+        Aabb2::from_center_half_extents(center, half)
+    }
+}
+
+impl Aabb3 {
+    /// a box containing nothing, which is the identity for [`Aabb3::union`]
+    pub const EMPTY: Aabb3 = Aabb3::new(Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY));
+
+    /// construct a new [`Aabb3`] from its corners
+    #[inline(always)]
+    pub const fn new(min: Vec3, max: Vec3) -> Aabb3 {
+        Aabb3 { min, max }
+    }
+
+    /// construct a new [`Aabb3`] from its center and half of its size
+    #[inline(always)]
+    pub fn from_center_half_extents(center: Vec3, half_extents: Vec3) -> Aabb3 {
+        Aabb3::new(center - half_extents, center + half_extents)
+    }
+
+    /// the smallest box containing all `points`, or [`Aabb3::EMPTY`] if there are none
+    pub fn from_points(points: &[Vec3]) -> Aabb3 {
+        points.iter().fold(Aabb3::EMPTY, |aabb, p| aabb.include(*p))
+    }
+
+    /// whether the box contains nothing, as [`Aabb3::EMPTY`] does
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    #[inline(always)]
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    #[inline(always)]
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    #[inline(always)]
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    #[inline(always)]
+    pub fn volume(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+
+    #[inline(always)]
+    pub fn surface_area(&self) -> f32 {
+        let size = self.size();
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    /// the index of the axis the box is longest along
+    #[inline(always)]
+    pub fn longest_axis(&self) -> usize {
+        let size = self.size();
+        if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// the smallest box containing both boxes
+    #[inline(always)]
+    pub fn union(self, other: Aabb3) -> Aabb3 {
+        Aabb3::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// the smallest box containing both this box and `point`
+    #[inline(always)]
+    pub fn include(self, point: Vec3) -> Aabb3 {
+        Aabb3::new(self.min.min(point), self.max.max(point))
+    }
+
+    /// the box grown by `margin` on every side
+    #[inline(always)]
+    pub fn grow(self, margin: f32) -> Aabb3 {
+        Aabb3::new(self.min - Vec3::splat(margin), self.max + Vec3::splat(margin))
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+            && point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    /// whether `other` is entirely inside this box
+    #[inline(always)]
+    pub fn contains_aabb(&self, other: &Aabb3) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// whether the boxes overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_aabb(&self, other: &Aabb3) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x
+            && self.min.y <= other.max.y && self.max.y >= other.min.y
+            && self.min.z <= other.max.z && self.max.z >= other.min.z
+    }
+
+    /// the closest point in the box to `point`, which is `point` itself if it's inside
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point.max(self.min).min(self.max)
+    }
+
+    #[inline(always)]
+    pub fn distance_squared_to_point(&self, point: Vec3) -> f32 {
+        (self.closest_point(point) - point).magnitude_squared()
+    }
+
+    /// the distance from `point` to the box, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        (self.closest_point(point) - point).magnitude()
+    }
+
+    /// the smallest box containing this box transformed by a 4x4 affine matrix,
+    /// an empty box stays empty
+    pub fn transform(&self, m: &Matrix<f32>) -> Aabb3 {
+        if self.is_empty() {
+            return Aabb3::EMPTY
+        }
+        let affine = Affine3::from_matrix(m);
+        let center = affine.transform_point3(self.center());
+        let half = self.half_extents();
+        let half = affine.x_axis.abs() * half.x + affine.y_axis.abs() * half.y + affine.z_axis.abs() * half.z;
+        Aabb3::from_center_half_extents(center, half)
+    }
+}
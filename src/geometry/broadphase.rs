@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use rayon::prelude::*;
+use crate::geometry::aabb::{Aabb2, Aabb3};
+
+/// A bounding box the broadphases can sort and hash, implemented for [`Aabb2`] and [`Aabb3`]
+pub trait Bounds: Copy + Send + Sync {
+    /// the integer coordinates of a grid cell
+    type Cell: Copy + Eq + Hash + Send + Sync;
+
+    /// how many axes the box has
+    const AXES: usize;
+
+    fn lower(&self, axis: usize) -> f32;
+    fn upper(&self, axis: usize) -> f32;
+
+    /// whether the boxes overlap, touching counts
+    fn overlaps(&self, other: &Self) -> bool;
+
+    /// the cell holding the lower corner of where the boxes overlap, which should be checked
+    /// with [`Bounds::overlaps`] first
+    fn overlap_cell(&self, other: &Self, cell_size: f32) -> Self::Cell;
+
+    /// call `visit` for each cell the box touches
+    fn for_each_cell<F: FnMut(Self::Cell)>(&self, cell_size: f32, visit: F);
+
+    /// the middle of the box along `axis`
+    #[inline(always)]
+    fn center(&self, axis: usize) -> f32 {
+        (self.lower(axis) + self.upper(axis)) * 0.5
+    }
+}
+
+#[inline(always)]
+fn cell_coordinate(v: f32, cell_size: f32) -> i32 {
+    (v / cell_size).floor() as i32
+}
+
+impl Bounds for Aabb2 {
+    type Cell = [i32; 2];
+    const AXES: usize = 2;
+
+    #[inline(always)]
+    fn lower(&self, axis: usize) -> f32 {
+        self.min[axis]
+    }
+
+    #[inline(always)]
+    fn upper(&self, axis: usize) -> f32 {
+        self.max[axis]
+    }
+
+    #[inline(always)]
+    fn overlaps(&self, other: &Aabb2) -> bool {
+        self.intersects_aabb(other)
+    }
+
+    fn overlap_cell(&self, other: &Aabb2, cell_size: f32) -> [i32; 2] {
+        let corner = self.min.max(other.min);
+        [cell_coordinate(corner.x, cell_size), cell_coordinate(corner.y, cell_size)]
+    }
+
+    fn for_each_cell<F: FnMut([i32; 2])>(&self, cell_size: f32, mut visit: F) {
+        let (lo, hi) = (self.min, self.max);
+        for y in cell_coordinate(lo.y, cell_size)..=cell_coordinate(hi.y, cell_size) {
+            for x in cell_coordinate(lo.x, cell_size)..=cell_coordinate(hi.x, cell_size) {
+                visit([x, y]);
+            }
+        }
+    }
+}
+
+impl Bounds for Aabb3 {
+    type Cell = [i32; 3];
+    const AXES: usize = 3;
+
+    #[inline(always)]
+    fn lower(&self, axis: usize) -> f32 {
+        self.min[axis]
+    }
+
+    #[inline(always)]
+    fn upper(&self, axis: usize) -> f32 {
+        self.max[axis]
+    }
+
+    #[inline(always)]
+    fn overlaps(&self, other: &Aabb3) -> bool {
+        self.intersects_aabb(other)
+    }
+
+    fn overlap_cell(&self, other: &Aabb3, cell_size: f32) -> [i32; 3] {
+        let corner = self.min.max(other.min);
+        [
+            cell_coordinate(corner.x, cell_size),
+            cell_coordinate(corner.y, cell_size),
+            cell_coordinate(corner.z, cell_size),
+        ]
+    }
+
+    fn for_each_cell<F: FnMut([i32; 3])>(&self, cell_size: f32, mut visit: F) {
+        let (lo, hi) = (self.min, self.max);
+        for z in cell_coordinate(lo.z, cell_size)..=cell_coordinate(hi.z, cell_size) {
+            for y in cell_coordinate(lo.y, cell_size)..=cell_coordinate(hi.y, cell_size) {
+                for x in cell_coordinate(lo.x, cell_size)..=cell_coordinate(hi.x, cell_size) {
+                    visit([x, y, z]);
+                }
+            }
+        }
+    }
+}
+
+/// A handle to an object in a broadphase.
+///
+/// handles of removed objects go stale, and their slot is reused with a new generation.
+/// pairs are ordered by handle, which is the order objects were first given their slot in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    /// the slot of the object, which is below the number of objects ever alive at once
+    #[inline(always)]
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Slot<B> {
+    aabb: B,
+    generation: u32,
+    alive: bool,
+}
+
+/// the objects of a broadphase, in slots reused after removal
+#[derive(Clone, Debug)]
+struct Slots<B> {
+    slots: Vec<Slot<B>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<B: Bounds> Slots<B> {
+    fn new() -> Slots<B> {
+        Slots { slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+
+    fn insert(&mut self, aabb: B) -> Handle {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.generation += 1;
+                slot.aabb = aabb;
+                slot.alive = true;
+                Handle { index, generation: slot.generation }
+            }
+            None => {
+                self.slots.push(Slot { aabb, generation: 0, alive: true });
+                Handle { index: self.slots.len() as u32 - 1, generation: 0 }
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn contains(&self, handle: Handle) -> bool {
+        self.slots.get(handle.index()).is_some_and(|s| s.alive && s.generation == handle.generation)
+    }
+
+    #[inline(always)]
+    fn get(&self, handle: Handle) -> Option<B> {
+        self.contains(handle).then(|| self.slots[handle.index()].aabb)
+    }
+
+    #[inline(always)]
+    fn handle(&self, index: u32) -> Handle {
+        Handle { index, generation: self.slots[index as usize].generation }
+    }
+
+    fn remove(&mut self, handle: Handle) -> Option<B> {
+        if !self.contains(handle) {
+            return None
+        }
+        self.len -= 1;
+        self.free.push(handle.index);
+        let slot = &mut self.slots[handle.index()];
+        slot.alive = false;
+        Some(slot.aabb)
+    }
+}
+
+#[inline(always)]
+fn ordered(a: Handle, b: Handle) -> (Handle, Handle) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// A uniform grid hashed by cell, for objects of roughly similar size.
+///
+/// each object is listed in every cell it touches, so `cell_size` should be around the size of
+/// a typical object: much smaller and large objects fill many cells, much larger and each cell
+/// holds many objects that don't overlap.
+#[derive(Clone, Debug)]
+pub struct SpatialHash<B: Bounds> {
+    cell_size: f32,
+    objects: Slots<B>,
+    cells: HashMap<B::Cell, Vec<u32>>,
+}
+
+impl<B: Bounds> SpatialHash<B> {
+    pub fn new(cell_size: f32) -> SpatialHash<B> {
+        assert!(cell_size > 0.0, "cell size must be positive");
+        SpatialHash { cell_size, objects: Slots::new(), cells: HashMap::new() }
+    }
+
+    #[inline(always)]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.objects.len
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.objects.len == 0
+    }
+
+    /// the box of the object, none if the handle is stale
+    #[inline(always)]
+    pub fn get(&self, handle: Handle) -> Option<B> {
+        self.objects.get(handle)
+    }
+
+    pub fn insert(&mut self, aabb: B) -> Handle {
+        let handle = self.objects.insert(aabb);
+        self.link(handle.index, &aabb);
+        handle
+    }
+
+    /// move an object, which only touches the grid if it changed cells.
+    /// returns false if the handle is stale
+    pub fn update(&mut self, handle: Handle, aabb: B) -> bool {
+        let Some(old) = self.objects.get(handle) else { return false };
+        self.objects.slots[handle.index()].aabb = aabb;
+
+        let size = self.cell_size;
+        let same = |axis: usize| {
+            cell_coordinate(old.lower(axis), size) == cell_coordinate(aabb.lower(axis), size)
+                && cell_coordinate(old.upper(axis), size) == cell_coordinate(aabb.upper(axis), size)
+        };
+        if !(0..B::AXES).all(same) {
+            self.unlink(handle.index, &old);
+            self.link(handle.index, &aabb);
+        }
+        true
+    }
+
+    /// returns the object's box, none if the handle is stale
+    pub fn remove(&mut self, handle: Handle) -> Option<B> {
+        let aabb = self.objects.remove(handle)?;
+        self.unlink(handle.index, &aabb);
+        Some(aabb)
+    }
+
+    fn link(&mut self, index: u32, aabb: &B) {
+        aabb.for_each_cell(self.cell_size, |cell| self.cells.entry(cell).or_default().push(index));
+    }
+
+    fn unlink(&mut self, index: u32, aabb: &B) {
+        aabb.for_each_cell(self.cell_size, |cell| {
+            let Some(list) = self.cells.get_mut(&cell) else { return };
+            if let Some(i) = list.iter().position(|j| *j == index) {
+                list.swap_remove(i);
+            }
+            if list.is_empty() {
+                self.cells.remove(&cell);
+            }
+        });
+    }
+
+    /// the overlapping pairs in one cell, each found only in the cell where its overlap starts
+    fn cell_pairs(&self, cell: &B::Cell, list: &[u32], pairs: &mut Vec<(Handle, Handle)>) {
+        let slots = &self.objects.slots;
+        for (n, &i) in list.iter().enumerate() {
+            let a = &slots[i as usize].aabb;
+            for &j in &list[n + 1..] {
+                let b = &slots[j as usize].aabb;
+                if a.overlaps(b) && a.overlap_cell(b, self.cell_size) == *cell {
+                    pairs.push(ordered(self.objects.handle(i), self.objects.handle(j)));
+                }
+            }
+        }
+    }
+
+    /// every pair of overlapping objects, each once with the lower handle first, sorted
+    pub fn pairs(&self) -> Vec<(Handle, Handle)> {
+        let mut pairs = Vec::new();
+        for (cell, list) in &self.cells {
+            self.cell_pairs(cell, list, &mut pairs);
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// the same as [`SpatialHash::pairs`], with the cells split between threads
+    pub fn pairs_parallel(&self) -> Vec<(Handle, Handle)> {
+        let mut pairs: Vec<(Handle, Handle)> = self.cells.par_iter()
+            .flat_map_iter(|(cell, list)| {
+                let mut pairs = Vec::new();
+                self.cell_pairs(cell, list, &mut pairs);
+                pairs
+            })
+            .collect();
+        pairs.par_sort_unstable();
+        pairs
+    }
+
+    /// call `visit` once for each object overlapping `region`, in no particular order.
+    ///
+    /// this walks every cell `region` touches, so huge regions are better served by a loop
+    /// over all the objects.
+    pub fn query<F: FnMut(Handle)>(&self, region: &B, mut visit: F) {
+        region.for_each_cell(self.cell_size, |cell| {
+            let Some(list) = self.cells.get(&cell) else { return };
+            for &i in list {
+                let aabb = &self.objects.slots[i as usize].aabb;
+                if aabb.overlaps(region) && aabb.overlap_cell(region, self.cell_size) == cell {
+                    visit(self.objects.handle(i));
+                }
+            }
+        });
+    }
+}
+
+/// Sweep and prune along a single axis, for objects of any size that move a little each frame.
+///
+/// the objects are kept sorted by their lower bound on the axis where their centers spread
+/// out the most, and resorted lazily with an insertion sort, which is close to linear when
+/// little has changed since the last sort.
+#[derive(Clone, Debug)]
+pub struct SweepAndPrune<B: Bounds> {
+    objects: Slots<B>,
+    /// lower bounds on `axis` and the objects they belong to, possibly with stale handles
+    order: Vec<(f32, Handle)>,
+    axis: usize,
+    dirty: bool,
+}
+
+impl<B: Bounds> Default for SweepAndPrune<B> {
+    fn default() -> SweepAndPrune<B> {
+        SweepAndPrune::new()
+    }
+}
+
+impl<B: Bounds> SweepAndPrune<B> {
+    pub fn new() -> SweepAndPrune<B> {
+        SweepAndPrune { objects: Slots::new(), order: Vec::new(), axis: 0, dirty: false }
+    }
+
+    /// the axis currently being swept along
+    #[inline(always)]
+    pub fn axis(&self) -> usize {
+        self.axis
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.objects.len
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.objects.len == 0
+    }
+
+    /// the box of the object, none if the handle is stale
+    #[inline(always)]
+    pub fn get(&self, handle: Handle) -> Option<B> {
+        self.objects.get(handle)
+    }
+
+    pub fn insert(&mut self, aabb: B) -> Handle {
+        let handle = self.objects.insert(aabb);
+        self.order.push((aabb.lower(self.axis), handle));
+        self.dirty = true;
+        handle
+    }
+
+    /// move an object, returning false if the handle is stale
+    pub fn update(&mut self, handle: Handle, aabb: B) -> bool {
+        if !self.objects.contains(handle) {
+            return false
+        }
+        self.objects.slots[handle.index()].aabb = aabb;
+        self.dirty = true;
+        true
+    }
+
+    /// returns the object's box, none if the handle is stale
+    pub fn remove(&mut self, handle: Handle) -> Option<B> {
+        let aabb = self.objects.remove(handle)?;
+        self.dirty = true;
+        Some(aabb)
+    }
+
+    /// bring the sorted order up to date, switching axis if another spreads the objects out more
+    fn sort(&mut self) {
+        if !self.dirty {
+            return
+        }
+        self.dirty = false;
+        let objects = &self.objects;
+        self.order.retain(|(_, handle)| objects.contains(*handle));
+
+        let n = self.order.len().max(1) as f32;
+        let variance = |axis: usize| {
+            let centers = || self.order.iter().map(|(_, h)| objects.slots[h.index()].aabb.center(axis));
+            let mean = centers().sum::<f32>() / n;
+            centers().map(|c| (c - mean) * (c - mean)).sum::<f32>() / n
+        };
+        let best = (0..B::AXES).max_by(|a, b| variance(*a).total_cmp(&variance(*b))).unwrap_or(0);
+        // only switch when it's clearly better, since switching costs a full sort
+        let switch = best != self.axis && variance(best) > variance(self.axis) * 1.5;
+        if switch {
+            self.axis = best;
+        }
+
+        let axis = self.axis;
+        for entry in &mut self.order {
+            entry.0 = objects.slots[entry.1.index()].aabb.lower(axis);
+        }
+        if switch {
+            self.order.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+        } else {
+            for i in 1..self.order.len() {
+                let mut j = i;
+                while j > 0 && self.order[j - 1].0 > self.order[j].0 {
+                    self.order.swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+        }
+    }
+
+    /// the overlapping pairs starting from the `i`th object in sorted order
+    fn pairs_from(&self, i: usize, pairs: &mut Vec<(Handle, Handle)>) {
+        let (_, a) = self.order[i];
+        let aabb = &self.objects.slots[a.index()].aabb;
+        let upper = aabb.upper(self.axis);
+        for &(lower, b) in &self.order[i + 1..] {
+            if lower > upper {
+                break
+            }
+            if aabb.overlaps(&self.objects.slots[b.index()].aabb) {
+                pairs.push(ordered(a, b));
+            }
+        }
+    }
+
+    /// every pair of overlapping objects, each once with the lower handle first, sorted
+    pub fn pairs(&mut self) -> Vec<(Handle, Handle)> {
+        self.sort();
+        let mut pairs = Vec::new();
+        for i in 0..self.order.len() {
+            self.pairs_from(i, &mut pairs);
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// the same as [`SweepAndPrune::pairs`], with the sweep split between threads
+    pub fn pairs_parallel(&mut self) -> Vec<(Handle, Handle)> {
+        self.sort();
+        let mut pairs: Vec<(Handle, Handle)> = (0..self.order.len()).into_par_iter()
+            .flat_map_iter(|i| {
+                let mut pairs = Vec::new();
+                self.pairs_from(i, &mut pairs);
+                pairs
+            })
+            .collect();
+        pairs.par_sort_unstable();
+        pairs
+    }
+
+    /// call `visit` once for each object overlapping `region`, in sorted order along the axis
+    pub fn query<F: FnMut(Handle)>(&mut self, region: &B, mut visit: F) {
+        self.sort();
+        let upper = region.upper(self.axis);
+        for &(lower, handle) in &self.order {
+            if lower > upper {
+                break
+            }
+            if self.objects.slots[handle.index()].aabb.overlaps(region) {
+                visit(handle);
+            }
+        }
+    }
+}
@@ -0,0 +1,498 @@
+use rayon::prelude::*;
+use crate::geometry::aabb::Aabb3;
+use crate::geometry::capsule::Capsule3;
+use crate::geometry::obb::Obb3;
+use crate::geometry::ray::{Ray3, RayHit3};
+use crate::geometry::segment::Segment3;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::triangle::Triangle3;
+use crate::math::vec3::Vec3;
+
+/// Anything with an axis aligned bounding box, which can be put in a [`Bvh`]
+pub trait Bounded {
+    fn aabb(&self) -> Aabb3;
+}
+
+impl Bounded for Aabb3 {
+    fn aabb(&self) -> Aabb3 {
+        *self
+    }
+}
+
+impl Bounded for Vec3 {
+    fn aabb(&self) -> Aabb3 {
+        Aabb3::new(*self, *self)
+    }
+}
+
+impl Bounded for Sphere {
+    fn aabb(&self) -> Aabb3 {
+        Sphere::aabb(self)
+    }
+}
+
+impl Bounded for Triangle3 {
+    fn aabb(&self) -> Aabb3 {
+        Triangle3::aabb(self)
+    }
+}
+
+impl Bounded for Segment3 {
+    fn aabb(&self) -> Aabb3 {
+        Segment3::aabb(self)
+    }
+}
+
+impl Bounded for Capsule3 {
+    fn aabb(&self) -> Aabb3 {
+        Capsule3::aabb(self)
+    }
+}
+
+impl Bounded for Obb3 {
+    fn aabb(&self) -> Aabb3 {
+        Obb3::aabb(self)
+    }
+}
+
+/// the most primitives a leaf holds when splitting would cost more by the sah
+const MAX_LEAF_SIZE: usize = 8;
+
+/// the number of buckets centroids are sorted into when looking for a split
+const SAH_BINS: usize = 16;
+
+/// the cost of visiting a node relative to testing one primitive
+const TRAVERSAL_COST: f32 = 1.0;
+
+/// subtrees with more primitives than this are built on another thread by [`Bvh::build_parallel`]
+const PARALLEL_THRESHOLD: usize = 4096;
+
+const NO_PARENT: u32 = u32::MAX;
+
+#[derive(Clone, Copy, Debug)]
+struct BvhNode {
+    aabb: Aabb3,
+    /// the first of the two children for inner nodes, or the first primitive for leaves
+    first: u32,
+    /// the number of primitives, zero for inner nodes
+    count: u32,
+}
+
+impl BvhNode {
+    #[inline(always)]
+    fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+/// A bounding volume hierarchy over indices into a slice of primitives, built with the
+/// surface area heuristic.
+///
+/// the tree only knows the bounds of each primitive, so queries take a closure
+/// which does the exact test against the primitive with a given index.
+#[derive(Clone, Debug)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    /// primitive indices in leaf order
+    indices: Vec<u32>,
+    parents: Vec<u32>,
+    /// the leaf each primitive is in
+    leaves: Vec<u32>,
+}
+
+#[derive(Clone, Copy)]
+struct BuildItem {
+    index: u32,
+    centroid: Vec3,
+    aabb: Aabb3,
+}
+
+impl BuildItem {
+    fn new<P: Bounded>(index: usize, primitive: &P) -> BuildItem {
+        let aabb = primitive.aabb();
+        BuildItem { index: index as u32, centroid: aabb.center(), aabb }
+    }
+}
+
+enum BuildNode {
+    Leaf { aabb: Aabb3, start: usize, count: usize },
+    Inner { aabb: Aabb3, children: Box<(BuildNode, BuildNode)> },
+}
+
+/// the axis and position to split at, or none if a leaf is cheaper
+fn find_split(items: &[BuildItem], aabb: &Aabb3) -> Option<(usize, f32)> {
+    let centroids = items.iter().fold(Aabb3::EMPTY, |b, item| b.include(item.centroid));
+    let leaf_cost = items.len() as f32;
+    let area = aabb.surface_area().max(f32::MIN_POSITIVE);
+    let mut best: Option<(usize, f32, f32)> = None;
+
+    for axis in 0..3 {
+        let (lo, hi) = (centroids.min[axis], centroids.max[axis]);
+        if hi - lo <= 0.0 {
+            continue
+        }
+        let scale = SAH_BINS as f32 / (hi - lo);
+        let bin = |c: Vec3| (((c[axis] - lo) * scale) as usize).min(SAH_BINS - 1);
+
+        let mut bounds = [Aabb3::EMPTY; SAH_BINS];
+        let mut counts = [0usize; SAH_BINS];
+        for item in items {
+            let b = bin(item.centroid);
+            bounds[b] = bounds[b].union(item.aabb);
+            counts[b] += 1;
+        }
+
+        // sweep from the right to get the cost of everything after each split
+        let mut right_area = [0.0; SAH_BINS];
+        let mut right_count = [0usize; SAH_BINS];
+        let (mut acc, mut n) = (Aabb3::EMPTY, 0);
+        for i in (1..SAH_BINS).rev() {
+            acc = acc.union(bounds[i]);
+            n += counts[i];
+            right_area[i] = if n > 0 { acc.surface_area() } else { 0.0 };
+            right_count[i] = n;
+        }
+
+        let (mut acc, mut n) = (Aabb3::EMPTY, 0);
+        for i in 0..SAH_BINS - 1 {
+            acc = acc.union(bounds[i]);
+            n += counts[i];
+            if n == 0 || right_count[i + 1] == 0 {
+                continue
+            }
+            let cost = TRAVERSAL_COST
+                + (acc.surface_area() * n as f32 + right_area[i + 1] * right_count[i + 1] as f32) / area;
+            if best.is_none_or(|(_, _, c)| cost < c) {
+                best = Some((axis, lo + (i + 1) as f32 / scale, cost));
+            }
+        }
+    }
+
+    match best {
+        Some((axis, position, cost)) if cost < leaf_cost || items.len() > MAX_LEAF_SIZE => Some((axis, position)),
+        _ => None,
+    }
+}
+
+fn build_node(items: &mut [BuildItem], start: usize, parallel: bool) -> BuildNode {
+    let aabb = items.iter().fold(Aabb3::EMPTY, |b, item| b.union(item.aabb));
+    let leaf = BuildNode::Leaf { aabb, start, count: items.len() };
+    if items.len() <= 1 {
+        return leaf
+    }
+
+    let mid = match find_split(items, &aabb) {
+        Some((axis, position)) => partition(items, |item| item.centroid[axis] < position),
+        // every centroid is in the same place, but the leaf is too big
+        None if items.len() > MAX_LEAF_SIZE => items.len() / 2,
+        None => return leaf,
+    };
+    // the bins can round a split onto one side
+    let mid = if mid == 0 || mid == items.len() { items.len() / 2 } else { mid };
+
+    let (left, right) = items.split_at_mut(mid);
+    let children = if parallel && left.len() + right.len() > PARALLEL_THRESHOLD {
+        rayon::join(
+            || build_node(left, start, parallel),
+            || build_node(right, start + mid, parallel),
+        )
+    } else {
+        (build_node(left, start, parallel), build_node(right, start + mid, parallel))
+    };
+    BuildNode::Inner { aabb, children: Box::new(children) }
+}
+
+/// move the items matching `pred` to the front, returning how many there are
+fn partition(items: &mut [BuildItem], pred: impl Fn(&BuildItem) -> bool) -> usize {
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if pred(&items[i]) {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
+
+impl Bvh {
+    /// build a tree over the bounds of `primitives`
+    pub fn build<P: Bounded>(primitives: &[P]) -> Bvh {
+        let items = primitives.iter().enumerate().map(|(i, p)| BuildItem::new(i, p)).collect();
+        Bvh::build_items(items, false)
+    }
+
+    /// build a tree over the bounds of `primitives`, building large subtrees in parallel.
+    ///
+    /// the result is the same as [`Bvh::build`].
+    pub fn build_parallel<P: Bounded + Sync>(primitives: &[P]) -> Bvh {
+        let items = primitives.par_iter().enumerate().map(|(i, p)| BuildItem::new(i, p)).collect();
+        Bvh::build_items(items, true)
+    }
+
+    fn build_items(mut items: Vec<BuildItem>, parallel: bool) -> Bvh {
+        assert!(items.len() < u32::MAX as usize);
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(2 * items.len()),
+            indices: Vec::new(),
+            parents: Vec::with_capacity(2 * items.len()),
+            leaves: vec![0; items.len()],
+        };
+        if items.is_empty() {
+            return bvh
+        }
+
+        let root = build_node(&mut items, 0, parallel);
+        bvh.indices = items.iter().map(|item| item.index).collect();
+        bvh.push_node(NO_PARENT);
+        bvh.flatten(root, 0);
+        bvh
+    }
+
+    fn push_node(&mut self, parent: u32) {
+        self.nodes.push(BvhNode { aabb: Aabb3::EMPTY, first: 0, count: 0 });
+        self.parents.push(parent);
+    }
+
+    /// write a built node into `slot`, with children side by side after it
+    fn flatten(&mut self, node: BuildNode, slot: usize) {
+        match node {
+            BuildNode::Leaf { aabb, start, count } => {
+                self.nodes[slot] = BvhNode { aabb, first: start as u32, count: count as u32 };
+                for &index in &self.indices[start..start + count] {
+                    self.leaves[index as usize] = slot as u32;
+                }
+            }
+            BuildNode::Inner { aabb, children } => {
+                let first = self.nodes.len();
+                self.push_node(slot as u32);
+                self.push_node(slot as u32);
+                self.nodes[slot] = BvhNode { aabb, first: first as u32, count: 0 };
+                let (left, right) = *children;
+                self.flatten(left, first);
+                self.flatten(right, first + 1);
+            }
+        }
+    }
+
+    /// the number of primitives in the tree
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// the bounds of everything in the tree, or [`Aabb3::EMPTY`] if there's nothing
+    #[inline(always)]
+    pub fn bounds(&self) -> Aabb3 {
+        self.nodes.first().map_or(Aabb3::EMPTY, |root| root.aabb)
+    }
+
+    /// the primitives in a leaf
+    #[inline(always)]
+    fn leaf_indices(&self, node: &BvhNode) -> impl Iterator<Item = usize> + '_ {
+        let start = node.first as usize;
+        self.indices[start..start + node.count as usize].iter().map(|&i| i as usize)
+    }
+
+    /// recompute every node's bounds after the primitives have moved.
+    ///
+    /// the tree's structure is kept, so queries stay correct but get slower
+    /// the further things move from where they were when it was built.
+    pub fn refit<P: Bounded>(&mut self, primitives: &[P]) {
+        assert_eq!(primitives.len(), self.len());
+        // children are always after their parents
+        for i in (0..self.nodes.len()).rev() {
+            let node = self.nodes[i];
+            self.nodes[i].aabb = if node.is_leaf() {
+                self.leaf_indices(&node).fold(Aabb3::EMPTY, |b, p| b.union(primitives[p].aabb()))
+            } else {
+                let first = node.first as usize;
+                self.nodes[first].aabb.union(self.nodes[first + 1].aabb)
+            };
+        }
+    }
+
+    /// grow the bounds on the path from one primitive's leaf to the root, after it has moved.
+    ///
+    /// bounds only ever grow here, so this is cheap for a few moving objects,
+    /// and a full [`Bvh::refit`] shrinks them again.
+    pub fn update(&mut self, index: usize, aabb: Aabb3) {
+        let mut node = self.leaves[index];
+        while node != NO_PARENT {
+            let bounds = &mut self.nodes[node as usize].aabb;
+            if bounds.contains_aabb(&aabb) {
+                break
+            }
+            *bounds = bounds.union(aabb);
+            node = self.parents[node as usize];
+        }
+    }
+
+    /// the closest hit along `ray` no further than `max_t`, and the primitive hit.
+    ///
+    /// `hit` tests the primitive with the given index against the ray.
+    pub fn ray_cast<F>(&self, ray: &Ray3, max_t: f32, hit: F) -> Option<(usize, RayHit3)>
+    where
+        F: Fn(usize) -> Option<RayHit3>,
+    {
+        let mut best: Option<(usize, RayHit3)> = None;
+        let mut max_t = max_t;
+        self.traverse_ray(ray, max_t, |index| {
+            if let Some(h) = hit(index).filter(|h| h.t <= max_t) {
+                max_t = h.t;
+                best = Some((index, h));
+            }
+            (false, max_t)
+        });
+        best
+    }
+
+    /// whether anything is hit along `ray` no further than `max_t`, stopping at the first hit found.
+    ///
+    /// cheaper than [`Bvh::ray_cast`] for visibility tests, where the closest hit doesn't matter.
+    pub fn ray_cast_any<F>(&self, ray: &Ray3, max_t: f32, hit: F) -> Option<usize>
+    where
+        F: Fn(usize) -> Option<RayHit3>,
+    {
+        let mut found = None;
+        self.traverse_ray(ray, max_t, |index| {
+            if hit(index).is_some_and(|h| h.t <= max_t) {
+                found = Some(index);
+                return (true, max_t)
+            }
+            (false, max_t)
+        });
+        found
+    }
+
+    /// visit leaves along a ray front to back, `visit` returns whether to stop and the new max t
+    fn traverse_ray<F>(&self, ray: &Ray3, max_t: f32, mut visit: F)
+    where
+        F: FnMut(usize) -> (bool, f32),
+    {
+        if self.nodes.is_empty() {
+            return
+        }
+        let inv = Vec3(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+        let mut max_t = max_t;
+        let mut stack = vec![(0u32, 0.0f32)];
+
+        while let Some((node, near)) = stack.pop() {
+            if near > max_t {
+                continue
+            }
+            let node = &self.nodes[node as usize];
+            if node.is_leaf() {
+                for index in self.leaf_indices(node) {
+                    let (stop, t) = visit(index);
+                    if stop {
+                        return
+                    }
+                    max_t = t;
+                }
+                continue
+            }
+
+            let (a, b) = (node.first, node.first + 1);
+            let ta = slab(&self.nodes[a as usize].aabb, ray.origin, inv, max_t);
+            let tb = slab(&self.nodes[b as usize].aabb, ray.origin, inv, max_t);
+            // push the farther child first so the nearer is visited first
+            match (ta, tb) {
+                (Some(ta), Some(tb)) if ta <= tb => stack.extend([(b, tb), (a, ta)]),
+                (Some(ta), Some(tb)) => stack.extend([(a, ta), (b, tb)]),
+                (Some(ta), None) => stack.push((a, ta)),
+                (None, Some(tb)) => stack.push((b, tb)),
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// visit every primitive whose bounds overlap `aabb`
+    pub fn query_aabb<F: FnMut(usize)>(&self, aabb: &Aabb3, visit: F) {
+        self.query(|bounds| bounds.intersects_aabb(aabb), visit);
+    }
+
+    /// visit every primitive whose bounds overlap `sphere`
+    pub fn query_sphere<F: FnMut(usize)>(&self, sphere: &Sphere, visit: F) {
+        self.query(|bounds| sphere.intersects_aabb(bounds), visit);
+    }
+
+    fn query<O, F>(&self, overlaps: O, mut visit: F)
+    where
+        O: Fn(&Aabb3) -> bool,
+        F: FnMut(usize),
+    {
+        if self.nodes.is_empty() {
+            return
+        }
+        let mut stack = vec![0u32];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node as usize];
+            if !overlaps(&node.aabb) {
+                continue
+            }
+            if node.is_leaf() {
+                self.leaf_indices(node).for_each(&mut visit);
+            } else {
+                stack.extend([node.first, node.first + 1]);
+            }
+        }
+    }
+
+    /// the primitive closest to `point` and its distance.
+    ///
+    /// `distance` gives the exact distance from `point` to the primitive with the given index,
+    /// which must be at least the distance to its bounds.
+    pub fn nearest<F>(&self, point: Vec3, distance: F) -> Option<(usize, f32)>
+    where
+        F: Fn(usize) -> f32,
+    {
+        if self.nodes.is_empty() {
+            return None
+        }
+        let mut best: Option<(usize, f32)> = None;
+        let mut best_sq = f32::INFINITY;
+        let mut stack = vec![(0u32, self.nodes[0].aabb.distance_squared_to_point(point))];
+
+        while let Some((node, d)) = stack.pop() {
+            if d > best_sq {
+                continue
+            }
+            let node = &self.nodes[node as usize];
+            if node.is_leaf() {
+                for index in self.leaf_indices(node) {
+                    let d = distance(index);
+                    if d * d < best_sq || best.is_none() {
+                        best_sq = d * d;
+                        best = Some((index, d));
+                    }
+                }
+                continue
+            }
+
+            let (a, b) = (node.first, node.first + 1);
+            let da = self.nodes[a as usize].aabb.distance_squared_to_point(point);
+            let db = self.nodes[b as usize].aabb.distance_squared_to_point(point);
+            if da <= db {
+                stack.extend([(b, db), (a, da)]);
+            } else {
+                stack.extend([(a, da), (b, db)]);
+            }
+        }
+        best
+    }
+}
+
+/// the distance along a ray to where it enters `aabb`, none if it misses or is further than `max_t`
+#[inline(always)]
+fn slab(aabb: &Aabb3, origin: Vec3, inv: Vec3, max_t: f32) -> Option<f32> {
+    let t1 = (aabb.min - origin) * inv;
+    let t2 = (aabb.max - origin) * inv;
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element().min(max_t);
+    (near <= far).then_some(near)
+}
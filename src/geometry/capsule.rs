@@ -0,0 +1,128 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::segment::{Segment2, Segment3};
+use crate::geometry::sphere::{Circle, Sphere};
+use crate::geometry::EPSILON;
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// A solid capsule in two dimensions, the points within `radius` of a segment
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capsule2 {
+    pub segment: Segment2,
+    pub radius: f32,
+}
+
+/// A solid capsule in three dimensions, the points within `radius` of a segment
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capsule3 {
+    pub segment: Segment3,
+    pub radius: f32,
+}
+
+impl Capsule2 {
+    #[inline(always)]
+    pub const fn new(a: Vec2, b: Vec2, radius: f32) -> Capsule2 {
+        Capsule2 { segment: Segment2::new(a, b), radius }
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb2 {
+        self.segment.aabb().grow(self.radius)
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        (self.segment.closest_point(point) - point).magnitude_squared() <= self.radius * self.radius
+    }
+
+    /// the closest point in the capsule to `point`, which is `point` itself if it's inside
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        Circle::new(self.segment.closest_point(point), self.radius).closest_point(point)
+    }
+
+    /// the distance from `point` to the capsule, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        (self.segment.distance_to_point(point) - self.radius).max(0.0)
+    }
+
+    /// whether the capsule and circle overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_circle(&self, circle: &Circle) -> bool {
+        let r = self.radius + circle.radius;
+        (self.segment.closest_point(circle.center) - circle.center).magnitude_squared() <= r * r
+    }
+
+    /// whether the capsules overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_capsule(&self, other: &Capsule2) -> bool {
+        let (p, q) = self.segment.closest_points(&other.segment, EPSILON);
+        let r = self.radius + other.radius;
+        (p - q).magnitude_squared() <= r * r
+    }
+
+    /// the capsule transformed by a 3x3 affine matrix, the radius grows by the largest scale
+    pub fn transform(&self, m: &Matrix<f32>) -> Capsule2 {
+        let affine = Affine2::from_matrix(m);
+        let scale = affine.x_axis.magnitude().max(affine.y_axis.magnitude());
+        Capsule2 { segment: self.segment.transform(m), radius: self.radius * scale }
+    }
+}
+
+impl Capsule3 {
+    #[inline(always)]
+    pub const fn new(a: Vec3, b: Vec3, radius: f32) -> Capsule3 {
+        Capsule3 { segment: Segment3::new(a, b), radius }
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb3 {
+        self.segment.aabb().grow(self.radius)
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        (self.segment.closest_point(point) - point).magnitude_squared() <= self.radius * self.radius
+    }
+
+    /// the closest point in the capsule to `point`, which is `point` itself if it's inside
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        Sphere::new(self.segment.closest_point(point), self.radius).closest_point(point)
+    }
+
+    /// the distance from `point` to the capsule, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        (self.segment.distance_to_point(point) - self.radius).max(0.0)
+    }
+
+    /// whether the capsule and sphere overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        let r = self.radius + sphere.radius;
+        (self.segment.closest_point(sphere.center) - sphere.center).magnitude_squared() <= r * r
+    }
+
+    /// whether the capsules overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_capsule(&self, other: &Capsule3) -> bool {
+        let (p, q) = self.segment.closest_points(&other.segment, EPSILON);
+        let r = self.radius + other.radius;
+        (p - q).magnitude_squared() <= r * r
+    }
+
+    /// the capsule transformed by a 4x4 affine matrix, the radius grows by the largest scale
+    pub fn transform(&self, m: &Matrix<f32>) -> Capsule3 {
+        let affine = Affine3::from_matrix(m);
+        let scale = affine.x_axis.magnitude()
+            .max(affine.y_axis.magnitude())
+            .max(affine.z_axis.magnitude());
+        Capsule3 { segment: self.segment.transform(m), radius: self.radius * scale }
+    }
+}
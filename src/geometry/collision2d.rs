@@ -0,0 +1,634 @@
+use crate::geometry::aabb::Aabb2;
+use crate::geometry::capsule::Capsule2;
+use crate::geometry::obb::Obb2;
+use crate::geometry::segment::Segment2;
+use crate::geometry::sphere::Circle;
+use crate::geometry::triangle::Triangle2;
+use crate::geometry::EPSILON;
+use crate::math::affine::Affine2;
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+
+/// the most iterations gjk and epa take before settling for what they have
+const MAX_ITERATIONS: usize = 64;
+
+/// how close gjk and epa have to get before stopping, relative to the shapes' size
+const TOLERANCE: f32 = 1e-5;
+
+/// A convex shape described by its support function, the furthest point in a given direction
+pub trait Support2 {
+    /// the point of the shape furthest along `direction`, which need not be normalized
+    fn support(&self, direction: Vec2) -> Vec2;
+}
+
+/// A convex polygon, with vertices counterclockwise
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvexPolygon {
+    vertices: Vec<Vec2>,
+    /// the outward unit normal of the edge from each vertex to the next
+    normals: Vec<Vec2>,
+}
+
+impl ConvexPolygon {
+    /// the convex hull of `points`, which needs at least three points that aren't collinear
+    pub fn new(points: &[Vec2]) -> ConvexPolygon {
+        let vertices = convex_hull(points);
+        assert!(vertices.len() >= 3, "polygon is degenerate");
+        let normals = (0..vertices.len()).map(|i| {
+            let edge = vertices[(i + 1) % vertices.len()] - vertices[i];
+            Vec2(edge.y, -edge.x).normalize()
+        }).collect();
+        ConvexPolygon { vertices, normals }
+    }
+
+    /// a rectangle centered on the origin
+    pub fn rect(half_extents: Vec2) -> ConvexPolygon {
+        let h = half_extents;
+        ConvexPolygon::new(&[Vec2(-h.x, -h.y), Vec2(h.x, -h.y), Vec2(h.x, h.y), Vec2(-h.x, h.y)])
+    }
+
+    /// a regular polygon with `sides` corners on a circle of `radius` around `center`
+    pub fn regular(center: Vec2, radius: f32, sides: usize) -> ConvexPolygon {
+        let points: Vec<Vec2> = (0..sides).map(|i| {
+            let (s, c) = (i as f32 / sides as f32 * std::f32::consts::TAU).sin_cos();
+            center + Vec2(c, s) * radius
+        }).collect();
+        ConvexPolygon::new(&points)
+    }
+
+    pub fn from_obb(obb: &Obb2) -> ConvexPolygon {
+        ConvexPolygon::new(&obb.corners())
+    }
+
+    #[inline(always)]
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+
+    #[inline(always)]
+    pub fn normals(&self) -> &[Vec2] {
+        &self.normals
+    }
+
+    pub fn centroid(&self) -> Vec2 {
+        self.vertices.iter().copied().sum::<Vec2>() / self.vertices.len() as f32
+    }
+
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::from_points(&self.vertices)
+    }
+
+    /// the polygon transformed by a 3x3 affine matrix, which must not mirror it
+    pub fn transform(&self, m: &Matrix<f32>) -> ConvexPolygon {
+        let affine = Affine2::from_matrix(m);
+        let points: Vec<Vec2> = self.vertices.iter().map(|v| affine.transform_point2(*v)).collect();
+        ConvexPolygon::new(&points)
+    }
+}
+
+/// the convex hull of `points` counterclockwise, by the monotone chain algorithm
+fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted
+    }
+
+    let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() + 1);
+    for pass in [&sorted[..], &sorted.iter().rev().copied().collect::<Vec<_>>()[..]] {
+        let start = hull.len();
+        for &p in pass {
+            while hull.len() >= start + 2 {
+                let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+                if (b - a).perp_dot(p - a) > EPSILON {
+                    break
+                }
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // the last point of each chain starts the other
+        hull.pop();
+    }
+    hull
+}
+
+impl Support2 for ConvexPolygon {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        *self.vertices.iter().max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction))).unwrap()
+    }
+}
+
+impl Support2 for Circle {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        self.center + direction.normalize_or_zero() * self.radius
+    }
+}
+
+impl Support2 for Capsule2 {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        self.segment.support(direction) + direction.normalize_or_zero() * self.radius
+    }
+}
+
+impl Support2 for Segment2 {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        if self.a.dot(direction) >= self.b.dot(direction) { self.a } else { self.b }
+    }
+}
+
+impl Support2 for Triangle2 {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        let (a, b, c) = (self.a.dot(direction), self.b.dot(direction), self.c.dot(direction));
+        if a >= b && a >= c { self.a } else if b >= c { self.b } else { self.c }
+    }
+}
+
+impl Support2 for Aabb2 {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        Vec2(
+            if direction.x >= 0.0 { self.max.x } else { self.min.x },
+            if direction.y >= 0.0 { self.max.y } else { self.min.y },
+        )
+    }
+}
+
+impl Support2 for Obb2 {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        let h = self.half_extents;
+        let x = if direction.dot(self.axes[0]) >= 0.0 { h.x } else { -h.x };
+        let y = if direction.dot(self.axes[1]) >= 0.0 { h.y } else { -h.y };
+        self.from_local(Vec2(x, y))
+    }
+}
+
+/// A point of the minkowski difference `a - b`, and the points of each shape that made it
+#[derive(Clone, Copy, Debug)]
+struct SupportPoint {
+    a: Vec2,
+    b: Vec2,
+    w: Vec2,
+}
+
+fn support_point<A: Support2, B: Support2>(a: &A, b: &B, direction: Vec2) -> SupportPoint {
+    let (pa, pb) = (a.support(direction), b.support(-direction));
+    SupportPoint { a: pa, b: pb, w: pa - pb }
+}
+
+/// The closest points between two separated shapes, see [`gjk_distance`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distance2 {
+    pub distance: f32,
+    pub point_a: Vec2,
+    pub point_b: Vec2,
+}
+
+/// How far two overlapping shapes overlap, see [`epa_penetration`].
+///
+/// `normal` points from `a` towards `b`, so moving `b` by `normal * depth` separates them.
+/// `point_a` and `point_b` are the deepest points of each shape inside the other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Penetration2 {
+    pub depth: f32,
+    pub normal: Vec2,
+    pub point_a: Vec2,
+    pub point_b: Vec2,
+}
+
+/// the gjk simplex, with the weights of the closest point to the origin
+struct Simplex {
+    points: Vec<SupportPoint>,
+    weights: Vec<f32>,
+}
+
+impl Simplex {
+    fn closest(&self) -> Vec2 {
+        self.points.iter().zip(&self.weights).map(|(p, w)| p.w * *w).sum()
+    }
+
+    fn witnesses(&self) -> (Vec2, Vec2) {
+        let a = self.points.iter().zip(&self.weights).map(|(p, w)| p.a * *w).sum();
+        let b = self.points.iter().zip(&self.weights).map(|(p, w)| p.b * *w).sum();
+        (a, b)
+    }
+
+    /// reduce to the smallest part of the simplex holding the closest point to the origin,
+    /// returning false if the origin is inside
+    fn reduce(&mut self) -> bool {
+        match self.points.len() {
+            1 => self.weights = vec![1.0],
+            2 => self.reduce_segment(0, 1),
+            _ => {
+                let [p0, p1, p2] = [self.points[0].w, self.points[1].w, self.points[2].w];
+                let area = (p1 - p0).perp_dot(p2 - p0);
+                let d0 = (p1 - p0).perp_dot(-p0) * area;
+                let d1 = (p2 - p1).perp_dot(-p1) * area;
+                let d2 = (p0 - p2).perp_dot(-p2) * area;
+                if d0 >= 0.0 && d1 >= 0.0 && d2 >= 0.0 && area.abs() > 0.0 {
+                    return false
+                }
+
+                // the closest of the three edges
+                let mut best: Option<(f32, Simplex)> = None;
+                for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+                    let mut edge = Simplex { points: vec![self.points[i], self.points[j]], weights: vec![] };
+                    edge.reduce_segment(0, 1);
+                    let d = edge.closest().magnitude_squared();
+                    if best.as_ref().is_none_or(|(bd, _)| d < *bd) {
+                        best = Some((d, edge));
+                    }
+                }
+                *self = best.unwrap().1;
+            }
+        }
+        true
+    }
+
+    fn reduce_segment(&mut self, i: usize, j: usize) {
+        let (a, b) = (self.points[i], self.points[j]);
+        let e = b.w - a.w;
+        let len2 = e.magnitude_squared();
+        let t = if len2 > 0.0 { (-a.w.dot(e) / len2).clamp(0.0, 1.0) } else { 0.0 };
+        if t <= 0.0 {
+            *self = Simplex { points: vec![a], weights: vec![1.0] };
+        } else if t >= 1.0 {
+            *self = Simplex { points: vec![b], weights: vec![1.0] };
+        } else {
+            *self = Simplex { points: vec![a, b], weights: vec![1.0 - t, t] };
+        }
+    }
+}
+
+/// run gjk, returning the final simplex and whether the shapes overlap
+fn gjk<A: Support2, B: Support2>(a: &A, b: &B) -> (Simplex, bool) {
+    let mut simplex = Simplex { points: vec![support_point(a, b, Vec2::X)], weights: vec![1.0] };
+    let scale = simplex.points[0].w.magnitude().max(1.0);
+
+    for _ in 0..MAX_ITERATIONS {
+        if !simplex.reduce() {
+            return (simplex, true)
+        }
+        let closest = simplex.closest();
+        if closest.magnitude() <= TOLERANCE * scale {
+            return (simplex, true)
+        }
+
+        let direction = -closest;
+        let next = support_point(a, b, direction);
+        // no further progress towards the origin is possible
+        let progress = (next.w - closest).dot(direction) / direction.magnitude();
+        if progress <= TOLERANCE * scale || simplex.points.iter().any(|p| p.w == next.w) {
+            return (simplex, false)
+        }
+        simplex.points.push(next);
+    }
+    (simplex, false)
+}
+
+/// whether two convex shapes overlap, touching counts
+pub fn gjk_intersects<A: Support2, B: Support2>(a: &A, b: &B) -> bool {
+    gjk(a, b).1
+}
+
+/// the distance between two convex shapes and the closest point on each,
+/// none if they overlap
+pub fn gjk_distance<A: Support2, B: Support2>(a: &A, b: &B) -> Option<Distance2> {
+    let (simplex, overlapping) = gjk(a, b);
+    if overlapping {
+        return None
+    }
+    let (point_a, point_b) = simplex.witnesses();
+    Some(Distance2 { distance: simplex.closest().magnitude(), point_a, point_b })
+}
+
+/// how deeply two convex shapes overlap, using the expanding polytope algorithm,
+/// none if they don't overlap
+pub fn epa_penetration<A: Support2, B: Support2>(a: &A, b: &B) -> Option<Penetration2> {
+    let (simplex, overlapping) = gjk(a, b);
+    if !overlapping {
+        return None
+    }
+
+    // grow whatever gjk ended with into a polygon around the origin,
+    // if that isn't possible the shapes are only just touching
+    let mut polytope = simplex.points;
+    if polytope.len() == 1 {
+        let first = polytope[0].w;
+        for direction in [-first, Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y] {
+            let next = support_point(a, b, direction);
+            if (next.w - first).magnitude_squared() > 0.0 {
+                polytope.push(next);
+                break
+            }
+        }
+    }
+    match polytope.len() {
+        1 => return Some(Penetration2 { depth: 0.0, normal: Vec2::X, point_a: polytope[0].a, point_b: polytope[0].b }),
+        2 => {
+            let side = (polytope[1].w - polytope[0].w).perp();
+            polytope = vec![
+                polytope[0],
+                support_point(a, b, -side),
+                polytope[1],
+                support_point(a, b, side),
+            ];
+        }
+        _ => {
+            if (polytope[1].w - polytope[0].w).perp_dot(polytope[2].w - polytope[0].w) < 0.0 {
+                polytope.swap(1, 2);
+            }
+        }
+    }
+    let scale = polytope.iter().map(|p| p.w.magnitude()).fold(1.0, f32::max);
+
+    let mut best = (0.0, Vec2::X, 0);
+    for _ in 0..MAX_ITERATIONS {
+        // the edge closest to the origin
+        best = (f32::INFINITY, Vec2::X, 0);
+        for i in 0..polytope.len() {
+            let (p, q) = (polytope[i].w, polytope[(i + 1) % polytope.len()].w);
+            let Some(normal) = Vec2((q - p).y, -(q - p).x).try_normalize() else { continue };
+            let distance = normal.dot(p);
+            if distance < best.0 {
+                best = (distance, normal, i);
+            }
+        }
+
+        let (distance, normal, i) = best;
+        let next = support_point(a, b, normal);
+        if next.w.dot(normal) - distance <= TOLERANCE * scale {
+            break
+        }
+        polytope.insert(i + 1, next);
+    }
+
+    let (depth, normal, i) = best;
+    let (p, q) = (polytope[i], polytope[(i + 1) % polytope.len()]);
+    let mut edge = Simplex { points: vec![p, q], weights: vec![] };
+    edge.reduce_segment(0, 1);
+    let (point_a, point_b) = edge.witnesses();
+    Some(Penetration2 { depth: depth.max(0.0), normal, point_a, point_b })
+}
+
+/// A point where two shapes touch, see [`Manifold2`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact2 {
+    /// half way between the two surfaces
+    pub point: Vec2,
+    /// how far the shapes overlap here along the manifold's normal, negative if they're apart
+    pub depth: f32,
+}
+
+/// The contact points between two overlapping shapes, sharing a normal from `a` towards `b`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifold2 {
+    pub normal: Vec2,
+    pub contacts: Vec<Contact2>,
+}
+
+/// A shape that [`collide`] can build contact manifolds for
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape2 {
+    Circle(Circle),
+    Polygon(ConvexPolygon),
+    Capsule(Capsule2),
+}
+
+impl Shape2 {
+    pub fn aabb(&self) -> Aabb2 {
+        match self {
+            Shape2::Circle(c) => c.aabb(),
+            Shape2::Polygon(p) => p.aabb(),
+            Shape2::Capsule(c) => c.aabb(),
+        }
+    }
+
+    /// the shape transformed by a 3x3 affine matrix, with circles growing by the largest scale
+    pub fn transform(&self, m: &Matrix<f32>) -> Shape2 {
+        match self {
+            Shape2::Circle(c) => Shape2::Circle(c.transform(m)),
+            Shape2::Polygon(p) => Shape2::Polygon(p.transform(m)),
+            Shape2::Capsule(c) => Shape2::Capsule(c.transform(m)),
+        }
+    }
+}
+
+impl Support2 for Shape2 {
+    fn support(&self, direction: Vec2) -> Vec2 {
+        match self {
+            Shape2::Circle(c) => c.support(direction),
+            Shape2::Polygon(p) => p.support(direction),
+            Shape2::Capsule(c) => c.support(direction),
+        }
+    }
+}
+
+/// the contact manifold between two shapes, none if they don't touch
+pub fn collide(a: &Shape2, b: &Shape2) -> Option<Manifold2> {
+    match (a, b) {
+        (Shape2::Circle(a), Shape2::Circle(b)) => collide_circles(a, b),
+        (Shape2::Circle(a), Shape2::Polygon(b)) => collide_circle_polygon(a, b),
+        (Shape2::Polygon(a), Shape2::Circle(b)) => collide_circle_polygon(b, a).map(flip),
+        (Shape2::Polygon(a), Shape2::Polygon(b)) => collide_polygons(a, b),
+        (Shape2::Circle(a), Shape2::Capsule(b)) => collide_circle_capsule(a, b),
+        (Shape2::Capsule(a), Shape2::Circle(b)) => collide_circle_capsule(b, a).map(flip),
+        (Shape2::Capsule(a), Shape2::Capsule(b)) => collide_capsules(a, b),
+        (Shape2::Capsule(a), Shape2::Polygon(b)) => collide_capsule_polygon(a, b),
+        (Shape2::Polygon(a), Shape2::Capsule(b)) => collide_capsule_polygon(b, a).map(flip),
+    }
+}
+
+fn flip(manifold: Manifold2) -> Manifold2 {
+    Manifold2 { normal: -manifold.normal, contacts: manifold.contacts }
+}
+
+pub fn collide_circles(a: &Circle, b: &Circle) -> Option<Manifold2> {
+    let d = b.center - a.center;
+    let distance = d.magnitude();
+    let depth = a.radius + b.radius - distance;
+    if depth < 0.0 {
+        return None
+    }
+    // concentric circles get pushed apart along x
+    let normal = d.try_normalize().unwrap_or(Vec2::X);
+    let point = a.center + normal * (a.radius - depth * 0.5);
+    Some(Manifold2 { normal, contacts: vec![Contact2 { point, depth }] })
+}
+
+pub fn collide_circle_polygon(a: &Circle, b: &ConvexPolygon) -> Option<Manifold2> {
+    let c = a.center;
+    let (vertices, normals) = (&b.vertices, &b.normals);
+
+    let (face, separation) = (0..vertices.len())
+        .map(|i| (i, normals[i].dot(c - vertices[i])))
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+        .unwrap();
+    if separation > a.radius {
+        return None
+    }
+
+    let (normal, depth) = if separation <= 0.0 {
+        // the center is inside, so push out through the nearest face
+        (-normals[face], a.radius - separation)
+    } else {
+        let closest = (0..vertices.len())
+            .map(|i| Segment2::new(vertices[i], vertices[(i + 1) % vertices.len()]).closest_point(c))
+            .min_by(|x, y| (*x - c).magnitude_squared().total_cmp(&(*y - c).magnitude_squared()))
+            .unwrap();
+        let d = closest - c;
+        let distance = d.magnitude();
+        if distance > a.radius {
+            return None
+        }
+        (d.try_normalize().unwrap_or(-normals[face]), a.radius - distance)
+    };
+
+    let point = c + normal * (a.radius - depth * 0.5);
+    Some(Manifold2 { normal, contacts: vec![Contact2 { point, depth }] })
+}
+
+/// the face of `a` that `b` is furthest outside of, and how far
+fn max_separation(a: &ConvexPolygon, b: &ConvexPolygon) -> (usize, f32) {
+    (0..a.vertices.len())
+        .map(|i| {
+            let (n, v) = (a.normals[i], a.vertices[i]);
+            let s = b.vertices.iter().map(|w| n.dot(*w - v)).fold(f32::INFINITY, f32::min);
+            (i, s)
+        })
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+        .unwrap()
+}
+
+/// keep the parts of a segment where `normal.dot(p) <= offset`
+fn clip(points: [Vec2; 2], normal: Vec2, offset: f32) -> Option<[Vec2; 2]> {
+    let d0 = normal.dot(points[0]) - offset;
+    let d1 = normal.dot(points[1]) - offset;
+    match (d0 <= 0.0, d1 <= 0.0) {
+        (true, true) => Some(points),
+        (false, false) => None,
+        (inside0, _) => {
+            let cut = points[0].lerp(points[1], d0 / (d0 - d1));
+            Some(if inside0 { [points[0], cut] } else { [cut, points[1]] })
+        }
+    }
+}
+
+/// the contact manifold between two polygons by the separating axis test, with up to two
+/// points found by clipping the most anti-parallel edge against the face of least overlap
+pub fn collide_polygons(a: &ConvexPolygon, b: &ConvexPolygon) -> Option<Manifold2> {
+    let (face_a, separation_a) = max_separation(a, b);
+    if separation_a > 0.0 {
+        return None
+    }
+    let (face_b, separation_b) = max_separation(b, a);
+    if separation_b > 0.0 {
+        return None
+    }
+    face_contacts(a, 0.0, b, 0.0, (face_a, separation_a), (face_b, separation_b))
+}
+
+/// clip the edge of the incident polygon most anti-parallel to the reference face of least
+/// overlap, preferring a's faces unless b's are clearly better so the choice doesn't flicker.
+///
+/// each polygon is rounded by its radius, and contacts closer than the sum of them count.
+fn face_contacts(
+    a: &ConvexPolygon, radius_a: f32,
+    b: &ConvexPolygon, radius_b: f32,
+    (face_a, separation_a): (usize, f32),
+    (face_b, separation_b): (usize, f32),
+) -> Option<Manifold2> {
+    let flipped = separation_b > separation_a + 1e-3 * separation_a.abs().max(EPSILON);
+    let (reference, incident, face) = if flipped { (b, a, face_b) } else { (a, b, face_a) };
+    let (radius_ref, radius_inc) = if flipped { (radius_b, radius_a) } else { (radius_a, radius_b) };
+
+    let n = reference.normals[face];
+    let v1 = reference.vertices[face];
+    let v2 = reference.vertices[(face + 1) % reference.vertices.len()];
+
+    let incident_face = (0..incident.normals.len())
+        .min_by(|i, j| incident.normals[*i].dot(n).total_cmp(&incident.normals[*j].dot(n)))
+        .unwrap();
+    let segment = [
+        incident.vertices[incident_face],
+        incident.vertices[(incident_face + 1) % incident.vertices.len()],
+    ];
+
+    // clip to the sides of the reference face
+    let tangent = (v2 - v1).normalize();
+    let segment = clip(segment, -tangent, -tangent.dot(v1))?;
+    let segment = clip(segment, tangent, tangent.dot(v2))?;
+
+    // half way between the rounded surfaces, which are the radii out from the cores
+    let radius = radius_ref + radius_inc;
+    let contacts: Vec<Contact2> = segment.iter().filter_map(|p| {
+        let separation = n.dot(*p - v1);
+        (separation <= radius).then(|| Contact2 {
+            point: *p - n * ((separation - radius_ref + radius_inc) * 0.5),
+            depth: radius - separation,
+        })
+    }).collect();
+    if contacts.is_empty() {
+        return None
+    }
+
+    let normal = if flipped { -n } else { n };
+    Some(Manifold2 { normal, contacts })
+}
+
+/// a segment as a polygon with two opposite faces, for the rounded polygon tests
+fn segment_polygon(segment: &Segment2) -> ConvexPolygon {
+    let normal = (segment.b - segment.a).normalize().perp();
+    ConvexPolygon { vertices: vec![segment.a, segment.b], normals: vec![-normal, normal] }
+}
+
+/// the contact manifold between two polygons rounded by a radius each.
+///
+/// the face axes find most contacts, but near corners the closest features can be a pair
+/// of vertices, which is checked with gjk and gives a single contact.
+fn collide_rounded(a: &ConvexPolygon, radius_a: f32, b: &ConvexPolygon, radius_b: f32) -> Option<Manifold2> {
+    let radius = radius_a + radius_b;
+    let (face_a, separation_a) = max_separation(a, b);
+    if separation_a > radius {
+        return None
+    }
+    let (face_b, separation_b) = max_separation(b, a);
+    if separation_b > radius {
+        return None
+    }
+
+    if let Some(closest) = gjk_distance(a, b) {
+        if closest.distance > radius {
+            return None
+        }
+        let face_separation = separation_a.max(separation_b);
+        if face_separation < closest.distance - 1e-3 * closest.distance.max(radius) {
+            let normal = (closest.point_b - closest.point_a).try_normalize()?;
+            let surface_a = closest.point_a + normal * radius_a;
+            let surface_b = closest.point_b - normal * radius_b;
+            let contact = Contact2 { point: (surface_a + surface_b) * 0.5, depth: radius - closest.distance };
+            return Some(Manifold2 { normal, contacts: vec![contact] })
+        }
+    }
+    face_contacts(a, radius_a, b, radius_b, (face_a, separation_a), (face_b, separation_b))
+}
+
+pub fn collide_circle_capsule(a: &Circle, b: &Capsule2) -> Option<Manifold2> {
+    let center = b.segment.closest_point(a.center);
+    collide_circles(a, &Circle { center, radius: b.radius })
+}
+
+pub fn collide_capsules(a: &Capsule2, b: &Capsule2) -> Option<Manifold2> {
+    match (a.segment.length() > EPSILON, b.segment.length() > EPSILON) {
+        (true, true) => collide_rounded(&segment_polygon(&a.segment), a.radius, &segment_polygon(&b.segment), b.radius),
+        (_, false) => collide_circle_capsule(&Circle { center: b.segment.a, radius: b.radius }, a).map(flip),
+        (false, true) => collide_circle_capsule(&Circle { center: a.segment.a, radius: a.radius }, b),
+    }
+}
+
+pub fn collide_capsule_polygon(a: &Capsule2, b: &ConvexPolygon) -> Option<Manifold2> {
+    if a.segment.length() <= EPSILON {
+        return collide_circle_polygon(&Circle { center: a.segment.a, radius: a.radius }, b)
+    }
+    collide_rounded(&segment_polygon(&a.segment), a.radius, b, 0.0)
+}
@@ -0,0 +1,838 @@
+use crate::geometry::aabb::Aabb3;
+use crate::geometry::capsule::Capsule3;
+use crate::geometry::obb::Obb3;
+use crate::geometry::segment::Segment3;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::triangle::Triangle3;
+use crate::geometry::EPSILON;
+use crate::math::matrix::Matrix;
+use crate::math::vec3::Vec3;
+
+/// the most iterations gjk and epa take before settling for what they have
+const MAX_ITERATIONS: usize = 64;
+
+/// how close gjk and epa have to get before stopping, relative to the shapes' size
+const TOLERANCE: f32 = 1e-5;
+
+/// the most contacts a box-box manifold keeps
+const MAX_CONTACTS: usize = 4;
+
+/// A convex shape described by its support function, the furthest point in a given direction
+pub trait Support3 {
+    /// the point of the shape furthest along `direction`, which need not be normalized
+    fn support(&self, direction: Vec3) -> Vec3;
+}
+
+/// A convex hull given by its points, any of which may lie inside it
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvexHull {
+    points: Vec<Vec3>,
+}
+
+impl ConvexHull {
+    pub fn new(points: &[Vec3]) -> ConvexHull {
+        assert!(!points.is_empty(), "hull has no points");
+        ConvexHull { points: points.to_vec() }
+    }
+
+    #[inline(always)]
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    pub fn aabb(&self) -> Aabb3 {
+        Aabb3::from_points(&self.points)
+    }
+
+    /// the triangles of the hull's surface, wound counterclockwise seen from outside,
+    /// found by adding the points one at a time and carving away the faces each one can see.
+    ///
+    /// empty if the points are all in one plane, so the hull has no volume.
+    pub fn triangles(&self) -> Vec<[Vec3; 3]> {
+        let points = &self.points;
+        let scale = self.aabb().half_extents().magnitude();
+        let tolerance = TOLERANCE * scale.max(EPSILON);
+        let furthest = |distance: &dyn Fn(Vec3) -> f32| {
+            (0..points.len()).max_by(|&i, &j| distance(points[i]).total_cmp(&distance(points[j]))).unwrap()
+        };
+
+        // start from the biggest tetrahedron that's easy to find
+        let a = 0;
+        let b = furthest(&|p| (p - points[a]).magnitude());
+        let line = points[b] - points[a];
+        let c = furthest(&|p| line.cross(p - points[a]).magnitude());
+        let normal = line.cross(points[c] - points[a]);
+        let d = furthest(&|p| normal.dot(p - points[a]).abs());
+        if line.magnitude() <= tolerance
+            || normal.magnitude() <= tolerance * line.magnitude()
+            || normal.dot(points[d] - points[a]).abs() <= tolerance * normal.magnitude() {
+            return Vec::new()
+        }
+
+        let normal_of = |[i, j, k]: [usize; 3]| (points[j] - points[i]).cross(points[k] - points[i]);
+        let sees = |face: [usize; 3], p: Vec3| {
+            let n = normal_of(face);
+            n.dot(p - points[face[0]]) > tolerance * n.magnitude()
+        };
+        let inside = (points[a] + points[b] + points[c] + points[d]) * 0.25;
+        let mut faces: Vec<[usize; 3]> = [[a, b, c], [a, d, b], [a, c, d], [b, d, c]].into_iter()
+            .map(|[i, j, k]| if normal_of([i, j, k]).dot(points[i] - inside) < 0.0 { [i, k, j] } else { [i, j, k] })
+            .collect();
+
+        for (index, &point) in points.iter().enumerate() {
+            if [a, b, c, d].contains(&index) || !faces.iter().any(|&face| sees(face, point)) {
+                continue
+            }
+            let mut horizon: Vec<(usize, usize)> = Vec::new();
+            faces.retain(|&face| {
+                if !sees(face, point) {
+                    return true
+                }
+                let [i, j, k] = face;
+                for (p, q) in [(i, j), (j, k), (k, i)] {
+                    match horizon.iter().position(|&edge| edge == (q, p)) {
+                        Some(shared) => { horizon.swap_remove(shared); }
+                        None => horizon.push((p, q)),
+                    }
+                }
+                false
+            });
+            faces.extend(horizon.iter().map(|&(p, q)| [p, q, index]));
+        }
+
+        faces.into_iter().map(|face| face.map(|i| points[i])).collect()
+    }
+}
+
+impl Support3 for ConvexHull {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        *self.points.iter().max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction))).unwrap()
+    }
+}
+
+impl Support3 for Sphere {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        self.center + direction.normalize_or_zero() * self.radius
+    }
+}
+
+impl Support3 for Capsule3 {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        self.segment.support(direction) + direction.normalize_or_zero() * self.radius
+    }
+}
+
+impl Support3 for Segment3 {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        if self.a.dot(direction) >= self.b.dot(direction) { self.a } else { self.b }
+    }
+}
+
+impl Support3 for Triangle3 {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let (a, b, c) = (self.a.dot(direction), self.b.dot(direction), self.c.dot(direction));
+        if a >= b && a >= c { self.a } else if b >= c { self.b } else { self.c }
+    }
+}
+
+impl Support3 for Aabb3 {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        Vec3(
+            if direction.x >= 0.0 { self.max.x } else { self.min.x },
+            if direction.y >= 0.0 { self.max.y } else { self.min.y },
+            if direction.z >= 0.0 { self.max.z } else { self.min.z },
+        )
+    }
+}
+
+impl Support3 for Obb3 {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let h = self.half_extents;
+        let pick = |axis: Vec3, v: f32| if direction.dot(axis) >= 0.0 { v } else { -v };
+        self.from_local(Vec3(pick(self.axes[0], h.x), pick(self.axes[1], h.y), pick(self.axes[2], h.z)))
+    }
+}
+
+/// The shape of a [`PlacedShape3`], in its local frame and centered on its origin.
+///
+/// capsules and cylinders run along the local y axis.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape3 {
+    Cuboid { half_extents: Vec3 },
+    Sphere { radius: f32 },
+    Capsule { half_height: f32, radius: f32 },
+    Cylinder { half_height: f32, radius: f32 },
+    Hull(ConvexHull),
+}
+
+impl Support3 for Shape3 {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        match self {
+            Shape3::Cuboid { half_extents: h } => Vec3(
+                if direction.x >= 0.0 { h.x } else { -h.x },
+                if direction.y >= 0.0 { h.y } else { -h.y },
+                if direction.z >= 0.0 { h.z } else { -h.z },
+            ),
+            Shape3::Sphere { radius } => direction.normalize_or_zero() * *radius,
+            Shape3::Capsule { half_height, radius } => {
+                let y = if direction.y >= 0.0 { *half_height } else { -*half_height };
+                Vec3(0.0, y, 0.0) + direction.normalize_or_zero() * *radius
+            }
+            Shape3::Cylinder { half_height, radius } => {
+                let y = if direction.y >= 0.0 { *half_height } else { -*half_height };
+                let rim = Vec3(direction.x, 0.0, direction.z).normalize_or_zero() * *radius;
+                Vec3(rim.x, y, rim.z)
+            }
+            Shape3::Hull(hull) => hull.support(direction),
+        }
+    }
+}
+
+/// A shape placed in the world, oriented by a rotation matrix.
+///
+/// the columns of the rotation are kept as the shape's axes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlacedShape3 {
+    pub shape: Shape3,
+    pub position: Vec3,
+    axes: [Vec3; 3],
+}
+
+impl PlacedShape3 {
+    /// `shape` rotated by the upper 3x3 of `rotation`, which must be 3x3 or 4x4 and orthonormal
+    pub fn new(shape: Shape3, position: Vec3, rotation: &Matrix<f32>) -> PlacedShape3 {
+        assert!(rotation.is_3x3() || rotation.is_4x4(), "rotation must be 3x3 or 4x4");
+        let (m, n) = (&rotation.data, rotation.cols);
+        let axes = [0, 1, 2].map(|j| Vec3(m[j], m[n + j], m[2 * n + j]));
+        PlacedShape3 { shape, position, axes }
+    }
+
+    /// `shape` without any rotation
+    pub fn from_position(shape: Shape3, position: Vec3) -> PlacedShape3 {
+        PlacedShape3 { shape, position, axes: [Vec3::X, Vec3::Y, Vec3::Z] }
+    }
+
+    #[inline(always)]
+    pub fn axes(&self) -> [Vec3; 3] {
+        self.axes
+    }
+
+    /// the orientation as a 3x3 matrix
+    pub fn rotation(&self) -> Matrix<f32> {
+        let [x, y, z] = self.axes;
+        Matrix::from_vec(3, 3, vec![x.x, y.x, z.x, x.y, y.y, z.y, x.z, y.z, z.z])
+    }
+
+    /// `point` in the shape's local frame
+    #[inline(always)]
+    pub fn to_local(&self, point: Vec3) -> Vec3 {
+        let d = point - self.position;
+        Vec3(d.dot(self.axes[0]), d.dot(self.axes[1]), d.dot(self.axes[2]))
+    }
+
+    /// a point in the shape's local frame back into world space
+    #[inline(always)]
+    pub fn from_local(&self, local: Vec3) -> Vec3 {
+        self.position + self.axes[0] * local.x + self.axes[1] * local.y + self.axes[2] * local.z
+    }
+
+    pub fn aabb(&self) -> Aabb3 {
+        let min = Vec3(self.support(Vec3::NEG_X).x, self.support(Vec3::NEG_Y).y, self.support(Vec3::NEG_Z).z);
+        let max = Vec3(self.support(Vec3::X).x, self.support(Vec3::Y).y, self.support(Vec3::Z).z);
+        Aabb3::new(min, max)
+    }
+}
+
+impl Support3 for PlacedShape3 {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let [x, y, z] = self.axes;
+        let local = Vec3(direction.dot(x), direction.dot(y), direction.dot(z));
+        self.from_local(self.shape.support(local))
+    }
+}
+
+/// A point of the minkowski difference `a - b`, and the points of each shape that made it
+#[derive(Clone, Copy, Debug)]
+struct SupportPoint {
+    a: Vec3,
+    b: Vec3,
+    w: Vec3,
+}
+
+fn support_point<A: Support3, B: Support3>(a: &A, b: &B, direction: Vec3) -> SupportPoint {
+    let (pa, pb) = (a.support(direction), b.support(-direction));
+    SupportPoint { a: pa, b: pb, w: pa - pb }
+}
+
+/// The closest points between two separated shapes, see [`gjk_distance`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distance3 {
+    pub distance: f32,
+    pub point_a: Vec3,
+    pub point_b: Vec3,
+}
+
+/// How far two overlapping shapes overlap, see [`epa_penetration`].
+///
+/// `normal` points from `a` towards `b`, so moving `b` by `normal * depth` separates them.
+/// `point_a` and `point_b` are the deepest points of each shape inside the other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Penetration3 {
+    pub depth: f32,
+    pub normal: Vec3,
+    pub point_a: Vec3,
+    pub point_b: Vec3,
+}
+
+/// the gjk simplex, with the weights of the closest point to the origin
+struct Simplex {
+    points: Vec<SupportPoint>,
+    weights: Vec<f32>,
+}
+
+impl Simplex {
+    fn closest(&self) -> Vec3 {
+        self.points.iter().zip(&self.weights).map(|(p, w)| p.w * *w).sum()
+    }
+
+    fn witnesses(&self) -> (Vec3, Vec3) {
+        let a = self.points.iter().zip(&self.weights).map(|(p, w)| p.a * *w).sum();
+        let b = self.points.iter().zip(&self.weights).map(|(p, w)| p.b * *w).sum();
+        (a, b)
+    }
+
+    /// the part of the simplex made of the points at `picks`, with their weights
+    fn pick(&self, picks: &[(usize, f32)]) -> Simplex {
+        Simplex {
+            points: picks.iter().map(|(i, _)| self.points[*i]).collect(),
+            weights: picks.iter().map(|(_, w)| *w).collect(),
+        }
+    }
+
+    /// reduce to the smallest part of the simplex holding the closest point to the origin,
+    /// returning false if the origin is inside
+    fn reduce(&mut self) -> bool {
+        match self.points.len() {
+            1 => self.weights = vec![1.0],
+            2 => *self = self.reduce_segment(0, 1),
+            3 => *self = self.reduce_triangle(0, 1, 2),
+            _ => {
+                let p = [self.points[0].w, self.points[1].w, self.points[2].w, self.points[3].w];
+                let volume = (p[1] - p[0]).cross(p[2] - p[0]).dot(p[3] - p[0]);
+                let flat = volume.abs() <= EPSILON * (p[1] - p[0]).magnitude_squared().max(EPSILON);
+
+                // the closest of the faces the origin is outside of
+                let mut best: Option<(f32, Simplex)> = None;
+                for (i, j, k, l) in [(0, 1, 2, 3), (0, 3, 1, 2), (0, 2, 3, 1), (1, 3, 2, 0)] {
+                    let n = (p[j] - p[i]).cross(p[k] - p[i]);
+                    if !flat && n.dot(-p[i]) * n.dot(p[l] - p[i]) >= 0.0 {
+                        continue
+                    }
+                    let face = self.reduce_triangle(i, j, k);
+                    let d = face.closest().magnitude_squared();
+                    if best.as_ref().is_none_or(|(bd, _)| d < *bd) {
+                        best = Some((d, face));
+                    }
+                }
+                match best {
+                    Some((_, face)) => *self = face,
+                    None => return false,
+                }
+            }
+        }
+        true
+    }
+
+    fn reduce_segment(&self, i: usize, j: usize) -> Simplex {
+        let (a, b) = (self.points[i].w, self.points[j].w);
+        let e = b - a;
+        let len2 = e.magnitude_squared();
+        let t = if len2 > 0.0 { (-a.dot(e) / len2).clamp(0.0, 1.0) } else { 0.0 };
+        if t <= 0.0 {
+            self.pick(&[(i, 1.0)])
+        } else if t >= 1.0 {
+            self.pick(&[(j, 1.0)])
+        } else {
+            self.pick(&[(i, 1.0 - t), (j, t)])
+        }
+    }
+
+    /// the closest point to the origin on a triangle, by its voronoi regions
+    fn reduce_triangle(&self, i: usize, j: usize, k: usize) -> Simplex {
+        let (a, b, c) = (self.points[i].w, self.points[j].w, self.points[k].w);
+        let (ab, ac) = (b - a, c - a);
+
+        let (d1, d2) = (ab.dot(-a), ac.dot(-a));
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return self.pick(&[(i, 1.0)])
+        }
+        let (d3, d4) = (ab.dot(-b), ac.dot(-b));
+        if d3 >= 0.0 && d4 <= d3 {
+            return self.pick(&[(j, 1.0)])
+        }
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            let t = d1 / (d1 - d3);
+            return self.pick(&[(i, 1.0 - t), (j, t)])
+        }
+        let (d5, d6) = (ab.dot(-c), ac.dot(-c));
+        if d6 >= 0.0 && d5 <= d6 {
+            return self.pick(&[(k, 1.0)])
+        }
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            let t = d2 / (d2 - d6);
+            return self.pick(&[(i, 1.0 - t), (k, t)])
+        }
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+            let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return self.pick(&[(j, 1.0 - t), (k, t)])
+        }
+
+        let sum = va + vb + vc;
+        if sum <= 0.0 {
+            // a degenerate triangle, so the closest point is on one of its edges
+            return [(i, j), (j, k), (k, i)]
+                .map(|(p, q)| self.reduce_segment(p, q))
+                .into_iter()
+                .min_by(|x, y| x.closest().magnitude_squared().total_cmp(&y.closest().magnitude_squared()))
+                .unwrap()
+        }
+        let (v, w) = (vb / sum, vc / sum);
+        self.pick(&[(i, 1.0 - v - w), (j, v), (k, w)])
+    }
+}
+
+/// run gjk, returning the final simplex and whether the shapes overlap
+fn gjk<A: Support3, B: Support3>(a: &A, b: &B) -> (Simplex, bool) {
+    let mut simplex = Simplex { points: vec![support_point(a, b, Vec3::X)], weights: vec![1.0] };
+    let scale = simplex.points[0].w.magnitude().max(1.0);
+
+    for _ in 0..MAX_ITERATIONS {
+        if !simplex.reduce() {
+            return (simplex, true)
+        }
+        let closest = simplex.closest();
+        if closest.magnitude() <= TOLERANCE * scale {
+            return (simplex, true)
+        }
+
+        let direction = -closest;
+        let next = support_point(a, b, direction);
+        // no further progress towards the origin is possible
+        let progress = (next.w - closest).dot(direction) / direction.magnitude();
+        if progress <= TOLERANCE * scale || simplex.points.iter().any(|p| p.w == next.w) {
+            return (simplex, false)
+        }
+        simplex.points.push(next);
+    }
+    (simplex, false)
+}
+
+/// whether two convex shapes overlap, touching counts
+pub fn gjk_intersects<A: Support3, B: Support3>(a: &A, b: &B) -> bool {
+    gjk(a, b).1
+}
+
+/// the distance between two convex shapes and the closest point on each,
+/// none if they overlap
+pub fn gjk_distance<A: Support3, B: Support3>(a: &A, b: &B) -> Option<Distance3> {
+    let (simplex, overlapping) = gjk(a, b);
+    if overlapping {
+        return None
+    }
+    let (point_a, point_b) = simplex.witnesses();
+    Some(Distance3 { distance: simplex.closest().magnitude(), point_a, point_b })
+}
+
+/// A triangle of the expanding polytope, wound counterclockwise seen from outside
+#[derive(Clone, Copy, Debug)]
+struct Face {
+    indices: [usize; 3],
+    normal: Vec3,
+    distance: f32,
+}
+
+impl Face {
+    fn new(points: &[SupportPoint], indices: [usize; 3]) -> Face {
+        let [a, b, c] = indices.map(|i| points[i].w);
+        match (b - a).cross(c - a).try_normalize() {
+            Some(normal) => Face { indices, normal, distance: normal.dot(a) },
+            // a sliver can't be the closest face, and is never seen from a new point
+            None => Face { indices, normal: Vec3::ZERO, distance: f32::INFINITY },
+        }
+    }
+}
+
+/// grow whatever gjk ended with into a tetrahedron, which fails if the shapes are only touching
+fn blow_up<A: Support3, B: Support3>(a: &A, b: &B, mut points: Vec<SupportPoint>) -> Vec<SupportPoint> {
+    let scale = points.iter().map(|p| p.w.magnitude()).fold(1.0, f32::max);
+    let tolerance = TOLERANCE * scale;
+
+    if points.len() == 1 {
+        let first = points[0].w;
+        for direction in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z] {
+            let next = support_point(a, b, direction);
+            if (next.w - first).magnitude() > tolerance {
+                points.push(next);
+                break
+            }
+        }
+    }
+    if points.len() == 2 {
+        let line = (points[1].w - points[0].w).normalize();
+        let u = line.any_orthonormal();
+        let v = line.cross(u);
+        for direction in [u, -u, v, -v] {
+            let next = support_point(a, b, direction);
+            if (next.w - points[0].w).reject_from(line).magnitude() > tolerance {
+                points.push(next);
+                break
+            }
+        }
+    }
+    if points.len() == 3 {
+        let [p, q, r] = [points[0].w, points[1].w, points[2].w];
+        if let Some(n) = (q - p).cross(r - p).try_normalize() {
+            for direction in [n, -n] {
+                let next = support_point(a, b, direction);
+                if n.dot(next.w - p).abs() > tolerance {
+                    points.push(next);
+                    break
+                }
+            }
+        }
+    }
+    points
+}
+
+/// how deeply two convex shapes overlap, using the expanding polytope algorithm,
+/// none if they don't overlap
+pub fn epa_penetration<A: Support3, B: Support3>(a: &A, b: &B) -> Option<Penetration3> {
+    let (simplex, overlapping) = gjk(a, b);
+    if !overlapping {
+        return None
+    }
+
+    let mut points = blow_up(a, b, simplex.points);
+    if points.len() < 4 {
+        let (point_a, point_b) = (points[0].a, points[0].b);
+        let normal = (point_b - point_a).try_normalize().unwrap_or(Vec3::X);
+        return Some(Penetration3 { depth: 0.0, normal, point_a, point_b })
+    }
+    points.truncate(4);
+    let scale = points.iter().map(|p| p.w.magnitude()).fold(1.0, f32::max);
+
+    let centroid = points.iter().map(|p| p.w).sum::<Vec3>() * 0.25;
+    let mut faces: Vec<Face> = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]].into_iter().map(|[i, j, k]| {
+        let face = Face::new(&points, [i, j, k]);
+        if face.normal.dot(points[i].w - centroid) < 0.0 { Face::new(&points, [i, k, j]) } else { face }
+    }).collect();
+
+    let mut best = faces[0];
+    for _ in 0..MAX_ITERATIONS {
+        best = *faces.iter().min_by(|x, y| x.distance.total_cmp(&y.distance)).unwrap();
+        let next = support_point(a, b, best.normal);
+        if !best.distance.is_finite() || next.w.dot(best.normal) - best.distance <= TOLERANCE * scale {
+            break
+        }
+
+        // carve out every face the new point can see, then close the hole around it
+        let index = points.len();
+        points.push(next);
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|face| {
+            if face.normal.dot(next.w - points[face.indices[0]].w) <= 0.0 {
+                return true
+            }
+            let [i, j, k] = face.indices;
+            for (p, q) in [(i, j), (j, k), (k, i)] {
+                match horizon.iter().position(|&edge| edge == (q, p)) {
+                    Some(shared) => { horizon.swap_remove(shared); }
+                    None => horizon.push((p, q)),
+                }
+            }
+            false
+        });
+        faces.extend(horizon.iter().map(|&(p, q)| Face::new(&points, [p, q, index])));
+        if faces.is_empty() {
+            break
+        }
+    }
+
+    // where the origin projects onto the closest face gives the witness points
+    let [i, j, k] = best.indices;
+    let triangle = Triangle3::new(points[i].w, points[j].w, points[k].w);
+    let weights = triangle.barycentric(best.normal * best.distance, EPSILON).unwrap_or(Vec3::splat(1.0 / 3.0));
+    let point_a = points[i].a * weights.x + points[j].a * weights.y + points[k].a * weights.z;
+    let point_b = points[i].b * weights.x + points[j].b * weights.y + points[k].b * weights.z;
+    Some(Penetration3 { depth: best.distance.max(0.0), normal: best.normal, point_a, point_b })
+}
+
+/// A point where two shapes touch, see [`Manifold3`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact3 {
+    /// half way between the two surfaces
+    pub point: Vec3,
+    /// how far the shapes overlap here along the manifold's normal, negative if they're apart
+    pub depth: f32,
+}
+
+/// The contact points between two overlapping shapes, sharing a normal from `a` towards `b`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifold3 {
+    pub normal: Vec3,
+    pub contacts: Vec<Contact3>,
+}
+
+/// the contact manifold between two placed shapes, none if they don't touch.
+///
+/// box pairs get up to four points from [`collide_boxes`], spheres are handled exactly
+/// and everything else gets the single deepest point from [`epa_penetration`].
+pub fn collide(a: &PlacedShape3, b: &PlacedShape3) -> Option<Manifold3> {
+    match (&a.shape, &b.shape) {
+        (Shape3::Sphere { radius: ra }, Shape3::Sphere { radius: rb }) => {
+            collide_spheres(&Sphere::new(a.position, *ra), &Sphere::new(b.position, *rb))
+        }
+        (Shape3::Sphere { radius }, Shape3::Cuboid { half_extents }) => {
+            collide_sphere_box(&Sphere::new(a.position, *radius), &box_of(b, *half_extents))
+        }
+        (Shape3::Cuboid { half_extents }, Shape3::Sphere { radius }) => {
+            collide_sphere_box(&Sphere::new(b.position, *radius), &box_of(a, *half_extents)).map(flip)
+        }
+        (Shape3::Cuboid { half_extents: ha }, Shape3::Cuboid { half_extents: hb }) => {
+            collide_boxes(&box_of(a, *ha), &box_of(b, *hb))
+        }
+        _ => collide_convex(a, b),
+    }
+}
+
+fn box_of(placed: &PlacedShape3, half_extents: Vec3) -> Obb3 {
+    Obb3 { center: placed.position, axes: placed.axes, half_extents }
+}
+
+fn flip(manifold: Manifold3) -> Manifold3 {
+    Manifold3 { normal: -manifold.normal, contacts: manifold.contacts }
+}
+
+/// a single contact at the deepest point between any two convex shapes
+pub fn collide_convex<A: Support3, B: Support3>(a: &A, b: &B) -> Option<Manifold3> {
+    let p = epa_penetration(a, b)?;
+    let point = (p.point_a + p.point_b) * 0.5;
+    Some(Manifold3 { normal: p.normal, contacts: vec![Contact3 { point, depth: p.depth }] })
+}
+
+pub fn collide_spheres(a: &Sphere, b: &Sphere) -> Option<Manifold3> {
+    let d = b.center - a.center;
+    let distance = d.magnitude();
+    let depth = a.radius + b.radius - distance;
+    if depth < 0.0 {
+        return None
+    }
+    // concentric spheres get pushed apart along x
+    let normal = d.try_normalize().unwrap_or(Vec3::X);
+    let point = a.center + normal * (a.radius - depth * 0.5);
+    Some(Manifold3 { normal, contacts: vec![Contact3 { point, depth }] })
+}
+
+pub fn collide_sphere_box(a: &Sphere, b: &Obb3) -> Option<Manifold3> {
+    let c = a.center;
+    let local = b.to_local(c);
+    let h = b.half_extents;
+
+    let (normal, depth) = if local.abs().x <= h.x && local.abs().y <= h.y && local.abs().z <= h.z {
+        // the center is inside, so push out through the nearest face
+        let gaps = h - local.abs();
+        let axis = if gaps.x <= gaps.y && gaps.x <= gaps.z { 0 } else if gaps.y <= gaps.z { 1 } else { 2 };
+        let outward = if local[axis] >= 0.0 { b.axes[axis] } else { -b.axes[axis] };
+        (-outward, a.radius + gaps[axis])
+    } else {
+        let d = b.closest_point(c) - c;
+        let distance = d.magnitude();
+        if distance > a.radius {
+            return None
+        }
+        (d / distance, a.radius - distance)
+    };
+
+    let point = c + normal * (a.radius - depth * 0.5);
+    Some(Manifold3 { normal, contacts: vec![Contact3 { point, depth }] })
+}
+
+/// keep the parts of a convex polygon where `normal.dot(p) <= offset`
+fn clip(polygon: &[Vec3], normal: Vec3, offset: f32) -> Vec<Vec3> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &p) in polygon.iter().enumerate() {
+        let q = polygon[(i + 1) % polygon.len()];
+        let (dp, dq) = (normal.dot(p) - offset, normal.dot(q) - offset);
+        if dp <= 0.0 {
+            clipped.push(p);
+        }
+        if (dp <= 0.0) != (dq <= 0.0) {
+            clipped.push(p.lerp(q, dp / (dp - dq)));
+        }
+    }
+    clipped
+}
+
+/// the projected radius of a box along `axis`
+fn radius(obb: &Obb3, axis: Vec3) -> f32 {
+    obb.axes[0].dot(axis).abs() * obb.half_extents.x
+        + obb.axes[1].dot(axis).abs() * obb.half_extents.y
+        + obb.axes[2].dot(axis).abs() * obb.half_extents.z
+}
+
+/// The axis of least overlap found by [`collide_boxes`]
+#[derive(Clone, Copy)]
+enum Axis {
+    FaceA(usize),
+    FaceB(usize),
+    Edges,
+}
+
+/// the contact manifold between two boxes by the separating axis test.
+///
+/// a face axis gives up to four points by clipping the most anti-parallel face of the other box
+/// against the reference face, an edge axis gives the single point between the two closest edges.
+/// faces of `a` are preferred over those of `b`, and faces over edges, unless the other is clearly
+/// better, so the choice doesn't flicker between frames. a face that clips away every point
+/// falls back to the edge axis.
+pub fn collide_boxes(a: &Obb3, b: &Obb3) -> Option<Manifold3> {
+    const RELATIVE: f32 = 0.95;
+    let absolute = 1e-3 * a.half_extents.max(b.half_extents).max_element();
+
+    let d = b.center - a.center;
+    let separation = |axis: Vec3| d.dot(axis).abs() - radius(a, axis) - radius(b, axis);
+
+    let mut face_a = (f32::NEG_INFINITY, 0);
+    let mut face_b = (f32::NEG_INFINITY, 0);
+    for i in 0..3 {
+        let s = separation(a.axes[i]);
+        if s > 0.0 {
+            return None
+        }
+        if s > face_a.0 {
+            face_a = (s, i);
+        }
+        let s = separation(b.axes[i]);
+        if s > 0.0 {
+            return None
+        }
+        if s > face_b.0 {
+            face_b = (s, i);
+        }
+    }
+    let mut edges = (f32::NEG_INFINITY, 0, 0, Vec3::ZERO);
+    for i in 0..3 {
+        for j in 0..3 {
+            // nearly parallel edges are covered by the face axes
+            let axis = a.axes[i].cross(b.axes[j]);
+            if axis.magnitude_squared() <= EPSILON {
+                continue
+            }
+            let axis = axis.normalize();
+            let s = separation(axis);
+            if s > 0.0 {
+                return None
+            }
+            if s > edges.0 {
+                edges = (s, i, j, axis);
+            }
+        }
+    }
+
+    let (mut best, mut axis) = (face_a.0, Axis::FaceA(face_a.1));
+    if face_b.0 > RELATIVE * best + absolute {
+        (best, axis) = (face_b.0, Axis::FaceB(face_b.1));
+    }
+    if edges.0 > RELATIVE * best + absolute {
+        axis = Axis::Edges;
+    }
+
+    // the single point between the two closest edges, none if every edge pair was parallel
+    let edge_contact = || {
+        let (separation, i, j, axis) = edges;
+        if separation == f32::NEG_INFINITY {
+            return None
+        }
+        let normal = if axis.dot(d) < 0.0 { -axis } else { axis };
+        let edge = |obb: &Obb3, along: usize, towards: Vec3| {
+            let mut mid = obb.center;
+            for k in (0..3).filter(|k| *k != along) {
+                let sign = if obb.axes[k].dot(towards) >= 0.0 { 1.0 } else { -1.0 };
+                mid += obb.axes[k] * (obb.half_extents[k] * sign);
+            }
+            let half = obb.axes[along] * obb.half_extents[along];
+            Segment3::new(mid - half, mid + half)
+        };
+        let (pa, pb) = edge(a, i, normal).closest_points(&edge(b, j, -normal), EPSILON);
+        let point = (pa + pb) * 0.5;
+        Some(Manifold3 { normal, contacts: vec![Contact3 { point, depth: -separation }] })
+    };
+
+    match axis {
+        Axis::FaceA(i) => box_face_contacts(a, b, i, false).or_else(edge_contact),
+        Axis::FaceB(i) => box_face_contacts(b, a, i, true).or_else(edge_contact),
+        Axis::Edges => edge_contact(),
+    }
+}
+
+/// clip the face of `incident` most facing `reference`'s face along axis `face` against it,
+/// none if no point is left at or below the reference face
+fn box_face_contacts(reference: &Obb3, incident: &Obb3, face: usize, flipped: bool) -> Option<Manifold3> {
+    let axis = reference.axes[face];
+    let n = if axis.dot(incident.center - reference.center) >= 0.0 { axis } else { -axis };
+    let face_center = reference.center + n * reference.half_extents[face];
+
+    let j = (0..3)
+        .max_by(|x, y| incident.axes[*x].dot(n).abs().total_cmp(&incident.axes[*y].dot(n).abs()))
+        .unwrap();
+    let incident_normal = if incident.axes[j].dot(n) > 0.0 { -incident.axes[j] } else { incident.axes[j] };
+    let center = incident.center + incident_normal * incident.half_extents[j];
+    let (u, v) = ((j + 1) % 3, (j + 2) % 3);
+    let (eu, ev) = (incident.axes[u] * incident.half_extents[u], incident.axes[v] * incident.half_extents[v]);
+    let mut polygon = vec![center + eu + ev, center - eu + ev, center - eu - ev, center + eu - ev];
+
+    // clip to the four sides of the reference face
+    for k in (0..3).filter(|k| *k != face) {
+        let (side, h) = (reference.axes[k], reference.half_extents[k]);
+        let offset = side.dot(reference.center);
+        polygon = clip(&polygon, side, offset + h);
+        polygon = clip(&polygon, -side, -offset + h);
+    }
+
+    let contacts: Vec<Contact3> = polygon.iter().filter_map(|p| {
+        let separation = n.dot(*p - face_center);
+        (separation <= 0.0).then(|| Contact3 { point: *p - n * (separation * 0.5), depth: -separation })
+    }).collect();
+    if contacts.is_empty() {
+        return None
+    }
+
+    let normal = if flipped { -n } else { n };
+    Some(Manifold3 { normal, contacts: reduce_contacts(contacts, normal) })
+}
+
+/// keep at most four contacts, the deepest and those spanning the largest area with it
+fn reduce_contacts(contacts: Vec<Contact3>, normal: Vec3) -> Vec<Contact3> {
+    if contacts.len() <= MAX_CONTACTS {
+        return contacts
+    }
+    let by = |score: &dyn Fn(&Contact3) -> f32| {
+        (0..contacts.len()).max_by(|x, y| score(&contacts[*x]).total_cmp(&score(&contacts[*y]))).unwrap()
+    };
+
+    let first = by(&|c| c.depth);
+    let p0 = contacts[first].point;
+    let second = by(&|c| (c.point - p0).magnitude_squared());
+    let p1 = contacts[second].point;
+    let area = |c: &Contact3| (p1 - p0).cross(c.point - p0).dot(normal);
+    let third = by(&|c| area(c));
+    let fourth = by(&|c| -area(c));
+
+    let mut picks = vec![first, second, third, fourth];
+    picks.sort_unstable();
+    picks.dedup();
+    picks.iter().map(|i| contacts[*i]).collect()
+}
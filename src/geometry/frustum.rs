@@ -0,0 +1,90 @@
+use crate::geometry::aabb::Aabb3;
+use crate::geometry::plane::Plane3;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::EPSILON;
+use crate::math::matrix::Matrix;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+
+/// The volume a camera sees, as planes with their normals pointing inwards.
+///
+/// the planes are left, right, bottom, top and then the two depth planes.
+/// a depth plane at infinity is left out, so there may be only five.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frustum {
+    pub planes: Vec<Plane3>,
+}
+
+/// How a shape sits with respect to a [`Frustum`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Containment {
+    Outside,
+    Intersecting,
+    Inside,
+}
+
+impl Frustum {
+    /// the frustum of a 4x4 view projection matrix with clip space depth in `0..=1`, as made by
+    /// [`Matrix::perspective`], with the planes in the space the matrix transforms from
+    pub fn from_matrix(m: &Matrix<f32>) -> Frustum {
+        assert!(m.is_4x4());
+        let row = |i: usize| Vec4(m.data[i * 4], m.data[i * 4 + 1], m.data[i * 4 + 2], m.data[i * 4 + 3]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        let planes = [w + x, w - x, w + y, w - y, z, w - z].into_iter().filter_map(|p| {
+            // inside where `p.dot(point, 1) >= 0`, so the plane is `normal.dot(point) == -p.w`
+            let normal = p.xyz();
+            let length = normal.magnitude();
+            (length > EPSILON).then(|| Plane3 { normal: normal / length, distance: -p.w / length })
+        });
+        Frustum { planes: planes.collect() }
+    }
+
+    /// whether the point is inside or on the frustum
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|p| p.signed_distance(point) >= 0.0)
+    }
+
+    /// whether any of the sphere might be inside the frustum.
+    ///
+    /// spheres near the frustum's corners can be outside and still pass, which is fine for culling.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes.iter().all(|p| p.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    /// whether any of the box might be inside the frustum, with the same leeway as [`Frustum::intersects_sphere`]
+    pub fn intersects_aabb(&self, aabb: &Aabb3) -> bool {
+        self.classify_aabb(aabb) != Containment::Outside
+    }
+
+    /// whether the box is outside, inside or crossing the frustum's planes
+    pub fn classify_aabb(&self, aabb: &Aabb3) -> Containment {
+        let (center, half_extents) = (aabb.center(), aabb.half_extents());
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            let distance = plane.signed_distance(center);
+            let radius = half_extents.dot(plane.normal.abs());
+            if distance < -radius {
+                return Containment::Outside
+            }
+            if distance < radius {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// whether the sphere is outside, inside or crossing the frustum's planes
+    pub fn classify_sphere(&self, sphere: &Sphere) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            let distance = plane.signed_distance(sphere.center);
+            if distance < -sphere.radius {
+                return Containment::Outside
+            }
+            if distance < sphere.radius {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,17 @@
+pub mod aabb;
+pub mod broadphase;
+pub mod bvh;
+pub mod capsule;
+pub mod collision2d;
+pub mod collision3d;
+pub mod frustum;
+pub mod obb;
+pub mod plane;
+pub mod ray;
+pub mod segment;
+pub mod sphere;
+pub mod triangle;
+
+/// the tolerance used by queries that don't take one, for deciding when
+/// a direction is parallel to something or a shape is degenerate
+pub const EPSILON: f32 = 1e-6;
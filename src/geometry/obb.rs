@@ -0,0 +1,221 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::sphere::{Circle, Sphere};
+use crate::geometry::EPSILON;
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::matrix::Matrix;
+use crate::math::quat::Quat;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// An oriented bounding box in two dimensions.
+///
+/// `axes` are the unit directions of the box's local x and y, which should be perpendicular.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obb2 {
+    pub center: Vec2,
+    pub axes: [Vec2; 2],
+    pub half_extents: Vec2,
+}
+
+/// An oriented bounding box in three dimensions.
+///
+/// `axes` are the unit directions of the box's local x, y and z, which should be perpendicular.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obb3 {
+    pub center: Vec3,
+    pub axes: [Vec3; 3],
+    pub half_extents: Vec3,
+}
+
+impl Obb2 {
+    /// a box rotated counterclockwise by `angle` radians about its center
+    pub fn new(center: Vec2, half_extents: Vec2, angle: f32) -> Obb2 {
+        let (s, c) = angle.sin_cos();
+        Obb2 { center, axes: [Vec2(c, s), Vec2(-s, c)], half_extents }
+    }
+
+    #[inline(always)]
+    pub fn from_aabb(aabb: &Aabb2) -> Obb2 {
+        Obb2 { center: aabb.center(), axes: [Vec2::X, Vec2::Y], half_extents: aabb.half_extents() }
+    }
+
+    /// `point` in the box's local frame, relative to its center
+    #[inline(always)]
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        let d = point - self.center;
+        Vec2(d.dot(self.axes[0]), d.dot(self.axes[1]))
+    }
+
+    /// a point in the box's local frame back into world space
+    #[inline(always)]
+    pub fn from_local(&self, local: Vec2) -> Vec2 {
+        self.center + self.axes[0] * local.x + self.axes[1] * local.y
+    }
+
+    /// the corners, counterclockwise from the local `-x -y` corner
+    pub fn corners(&self) -> [Vec2; 4] {
+        let h = self.half_extents;
+        [Vec2(-h.x, -h.y), Vec2(h.x, -h.y), Vec2(h.x, h.y), Vec2(-h.x, h.y)].map(|c| self.from_local(c))
+    }
+
+    pub fn aabb(&self) -> Aabb2 {
+        let h = self.half_extents;
+        let half = self.axes[0].abs() * h.x + self.axes[1].abs() * h.y;
+        Aabb2::from_center_half_extents(self.center, half)
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        let local = self.to_local(point).abs();
+        local.x <= self.half_extents.x && local.y <= self.half_extents.y
+    }
+
+    /// the closest point in the box to `point`, which is `point` itself if it's inside
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        self.from_local(self.to_local(point).clamp(-self.half_extents, self.half_extents))
+    }
+
+    /// the distance from `point` to the box, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    #[inline(always)]
+    pub fn intersects_circle(&self, circle: &Circle) -> bool {
+        (self.closest_point(circle.center) - circle.center).magnitude_squared() <= circle.radius * circle.radius
+    }
+
+    /// whether the boxes overlap, touching counts, using the separating axis test
+    pub fn intersects_obb(&self, other: &Obb2) -> bool {
+        let d = other.center - self.center;
+        let radius = |obb: &Obb2, axis: Vec2| {
+            obb.axes[0].dot(axis).abs() * obb.half_extents.x + obb.axes[1].dot(axis).abs() * obb.half_extents.y
+        };
+        self.axes.iter().chain(other.axes.iter()).all(|&axis| {
+            d.dot(axis).abs() <= radius(self, axis) + radius(other, axis)
+        })
+    }
+
+    /// the box transformed by a 3x3 affine matrix, which should not shear it
+    pub fn transform(&self, m: &Matrix<f32>) -> Obb2 {
+        let affine = Affine2::from_matrix(m);
+        let x = affine.transform_vector2(self.axes[0]);
+        let y = affine.transform_vector2(self.axes[1]);
+        let (sx, sy) = (x.magnitude(), y.magnitude());
+        Obb2 {
+            center: affine.transform_point2(self.center),
+            axes: [x / sx, y / sy],
+            half_extents: Vec2(self.half_extents.x * sx, self.half_extents.y * sy),
+        }
+    }
+}
+
+impl Obb3 {
+    /// a box rotated by `rotation` about its center
+    pub fn new(center: Vec3, half_extents: Vec3, rotation: Quat) -> Obb3 {
+        Obb3 {
+            center,
+            axes: [rotation * Vec3::X, rotation * Vec3::Y, rotation * Vec3::Z],
+            half_extents,
+        }
+    }
+
+    #[inline(always)]
+    pub fn from_aabb(aabb: &Aabb3) -> Obb3 {
+        Obb3 { center: aabb.center(), axes: [Vec3::X, Vec3::Y, Vec3::Z], half_extents: aabb.half_extents() }
+    }
+
+    /// `point` in the box's local frame, relative to its center
+    #[inline(always)]
+    pub fn to_local(&self, point: Vec3) -> Vec3 {
+        let d = point - self.center;
+        Vec3(d.dot(self.axes[0]), d.dot(self.axes[1]), d.dot(self.axes[2]))
+    }
+
+    /// a point in the box's local frame back into world space
+    #[inline(always)]
+    pub fn from_local(&self, local: Vec3) -> Vec3 {
+        self.center + self.axes[0] * local.x + self.axes[1] * local.y + self.axes[2] * local.z
+    }
+
+    /// the corners, with bit 0 of the index choosing `+x`, bit 1 `+y` and bit 2 `+z`
+    pub fn corners(&self) -> [Vec3; 8] {
+        let h = self.half_extents;
+        std::array::from_fn(|i| {
+            let pick = |bit: usize, v: f32| if i & (1 << bit) != 0 { v } else { -v };
+            self.from_local(Vec3(pick(0, h.x), pick(1, h.y), pick(2, h.z)))
+        })
+    }
+
+    pub fn aabb(&self) -> Aabb3 {
+        let h = self.half_extents;
+        let half = self.axes[0].abs() * h.x + self.axes[1].abs() * h.y + self.axes[2].abs() * h.z;
+        Aabb3::from_center_half_extents(self.center, half)
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        let local = self.to_local(point).abs();
+        local.x <= self.half_extents.x && local.y <= self.half_extents.y && local.z <= self.half_extents.z
+    }
+
+    /// the closest point in the box to `point`, which is `point` itself if it's inside
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        self.from_local(self.to_local(point).clamp(-self.half_extents, self.half_extents))
+    }
+
+    /// the distance from `point` to the box, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    #[inline(always)]
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        (self.closest_point(sphere.center) - sphere.center).magnitude_squared() <= sphere.radius * sphere.radius
+    }
+
+    /// whether the boxes overlap, touching counts, using the separating axis test.
+    ///
+    /// the cross products of nearly parallel edges are skipped when shorter than `EPSILON`,
+    /// since the face axes already cover those cases.
+    pub fn intersects_obb(&self, other: &Obb3) -> bool {
+        let d = other.center - self.center;
+        let radius = |obb: &Obb3, axis: Vec3| {
+            obb.axes[0].dot(axis).abs() * obb.half_extents.x
+                + obb.axes[1].dot(axis).abs() * obb.half_extents.y
+                + obb.axes[2].dot(axis).abs() * obb.half_extents.z
+        };
+        let separated = |axis: Vec3| d.dot(axis).abs() > radius(self, axis) + radius(other, axis);
+
+        if self.axes.iter().chain(other.axes.iter()).any(|&axis| separated(axis)) {
+            return false
+        }
+        for a in self.axes {
+            for b in other.axes {
+                let axis = a.cross(b);
+                if axis.magnitude_squared() > EPSILON && separated(axis) {
+                    return false
+                }
+            }
+        }
+        true
+    }
+
+    /// the box transformed by a 4x4 affine matrix, which should not shear it
+    pub fn transform(&self, m: &Matrix<f32>) -> Obb3 {
+        let affine = Affine3::from_matrix(m);
+        let axes = self.axes.map(|axis| affine.transform_vector3(axis));
+        let scale = Vec3(axes[0].magnitude(), axes[1].magnitude(), axes[2].magnitude());
+        Obb3 {
+            center: affine.transform_point3(self.center),
+            axes: [axes[0] / scale.x, axes[1] / scale.y, axes[2] / scale.z],
+            half_extents: self.half_extents * scale,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::sphere::{Circle, Sphere};
+use crate::geometry::EPSILON;
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// An infinite line in two dimensions, the points `p` where `normal.dot(p) == distance`.
+///
+/// the normal is unit length and points to the positive side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane2 {
+    pub normal: Vec2,
+    pub distance: f32,
+}
+
+/// An infinite plane, the points `p` where `normal.dot(p) == distance`.
+///
+/// the normal is unit length and points to the positive side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane3 {
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+impl Plane2 {
+    /// the line through `point` perpendicular to `normal`, which need not be normalized
+    #[inline(always)]
+    pub fn from_point_normal(point: Vec2, normal: Vec2) -> Plane2 {
+        let normal = normal.normalize();
+        Plane2 { normal, distance: normal.dot(point) }
+    }
+
+    /// the line through `a` and `b`, with the positive side on the right going from `a` to `b`.
+    ///
+    /// returns none if the points are closer than `epsilon`.
+    pub fn from_points(a: Vec2, b: Vec2, epsilon: f32) -> Option<Plane2> {
+        let d = b - a;
+        if d.magnitude() <= epsilon {
+            return None
+        }
+        Some(Plane2::from_point_normal(a, Vec2(d.y, -d.x)))
+    }
+
+    /// the distance from the line, positive on the side the normal points to
+    #[inline(always)]
+    pub fn signed_distance(&self, point: Vec2) -> f32 {
+        self.normal.dot(point) - self.distance
+    }
+
+    /// the closest point on the line to `point`
+    #[inline(always)]
+    pub fn project_point(&self, point: Vec2) -> Vec2 {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// whether the circle touches the line
+    #[inline(always)]
+    pub fn intersects_circle(&self, circle: &Circle) -> bool {
+        self.signed_distance(circle.center).abs() <= circle.radius
+    }
+
+    /// whether the box touches the line
+    #[inline(always)]
+    pub fn intersects_aabb(&self, aabb: &Aabb2) -> bool {
+        let radius = aabb.half_extents().dot(self.normal.abs());
+        self.signed_distance(aabb.center()).abs() <= radius
+    }
+
+    /// the line transformed by a 3x3 affine matrix, panics if the matrix is singular
+    pub fn transform(&self, m: &Matrix<f32>) -> Plane2 {
+        let affine = Affine2::from_matrix(m);
+        // normals transform by the inverse transpose
+        let inverse = affine.inverse();
+        let normal = Vec2(inverse.x_axis.dot(self.normal), inverse.y_axis.dot(self.normal));
+        let point = affine.transform_point2(self.normal * self.distance);
+        Plane2::from_point_normal(point, normal)
+    }
+}
+
+impl Plane3 {
+    /// the plane through `point` perpendicular to `normal`, which need not be normalized
+    #[inline(always)]
+    pub fn from_point_normal(point: Vec3, normal: Vec3) -> Plane3 {
+        let normal = normal.normalize();
+        Plane3 { normal, distance: normal.dot(point) }
+    }
+
+    /// the plane through three points, facing the side they wind counterclockwise around.
+    ///
+    /// returns none if the points are collinear, within `epsilon`.
+    pub fn from_points(a: Vec3, b: Vec3, c: Vec3, epsilon: f32) -> Option<Plane3> {
+        let n = (b - a).cross(c - a);
+        if n.magnitude() <= epsilon {
+            return None
+        }
+        Some(Plane3::from_point_normal(a, n))
+    }
+
+    /// the distance from the plane, positive on the side the normal points to
+    #[inline(always)]
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) - self.distance
+    }
+
+    /// the closest point on the plane to `point`
+    #[inline(always)]
+    pub fn project_point(&self, point: Vec3) -> Vec3 {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// whether the sphere touches the plane
+    #[inline(always)]
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.signed_distance(sphere.center).abs() <= sphere.radius
+    }
+
+    /// whether the box touches the plane
+    #[inline(always)]
+    pub fn intersects_aabb(&self, aabb: &Aabb3) -> bool {
+        let radius = aabb.half_extents().dot(self.normal.abs());
+        self.signed_distance(aabb.center()).abs() <= radius
+    }
+
+    /// the line where two planes meet as a point and direction, none if they're parallel
+    pub fn intersect_plane(&self, other: &Plane3) -> Option<(Vec3, Vec3)> {
+        let direction = self.normal.cross(other.normal);
+        let denom = direction.magnitude_squared();
+        if denom <= EPSILON {
+            return None
+        }
+        let point = (other.normal * self.distance - self.normal * other.distance).cross(direction) / denom;
+        Some((point, direction.normalize()))
+    }
+
+    /// the plane transformed by a 4x4 affine matrix, panics if the matrix is singular
+    pub fn transform(&self, m: &Matrix<f32>) -> Plane3 {
+        let affine = Affine3::from_matrix(m);
+        // normals transform by the inverse transpose
+        let inverse = affine.inverse();
+        let normal = Vec3(
+            inverse.x_axis.dot(self.normal),
+            inverse.y_axis.dot(self.normal),
+            inverse.z_axis.dot(self.normal),
+        );
+        let point = affine.transform_point3(self.normal * self.distance);
+        Plane3::from_point_normal(point, normal)
+    }
+}
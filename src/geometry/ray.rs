@@ -0,0 +1,340 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::capsule::{Capsule2, Capsule3};
+use crate::geometry::obb::{Obb2, Obb3};
+use crate::geometry::plane::{Plane2, Plane3};
+use crate::geometry::segment::Segment2;
+use crate::geometry::sphere::{Circle, Sphere};
+use crate::geometry::triangle::{Triangle2, Triangle3};
+use crate::geometry::EPSILON;
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::deterministic::sqrt;
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// A half-infinite line in two dimensions, with a unit direction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray2 {
+    pub origin: Vec2,
+    pub direction: Vec2,
+}
+
+/// A half-infinite line in three dimensions, with a unit direction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray3 {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+/// Where a [`Ray2`] first hits a shape.
+///
+/// `t` is the distance along the ray and `normal` is the unit surface normal, facing the ray.
+/// a ray starting inside a solid shape hits it immediately, with `t = 0`
+/// and the normal pointing back along the ray.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit2 {
+    pub t: f32,
+    pub normal: Vec2,
+}
+
+/// Where a [`Ray3`] first hits a shape, see [`RayHit2`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit3 {
+    pub t: f32,
+    pub normal: Vec3,
+}
+
+/// the closer of two optional hits
+#[inline(always)]
+fn nearest<H: Copy>(a: Option<H>, b: Option<H>, t: impl Fn(&H) -> f32) -> Option<H> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if t(&a) <= t(&b) { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// the range of `t` where a ray is inside a box at the origin with the given half extents,
+/// and the axis it enters through
+fn slabs<const N: usize>(origin: [f32; N], direction: [f32; N], half: [f32; N]) -> Option<(f32, f32, usize)> {
+    let (mut near, mut far, mut axis) = (f32::NEG_INFINITY, f32::INFINITY, 0);
+    for i in 0..N {
+        if direction[i].abs() <= EPSILON {
+            // parallel to this slab, so either always or never inside it
+            if origin[i].abs() > half[i] {
+                return None
+            }
+            continue
+        }
+        let inv = 1.0 / direction[i];
+        let t1 = (-half[i] - origin[i]) * inv;
+        let t2 = (half[i] - origin[i]) * inv;
+        let (t1, t2) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if t1 > near {
+            near = t1;
+            axis = i;
+        }
+        far = far.min(t2);
+        if near > far {
+            return None
+        }
+    }
+    (far >= 0.0).then_some((near, far, axis))
+}
+
+impl Ray2 {
+    /// construct a new [`Ray2`], normalizing `direction`
+    #[inline(always)]
+    pub fn new(origin: Vec2, direction: Vec2) -> Ray2 {
+        Ray2 { origin, direction: direction.normalize() }
+    }
+
+    /// the point `t` along the ray
+    #[inline(always)]
+    pub fn at(&self, t: f32) -> Vec2 {
+        self.origin + self.direction * t
+    }
+
+    #[inline(always)]
+    fn inside(&self) -> RayHit2 {
+        RayHit2 { t: 0.0, normal: -self.direction }
+    }
+
+    pub fn intersect_aabb(&self, aabb: &Aabb2) -> Option<RayHit2> {
+        let (o, d, h) = (self.origin - aabb.center(), self.direction, aabb.half_extents());
+        let (near, _, axis) = slabs([o.x, o.y], [d.x, d.y], [h.x, h.y])?;
+        if near < 0.0 {
+            return Some(self.inside())
+        }
+        let mut normal = Vec2::ZERO;
+        normal[axis] = -d[axis].signum();
+        Some(RayHit2 { t: near, normal })
+    }
+
+    pub fn intersect_obb(&self, obb: &Obb2) -> Option<RayHit2> {
+        let o = obb.to_local(self.origin);
+        let d = Vec2(self.direction.dot(obb.axes[0]), self.direction.dot(obb.axes[1]));
+        let local = Ray2 { origin: o, direction: d };
+        let hit = local.intersect_aabb(&Aabb2::from_center_half_extents(Vec2::ZERO, obb.half_extents))?;
+        let normal = obb.axes[0] * hit.normal.x + obb.axes[1] * hit.normal.y;
+        Some(RayHit2 { t: hit.t, normal })
+    }
+
+    pub fn intersect_circle(&self, circle: &Circle) -> Option<RayHit2> {
+        let oc = self.origin - circle.center;
+        let b = oc.dot(self.direction);
+        let c = oc.dot(oc) - circle.radius * circle.radius;
+        if c <= 0.0 {
+            return Some(self.inside())
+        }
+        let disc = b * b - c;
+        if b > 0.0 || disc < 0.0 {
+            return None
+        }
+        let t = -b - sqrt(disc);
+        Some(RayHit2 { t, normal: (self.at(t) - circle.center) / circle.radius })
+    }
+
+    /// where the ray crosses the line, none if it's parallel or pointing away
+    pub fn intersect_plane(&self, plane: &Plane2) -> Option<RayHit2> {
+        let denom = plane.normal.dot(self.direction);
+        if denom.abs() <= EPSILON {
+            return None
+        }
+        let t = -plane.signed_distance(self.origin) / denom;
+        let normal = if denom < 0.0 { plane.normal } else { -plane.normal };
+        (t >= 0.0).then_some(RayHit2 { t, normal })
+    }
+
+    /// where the ray crosses the segment, none if it misses or is parallel
+    pub fn intersect_segment(&self, segment: &Segment2) -> Option<RayHit2> {
+        let e = segment.b - segment.a;
+        let denom = self.direction.perp_dot(e);
+        if denom.abs() <= EPSILON {
+            return None
+        }
+        let r = segment.a - self.origin;
+        let t = r.perp_dot(e) / denom;
+        let s = r.perp_dot(self.direction) / denom;
+        if t < 0.0 || !(0.0..=1.0).contains(&s) {
+            return None
+        }
+        let normal = e.perp().normalize();
+        let normal = if normal.dot(self.direction) > 0.0 { -normal } else { normal };
+        Some(RayHit2 { t, normal })
+    }
+
+    pub fn intersect_triangle(&self, triangle: &Triangle2) -> Option<RayHit2> {
+        if triangle.contains_point(self.origin) {
+            return Some(self.inside())
+        }
+        let Triangle2 { a, b, c } = *triangle;
+        [Segment2::new(a, b), Segment2::new(b, c), Segment2::new(c, a)]
+            .iter()
+            .fold(None, |best, edge| nearest(best, self.intersect_segment(edge), |h| h.t))
+    }
+
+    pub fn intersect_capsule(&self, capsule: &Capsule2) -> Option<RayHit2> {
+        if capsule.contains_point(self.origin) {
+            return Some(self.inside())
+        }
+        let Segment2 { a, b } = capsule.segment;
+        let mut best = nearest(
+            self.intersect_circle(&Circle::new(a, capsule.radius)),
+            self.intersect_circle(&Circle::new(b, capsule.radius)),
+            |h| h.t,
+        );
+        if let Some(side) = (b - a).perp().try_normalize() {
+            let offset = side * capsule.radius;
+            for edge in [Segment2::new(a + offset, b + offset), Segment2::new(a - offset, b - offset)] {
+                best = nearest(best, self.intersect_segment(&edge), |h| h.t);
+            }
+        }
+        best
+    }
+
+    /// the ray transformed by a 3x3 affine matrix, with the direction renormalized
+    pub fn transform(&self, m: &Matrix<f32>) -> Ray2 {
+        let affine = Affine2::from_matrix(m);
+        Ray2::new(affine.transform_point2(self.origin), affine.transform_vector2(self.direction))
+    }
+}
+
+impl Ray3 {
+    /// construct a new [`Ray3`], normalizing `direction`
+    #[inline(always)]
+    pub fn new(origin: Vec3, direction: Vec3) -> Ray3 {
+        Ray3 { origin, direction: direction.normalize() }
+    }
+
+    /// the point `t` along the ray
+    #[inline(always)]
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    #[inline(always)]
+    fn inside(&self) -> RayHit3 {
+        RayHit3 { t: 0.0, normal: -self.direction }
+    }
+
+    pub fn intersect_aabb(&self, aabb: &Aabb3) -> Option<RayHit3> {
+        let (o, d, h) = (self.origin - aabb.center(), self.direction, aabb.half_extents());
+        let (near, _, axis) = slabs([o.x, o.y, o.z], [d.x, d.y, d.z], [h.x, h.y, h.z])?;
+        if near < 0.0 {
+            return Some(self.inside())
+        }
+        let mut normal = Vec3::ZERO;
+        normal[axis] = -d[axis].signum();
+        Some(RayHit3 { t: near, normal })
+    }
+
+    pub fn intersect_obb(&self, obb: &Obb3) -> Option<RayHit3> {
+        let o = obb.to_local(self.origin);
+        let d = Vec3(
+            self.direction.dot(obb.axes[0]),
+            self.direction.dot(obb.axes[1]),
+            self.direction.dot(obb.axes[2]),
+        );
+        let local = Ray3 { origin: o, direction: d };
+        let hit = local.intersect_aabb(&Aabb3::from_center_half_extents(Vec3::ZERO, obb.half_extents))?;
+        let normal = obb.axes[0] * hit.normal.x + obb.axes[1] * hit.normal.y + obb.axes[2] * hit.normal.z;
+        Some(RayHit3 { t: hit.t, normal })
+    }
+
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<RayHit3> {
+        let oc = self.origin - sphere.center;
+        let b = oc.dot(self.direction);
+        let c = oc.dot(oc) - sphere.radius * sphere.radius;
+        if c <= 0.0 {
+            return Some(self.inside())
+        }
+        let disc = b * b - c;
+        if b > 0.0 || disc < 0.0 {
+            return None
+        }
+        let t = -b - sqrt(disc);
+        Some(RayHit3 { t, normal: (self.at(t) - sphere.center) / sphere.radius })
+    }
+
+    /// where the ray crosses the plane, none if it's parallel or pointing away
+    pub fn intersect_plane(&self, plane: &Plane3) -> Option<RayHit3> {
+        let denom = plane.normal.dot(self.direction);
+        if denom.abs() <= EPSILON {
+            return None
+        }
+        let t = -plane.signed_distance(self.origin) / denom;
+        let normal = if denom < 0.0 { plane.normal } else { -plane.normal };
+        (t >= 0.0).then_some(RayHit3 { t, normal })
+    }
+
+    /// where the ray hits either side of the triangle.
+    ///
+    /// rays within `epsilon` of parallel to the triangle, and degenerate triangles, never hit.
+    pub fn intersect_triangle(&self, triangle: &Triangle3, epsilon: f32) -> Option<RayHit3> {
+        let (e1, e2) = (triangle.b - triangle.a, triangle.c - triangle.a);
+        let p = self.direction.cross(e2);
+        let det = e1.dot(p);
+        if det.abs() <= epsilon {
+            return None
+        }
+
+        let inv = 1.0 / det;
+        let s = self.origin - triangle.a;
+        let u = s.dot(p) * inv;
+        if !(0.0..=1.0).contains(&u) {
+            return None
+        }
+        let q = s.cross(e1);
+        let v = self.direction.dot(q) * inv;
+        if v < 0.0 || u + v > 1.0 {
+            return None
+        }
+        let t = e2.dot(q) * inv;
+        if t < 0.0 {
+            return None
+        }
+
+        // det is positive when the ray hits the counterclockwise side
+        let normal = e1.cross(e2).normalize();
+        let normal = if det > 0.0 { normal } else { -normal };
+        Some(RayHit3 { t, normal })
+    }
+
+    pub fn intersect_capsule(&self, capsule: &Capsule3) -> Option<RayHit3> {
+        if capsule.contains_point(self.origin) {
+            return Some(self.inside())
+        }
+        let (a, b, r) = (capsule.segment.a, capsule.segment.b, capsule.radius);
+        let caps = nearest(
+            self.intersect_sphere(&Sphere::new(a, r)),
+            self.intersect_sphere(&Sphere::new(b, r)),
+            |h| h.t,
+        );
+
+        // the side of the cylinder between the caps
+        let (ba, oa) = (b - a, self.origin - a);
+        let baba = ba.dot(ba);
+        let bard = ba.dot(self.direction);
+        let baoa = ba.dot(oa);
+        let qa = baba - bard * bard;
+        let qb = baba * self.direction.dot(oa) - baoa * bard;
+        let qc = baba * oa.dot(oa) - baoa * baoa - r * r * baba;
+        let h = qb * qb - qa * qc;
+        if qa <= EPSILON || h < 0.0 {
+            return caps
+        }
+        let t = (-qb - sqrt(h)) / qa;
+        let y = baoa + t * bard;
+        if t < 0.0 || y <= 0.0 || y >= baba {
+            return caps
+        }
+        let normal = (oa + self.direction * t - ba * (y / baba)) / r;
+        nearest(caps, Some(RayHit3 { t, normal }), |h| h.t)
+    }
+
+    /// the ray transformed by a 4x4 affine matrix, with the direction renormalized
+    pub fn transform(&self, m: &Matrix<f32>) -> Ray3 {
+        let affine = Affine3::from_matrix(m);
+        Ray3::new(affine.transform_point3(self.origin), affine.transform_vector3(self.direction))
+    }
+}
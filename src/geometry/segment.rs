@@ -0,0 +1,201 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::EPSILON;
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// A line segment between two points in two dimensions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment2 {
+    pub a: Vec2,
+    pub b: Vec2,
+}
+
+/// A line segment between two points in three dimensions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment3 {
+    pub a: Vec3,
+    pub b: Vec3,
+}
+
+/// the parameters `(s, t)` of the closest points between the segments `p1 + d1 * s`
+/// and `p2 + d2 * t`, given `r = p1 - p2` and the dot products between them.
+///
+/// segments shorter than `epsilon` are treated as points.
+fn closest_parameters(dd1: f32, dd2: f32, d1d2: f32, d1r: f32, d2r: f32, epsilon: f32) -> (f32, f32) {
+    let eps = epsilon * epsilon;
+    if dd1 <= eps && dd2 <= eps {
+        return (0.0, 0.0)
+    }
+    if dd1 <= eps {
+        return (0.0, (d2r / dd2).clamp(0.0, 1.0))
+    }
+    if dd2 <= eps {
+        return ((-d1r / dd1).clamp(0.0, 1.0), 0.0)
+    }
+
+    // parallel segments have no unique answer, so start from the first ones start
+    let denom = dd1 * dd2 - d1d2 * d1d2;
+    let mut s = if denom > eps * dd1 * dd2 {
+        ((d1d2 * d2r - d1r * dd2) / denom).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let mut t = (d1d2 * s + d2r) / dd2;
+    if t < 0.0 {
+        t = 0.0;
+        s = (-d1r / dd1).clamp(0.0, 1.0);
+    } else if t > 1.0 {
+        t = 1.0;
+        s = ((d1d2 - d1r) / dd1).clamp(0.0, 1.0);
+    }
+    (s, t)
+}
+
+impl Segment2 {
+    #[inline(always)]
+    pub const fn new(a: Vec2, b: Vec2) -> Segment2 {
+        Segment2 { a, b }
+    }
+
+    #[inline(always)]
+    pub fn length(&self) -> f32 {
+        self.a.distance(self.b)
+    }
+
+    /// the point `t` of the way from `a` to `b`
+    #[inline(always)]
+    pub fn at(&self, t: f32) -> Vec2 {
+        self.a.lerp(self.b, t)
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::new(self.a.min(self.b), self.a.max(self.b))
+    }
+
+    /// how far along the segment the closest point to `point` is, from 0 at `a` to 1 at `b`
+    pub fn closest_parameter(&self, point: Vec2) -> f32 {
+        let d = self.b - self.a;
+        let len2 = d.magnitude_squared();
+        if len2 <= EPSILON * EPSILON {
+            return 0.0
+        }
+        ((point - self.a).dot(d) / len2).clamp(0.0, 1.0)
+    }
+
+    /// the closest point on the segment to `point`
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        self.at(self.closest_parameter(point))
+    }
+
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// the closest pair of points between two segments, the first on this segment.
+    ///
+    /// segments shorter than `epsilon` are treated as points.
+    pub fn closest_points(&self, other: &Segment2, epsilon: f32) -> (Vec2, Vec2) {
+        let (d1, d2, r) = (self.b - self.a, other.b - other.a, self.a - other.a);
+        let (s, t) = closest_parameters(
+            d1.dot(d1), d2.dot(d2), d1.dot(d2), d1.dot(r), d2.dot(r), epsilon,
+        );
+        (self.at(s), other.at(t))
+    }
+
+    #[inline(always)]
+    pub fn distance_to_segment(&self, other: &Segment2) -> f32 {
+        let (p, q) = self.closest_points(other, EPSILON);
+        p.distance(q)
+    }
+
+    /// the point where two segments cross, none if they don't or if they're parallel
+    pub fn intersection(&self, other: &Segment2) -> Option<Vec2> {
+        let (d1, d2, r) = (self.b - self.a, other.b - other.a, other.a - self.a);
+        let denom = d1.perp_dot(d2);
+        if denom.abs() <= EPSILON {
+            return None
+        }
+        let s = r.perp_dot(d2) / denom;
+        let t = r.perp_dot(d1) / denom;
+        ((0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&t)).then(|| self.at(s))
+    }
+
+    /// the segment transformed by a 3x3 affine matrix
+    pub fn transform(&self, m: &Matrix<f32>) -> Segment2 {
+        let affine = Affine2::from_matrix(m);
+        Segment2::new(affine.transform_point2(self.a), affine.transform_point2(self.b))
+    }
+}
+
+impl Segment3 {
+    #[inline(always)]
+    pub const fn new(a: Vec3, b: Vec3) -> Segment3 {
+        Segment3 { a, b }
+    }
+
+    #[inline(always)]
+    pub fn length(&self) -> f32 {
+        self.a.distance(self.b)
+    }
+
+    /// the point `t` of the way from `a` to `b`
+    #[inline(always)]
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.a.lerp(self.b, t)
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb3 {
+        Aabb3::new(self.a.min(self.b), self.a.max(self.b))
+    }
+
+    /// how far along the segment the closest point to `point` is, from 0 at `a` to 1 at `b`
+    pub fn closest_parameter(&self, point: Vec3) -> f32 {
+        let d = self.b - self.a;
+        let len2 = d.magnitude_squared();
+        if len2 <= EPSILON * EPSILON {
+            return 0.0
+        }
+        ((point - self.a).dot(d) / len2).clamp(0.0, 1.0)
+    }
+
+    /// the closest point on the segment to `point`
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        self.at(self.closest_parameter(point))
+    }
+
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// the closest pair of points between two segments, the first on this segment.
+    ///
+    /// segments shorter than `epsilon` are treated as points.
+    pub fn closest_points(&self, other: &Segment3, epsilon: f32) -> (Vec3, Vec3) {
+        let (d1, d2, r) = (self.b - self.a, other.b - other.a, self.a - other.a);
+        let (s, t) = closest_parameters(
+            d1.dot(d1), d2.dot(d2), d1.dot(d2), d1.dot(r), d2.dot(r), epsilon,
+        );
+        (self.at(s), other.at(t))
+    }
+
+    #[inline(always)]
+    pub fn distance_to_segment(&self, other: &Segment3) -> f32 {
+        let (p, q) = self.closest_points(other, EPSILON);
+        p.distance(q)
+    }
+
+    /// the segment transformed by a 4x4 affine matrix
+    pub fn transform(&self, m: &Matrix<f32>) -> Segment3 {
+        let affine = Affine3::from_matrix(m);
+        Segment3::new(affine.transform_point3(self.a), affine.transform_point3(self.b))
+    }
+}
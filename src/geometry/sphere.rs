@@ -0,0 +1,145 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// A solid circle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+/// A solid sphere
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl Circle {
+    #[inline(always)]
+    pub const fn new(center: Vec2, radius: f32) -> Circle {
+        Circle { center, radius }
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::from_center_half_extents(self.center, Vec2::splat(self.radius))
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        (point - self.center).magnitude_squared() <= self.radius * self.radius
+    }
+
+    /// whether `other` is entirely inside this circle
+    #[inline(always)]
+    pub fn contains_circle(&self, other: &Circle) -> bool {
+        self.radius >= other.radius
+            && self.center.distance(other.center) + other.radius <= self.radius
+    }
+
+    /// whether the circles overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_circle(&self, other: &Circle) -> bool {
+        let r = self.radius + other.radius;
+        (self.center - other.center).magnitude_squared() <= r * r
+    }
+
+    /// whether the circle and box overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_aabb(&self, aabb: &Aabb2) -> bool {
+        aabb.distance_squared_to_point(self.center) <= self.radius * self.radius
+    }
+
+    /// the closest point in the circle to `point`, which is `point` itself if it's inside
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        let offset = point - self.center;
+        if offset.magnitude_squared() <= self.radius * self.radius {
+            return point
+        }
+        self.center + offset.normalize() * self.radius
+    }
+
+    /// the distance from `point` to the circle, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        (self.center.distance(point) - self.radius).max(0.0)
+    }
+
+    /// the circle transformed by a 3x3 affine matrix.
+    ///
+    /// non-uniform scale would make an ellipse, so the radius grows by the largest scale instead.
+    pub fn transform(&self, m: &Matrix<f32>) -> Circle {
+        let affine = Affine2::from_matrix(m);
+        let scale = affine.x_axis.magnitude().max(affine.y_axis.magnitude());
+        Circle::new(affine.transform_point2(self.center), self.radius * scale)
+    }
+}
+
+impl Sphere {
+    #[inline(always)]
+    pub const fn new(center: Vec3, radius: f32) -> Sphere {
+        Sphere { center, radius }
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb3 {
+        Aabb3::from_center_half_extents(self.center, Vec3::splat(self.radius))
+    }
+
+    /// whether `point` is inside or on the boundary
+    #[inline(always)]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        (point - self.center).magnitude_squared() <= self.radius * self.radius
+    }
+
+    /// whether `other` is entirely inside this sphere
+    #[inline(always)]
+    pub fn contains_sphere(&self, other: &Sphere) -> bool {
+        self.radius >= other.radius
+            && self.center.distance(other.center) + other.radius <= self.radius
+    }
+
+    /// whether the spheres overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_sphere(&self, other: &Sphere) -> bool {
+        let r = self.radius + other.radius;
+        (self.center - other.center).magnitude_squared() <= r * r
+    }
+
+    /// whether the sphere and box overlap, touching counts
+    #[inline(always)]
+    pub fn intersects_aabb(&self, aabb: &Aabb3) -> bool {
+        aabb.distance_squared_to_point(self.center) <= self.radius * self.radius
+    }
+
+    /// the closest point in the sphere to `point`, which is `point` itself if it's inside
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let offset = point - self.center;
+        if offset.magnitude_squared() <= self.radius * self.radius {
+            return point
+        }
+        self.center + offset.normalize() * self.radius
+    }
+
+    /// the distance from `point` to the sphere, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        (self.center.distance(point) - self.radius).max(0.0)
+    }
+
+    /// the sphere transformed by a 4x4 affine matrix.
+    ///
+    /// non-uniform scale would make an ellipsoid, so the radius grows by the largest scale instead.
+    pub fn transform(&self, m: &Matrix<f32>) -> Sphere {
+        let affine = Affine3::from_matrix(m);
+        let scale = affine.x_axis.magnitude()
+            .max(affine.y_axis.magnitude())
+            .max(affine.z_axis.magnitude());
+        Sphere::new(affine.transform_point3(self.center), self.radius * scale)
+    }
+}
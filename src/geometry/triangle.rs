@@ -0,0 +1,209 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::plane::Plane3;
+use crate::geometry::EPSILON;
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// A solid triangle in two dimensions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle2 {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub c: Vec2,
+}
+
+/// A triangle in three dimensions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle3 {
+    pub a: Vec3,
+    pub b: Vec3,
+    pub c: Vec3,
+}
+
+impl Triangle2 {
+    #[inline(always)]
+    pub const fn new(a: Vec2, b: Vec2, c: Vec2) -> Triangle2 {
+        Triangle2 { a, b, c }
+    }
+
+    /// the area, positive if the corners wind counterclockwise
+    #[inline(always)]
+    pub fn signed_area(&self) -> f32 {
+        (self.b - self.a).perp_dot(self.c - self.a) * 0.5
+    }
+
+    #[inline(always)]
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+
+    #[inline(always)]
+    pub fn centroid(&self) -> Vec2 {
+        (self.a + self.b + self.c) / 3.0
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::new(self.a.min(self.b).min(self.c), self.a.max(self.b).max(self.c))
+    }
+
+    /// the barycentric weights of `point` for `a`, `b` and `c`,
+    /// none if the triangle's area is within `epsilon` of zero
+    pub fn barycentric(&self, point: Vec2, epsilon: f32) -> Option<Vec3> {
+        let area = (self.b - self.a).perp_dot(self.c - self.a);
+        if area.abs() <= epsilon {
+            return None
+        }
+        let u = (self.b - point).perp_dot(self.c - point) / area;
+        let v = (self.c - point).perp_dot(self.a - point) / area;
+        Some(Vec3(u, v, 1.0 - u - v))
+    }
+
+    /// whether `point` is inside or on the boundary, for either winding
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        let d1 = (self.b - self.a).perp_dot(point - self.a);
+        let d2 = (self.c - self.b).perp_dot(point - self.b);
+        let d3 = (self.a - self.c).perp_dot(point - self.c);
+        let negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(negative && positive)
+    }
+
+    /// the closest point in the triangle to `point`, which is `point` itself if it's inside
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        if self.contains_point(point) {
+            return point
+        }
+        let (a, b, c) = (self.a.extend(0.0), self.b.extend(0.0), self.c.extend(0.0));
+        closest_point_on_triangle(a, b, c, point.extend(0.0)).xy()
+    }
+
+    /// the distance from `point` to the triangle, zero if it's inside
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// the triangle transformed by a 3x3 affine matrix
+    pub fn transform(&self, m: &Matrix<f32>) -> Triangle2 {
+        let affine = Affine2::from_matrix(m);
+        Triangle2::new(
+            affine.transform_point2(self.a),
+            affine.transform_point2(self.b),
+            affine.transform_point2(self.c),
+        )
+    }
+}
+
+impl Triangle3 {
+    #[inline(always)]
+    pub const fn new(a: Vec3, b: Vec3, c: Vec3) -> Triangle3 {
+        Triangle3 { a, b, c }
+    }
+
+    /// the unit normal on the side the corners wind counterclockwise around,
+    /// none if the triangle is degenerate
+    #[inline(always)]
+    pub fn normal(&self) -> Option<Vec3> {
+        (self.b - self.a).cross(self.c - self.a).try_normalize()
+    }
+
+    #[inline(always)]
+    pub fn area(&self) -> f32 {
+        (self.b - self.a).cross(self.c - self.a).magnitude() * 0.5
+    }
+
+    #[inline(always)]
+    pub fn centroid(&self) -> Vec3 {
+        (self.a + self.b + self.c) / 3.0
+    }
+
+    #[inline(always)]
+    pub fn aabb(&self) -> Aabb3 {
+        Aabb3::new(self.a.min(self.b).min(self.c), self.a.max(self.b).max(self.c))
+    }
+
+    /// the plane the triangle lies in, none if the triangle is degenerate
+    #[inline(always)]
+    pub fn plane(&self) -> Option<Plane3> {
+        Plane3::from_points(self.a, self.b, self.c, EPSILON)
+    }
+
+    /// the barycentric weights for `a`, `b` and `c` of `point` projected onto the triangle's plane,
+    /// none if the triangle's area is within `epsilon` of zero
+    pub fn barycentric(&self, point: Vec3, epsilon: f32) -> Option<Vec3> {
+        let (v0, v1, v2) = (self.b - self.a, self.c - self.a, point - self.a);
+        let (d00, d01, d11) = (v0.dot(v0), v0.dot(v1), v1.dot(v1));
+        let (d20, d21) = (v2.dot(v0), v2.dot(v1));
+        let denom = d00 * d11 - d01 * d01;
+        if denom.abs() <= epsilon * epsilon {
+            return None
+        }
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+        Some(Vec3(1.0 - v - w, v, w))
+    }
+
+    /// the closest point on the triangle to `point`
+    #[inline(always)]
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        closest_point_on_triangle(self.a, self.b, self.c, point)
+    }
+
+    #[inline(always)]
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// the triangle transformed by a 4x4 affine matrix
+    pub fn transform(&self, m: &Matrix<f32>) -> Triangle3 {
+        let affine = Affine3::from_matrix(m);
+        Triangle3::new(
+            affine.transform_point3(self.a),
+            affine.transform_point3(self.b),
+            affine.transform_point3(self.c),
+        )
+    }
+}
+
+/// the closest point on the triangle `abc` to `p`, by finding which voronoi region `p` is in
+fn closest_point_on_triangle(a: Vec3, b: Vec3, c: Vec3, p: Vec3) -> Vec3 {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a
+    }
+
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return b
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3))
+    }
+
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return c
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6))
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)))
+    }
+
+    // inside the face, degenerate triangles end up in one of the regions above
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
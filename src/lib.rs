@@ -1,7 +1,31 @@
 #![feature(portable_simd)]
+pub mod app;
+pub mod camera;
+pub mod ecs;
+pub mod geometry;
+pub mod particles;
+pub mod physics;
+pub mod render;
+pub mod scene;
 pub mod math {
+    pub mod affine;
+    pub mod curves;
+    pub mod deterministic;
+    pub mod dvec;
+    pub mod ivec;
     pub mod matrix;
     pub mod matrix_simd;
+    pub mod projection;
+    pub mod half;
+    pub mod quantize;
+    pub mod quat;
+    pub mod random;
+    pub mod strategy;
+    pub mod tuning;
+    pub mod uvec;
     pub mod vec2;
     pub mod vec3;
+    pub mod vec4;
+    pub mod vec_convert;
+    pub mod vec_ops;
 }
\ No newline at end of file
@@ -1,8 +1,98 @@
 #![feature(portable_simd)]
 
+pub mod app;
+pub mod camera;
+pub mod ecs;
+pub mod geometry;
+pub mod particles;
 pub mod math;
+pub mod physics;
+pub mod render;
+pub mod scene;
 mod tests;
 
-fn main() {
+use app::{App, Exit, Runner, Time};
+use ecs::schedule::System;
+use math::random::Pcg32;
+use math::vec2::Vec2;
+
+#[derive(Clone, Copy, Debug)]
+struct Position(Vec2);
+
+#[derive(Clone, Copy, Debug)]
+struct Velocity(Vec2);
+
+/// how many times anything has hit the floor
+#[derive(Default)]
+struct Bounces(u64);
+
+const GRAVITY: Vec2 = Vec2(0.0, -9.81);
+
+/// balls dropped into a box, bouncing until the simulation has run for `seconds`
+fn scene(seconds: f64) -> App {
+    let mut app = App::new();
+    let mut rng = Pcg32::new(7);
+    for _ in 0..1000 {
+        let position = Vec2(rng.range(-10.0, 10.0), rng.range(1.0, 20.0));
+        let velocity = Vec2(rng.range(-2.0, 2.0), 0.0);
+        app.world.spawn((Position(position), Velocity(velocity)));
+    }
+    app.world.insert_resource(Bounces::default());
 
-}
\ No newline at end of file
+    app.add_fixed_system(System::new("gravity", |cx| {
+        let dt = cx.resource::<Time>().unwrap().step;
+        cx.query::<&mut Velocity>().for_each(|mut v| v.0 += GRAVITY * dt);
+    }).write::<Velocity>().read_resource::<Time>());
+
+    app.add_fixed_system(System::new("integrate", |cx| {
+        let dt = cx.resource::<Time>().unwrap().step;
+        let mut bounces = 0;
+        cx.query::<(&mut Position, &mut Velocity)>().for_each(|(mut p, mut v)| {
+            p.0 += v.0 * dt;
+            if p.0.y < 0.0 && v.0.y < 0.0 {
+                p.0.y = -p.0.y;
+                v.0.y *= -0.8;
+                bounces += 1;
+            }
+            if p.0.x.abs() > 10.0 {
+                p.0.x = p.0.x.clamp(-10.0, 10.0);
+                v.0.x = -v.0.x;
+            }
+        });
+        cx.resource_mut::<Bounces>().unwrap().0 += bounces;
+    }).write::<Position>().write::<Velocity>().write_resource::<Bounces>().read_resource::<Time>().after("gravity"));
+
+    let mut next_report = 1.0;
+    app.add_system(System::new("report", move |cx| {
+        let time = *cx.resource::<Time>().unwrap();
+        if time.elapsed >= next_report || time.elapsed >= seconds {
+            next_report = time.elapsed.floor() + 1.0;
+            let mut height = 0.0;
+            cx.query::<&Position>().for_each(|p| height += p.0.y);
+            let bounces = cx.resource::<Bounces>().unwrap().0;
+            println!(
+                "t = {:5.2}s  ticks {:5}  frames {:5}  mean height {:6.3}  bounces {}",
+                time.elapsed, time.ticks, time.frames, height / cx.world().len() as f32, bounces,
+            );
+        }
+        if time.elapsed >= seconds {
+            cx.commands().push(|world| { world.insert_resource(Exit); });
+        }
+    }).read::<Position>().read_resource::<Time>().read_resource::<Bounces>());
+
+    app
+}
+
+/// `project [--headless ticks]`, which runs the sample scene for a few seconds in real time,
+/// or for a number of ticks as fast as possible
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => scene(3.0).run(Runner::Realtime),
+        [flag, ticks] if flag == "--headless" => match ticks.parse() {
+            Ok(ticks) => scene(f64::INFINITY).run(Runner::Headless { ticks }),
+            Err(_) => eprintln!("invalid tick count: {ticks:?}"),
+        },
+        _ => eprintln!("usage: project [--headless ticks]"),
+    }
+}
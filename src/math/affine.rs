@@ -0,0 +1,376 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::{Mul, MulAssign};
+use crate::math::matrix::Matrix;
+use crate::math::quat::Quat;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// A two-dimensional affine transform, a 2x2 linear part followed by a translation.
+///
+/// stored as the columns of the linear part, so `x_axis` is where [`Vec2::X`] ends up.
+/// positive angles rotate counterclockwise, `a * b` applies `b` first and then `a`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Affine2 {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub translation: Vec2,
+}
+
+/// A three-dimensional affine transform, a 3x3 linear part followed by a translation.
+///
+/// stored as the columns of the linear part, so `x_axis` is where [`Vec3::X`] ends up.
+/// the coordinate system is right handed, and views built with [`Affine3::look_at`]
+/// look down the negative z-axis with y up, to match the projections on [`Matrix`].
+/// `a * b` applies `b` first and then `a`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Affine3 {
+    pub x_axis: Vec3,
+    pub y_axis: Vec3,
+    pub z_axis: Vec3,
+    pub translation: Vec3,
+}
+
+impl Affine2 {
+    /// the transform which does nothing
+    pub const IDENTITY: Affine2 = Affine2 { x_axis: Vec2::X, y_axis: Vec2::Y, translation: Vec2::ZERO };
+
+    /// construct a transform from the columns of its linear part and a translation
+    #[inline(always)]
+    pub const fn from_cols(x_axis: Vec2, y_axis: Vec2, translation: Vec2) -> Affine2 {
+        Affine2 { x_axis, y_axis, translation }
+    }
+
+    /// a transform which moves points by `translation`
+    #[inline(always)]
+    pub const fn from_translation(translation: Vec2) -> Affine2 {
+        Affine2::from_cols(Vec2::X, Vec2::Y, translation)
+    }
+
+    /// a counterclockwise rotation of `angle` radians about the origin
+    #[inline(always)]
+    pub fn from_angle(angle: f32) -> Affine2 {
+        let (s, c) = angle.sin_cos();
+        Affine2::from_cols(Vec2(c, s), Vec2(-s, c), Vec2::ZERO)
+    }
+
+    /// a non-uniform scale about the origin
+    #[inline(always)]
+    pub const fn from_scale(scale: Vec2) -> Affine2 {
+        Affine2::from_cols(Vec2(scale.x, 0.0), Vec2(0.0, scale.y), Vec2::ZERO)
+    }
+
+    /// a shear, where `x` is how far x moves per unit of y, and `y` how far y moves per unit of x
+    #[inline(always)]
+    pub const fn from_shear(x: f32, y: f32) -> Affine2 {
+        Affine2::from_cols(Vec2(1.0, y), Vec2(x, 1.0), Vec2::ZERO)
+    }
+
+    /// scale, then rotate, then translate
+    pub fn from_scale_angle_translation(scale: Vec2, angle: f32, translation: Vec2) -> Affine2 {
+        let (s, c) = angle.sin_cos();
+        Affine2::from_cols(Vec2(c, s) * scale.x, Vec2(-s, c) * scale.y, translation)
+    }
+
+    /// the 3x3 homogeneous matrix for this transform
+    pub fn to_matrix(&self) -> Matrix<f32> {
+        let Affine2 { x_axis: x, y_axis: y, translation: t } = *self;
+        Matrix::from_vec(3, 3, vec![
+            x.x, y.x, t.x,
+            x.y, y.y, t.y,
+            0.0, 0.0, 1.0,
+        ])
+    }
+
+    /// the transform for a 3x3 homogeneous matrix, ignoring the bottom row
+    pub fn from_matrix(m: &Matrix<f32>) -> Affine2 {
+        assert!(m.is_3x3());
+        let m = &m.data;
+        Affine2::from_cols(Vec2(m[0], m[3]), Vec2(m[1], m[4]), Vec2(m[2], m[5]))
+    }
+
+    /// the determinant of the linear part, negative if the transform mirrors
+    #[inline(always)]
+    pub fn determinant(&self) -> f32 {
+        self.x_axis.perp_dot(self.y_axis)
+    }
+
+    /// transform a point, applying translation
+    #[inline(always)]
+    pub fn transform_point2(&self, point: Vec2) -> Vec2 {
+        self.transform_vector2(point) + self.translation
+    }
+
+    /// transform a direction, ignoring translation
+    #[inline(always)]
+    pub fn transform_vector2(&self, direction: Vec2) -> Vec2 {
+        self.x_axis * direction.x + self.y_axis * direction.y
+    }
+
+    /// the inverse transform, panics if the linear part is singular
+    pub fn inverse(&self) -> Affine2 {
+        let det = self.determinant();
+        assert!(det != 0.0 && det.is_finite());
+        let inv_det = 1.0 / det;
+        let x_axis = Vec2(self.y_axis.y, -self.x_axis.y) * inv_det;
+        let y_axis = Vec2(-self.y_axis.x, self.x_axis.x) * inv_det;
+        let linear = Affine2::from_cols(x_axis, y_axis, Vec2::ZERO);
+        Affine2::from_cols(x_axis, y_axis, -linear.transform_vector2(self.translation))
+    }
+
+    /// the inverse of a transform made only of rotation and translation,
+    /// cheaper than [`Affine2::inverse`] but wrong if there is any scale or shear
+    pub fn inverse_rigid(&self) -> Affine2 {
+        let x_axis = Vec2(self.x_axis.x, self.y_axis.x);
+        let y_axis = Vec2(self.x_axis.y, self.y_axis.y);
+        let linear = Affine2::from_cols(x_axis, y_axis, Vec2::ZERO);
+        Affine2::from_cols(x_axis, y_axis, -linear.transform_vector2(self.translation))
+    }
+
+    /// split into the scale, angle and translation given to [`Affine2::from_scale_angle_translation`].
+    ///
+    /// a mirroring transform gets a negative x scale, shear can't be represented and is lost.
+    pub fn to_scale_angle_translation(&self) -> (Vec2, f32, Vec2) {
+        let sign = self.determinant().signum();
+        let scale = Vec2(self.x_axis.magnitude() * sign, self.y_axis.magnitude());
+        let x_axis = self.x_axis / scale.x;
+        (scale, x_axis.y.atan2(x_axis.x), self.translation)
+    }
+}
+
+impl Affine3 {
+    /// the transform which does nothing
+    pub const IDENTITY: Affine3 = Affine3 {
+        x_axis: Vec3::X,
+        y_axis: Vec3::Y,
+        z_axis: Vec3::Z,
+        translation: Vec3::ZERO,
+    };
+
+    /// construct a transform from the columns of its linear part and a translation
+    #[inline(always)]
+    pub const fn from_cols(x_axis: Vec3, y_axis: Vec3, z_axis: Vec3, translation: Vec3) -> Affine3 {
+        Affine3 { x_axis, y_axis, z_axis, translation }
+    }
+
+    /// a transform which moves points by `translation`
+    #[inline(always)]
+    pub const fn from_translation(translation: Vec3) -> Affine3 {
+        Affine3::from_cols(Vec3::X, Vec3::Y, Vec3::Z, translation)
+    }
+
+    /// a rotation about the origin
+    #[inline(always)]
+    pub fn from_quat(rotation: Quat) -> Affine3 {
+        Affine3::from_cols(rotation * Vec3::X, rotation * Vec3::Y, rotation * Vec3::Z, Vec3::ZERO)
+    }
+
+    /// a rotation of `angle` radians about `axis`, through the origin
+    #[inline(always)]
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Affine3 {
+        Affine3::from_quat(Quat::from_axis_angle(axis, angle))
+    }
+
+    /// a non-uniform scale about the origin
+    #[inline(always)]
+    pub const fn from_scale(scale: Vec3) -> Affine3 {
+        Affine3::from_cols(
+            Vec3(scale.x, 0.0, 0.0),
+            Vec3(0.0, scale.y, 0.0),
+            Vec3(0.0, 0.0, scale.z),
+            Vec3::ZERO,
+        )
+    }
+
+    /// a shear, where `xy` is how far x moves per unit of y, `xz` how far x moves per unit of z,
+    /// and so on for the other pairs
+    #[inline(always)]
+    pub const fn from_shear(xy: f32, xz: f32, yx: f32, yz: f32, zx: f32, zy: f32) -> Affine3 {
+        Affine3::from_cols(Vec3(1.0, yx, zx), Vec3(xy, 1.0, zy), Vec3(xz, yz, 1.0), Vec3::ZERO)
+    }
+
+    /// scale, then rotate, then translate
+    pub fn from_scale_rotation_translation(scale: Vec3, rotation: Quat, translation: Vec3) -> Affine3 {
+        Affine3::from_cols(
+            rotation * Vec3::X * scale.x,
+            rotation * Vec3::Y * scale.y,
+            rotation * Vec3::Z * scale.z,
+            translation,
+        )
+    }
+
+    /// a view transform for a camera at `eye` looking at `target`.
+    ///
+    /// the result maps world space into view space, where the camera looks down
+    /// the negative z-axis and `up` is as close to the positive y-axis as it can be.
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Affine3 {
+        Affine3::look_to(eye, target - eye, up)
+    }
+
+    /// a view transform for a camera at `eye` looking along `direction`, see [`Affine3::look_at`]
+    pub fn look_to(eye: Vec3, direction: Vec3, up: Vec3) -> Affine3 {
+        let f = direction.normalize();
+        let s = f.cross(up).normalize();
+        let u = s.cross(f);
+        Affine3::from_cols(
+            Vec3(s.x, u.x, -f.x),
+            Vec3(s.y, u.y, -f.y),
+            Vec3(s.z, u.z, -f.z),
+            Vec3(-s.dot(eye), -u.dot(eye), f.dot(eye)),
+        )
+    }
+
+    /// the 4x4 homogeneous matrix for this transform
+    pub fn to_matrix(&self) -> Matrix<f32> {
+        let Affine3 { x_axis: x, y_axis: y, z_axis: z, translation: t } = *self;
+        Matrix::from_vec(4, 4, vec![
+            x.x, y.x, z.x, t.x,
+            x.y, y.y, z.y, t.y,
+            x.z, y.z, z.z, t.z,
+            0.0, 0.0, 0.0, 1.0,
+        ])
+    }
+
+    /// the transform for a 4x4 homogeneous matrix, ignoring the bottom row
+    pub fn from_matrix(m: &Matrix<f32>) -> Affine3 {
+        assert!(m.is_4x4());
+        let m = &m.data;
+        Affine3::from_cols(
+            Vec3(m[0], m[4], m[8]),
+            Vec3(m[1], m[5], m[9]),
+            Vec3(m[2], m[6], m[10]),
+            Vec3(m[3], m[7], m[11]),
+        )
+    }
+
+    /// the determinant of the linear part, negative if the transform mirrors
+    #[inline(always)]
+    pub fn determinant(&self) -> f32 {
+        self.x_axis.dot(self.y_axis.cross(self.z_axis))
+    }
+
+    /// transform a point, applying translation
+    #[inline(always)]
+    pub fn transform_point3(&self, point: Vec3) -> Vec3 {
+        self.transform_vector3(point) + self.translation
+    }
+
+    /// transform a direction, ignoring translation
+    #[inline(always)]
+    pub fn transform_vector3(&self, direction: Vec3) -> Vec3 {
+        self.x_axis * direction.x + self.y_axis * direction.y + self.z_axis * direction.z
+    }
+
+    /// the inverse transform, panics if the linear part is singular
+    pub fn inverse(&self) -> Affine3 {
+        let det = self.determinant();
+        assert!(det != 0.0 && det.is_finite());
+
+        // the rows of the inverse are the cross products of the columns
+        let inv_det = 1.0 / det;
+        let r0 = self.y_axis.cross(self.z_axis) * inv_det;
+        let r1 = self.z_axis.cross(self.x_axis) * inv_det;
+        let r2 = self.x_axis.cross(self.y_axis) * inv_det;
+        Affine3::from_rows(r0, r1, r2, self.translation)
+    }
+
+    /// the inverse of a transform made only of rotation and translation,
+    /// cheaper than [`Affine3::inverse`] but wrong if there is any scale or shear
+    pub fn inverse_rigid(&self) -> Affine3 {
+        Affine3::from_rows(self.x_axis, self.y_axis, self.z_axis, self.translation)
+    }
+
+    /// the transform with the given rows for its linear part, undoing `translation`
+    fn from_rows(r0: Vec3, r1: Vec3, r2: Vec3, translation: Vec3) -> Affine3 {
+        Affine3::from_cols(
+            Vec3(r0.x, r1.x, r2.x),
+            Vec3(r0.y, r1.y, r2.y),
+            Vec3(r0.z, r1.z, r2.z),
+            -Vec3(r0.dot(translation), r1.dot(translation), r2.dot(translation)),
+        )
+    }
+
+    /// split into the scale, rotation and translation given to [`Affine3::from_scale_rotation_translation`].
+    ///
+    /// a mirroring transform gets a negative x scale, shear can't be represented and is lost.
+    pub fn to_scale_rotation_translation(&self) -> (Vec3, Quat, Vec3) {
+        let sign = self.determinant().signum();
+        let scale = Vec3(
+            self.x_axis.magnitude() * sign,
+            self.y_axis.magnitude(),
+            self.z_axis.magnitude(),
+        );
+        let (x, y, z) = (self.x_axis / scale.x, self.y_axis / scale.y, self.z_axis / scale.z);
+        let rotation = Quat::from_matrix(&Matrix::from_vec(3, 3, vec![
+            x.x, y.x, z.x,
+            x.y, y.y, z.y,
+            x.z, y.z, z.z,
+        ]));
+        (scale, rotation, self.translation)
+    }
+}
+
+impl Default for Affine2 {
+    fn default() -> Self {
+        Affine2::IDENTITY
+    }
+}
+
+impl Default for Affine3 {
+    fn default() -> Self {
+        Affine3::IDENTITY
+    }
+}
+
+impl Debug for Affine2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Affine2 { x_axis: x, y_axis: y, translation: t } = self;
+        writeln!(f, "[{}, {}, {}]", x.x, y.x, t.x)?;
+        writeln!(f, "[{}, {}, {}]", x.y, y.y, t.y)
+    }
+}
+
+impl Debug for Affine3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Affine3 { x_axis: x, y_axis: y, z_axis: z, translation: t } = self;
+        writeln!(f, "[{}, {}, {}, {}]", x.x, y.x, z.x, t.x)?;
+        writeln!(f, "[{}, {}, {}, {}]", x.y, y.y, z.y, t.y)?;
+        writeln!(f, "[{}, {}, {}, {}]", x.z, y.z, z.z, t.z)
+    }
+}
+
+impl Mul for Affine2 {
+    type Output = Affine2;
+
+    fn mul(self, rhs: Affine2) -> Self::Output {
+        Affine2::from_cols(
+            self.transform_vector2(rhs.x_axis),
+            self.transform_vector2(rhs.y_axis),
+            self.transform_point2(rhs.translation),
+        )
+    }
+}
+
+impl MulAssign for Affine2 {
+    fn mul_assign(&mut self, rhs: Affine2) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul for Affine3 {
+    type Output = Affine3;
+
+    fn mul(self, rhs: Affine3) -> Self::Output {
+        Affine3::from_cols(
+            self.transform_vector3(rhs.x_axis),
+            self.transform_vector3(rhs.y_axis),
+            self.transform_vector3(rhs.z_axis),
+            self.transform_point3(rhs.translation),
+        )
+    }
+}
+
+impl MulAssign for Affine3 {
+    fn mul_assign(&mut self, rhs: Affine3) {
+        *self = *self * rhs;
+    }
+}
@@ -0,0 +1,440 @@
+use std::f32::consts::PI;
+use std::ops::{Add, Mul, Sub};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+
+/// Anything that curves can be built from, implemented for `f32`, [`Vec2`], [`Vec3`] and [`Vec4`]
+pub trait CurvePoint: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self> {
+    fn dot(self, other: Self) -> f32;
+
+    #[inline(always)]
+    fn distance(self, other: Self) -> f32 {
+        let d = self - other;
+        crate::math::deterministic::sqrt(d.dot(d))
+    }
+}
+
+impl CurvePoint for f32 {
+    #[inline(always)]
+    fn dot(self, other: f32) -> f32 {
+        self * other
+    }
+}
+
+impl CurvePoint for Vec2 {
+    #[inline(always)]
+    fn dot(self, other: Vec2) -> f32 {
+        Vec2::dot(self, other)
+    }
+}
+
+impl CurvePoint for Vec3 {
+    #[inline(always)]
+    fn dot(self, other: Vec3) -> f32 {
+        Vec3::dot(self, other)
+    }
+}
+
+impl CurvePoint for Vec4 {
+    #[inline(always)]
+    fn dot(self, other: Vec4) -> f32 {
+        Vec4::dot(self, other)
+    }
+}
+
+/// linearly interpolate between `a` and `b`, where `t = 0` gives `a` and `t = 1` gives `b`
+#[inline(always)]
+pub fn lerp<P: CurvePoint>(a: P, b: P, t: f32) -> P {
+    a + (b - a) * t
+}
+
+/// the `t` for which `lerp(a, b, t)` gives `value`
+#[inline(always)]
+pub fn inverse_lerp(a: f32, b: f32, value: f32) -> f32 {
+    (value - a) / (b - a)
+}
+
+/// map `value` from the range `from` to the range `to`, without clamping
+#[inline(always)]
+pub fn remap(value: f32, from: (f32, f32), to: (f32, f32)) -> f32 {
+    lerp(to.0, to.1, inverse_lerp(from.0, from.1, value))
+}
+
+/// a smooth step from 0 to 1 as `x` goes from `edge0` to `edge1`, clamped outside the edges
+#[inline(always)]
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = inverse_lerp(edge0, edge1, x).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// like [`smoothstep`] but with zero second derivative at the edges too
+#[inline(always)]
+pub fn smootherstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = inverse_lerp(edge0, edge1, x).clamp(0.0, 1.0);
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// The standard easing families.
+///
+/// every family maps 0 to 0 and 1 to 1, with `ease_in` starting slowly,
+/// `ease_out` finishing slowly and `ease_in_out` doing both.
+/// [`Ease::Back`] and [`Ease::Elastic`] overshoot outside `0..=1` on the way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ease {
+    Linear,
+    Quad,
+    Cubic,
+    Quart,
+    Quint,
+    Sine,
+    Expo,
+    Circ,
+    Back,
+    Elastic,
+    Bounce,
+}
+
+impl Ease {
+    /// the ease starting slowly, `t` is clamped to `0..=1`
+    pub fn ease_in(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::Quad => t * t,
+            Ease::Cubic => t * t * t,
+            Ease::Quart => t * t * t * t,
+            Ease::Quint => t * t * t * t * t,
+            Ease::Sine => 1.0 - (t * PI * 0.5).cos(),
+            Ease::Expo => if t == 0.0 { 0.0 } else { 2f32.powf(10.0 * t - 10.0) },
+            Ease::Circ => 1.0 - (1.0 - t * t).sqrt(),
+            Ease::Back => {
+                const C1: f32 = 1.70158;
+                (C1 + 1.0) * t * t * t - C1 * t * t
+            }
+            Ease::Elastic => {
+                if t == 0.0 || t == 1.0 {
+                    return t
+                }
+                let c4 = 2.0 * PI / 3.0;
+                -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * c4).sin()
+            }
+            Ease::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+
+    /// the ease finishing slowly, the mirror image of [`Ease::ease_in`]
+    pub fn ease_out(self, t: f32) -> f32 {
+        1.0 - self.ease_in(1.0 - t)
+    }
+
+    /// the ease starting and finishing slowly, [`Ease::ease_in`] for the first
+    /// half and [`Ease::ease_out`] for the second
+    pub fn ease_in_out(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        if t < 0.5 {
+            self.ease_in(2.0 * t) * 0.5
+        } else {
+            0.5 + self.ease_out(2.0 * t - 1.0) * 0.5
+        }
+    }
+}
+
+/// the usual bounce, which is defined going out rather than in
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// A parametric curve, where `t` runs from 0 at the start to 1 at the end
+pub trait Curve<P: CurvePoint> {
+    /// the point at `t`
+    fn point(&self, t: f32) -> P;
+
+    /// the derivative with respect to `t` at `t`
+    fn derivative(&self, t: f32) -> P;
+}
+
+/// A quadratic Bézier curve, passing through `p0` and `p2` and pulled towards `p1`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuadraticBezier<P> {
+    pub p0: P,
+    pub p1: P,
+    pub p2: P,
+}
+
+/// A cubic Bézier curve, passing through `p0` and `p3` and pulled towards `p1` and `p2`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier<P> {
+    pub p0: P,
+    pub p1: P,
+    pub p2: P,
+    pub p3: P,
+}
+
+/// A cubic Hermite curve from `p0` to `p1`, with tangents `m0` and `m1` at each end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hermite<P> {
+    pub p0: P,
+    pub m0: P,
+    pub p1: P,
+    pub m1: P,
+}
+
+/// A uniform Catmull-Rom spline passing through every point,
+/// with each segment taking an equal share of `t`.
+///
+/// the end points are repeated to give the first and last segments their neighbours.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatmullRom<P> {
+    pub points: Vec<P>,
+}
+
+/// A uniform cubic B-spline, which is smooth but doesn't generally pass through its control points.
+///
+/// `n` control points make `n - 3` segments, each taking an equal share of `t`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BSpline<P> {
+    pub points: Vec<P>,
+}
+
+impl<P: CurvePoint> Curve<P> for QuadraticBezier<P> {
+    fn point(&self, t: f32) -> P {
+        let u = 1.0 - t;
+        self.p0 * (u * u) + self.p1 * (2.0 * u * t) + self.p2 * (t * t)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        (self.p1 - self.p0) * (2.0 * (1.0 - t)) + (self.p2 - self.p1) * (2.0 * t)
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for CubicBezier<P> {
+    fn point(&self, t: f32) -> P {
+        let u = 1.0 - t;
+        self.p0 * (u * u * u)
+            + self.p1 * (3.0 * u * u * t)
+            + self.p2 * (3.0 * u * t * t)
+            + self.p3 * (t * t * t)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        let u = 1.0 - t;
+        (self.p1 - self.p0) * (3.0 * u * u)
+            + (self.p2 - self.p1) * (6.0 * u * t)
+            + (self.p3 - self.p2) * (3.0 * t * t)
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for Hermite<P> {
+    fn point(&self, t: f32) -> P {
+        let (t2, t3) = (t * t, t * t * t);
+        self.p0 * (2.0 * t3 - 3.0 * t2 + 1.0)
+            + self.m0 * (t3 - 2.0 * t2 + t)
+            + self.p1 * (-2.0 * t3 + 3.0 * t2)
+            + self.m1 * (t3 - t2)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        let t2 = t * t;
+        self.p0 * (6.0 * t2 - 6.0 * t)
+            + self.m0 * (3.0 * t2 - 4.0 * t + 1.0)
+            + self.p1 * (-6.0 * t2 + 6.0 * t)
+            + self.m1 * (3.0 * t2 - 2.0 * t)
+    }
+}
+
+/// split `t` over `count` equal segments, giving the segment and the local `t` within it
+#[inline(always)]
+fn segment(t: f32, count: usize) -> (usize, f32) {
+    let scaled = t.clamp(0.0, 1.0) * count as f32;
+    let index = (scaled as usize).min(count - 1);
+    (index, scaled - index as f32)
+}
+
+impl<P: CurvePoint> CatmullRom<P> {
+    /// construct a spline through `points`, needs at least two
+    pub fn new(points: Vec<P>) -> CatmullRom<P> {
+        assert!(points.len() >= 2);
+        CatmullRom { points }
+    }
+
+    /// the four points around segment `i`, repeating the ends
+    fn window(&self, i: usize) -> (P, P, P, P) {
+        let last = self.points.len() - 1;
+        let at = |j: isize| self.points[j.clamp(0, last as isize) as usize];
+        let i = i as isize;
+        (at(i - 1), at(i), at(i + 1), at(i + 2))
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for CatmullRom<P> {
+    fn point(&self, t: f32) -> P {
+        let (i, t) = segment(t, self.points.len() - 1);
+        let (p0, p1, p2, p3) = self.window(i);
+        let (t2, t3) = (t * t, t * t * t);
+        (p1 * 2.0
+            + (p2 - p0) * t
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3) * 0.5
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        let count = self.points.len() - 1;
+        let (i, t) = segment(t, count);
+        let (p0, p1, p2, p3) = self.window(i);
+        let local = ((p2 - p0)
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * (2.0 * t)
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * (3.0 * t * t)) * 0.5;
+        local * count as f32
+    }
+}
+
+impl<P: CurvePoint> BSpline<P> {
+    /// construct a spline from control `points`, needs at least four
+    pub fn new(points: Vec<P>) -> BSpline<P> {
+        assert!(points.len() >= 4);
+        BSpline { points }
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for BSpline<P> {
+    fn point(&self, t: f32) -> P {
+        let (i, t) = segment(t, self.points.len() - 3);
+        let p = &self.points[i..i + 4];
+        let (u, t2, t3) = (1.0 - t, t * t, t * t * t);
+        (p[0] * (u * u * u)
+            + p[1] * (3.0 * t3 - 6.0 * t2 + 4.0)
+            + p[2] * (-3.0 * t3 + 3.0 * t2 + 3.0 * t + 1.0)
+            + p[3] * t3) * (1.0 / 6.0)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        let count = self.points.len() - 3;
+        let (i, t) = segment(t, count);
+        let p = &self.points[i..i + 4];
+        let (u, t2) = (1.0 - t, t * t);
+        let local = (p[0] * (-3.0 * u * u)
+            + p[1] * (9.0 * t2 - 12.0 * t)
+            + p[2] * (-9.0 * t2 + 6.0 * t + 3.0)
+            + p[3] * (3.0 * t2)) * (1.0 / 6.0);
+        local * count as f32
+    }
+}
+
+/// A table of distance along a curve, for moving along it at a constant speed.
+///
+/// the curve is approximated by straight lines between evenly spaced values of `t`,
+/// so more samples give a more accurate result.
+#[derive(Clone, Debug)]
+pub struct ArcLength {
+    /// the distance along the curve at each sample, starting from zero
+    lengths: Vec<f32>,
+}
+
+impl ArcLength {
+    /// measure `curve` using `samples` straight segments
+    pub fn new<P: CurvePoint, C: Curve<P>>(curve: &C, samples: usize) -> ArcLength {
+        assert!(samples > 0);
+        let mut lengths = Vec::with_capacity(samples + 1);
+        let mut previous = curve.point(0.0);
+        let mut total = 0.0;
+        lengths.push(0.0);
+
+        for i in 1..=samples {
+            let point = curve.point(i as f32 / samples as f32);
+            total += point.distance(previous);
+            lengths.push(total);
+            previous = point;
+        }
+
+        ArcLength { lengths }
+    }
+
+    /// the total length of the curve
+    #[inline(always)]
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().unwrap()
+    }
+
+    /// the `t` which is `distance` along the curve, clamped to either end
+    pub fn parameter_at(&self, distance: f32) -> f32 {
+        let samples = self.lengths.len() - 1;
+        if distance <= 0.0 {
+            return 0.0
+        }
+        if distance >= self.length() {
+            return 1.0
+        }
+
+        // the first sample past the distance, then interpolate within its segment
+        let i = self.lengths.partition_point(|&l| l < distance).max(1);
+        let (a, b) = (self.lengths[i - 1], self.lengths[i]);
+        let local = if b > a { (distance - a) / (b - a) } else { 0.0 };
+        (i as f32 - 1.0 + local) / samples as f32
+    }
+
+    /// the `t` which is `fraction` of the way along the curve by distance
+    #[inline(always)]
+    pub fn parameter_at_fraction(&self, fraction: f32) -> f32 {
+        self.parameter_at(fraction * self.length())
+    }
+}
+
+/// the `t` of the point on `curve` closest to `target`.
+///
+/// the curve is sampled `samples` times to find the nearest region, then refined by
+/// bisecting on where the direction to `target` becomes perpendicular to the curve.
+pub fn closest_point<P: CurvePoint, C: Curve<P>>(curve: &C, target: P, samples: usize) -> f32 {
+    assert!(samples > 0);
+    let distance = |t: f32| {
+        let d = curve.point(t) - target;
+        d.dot(d)
+    };
+    // half the derivative of the squared distance, which is zero at the closest point
+    let slope = |t: f32| (curve.point(t) - target).dot(curve.derivative(t));
+
+    let mut best = 0;
+    let mut best_distance = f32::INFINITY;
+    for i in 0..=samples {
+        let d = distance(i as f32 / samples as f32);
+        if d < best_distance {
+            best = i;
+            best_distance = d;
+        }
+    }
+
+    let step = 1.0 / samples as f32;
+    let mut lo = (best as f32 - 1.0).max(0.0) * step;
+    let mut hi = (best as f32 + 1.0).min(samples as f32) * step;
+
+    if slope(lo) < 0.0 && slope(hi) > 0.0 {
+        for _ in 0..32 {
+            let mid = (lo + hi) * 0.5;
+            if slope(mid) < 0.0 {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        return (lo + hi) * 0.5
+    }
+
+    // no minimum inside the bracket, so it's at one of the ends or the best sample
+    [lo, hi, best as f32 * step]
+        .into_iter()
+        .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+        .unwrap()
+}
@@ -0,0 +1,88 @@
+/// the square root used by vector types.
+///
+/// this is [`soft_sqrt`] with the `deterministic` feature, and [`f32::sqrt`] otherwise,
+/// so that lockstep simulations get identical magnitudes on every client.
+#[inline(always)]
+pub fn sqrt(x: f32) -> f32 {
+    #[cfg(feature = "deterministic")]
+    {
+        soft_sqrt(x)
+    }
+
+    #[cfg(not(feature = "deterministic"))]
+    {
+        x.sqrt()
+    }
+}
+
+/// a correctly rounded square root computed with integer arithmetic only,
+/// so the result is the same on every platform and compiler.
+pub fn soft_sqrt(x: f32) -> f32 {
+    let bits = x.to_bits();
+
+    // zero, nan and infinity
+    if x == 0.0 || x.is_nan() || x == f32::INFINITY {
+        return x
+    }
+    if bits >> 31 == 1 {
+        return f32::NAN
+    }
+
+    let mut exp = ((bits >> 23) & 0xff) as i32;
+    let mut man = bits & 0x7f_ffff;
+
+    // normalize subnormal inputs
+    if exp == 0 {
+        exp = 1;
+        while man & 0x80_0000 == 0 {
+            man <<= 1;
+            exp -= 1;
+        }
+    } else {
+        man |= 0x80_0000;
+    }
+
+    // x = man * 2^(exp - 150), make the exponent even so it can be halved
+    let mut e = exp - 127;
+    let mut m = man as u64;
+    if e & 1 != 0 {
+        m <<= 1;
+        e -= 1;
+    }
+
+    // 24 bit root of a 47 or 48 bit radicand
+    let radicand = m << 23;
+    let mut root = isqrt(radicand);
+    let rem = radicand - root * root;
+
+    // the root can never be exactly halfway between two integers
+    if rem > root {
+        root += 1;
+    }
+
+    // a rounded up root of 2^24 carries into the exponent
+    f32::from_bits((((e / 2 + 126) as u32) << 23) + root as u32)
+}
+
+/// the integer square root of `n`, rounded down
+fn isqrt(n: u64) -> u64 {
+    let mut rem = n;
+    let mut root = 0u64;
+    let mut bit = 1u64 << 62;
+
+    while bit > n {
+        bit >>= 2;
+    }
+
+    while bit != 0 {
+        if rem >= root + bit {
+            rem -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    root
+}
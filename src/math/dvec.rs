@@ -0,0 +1,16 @@
+use crate::math::vec_ops::{define_vec2, define_vec3, impl_vec3_cross, impl_vec_float, impl_vec_signed};
+
+define_vec2!(
+    /// A two-dimensional vector of `f64`, for positions in large worlds
+    DVec2, f64
+);
+impl_vec_signed!(DVec2, f64, { x, y });
+impl_vec_float!(DVec2, f64, { x, y });
+
+define_vec3!(
+    /// A three-dimensional vector of `f64`, for positions in large worlds
+    DVec3, f64
+);
+impl_vec_signed!(DVec3, f64, { x, y, z });
+impl_vec_float!(DVec3, f64, { x, y, z });
+impl_vec3_cross!(DVec3);
@@ -0,0 +1,142 @@
+use crate::math::matrix::Matrix;
+
+/// 16 bit floating point formats, stored as raw bits
+/// and converted to `f32` for arithmetic.
+pub trait HalfFloat: Default + Clone + Copy {
+    /// convert from `f32`, rounding to the nearest representable value (ties to even)
+    fn from_f32(value: f32) -> Self;
+
+    /// convert to `f32`, this is always exact
+    fn to_f32(self) -> f32;
+}
+
+/// an IEEE 754 binary16 value
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct F16(pub u16);
+
+/// a bfloat16 value, the upper half of an `f32`
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Bf16(pub u16);
+
+impl HalfFloat for F16 {
+    fn from_f32(value: f32) -> Self {
+        let x = value.to_bits();
+        let sign = ((x >> 16) & 0x8000) as u16;
+        let exp = ((x >> 23) & 0xff) as i32;
+        let man = x & 0x7f_ffff;
+
+        // infinity and nan, keeping nan quiet
+        if exp == 0xff {
+            let nan = if man != 0 { 0x200 | (man >> 13) as u16 } else { 0 };
+            return F16(sign | 0x7c00 | nan)
+        }
+
+        let e = exp - 127 + 15;
+
+        // too large, round to infinity
+        if e >= 0x1f {
+            return F16(sign | 0x7c00)
+        }
+
+        // subnormal in f16, or too small to represent at all
+        if e <= 0 {
+            if e < -10 {
+                return F16(sign)
+            }
+            let m = man | 0x80_0000;
+            let shift = (14 - e) as u32;
+            let halfway = 1 << (shift - 1);
+            let rem = m & ((1 << shift) - 1);
+            let mut r = m >> shift;
+            if rem > halfway || (rem == halfway && r & 1 == 1) {
+                r += 1;
+            }
+            return F16(sign | r as u16)
+        }
+
+        // a carry out of the mantissa correctly bumps the exponent
+        let mut r = ((e as u32) << 10) | (man >> 13);
+        let rem = man & 0x1fff;
+        if rem > 0x1000 || (rem == 0x1000 && r & 1 == 1) {
+            r += 1;
+        }
+        F16(sign | r as u16)
+    }
+
+    fn to_f32(self) -> f32 {
+        let h = self.0;
+        let sign = ((h & 0x8000) as u32) << 16;
+        let exp = ((h >> 10) & 0x1f) as u32;
+        let man = (h & 0x3ff) as u32;
+
+        let bits = match (exp, man) {
+            (0, 0) => sign,
+            (0, _) => {
+                // renormalize a subnormal value
+                let mut e = 127 - 15 + 1;
+                let mut m = man;
+                while m & 0x400 == 0 {
+                    m <<= 1;
+                    e -= 1;
+                }
+                sign | (e << 23) | ((m & 0x3ff) << 13)
+            }
+            (0x1f, _) => sign | 0x7f80_0000 | (man << 13),
+            _ => sign | ((exp + 127 - 15) << 23) | (man << 13),
+        };
+        f32::from_bits(bits)
+    }
+}
+
+impl HalfFloat for Bf16 {
+    fn from_f32(value: f32) -> Self {
+        let x = value.to_bits();
+        if value.is_nan() {
+            return Bf16((x >> 16) as u16 | 0x40)
+        }
+        let rounding = 0x7fff + ((x >> 16) & 1);
+        Bf16((x.wrapping_add(rounding) >> 16) as u16)
+    }
+
+    fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+}
+
+/// a row major matrix of 16 bit floats.
+///
+/// this is a storage format, arithmetic is done by widening to `f32`.
+#[derive(Clone, Debug)]
+pub struct HalfMatrix<H: HalfFloat> {
+    pub data: Vec<H>,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl<H: HalfFloat> HalfMatrix<H> {
+    /// narrow an `f32` matrix to 16 bits per value
+    pub fn from_f32(matrix: &Matrix<f32>) -> HalfMatrix<H> {
+        HalfMatrix {
+            data: matrix.data.iter().map(|&v| H::from_f32(v)).collect(),
+            rows: matrix.rows,
+            cols: matrix.cols,
+        }
+    }
+
+    /// widen back to an `f32` matrix
+    pub fn to_f32(&self) -> Matrix<f32> {
+        Matrix::from_vec(
+            self.rows,
+            self.cols,
+            self.data.iter().map(|h| h.to_f32()).collect()
+        )
+    }
+}
+
+/// multiplies two half precision matrices, accumulating in `f32`.
+pub fn hgemm<H: HalfFloat>(a: &HalfMatrix<H>, b: &HalfMatrix<H>) -> Matrix<f32> {
+    assert_eq!(a.cols, b.rows);
+
+    // widening both sides once is cheaper than converting inside the loop
+    a.to_f32().gemm(b.to_f32())
+}
@@ -0,0 +1,14 @@
+use crate::math::vec_ops::{define_vec2, define_vec3, impl_vec3_cross, impl_vec_signed};
+
+define_vec2!(
+    /// A two-dimensional vector of `i32`, for things like tile coordinates
+    IVec2, i32
+);
+impl_vec_signed!(IVec2, i32, { x, y });
+
+define_vec3!(
+    /// A three-dimensional vector of `i32`, for things like voxel coordinates
+    IVec3, i32
+);
+impl_vec_signed!(IVec3, i32, { x, y, z });
+impl_vec3_cross!(IVec3);
@@ -1,6 +1,12 @@
 use std::cmp::max;
 use std::ops::{Add, AddAssign, Mul, Sub};
+use rayon::prelude::*;
 use crate::math::matrix_simd::MatrixSimd;
+use crate::math::strategy::{MatmulPlan, MatmulStrategy};
+use crate::math::tuning::MatmulTuning;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
 
 #[derive(Clone, Debug)]
 pub struct Matrix<T>
@@ -455,7 +461,65 @@ where
         res
     }
 
-    /// multiplies two matrices by Strassen's algorithm
+    /// multiplies two matrices by the standard
+    /// General Matrix-Matrix Multiplication algorithm,
+    /// working on `block` x `block` tiles so that each tile stays in cache.
+    pub fn gemm_blocked(&self, rhs: Matrix<T>, block: usize) -> Matrix<T> {
+        assert_eq!(self.cols, rhs.rows);
+        assert!(block > 0);
+
+        let mut res = Matrix::from_vec(
+            self.rows,
+            rhs.cols,
+            vec![T::default(); self.rows * rhs.cols],
+        );
+
+        for ii in (0..self.rows).step_by(block) {
+            for kk in (0..self.cols).step_by(block) {
+                for jj in (0..rhs.cols).step_by(block) {
+                    for i in ii..(ii + block).min(self.rows) {
+                        for k in kk..(kk + block).min(self.cols) {
+                            let aik = self.data[i * self.cols + k];
+                            for j in jj..(jj + block).min(rhs.cols) {
+                                let bkj = rhs.data[k * rhs.cols + j];
+                                res.data[i * res.cols + j] += aik * bkj;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// multiplies two matrices with a bitwise reproducible result.
+    ///
+    /// every element is summed from zero in order of increasing `k`,
+    /// with no fused multiply-add, regardless of size or element type.
+    /// the SIMD kernels and Strassen's algorithm sum in different orders,
+    /// so their floating point results can differ in the last bits.
+    pub fn mul_deterministic(&self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, rhs.rows);
+
+        let mut res = Matrix::new(self.rows, rhs.cols);
+
+        for i in 0..self.rows {
+            for j in 0..rhs.cols {
+                let mut acc = T::default();
+                for k in 0..self.cols {
+                    acc += self.data[i * self.cols + k] * rhs.data[k * rhs.cols + j];
+                }
+                res.data[i * res.cols + j] = acc;
+            }
+        }
+        res
+    }
+
+    /// multiplies two matrices by Strassen's algorithm,
+    /// padding both to `size`, which must be a power of two
+    /// no smaller than any dimension of either matrix.
+    ///
+    /// [`Matrix::strassen_with_cutoff`] chooses the padding itself.
     pub fn strassen(&self, rhs: Matrix<T>, size: usize) -> Matrix<T> {
         let pad_self = self.pad_to_size(size);
         let pad_rhs = rhs.pad_to_size(size);
@@ -498,6 +562,241 @@ where
 
         Matrix::from_vec(rows, cols, data)
     }
+
+    /// multiplies two matrices by Strassen's algorithm,
+    /// recursing until the sub-matrices are at most `cutoff` wide.
+    ///
+    /// the sub-matrices at the bottom of the recursion are multiplied
+    /// with [`Matrix::gemm`] or [`Matrix::gemm_blocked`], as chosen by the installed tuning.
+    pub fn strassen_with_cutoff(&self, rhs: &Matrix<T>, cutoff: usize) -> Matrix<T> {
+        assert_eq!(self.cols, rhs.rows);
+        assert!(cutoff > 0);
+
+        self.strassen_tuned(rhs, cutoff, &MatmulTuning::current::<T>())
+    }
+
+    /// [`Matrix::strassen_with_cutoff`] with the tuning already read
+    fn strassen_tuned(&self, rhs: &Matrix<T>, cutoff: usize, tuning: &MatmulTuning) -> Matrix<T> {
+        let size = self.padded_size(rhs);
+        let padded = Self::strassen_padded(
+            &self.pad_to_size(size),
+            &rhs.pad_to_size(size),
+            cutoff,
+            tuning
+        );
+
+        // remove padding
+        let mut data = Vec::with_capacity(self.rows * rhs.cols);
+        for i in 0..self.rows {
+            for j in 0..rhs.cols {
+                data.push(*padded.get(i, j).unwrap());
+            }
+        }
+
+        Matrix::from_vec(self.rows, rhs.cols, data)
+    }
+
+    /// one level of [`Matrix::strassen_with_cutoff`] on square, power of two matrices
+    fn strassen_padded(a: &Matrix<T>, b: &Matrix<T>, cutoff: usize, tuning: &MatmulTuning) -> Matrix<T> {
+        if a.rows <= cutoff {
+            return if a.rows <= tuning.gemm_max {
+                a.gemm(b.clone())
+            } else {
+                a.gemm_blocked(b.clone(), tuning.block_size)
+            }
+        }
+
+        let (a11, a12, a21, a22) = a.quarters();
+        let (b11, b12, b21, b22) = b.quarters();
+
+        let p1 = Self::strassen_padded(&(a11.clone() + a22.clone()), &(b11.clone() + b22.clone()), cutoff, tuning);
+        let p2 = Self::strassen_padded(&(a21.clone() + a22.clone()), &b11, cutoff, tuning);
+        let p3 = Self::strassen_padded(&a11, &(b12.clone() - b22.clone()), cutoff, tuning);
+        let p4 = Self::strassen_padded(&a22, &(b21.clone() - b11.clone()), cutoff, tuning);
+        let p5 = Self::strassen_padded(&(a11.clone() + a12.clone()), &b22, cutoff, tuning);
+        let p6 = Self::strassen_padded(&(a21 - a11), &(b11 + b12), cutoff, tuning);
+        let p7 = Self::strassen_padded(&(a12 - a22), &(b21 + b22), cutoff, tuning);
+
+        let q0 = p1.clone() + p4.clone() - p5.clone() + p7;
+        let q1 = p3.clone() + p5;
+        let q2 = p2.clone() + p4;
+        let q3 = p1 - p2 + p3 + p6;
+
+        Matrix::from_quarters(q0, q1, q2, q3)
+    }
+
+    /// the largest dimension of either matrix, rounded up to a power of two
+    fn padded_size(&self, rhs: &Matrix<T>) -> usize {
+        let largest_dimension = max(
+            max(self.rows, self.cols),
+            max(rhs.rows, rhs.cols)
+        );
+
+        if largest_dimension.is_power_of_two() {
+            largest_dimension
+        } else {
+            2_usize.pow((largest_dimension as f32).log2().ceil() as u32)
+        }
+    }
+
+    /// work out how `self * rhs` would be computed with `strategy`, and why.
+    ///
+    /// [`MatmulStrategy::Auto`] resolves the same way as `*`,
+    /// any other strategy is checked against the matrix shapes and returned as is.
+    pub fn plan(&self, rhs: &Matrix<T>, strategy: MatmulStrategy) -> MatmulPlan {
+        self.plan_lazy(rhs, strategy).0
+    }
+
+    /// like [`Matrix::plan`], but with the thresholds from `tuning` instead of the installed one
+    pub fn plan_tuned(&self, rhs: &Matrix<T>, strategy: MatmulStrategy, tuning: &MatmulTuning) -> MatmulPlan {
+        self.plan_untuned(rhs, strategy).unwrap_or_else(|| self.plan_by_size(rhs, tuning))
+    }
+
+    /// like `self * rhs`, but with the thresholds from `tuning` instead of the installed one
+    pub fn mul_tuned(&self, rhs: &Matrix<T>, tuning: &MatmulTuning) -> Matrix<T> {
+        let plan = self.plan_tuned(rhs, MatmulStrategy::Auto, tuning);
+        self.mul_planned(rhs, &plan, Some(*tuning))
+    }
+
+    /// [`Matrix::plan`], reading the installed tuning only if its thresholds decide,
+    /// and handing it back so carrying out the plan doesn't read it again
+    fn plan_lazy(&self, rhs: &Matrix<T>, strategy: MatmulStrategy) -> (MatmulPlan, Option<MatmulTuning>) {
+        match self.plan_untuned(rhs, strategy) {
+            Some(plan) => (plan, None),
+            None => {
+                let tuning = MatmulTuning::current::<T>();
+                (self.plan_by_size(rhs, &tuning), Some(tuning))
+            }
+        }
+    }
+
+    /// the plan for any case that doesn't depend on the tuning,
+    /// none if it comes down to the size thresholds
+    fn plan_untuned(&self, rhs: &Matrix<T>, strategy: MatmulStrategy) -> Option<MatmulPlan> {
+        assert_eq!(self.cols, rhs.rows);
+
+        let size = self.padded_size(rhs);
+        let small = (self.is_2x2() && rhs.is_2x2())
+            || (self.is_3x3() && rhs.is_3x3())
+            || (self.is_4x4() && rhs.is_4x4());
+
+        let plan = |strategy, reason: String| Some(MatmulPlan { strategy, size, reason });
+
+        match strategy {
+            MatmulStrategy::Auto => {}
+            MatmulStrategy::Simd => {
+                assert!(small, "simd multiplication needs two 2x2, 3x3 or 4x4 matrices");
+                return plan(strategy, "requested".to_string())
+            }
+            MatmulStrategy::Strassen { cutoff } => {
+                assert!(cutoff > 0, "strassen cutoff must be at least 1");
+                return plan(strategy, "requested".to_string())
+            }
+            _ => return plan(strategy, "requested".to_string()),
+        }
+
+        if cfg!(feature = "deterministic") {
+            return plan(
+                MatmulStrategy::Naive,
+                "the deterministic feature fixes the summation order".to_string()
+            )
+        }
+
+        if small {
+            return plan(
+                MatmulStrategy::Simd,
+                format!("{}x{} matrices have a dedicated simd kernel", self.rows, self.cols)
+            )
+        }
+
+        None
+    }
+
+    /// choose between the general algorithms by the padded size and the thresholds in `tuning`
+    fn plan_by_size(&self, rhs: &Matrix<T>, tuning: &MatmulTuning) -> MatmulPlan {
+        let size = self.padded_size(rhs);
+        let plan = |strategy, reason: String| MatmulPlan { strategy, size, reason };
+
+        if size <= tuning.gemm_max {
+            plan(
+                MatmulStrategy::Naive,
+                format!("padded size {size} is at most gemm_max {}", tuning.gemm_max)
+            )
+        } else if size <= tuning.blocked_max {
+            plan(
+                MatmulStrategy::Blocked,
+                format!("padded size {size} is at most blocked_max {}", tuning.blocked_max)
+            )
+        } else {
+            plan(
+                MatmulStrategy::Strassen { cutoff: tuning.blocked_max.max(1) },
+                format!("padded size {size} is above blocked_max {}", tuning.blocked_max)
+            )
+        }
+    }
+
+    /// carry out a resolved plan, for every strategy except [`MatmulStrategy::Parallel`],
+    /// reading the installed tuning if the plan needs one and none was given
+    fn mul_planned(&self, rhs: &Matrix<T>, plan: &MatmulPlan, tuning: Option<MatmulTuning>) -> Matrix<T> {
+        let tuning = || tuning.unwrap_or_else(MatmulTuning::current::<T>);
+        match plan.strategy {
+            MatmulStrategy::Naive => self.gemm(rhs.clone()),
+            MatmulStrategy::Blocked => self.gemm_blocked(rhs.clone(), tuning().block_size),
+            MatmulStrategy::Strassen { cutoff } => self.strassen_tuned(rhs, cutoff, &tuning()),
+            MatmulStrategy::Simd if self.is_2x2() => MatrixSimd::simd_2x2_mul(self, rhs),
+            MatmulStrategy::Simd if self.is_3x3() => MatrixSimd::simd_3x3_mul(self, rhs),
+            MatmulStrategy::Simd => MatrixSimd::simd_4x4_mul(self, rhs),
+            MatmulStrategy::Auto | MatmulStrategy::Parallel => {
+                unreachable!("{} is not handled by mul_planned", plan.strategy)
+            }
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Default + Clone + Copy
+    + Mul<Output = T> + Add<Output = T> + Sub<Output = T>
+    + AddAssign
+    + MatrixSimd
+    + Send + Sync,
+{
+    /// multiplies two matrices using the given `strategy`,
+    /// see [`Matrix::plan`] for how it is resolved.
+    pub fn mul_with(&self, rhs: &Matrix<T>, strategy: MatmulStrategy) -> Matrix<T> {
+        let (plan, tuning) = self.plan_lazy(rhs, strategy);
+        match plan.strategy {
+            MatmulStrategy::Parallel => self.gemm_parallel(rhs),
+            _ => self.mul_planned(rhs, &plan, tuning),
+        }
+    }
+
+    /// multiplies two matrices, splitting rows of the result across the rayon thread pool.
+    ///
+    /// every element is summed in the same order as [`Matrix::mul_deterministic`],
+    /// so the result doesn't depend on the number of threads.
+    pub fn gemm_parallel(&self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, rhs.rows);
+
+        let mut res = Matrix::new(self.rows, rhs.cols);
+        if rhs.cols == 0 {
+            return res
+        }
+
+        res.data
+            .par_chunks_mut(rhs.cols)
+            .enumerate()
+            .for_each(|(i, row)| {
+                for (j, out) in row.iter_mut().enumerate() {
+                    let mut acc = T::default();
+                    for k in 0..self.cols {
+                        acc += self.data[i * self.cols + k] * rhs.data[k * rhs.cols + j];
+                    }
+                    *out = acc;
+                }
+            });
+        res
+    }
 }
 
 impl<T> Add for Matrix<T>
@@ -551,37 +850,137 @@ where
 {
     type Output = Matrix<T>;
 
-    /// performs general matrix multiplication for two matrices
+    /// performs general matrix multiplication for two matrices,
+    /// choosing an algorithm as described by [`Matrix::plan`] with [`MatmulStrategy::Auto`].
+    ///
+    /// with the `deterministic` feature enabled this always uses [`Matrix::gemm`],
+    /// which sums in the same order as [`Matrix::mul_deterministic`].
     fn mul(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.cols, rhs.rows);
+        // small products never touch the tuning's lock, and bigger ones read it once
+        // so a tuning installed part way through can't mix thresholds
+        let (plan, tuning) = self.plan_lazy(&rhs, MatmulStrategy::Auto);
+        self.mul_planned(&rhs, &plan, tuning)
+    }
+}
 
-        // special matrix cases
-        if self.is_2x2() && rhs.is_2x2() {
-            return MatrixSimd::simd_2x2_mul(&self, &rhs)
-        }
+impl Matrix<f32> {
+    /// transforms `point` by a 3x3 homogeneous matrix,
+    /// treating it as a point with `w = 1` so translation applies.
+    ///
+    /// the result is divided by the resulting `w` component.
+    pub fn transform_point2(&self, point: Vec2) -> Vec2 {
+        assert!(self.is_3x3());
+        let m = &self.data;
+        let x = m[0] * point.x + m[1] * point.y + m[2];
+        let y = m[3] * point.x + m[4] * point.y + m[5];
+        let w = m[6] * point.x + m[7] * point.y + m[8];
+        Vec2(x / w, y / w)
+    }
 
-        if self.is_3x3() && rhs.is_3x3() {
-            return MatrixSimd::simd_3x3_mul(&self, &rhs)
-        }
+    /// transforms `direction` by a 3x3 homogeneous matrix,
+    /// treating it as a direction with `w = 0` so translation is ignored.
+    pub fn transform_vector2(&self, direction: Vec2) -> Vec2 {
+        assert!(self.is_3x3());
+        let m = &self.data;
+        Vec2(
+            m[0] * direction.x + m[1] * direction.y,
+            m[3] * direction.x + m[4] * direction.y,
+        )
+    }
 
-        if self.is_4x4() && rhs.is_4x4() {
-            return MatrixSimd::simd_4x4_mul(&self, &rhs)
-        }
+    /// transforms `point` by a 4x4 homogeneous matrix,
+    /// treating it as a point with `w = 1` so translation applies.
+    ///
+    /// the result is divided by the resulting `w` component,
+    /// so this also works for projection matrices.
+    pub fn transform_point3(&self, point: Vec3) -> Vec3 {
+        assert!(self.is_4x4());
+        let m = &self.data;
+        let x = m[0] * point.x + m[1] * point.y + m[2] * point.z + m[3];
+        let y = m[4] * point.x + m[5] * point.y + m[6] * point.z + m[7];
+        let z = m[8] * point.x + m[9] * point.y + m[10] * point.z + m[11];
+        let w = m[12] * point.x + m[13] * point.y + m[14] * point.z + m[15];
+        Vec3(x / w, y / w, z / w)
+    }
 
-        let largest_dimension = max(
-            max(self.rows, self.cols),
-            max(rhs.rows, rhs.cols)
-        );
+    /// transforms `direction` by a 4x4 homogeneous matrix,
+    /// treating it as a direction with `w = 0` so translation is ignored.
+    pub fn transform_vector3(&self, direction: Vec3) -> Vec3 {
+        assert!(self.is_4x4());
+        let m = &self.data;
+        Vec3(
+            m[0] * direction.x + m[1] * direction.y + m[2] * direction.z,
+            m[4] * direction.x + m[5] * direction.y + m[6] * direction.z,
+            m[8] * direction.x + m[9] * direction.y + m[10] * direction.z,
+        )
+    }
+}
 
-        let size = if largest_dimension.is_power_of_two() {
-            largest_dimension
-        } else {
-            2_usize.pow((largest_dimension as f32).log2().ceil() as u32)
-        };
+impl Mul<Vec2> for &Matrix<f32> {
+    type Output = Vec2;
 
-        match size {
-            0..=256 => self.gemm(rhs),
-            _ => self.strassen(rhs, size)
-        }
+    /// multiplies a 2x2 matrix by a column vector
+    fn mul(self, rhs: Vec2) -> Self::Output {
+        assert!(self.is_2x2());
+        let m = &self.data;
+        Vec2(
+            m[0] * rhs.x + m[1] * rhs.y,
+            m[2] * rhs.x + m[3] * rhs.y,
+        )
+    }
+}
+
+impl Mul<Vec2> for Matrix<f32> {
+    type Output = Vec2;
+
+    fn mul(self, rhs: Vec2) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl Mul<Vec3> for &Matrix<f32> {
+    type Output = Vec3;
+
+    /// multiplies a 3x3 matrix by a column vector
+    fn mul(self, rhs: Vec3) -> Self::Output {
+        assert!(self.is_3x3());
+        let m = &self.data;
+        Vec3(
+            m[0] * rhs.x + m[1] * rhs.y + m[2] * rhs.z,
+            m[3] * rhs.x + m[4] * rhs.y + m[5] * rhs.z,
+            m[6] * rhs.x + m[7] * rhs.y + m[8] * rhs.z,
+        )
+    }
+}
+
+impl Mul<Vec3> for Matrix<f32> {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl Mul<Vec4> for &Matrix<f32> {
+    type Output = Vec4;
+
+    /// multiplies a 4x4 matrix by a column vector
+    fn mul(self, rhs: Vec4) -> Self::Output {
+        assert!(self.is_4x4());
+        let m = &self.data;
+        Vec4(
+            m[0] * rhs.x + m[1] * rhs.y + m[2] * rhs.z + m[3] * rhs.w,
+            m[4] * rhs.x + m[5] * rhs.y + m[6] * rhs.z + m[7] * rhs.w,
+            m[8] * rhs.x + m[9] * rhs.y + m[10] * rhs.z + m[11] * rhs.w,
+            m[12] * rhs.x + m[13] * rhs.y + m[14] * rhs.z + m[15] * rhs.w,
+        )
+    }
+}
+
+impl Mul<Vec4> for Matrix<f32> {
+    type Output = Vec4;
+
+    fn mul(self, rhs: Vec4) -> Self::Output {
+        &self * rhs
     }
 }
\ No newline at end of file
@@ -1,9 +1,12 @@
 use std::ops::{Add, AddAssign, Mul, Sub};
 use std::simd::{f32x1, f32x16, f32x2, f32x32, f32x4, f32x64, f32x8, f64x1, f64x16, f64x2, f64x32, f64x4, f64x64, f64x8, i16x1, i16x16, i16x2, i16x32, i16x4, i16x64, i16x8, i32x1, i32x16, i32x2, i32x32, i32x4, i32x64, i32x8, i64x1, i64x16, i64x2, i64x32, i64x4, i64x64, i64x8, i8x1, i8x16, i8x2, i8x32, i8x4, i8x64, i8x8, isizex1, isizex16, isizex2, isizex32, isizex4, isizex64, isizex8, u16x1, u16x16, u16x2, u16x32, u16x4, u16x64, u16x8, u32x1, u32x16, u32x2, u32x32, u32x4, u32x64, u32x8, u64x1, u64x16, u64x2, u64x32, u64x4, u64x64, u64x8, u8x1, u8x16, u8x2, u8x32, u8x4, u8x64, u8x8, usizex1, usizex16, usizex2, usizex32, usizex4, usizex64, usizex8};
 use crate::math::matrix::Matrix;
+use crate::math::vec3::Vec3;
 
-/// specialized simd matrix multiplications for matrices of size 2x2, 3x3, and 4x4
-pub trait MatrixSimd: Sized + Copy {
+/// specialized simd matrix multiplications for matrices of size 2x2, 3x3, and 4x4.
+///
+/// implementors are `'static` so each one's installed [`crate::math::tuning::MatmulTuning`] can be found by type id.
+pub trait MatrixSimd: Sized + Copy + 'static {
     type Simd1: Copy + Mul<Self::Simd1, Output = Self::Simd1>
     + Add<Self::Simd1, Output = Self::Simd1>;
 
@@ -1139,4 +1142,63 @@ impl MatrixSimd for usize {
     fn simd64_to_arr(simd: Self::Simd64) -> [Self; 64] {
         simd.to_array()
     }
+}
+
+/// transforms every point in `points` by `matrix`, writing the results to `out`.
+///
+/// `matrix` may be 3x3, in which case each point is multiplied as a column vector,
+/// or 4x4, in which case each point is treated as homogeneous with `w = 1`
+/// and divided by the resulting `w` (see [`Matrix::transform_point3`]).
+///
+/// points are processed four at a time in structure-of-arrays form
+/// using the [`MatrixSimd::Simd4`] lanes of `f32`.
+pub fn transform_points(matrix: &Matrix<f32>, points: &[Vec3], out: &mut [Vec3]) {
+    assert_eq!(points.len(), out.len());
+    assert!(matrix.is_3x3() || matrix.is_4x4());
+
+    type Lanes = <f32 as MatrixSimd>::Simd4;
+    let splat = |v: f32| -> Lanes { f32::simd4_from_arr([v; 4]) };
+
+    // pad a 3x3 matrix out to a 4x4 affine matrix with no translation
+    let mat: [f32; 16] = if matrix.is_3x3() {
+        let d = &matrix.data;
+        [d[0], d[1], d[2], 0.0,
+         d[3], d[4], d[5], 0.0,
+         d[6], d[7], d[8], 0.0,
+         0.0, 0.0, 0.0, 1.0]
+    } else {
+        matrix.data.as_slice().try_into().unwrap()
+    };
+    let m = mat.map(splat);
+
+    let mut in_chunks = points.chunks_exact(4);
+    let mut out_chunks = out.chunks_exact_mut(4);
+
+    for (src, dst) in (&mut in_chunks).zip(&mut out_chunks) {
+        let x = f32::simd4_from_arr([src[0].x, src[1].x, src[2].x, src[3].x]);
+        let y = f32::simd4_from_arr([src[0].y, src[1].y, src[2].y, src[3].y]);
+        let z = f32::simd4_from_arr([src[0].z, src[1].z, src[2].z, src[3].z]);
+
+        let tx = m[0] * x + m[1] * y + m[2] * z + m[3];
+        let ty = m[4] * x + m[5] * y + m[6] * z + m[7];
+        let tz = m[8] * x + m[9] * y + m[10] * z + m[11];
+        let tw = m[12] * x + m[13] * y + m[14] * z + m[15];
+
+        let tx = f32::simd4_to_arr(tx / tw);
+        let ty = f32::simd4_to_arr(ty / tw);
+        let tz = f32::simd4_to_arr(tz / tw);
+
+        for i in 0..4 {
+            dst[i] = Vec3(tx[i], ty[i], tz[i]);
+        }
+    }
+
+    // leftover points that don't fill a full set of lanes
+    for (src, dst) in in_chunks.remainder().iter().zip(out_chunks.into_remainder()) {
+        let x = mat[0] * src.x + mat[1] * src.y + mat[2] * src.z + mat[3];
+        let y = mat[4] * src.x + mat[5] * src.y + mat[6] * src.z + mat[7];
+        let z = mat[8] * src.x + mat[9] * src.y + mat[10] * src.z + mat[11];
+        let w = mat[12] * src.x + mat[13] * src.y + mat[14] * src.z + mat[15];
+        *dst = Vec3(x / w, y / w, z / w);
+    }
 }
\ No newline at end of file
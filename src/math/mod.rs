@@ -1,4 +1,20 @@
+pub mod affine;
+pub mod curves;
+pub mod deterministic;
+pub mod dvec;
+pub mod ivec;
 pub mod matrix;
 pub mod vec2;
 pub mod vec3;
-pub mod matrix_simd;
\ No newline at end of file
+pub mod vec4;
+pub mod vec_convert;
+pub mod vec_ops;
+pub mod matrix_simd;
+pub mod half;
+pub mod projection;
+pub mod quantize;
+pub mod quat;
+pub mod random;
+pub mod strategy;
+pub mod tuning;
+pub mod uvec;
\ No newline at end of file
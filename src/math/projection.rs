@@ -0,0 +1,73 @@
+use crate::math::matrix::Matrix;
+
+/// Projection matrices, for a right handed view space looking down the negative z-axis
+/// with y up, as made by [`crate::math::affine::Affine3::look_at`].
+///
+/// clip space has x and y in `-1..=1` with y up, and depth in `0..=1`,
+/// where the near plane maps to 0 and the far plane to 1.
+/// the reversed variants swap this, which spreads float precision more evenly over depth.
+impl Matrix<f32> {
+    /// a perspective projection with a vertical field of view of `fov_y` radians
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Matrix<f32> {
+        assert!(near > 0.0 && far > near);
+        let f = 1.0 / (fov_y * 0.5).tan();
+        let range = 1.0 / (near - far);
+        Matrix::from_vec(4, 4, vec![
+            f / aspect, 0.0, 0.0, 0.0,
+            0.0, f, 0.0, 0.0,
+            0.0, 0.0, far * range, near * far * range,
+            0.0, 0.0, -1.0, 0.0,
+        ])
+    }
+
+    /// a perspective projection mapping the near plane to depth 1 and the far plane to 0
+    pub fn perspective_reversed(fov_y: f32, aspect: f32, near: f32, far: f32) -> Matrix<f32> {
+        assert!(near > 0.0 && far > near);
+        let f = 1.0 / (fov_y * 0.5).tan();
+        let range = 1.0 / (far - near);
+        Matrix::from_vec(4, 4, vec![
+            f / aspect, 0.0, 0.0, 0.0,
+            0.0, f, 0.0, 0.0,
+            0.0, 0.0, near * range, near * far * range,
+            0.0, 0.0, -1.0, 0.0,
+        ])
+    }
+
+    /// a perspective projection with no far plane, depth approaches 1 at infinity
+    pub fn perspective_infinite(fov_y: f32, aspect: f32, near: f32) -> Matrix<f32> {
+        assert!(near > 0.0);
+        let f = 1.0 / (fov_y * 0.5).tan();
+        Matrix::from_vec(4, 4, vec![
+            f / aspect, 0.0, 0.0, 0.0,
+            0.0, f, 0.0, 0.0,
+            0.0, 0.0, -1.0, -near,
+            0.0, 0.0, -1.0, 0.0,
+        ])
+    }
+
+    /// a perspective projection with no far plane, mapping the near plane
+    /// to depth 1 and approaching 0 at infinity
+    pub fn perspective_infinite_reversed(fov_y: f32, aspect: f32, near: f32) -> Matrix<f32> {
+        assert!(near > 0.0);
+        let f = 1.0 / (fov_y * 0.5).tan();
+        Matrix::from_vec(4, 4, vec![
+            f / aspect, 0.0, 0.0, 0.0,
+            0.0, f, 0.0, 0.0,
+            0.0, 0.0, 0.0, near,
+            0.0, 0.0, -1.0, 0.0,
+        ])
+    }
+
+    /// an orthographic projection of the box between the given planes,
+    /// where `near` and `far` are distances along the negative z-axis
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix<f32> {
+        assert!(left != right && bottom != top && near != far);
+        let (w, h, d) = (1.0 / (right - left), 1.0 / (top - bottom), 1.0 / (near - far));
+        Matrix::from_vec(4, 4, vec![
+            2.0 * w, 0.0, 0.0, -(right + left) * w,
+            0.0, 2.0 * h, 0.0, -(top + bottom) * h,
+            0.0, 0.0, d, near * d,
+            0.0, 0.0, 0.0, 1.0,
+        ])
+    }
+}
@@ -0,0 +1,189 @@
+use std::ops::{Add, AddAssign, Mul, Sub};
+use crate::math::matrix::Matrix;
+use crate::math::matrix_simd::MatrixSimd;
+
+/// integer types which can hold quantized matrix values
+pub trait Quantized: Default + Clone + Copy
+    + Mul<Output = Self> + Add<Output = Self> + Sub<Output = Self>
+    + AddAssign
+    + MatrixSimd
+{
+    /// the smallest representable quantized value
+    const QMIN: i32;
+
+    /// the largest representable quantized value
+    const QMAX: i32;
+
+    /// widen a quantized value for accumulation
+    fn to_i32(self) -> i32;
+
+    /// narrow an accumulated value, saturating at [`Quantized::QMIN`] and [`Quantized::QMAX`]
+    fn from_i32(value: i32) -> Self;
+}
+
+impl Quantized for i8 {
+    const QMIN: i32 = i8::MIN as i32;
+    const QMAX: i32 = i8::MAX as i32;
+
+    #[inline(always)]
+    fn to_i32(self) -> i32 {
+        self as i32
+    }
+
+    #[inline(always)]
+    fn from_i32(value: i32) -> Self {
+        value.clamp(Self::QMIN, Self::QMAX) as i8
+    }
+}
+
+impl Quantized for u8 {
+    const QMIN: i32 = u8::MIN as i32;
+    const QMAX: i32 = u8::MAX as i32;
+
+    #[inline(always)]
+    fn to_i32(self) -> i32 {
+        self as i32
+    }
+
+    #[inline(always)]
+    fn from_i32(value: i32) -> Self {
+        value.clamp(Self::QMIN, Self::QMAX) as u8
+    }
+}
+
+/// affine quantization parameters,
+/// where a real value is recovered as `scale * (q - zero_point)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuantParams {
+    pub scale: f32,
+    pub zero_point: i32,
+}
+
+impl QuantParams {
+    /// construct a new [`QuantParams`]
+    pub const fn new(scale: f32, zero_point: i32) -> QuantParams {
+        QuantParams { scale, zero_point }
+    }
+
+    /// choose parameters that map the range `min..=max` onto the full range of `Q`.
+    ///
+    /// the range is widened to include zero, so that zero is always exactly representable.
+    pub fn from_range<Q: Quantized>(min: f32, max: f32) -> QuantParams {
+        let min = min.min(0.0);
+        let max = max.max(0.0);
+
+        let steps = (Q::QMAX - Q::QMIN) as f32;
+        let scale = if max > min { (max - min) / steps } else { 1.0 };
+        let zero_point = (Q::QMIN as f32 - min / scale)
+            .round()
+            .clamp(Q::QMIN as f32, Q::QMAX as f32) as i32;
+
+        QuantParams { scale, zero_point }
+    }
+
+    /// quantize a single value, saturating at [`Quantized::QMIN`] and [`Quantized::QMAX`]
+    #[inline(always)]
+    pub fn quantize<Q: Quantized>(&self, value: f32) -> Q {
+        // clamp before leaving f32, so values far out of range can't overflow when shifted by the zero point
+        let zero_point = self.zero_point as f32;
+        let steps = (value / self.scale).round().clamp(Q::QMIN as f32 - zero_point, Q::QMAX as f32 - zero_point);
+        Q::from_i32((steps as i32).saturating_add(self.zero_point))
+    }
+
+    /// recover the real value of a single quantized value
+    #[inline(always)]
+    pub fn dequantize<Q: Quantized>(&self, value: Q) -> f32 {
+        self.scale * (value.to_i32() - self.zero_point) as f32
+    }
+}
+
+/// a matrix of quantized values, along with the parameters needed to recover them
+#[derive(Clone, Debug)]
+pub struct QuantizedMatrix<Q: Quantized> {
+    pub matrix: Matrix<Q>,
+    pub params: QuantParams,
+}
+
+impl<Q: Quantized> QuantizedMatrix<Q> {
+    /// quantize a matrix, choosing parameters from the range of its values
+    pub fn quantize(matrix: &Matrix<f32>) -> QuantizedMatrix<Q> {
+        let (min, max) = matrix.data.iter().fold(
+            (0.0f32, 0.0f32),
+            |(min, max), &v| (min.min(v), max.max(v))
+        );
+        Self::quantize_with(matrix, QuantParams::from_range::<Q>(min, max))
+    }
+
+    /// quantize a matrix with the given parameters
+    pub fn quantize_with(matrix: &Matrix<f32>, params: QuantParams) -> QuantizedMatrix<Q> {
+        let data = matrix.data.iter().map(|&v| params.quantize(v)).collect();
+        QuantizedMatrix {
+            matrix: Matrix::from_vec(matrix.rows, matrix.cols, data),
+            params,
+        }
+    }
+
+    /// recover an approximation of the original `f32` matrix
+    pub fn dequantize(&self) -> Matrix<f32> {
+        let data = self.matrix.data.iter().map(|&q| self.params.dequantize(q)).collect();
+        Matrix::from_vec(self.matrix.rows, self.matrix.cols, data)
+    }
+}
+
+/// computes the dot product of two equal length slices,
+/// eight lanes at a time.
+#[inline(always)]
+fn dot_i32(a: &[i32], b: &[i32]) -> i32 {
+    let mut acc = i32::simd8_from_arr([0; 8]);
+    let a_chunks = a.chunks_exact(8);
+    let b_chunks = b.chunks_exact(8);
+    let tail = a_chunks.remainder().iter()
+        .zip(b_chunks.remainder())
+        .fold(0, |sum, (&x, &y)| sum + x * y);
+
+    for (x, y) in a_chunks.zip(b_chunks) {
+        let x = i32::simd8_from_arr(x.try_into().unwrap());
+        let y = i32::simd8_from_arr(y.try_into().unwrap());
+        acc += x * y;
+    }
+
+    i32::simd8_to_arr(acc).iter().sum::<i32>() + tail
+}
+
+/// multiplies two quantized matrices, accumulating in `i32`.
+///
+/// zero points are subtracted before accumulation,
+/// so the result is in units of `a.params.scale * b.params.scale`.
+pub fn qgemm<Q: Quantized>(a: &QuantizedMatrix<Q>, b: &QuantizedMatrix<Q>) -> Matrix<i32> {
+    assert_eq!(a.matrix.cols, b.matrix.rows);
+
+    let (rows, inner, cols) = (a.matrix.rows, a.matrix.cols, b.matrix.cols);
+    let (za, zb) = (a.params.zero_point, b.params.zero_point);
+
+    // widen once up front, transposing `b` so each dot product reads contiguous rows
+    let a_wide = a.matrix.data.iter()
+        .map(|q| q.to_i32() - za)
+        .collect::<Vec<i32>>();
+    let b_wide = b.matrix.transpose().data.iter()
+        .map(|q| q.to_i32() - zb)
+        .collect::<Vec<i32>>();
+
+    let mut res = Matrix::new(rows, cols);
+    for i in 0..rows {
+        let a_row = &a_wide[i * inner..(i + 1) * inner];
+        for j in 0..cols {
+            let b_col = &b_wide[j * inner..(j + 1) * inner];
+            res.data[i * cols + j] = dot_i32(a_row, b_col);
+        }
+    }
+    res
+}
+
+/// multiplies two quantized matrices, accumulating in `i32`,
+/// and rescales the result back into `f32`.
+pub fn qgemm_f32<Q: Quantized>(a: &QuantizedMatrix<Q>, b: &QuantizedMatrix<Q>) -> Matrix<f32> {
+    let acc = qgemm(a, b);
+    let scale = a.params.scale * b.params.scale;
+    let data = acc.data.iter().map(|&v| v as f32 * scale).collect();
+    Matrix::from_vec(acc.rows, acc.cols, data)
+}
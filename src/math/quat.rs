@@ -0,0 +1,361 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::{Mul, MulAssign, Neg};
+use crate::math::deterministic::sqrt;
+use crate::math::matrix::Matrix;
+use crate::math::vec3::Vec3;
+
+/// A quaternion representing a rotation in three dimensions.
+///
+/// rotations follow the right hand rule, and `a * b` rotates by `b` first and then by `a`,
+/// matching how the equivalent matrices multiply column vectors.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// wrapper function for [`Quat::new()`]
+#[inline(always)]
+#[allow(non_snake_case)]
+pub const fn Quat(x: f32, y: f32, z: f32, w: f32) -> Quat {
+    Quat::new(x, y, z, w)
+}
+
+/// the order in which euler angles are applied.
+///
+/// `XYZ` means the rotation is `rx * ry * rz`,
+/// that is intrinsic rotations about x, then the new y, then the new z.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+}
+
+impl EulerOrder {
+    /// the axis index of each angle, and whether the order is a cyclic permutation of xyz
+    fn axes(self) -> (usize, usize, usize, bool) {
+        match self {
+            EulerOrder::XYZ => (0, 1, 2, true),
+            EulerOrder::YZX => (1, 2, 0, true),
+            EulerOrder::ZXY => (2, 0, 1, true),
+            EulerOrder::XZY => (0, 2, 1, false),
+            EulerOrder::ZYX => (2, 1, 0, false),
+            EulerOrder::YXZ => (1, 0, 2, false),
+        }
+    }
+}
+
+impl Quat {
+    /// construct a new [`Quat`] from its raw components
+    #[inline(always)]
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Quat {
+        Quat { x, y, z, w }
+    }
+
+    /// the rotation which does nothing
+    pub const IDENTITY: Quat = Quat(0.0, 0.0, 0.0, 1.0);
+
+    /// a rotation of `angle` radians about `axis`, which need not be normalized
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+        let axis = axis.normalize();
+        let (s, c) = (angle * 0.5).sin_cos();
+        Quat(axis.x * s, axis.y * s, axis.z * s, c)
+    }
+
+    /// a rotation of `angle` radians about the x-axis
+    pub fn from_rotation_x(angle: f32) -> Quat {
+        let (s, c) = (angle * 0.5).sin_cos();
+        Quat(s, 0.0, 0.0, c)
+    }
+
+    /// a rotation of `angle` radians about the y-axis
+    pub fn from_rotation_y(angle: f32) -> Quat {
+        let (s, c) = (angle * 0.5).sin_cos();
+        Quat(0.0, s, 0.0, c)
+    }
+
+    /// a rotation of `angle` radians about the z-axis
+    pub fn from_rotation_z(angle: f32) -> Quat {
+        let (s, c) = (angle * 0.5).sin_cos();
+        Quat(0.0, 0.0, s, c)
+    }
+
+    /// a rotation from euler angles, where `a`, `b` and `c`
+    /// are the angles about the first, second and third axes of `order`
+    pub fn from_euler(order: EulerOrder, a: f32, b: f32, c: f32) -> Quat {
+        let about = |axis: usize, angle: f32| match axis {
+            0 => Quat::from_rotation_x(angle),
+            1 => Quat::from_rotation_y(angle),
+            _ => Quat::from_rotation_z(angle),
+        };
+        let (i, j, k, _) = order.axes();
+        about(i, a) * about(j, b) * about(k, c)
+    }
+
+    /// the euler angles `(a, b, c)` which give this rotation with [`Quat::from_euler`].
+    ///
+    /// the middle angle is in `-PI/2..=PI/2`. when it is at either end
+    /// the first and last axes line up, and the whole rotation is put in the first angle.
+    pub fn to_euler(self, order: EulerOrder) -> (f32, f32, f32) {
+        let m = self.to_matrix3();
+        let r = |row: usize, col: usize| m.data[row * 3 + col];
+        let (i, j, k, cyclic) = order.axes();
+        let s = if cyclic { 1.0 } else { -1.0 };
+
+        let sin_b = (s * r(i, k)).clamp(-1.0, 1.0);
+        let b = sin_b.asin();
+
+        if sin_b.abs() < 0.999_999 {
+            let a = (-s * r(j, k)).atan2(r(k, k));
+            let c = (-s * r(i, j)).atan2(r(i, i));
+            (a, b, c)
+        } else {
+            let a = (s * r(k, j)).atan2(r(j, j));
+            (a, b, 0.0)
+        }
+    }
+
+    /// the shortest rotation taking the direction `from` onto the direction `to`.
+    ///
+    /// neither needs to be normalized, if they point in opposite directions
+    /// the rotation is half a turn about some axis perpendicular to both.
+    pub fn from_rotation_arc(from: Vec3, to: Vec3) -> Quat {
+        let from = from.normalize();
+        let to = to.normalize();
+        let d = from.dot(to);
+
+        if d < -0.999_999 {
+            let axis = from.any_orthonormal();
+            return Quat(axis.x, axis.y, axis.z, 0.0)
+        }
+
+        // half way between the identity and the double angle rotation
+        let c = from.cross(to);
+        Quat(c.x, c.y, c.z, 1.0 + d).normalize()
+    }
+
+    /// the axis and angle in radians of this rotation,
+    /// the identity gives an angle of zero about the x-axis
+    pub fn to_axis_angle(self) -> (Vec3, f32) {
+        let q = self.normalize();
+        let q = if q.w < 0.0 { -q } else { q };
+        let angle = 2.0 * q.w.clamp(-1.0, 1.0).acos();
+        let axis = Vec3(q.x, q.y, q.z).try_normalize().unwrap_or(Vec3::X);
+        (axis, angle)
+    }
+
+    /// the x, y and z components
+    #[inline(always)]
+    pub const fn xyz(self) -> Vec3 {
+        Vec3(self.x, self.y, self.z)
+    }
+
+    /// check whether a quaternions components are finite values
+    #[inline(always)]
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite() && self.w.is_finite()
+    }
+
+    /// computes the dot product of two [`Quat`]s
+    #[inline(always)]
+    pub fn dot(self, other: Quat) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// computes the magnitude of a [`Quat`]
+    #[inline(always)]
+    pub fn magnitude(self) -> f32 {
+        sqrt(self.dot(self))
+    }
+
+    /// scale a [`Quat`] to unit length, panics if it has zero length or is not finite
+    #[inline(always)]
+    pub fn normalize(self) -> Quat {
+        let res = self.scale(1.0 / self.magnitude());
+        assert!(res.is_finite());
+        res
+    }
+
+    /// scale a [`Quat`] to unit length, returning none if it has zero length or is not finite
+    #[inline(always)]
+    pub fn try_normalize(self) -> Option<Quat> {
+        let res = self.scale(1.0 / self.magnitude());
+        res.is_finite().then_some(res)
+    }
+
+    /// the conjugate, which for a unit quaternion is the opposite rotation
+    #[inline(always)]
+    pub fn conjugate(self) -> Quat {
+        Quat(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// the multiplicative inverse, this also works for quaternions that aren't unit length
+    #[inline(always)]
+    pub fn inverse(self) -> Quat {
+        self.conjugate().scale(1.0 / self.dot(self))
+    }
+
+    /// multiply every component by `s`
+    #[inline(always)]
+    fn scale(self, s: f32) -> Quat {
+        Quat(self.x * s, self.y * s, self.z * s, self.w * s)
+    }
+
+    /// rotate a [`Vec3`] by this rotation
+    #[inline(always)]
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        // v + 2w(q x v) + 2q x (q x v), with q the vector part
+        let q = self.xyz();
+        let t = q.cross(v) * 2.0;
+        v + t * self.w + q.cross(t)
+    }
+
+    /// normalized linear interpolation, taking the shorter path.
+    ///
+    /// cheaper than [`Quat::slerp`] but doesn't move at a constant angular speed.
+    pub fn nlerp(self, other: Quat, t: f32) -> Quat {
+        let other = if self.dot(other) < 0.0 { -other } else { other };
+        Quat(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+            self.w + (other.w - self.w) * t,
+        ).normalize()
+    }
+
+    /// spherical linear interpolation, taking the shorter path at a constant angular speed
+    pub fn slerp(self, other: Quat, t: f32) -> Quat {
+        let mut d = self.dot(other);
+        let other = if d < 0.0 {
+            d = -d;
+            -other
+        } else {
+            other
+        };
+
+        // nearly parallel, where sin(theta) is too small to divide by
+        if d > 0.9995 {
+            return self.nlerp(other, t)
+        }
+
+        let theta = d.acos();
+        let sin_theta = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+        Quat(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+    }
+
+    /// the equivalent 3x3 rotation matrix, for column vectors
+    pub fn to_matrix3(self) -> Matrix<f32> {
+        let Quat { x, y, z, w } = self;
+        let (x2, y2, z2) = (x + x, y + y, z + z);
+        let (xx, xy, xz) = (x * x2, x * y2, x * z2);
+        let (yy, yz, zz) = (y * y2, y * z2, z * z2);
+        let (wx, wy, wz) = (w * x2, w * y2, w * z2);
+
+        Matrix::from_vec(3, 3, vec![
+            1.0 - (yy + zz), xy - wz, xz + wy,
+            xy + wz, 1.0 - (xx + zz), yz - wx,
+            xz - wy, yz + wx, 1.0 - (xx + yy),
+        ])
+    }
+
+    /// the equivalent 4x4 homogeneous rotation matrix, for column vectors
+    pub fn to_matrix4(self) -> Matrix<f32> {
+        let m = self.to_matrix3().data;
+        Matrix::from_vec(4, 4, vec![
+            m[0], m[1], m[2], 0.0,
+            m[3], m[4], m[5], 0.0,
+            m[6], m[7], m[8], 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ])
+    }
+
+    /// the rotation of a 3x3 rotation matrix, or the upper left 3x3 of a 4x4 matrix.
+    ///
+    /// the matrix should be orthonormal, scale or shear will give a meaningless result.
+    pub fn from_matrix(m: &Matrix<f32>) -> Quat {
+        assert!(m.is_3x3() || m.is_4x4());
+        let n = m.cols;
+        let r = |row: usize, col: usize| m.data[row * n + col];
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+
+        // pick the largest of w, x, y, z to divide by, for stability
+        let q = if trace > 0.0 {
+            let s = sqrt(trace + 1.0) * 2.0;
+            Quat((r(2, 1) - r(1, 2)) / s, (r(0, 2) - r(2, 0)) / s, (r(1, 0) - r(0, 1)) / s, 0.25 * s)
+        } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+            let s = sqrt(1.0 + r(0, 0) - r(1, 1) - r(2, 2)) * 2.0;
+            Quat(0.25 * s, (r(0, 1) + r(1, 0)) / s, (r(0, 2) + r(2, 0)) / s, (r(2, 1) - r(1, 2)) / s)
+        } else if r(1, 1) > r(2, 2) {
+            let s = sqrt(1.0 + r(1, 1) - r(0, 0) - r(2, 2)) * 2.0;
+            Quat((r(0, 1) + r(1, 0)) / s, 0.25 * s, (r(1, 2) + r(2, 1)) / s, (r(0, 2) - r(2, 0)) / s)
+        } else {
+            let s = sqrt(1.0 + r(2, 2) - r(0, 0) - r(1, 1)) * 2.0;
+            Quat((r(0, 2) + r(2, 0)) / s, (r(1, 2) + r(2, 1)) / s, 0.25 * s, (r(1, 0) - r(0, 1)) / s)
+        };
+
+        q.normalize()
+    }
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Quat::IDENTITY
+    }
+}
+
+impl Debug for Quat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "<{}, {}, {}, {}>", self.x, self.y, self.z, self.w)
+    }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+
+    /// the hamilton product, rotating by `rhs` and then by `self`
+    fn mul(self, rhs: Quat) -> Self::Output {
+        let (a, b) = (self, rhs);
+        Quat(
+            a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+            a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+        )
+    }
+}
+
+impl MulAssign for Quat {
+    fn mul_assign(&mut self, rhs: Quat) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Self::Output {
+        self.rotate(rhs)
+    }
+}
+
+impl Neg for Quat {
+    type Output = Quat;
+
+    /// negating every component gives the same rotation
+    fn neg(self) -> Self::Output {
+        Quat(-self.x, -self.y, -self.z, -self.w)
+    }
+}
@@ -0,0 +1,333 @@
+use std::f32::consts::{LN_2, SQRT_2};
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::triangle::{Triangle2, Triangle3};
+use crate::math::deterministic::sqrt;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// the stream [`Pcg32::new`] uses
+const DEFAULT_STREAM: u64 = 0xda3e39cb94b95bdb;
+
+const MULTIPLIER: u64 = 6364136223846793005;
+
+/// how many candidates poisson disk sampling tries around a point before retiring it
+const POISSON_ATTEMPTS: usize = 30;
+
+/// A small seedable random number generator, the 32 bit output variant of PCG.
+///
+/// the sequence for a given seed and stream is fixed, and everything built on it uses only
+/// integer arithmetic, basic float operations and [`sqrt`], so with the `deterministic`
+/// feature the same seed gives the same values on every platform.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    #[inline(always)]
+    pub fn new(seed: u64) -> Pcg32 {
+        Pcg32::with_stream(seed, DEFAULT_STREAM)
+    }
+
+    /// a generator on one of 2^63 independent streams, so generators sharing a seed can
+    /// still give unrelated sequences
+    pub fn with_stream(seed: u64, stream: u64) -> Pcg32 {
+        let mut rng = Pcg32 { state: 0, increment: (stream << 1) | 1 };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    #[inline(always)]
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    #[inline(always)]
+    pub fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        (high << 32) | self.next_u32() as u64
+    }
+
+    /// a float in `[0, 1)` with 24 random bits
+    #[inline(always)]
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+    }
+
+    /// whether an event of probability `p` happened
+    #[inline(always)]
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    /// a uniform integer in `[0, bound)`, without bias, by lemire's method
+    pub fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "bound must be positive");
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let m = self.next_u32() as u64 * bound as u64;
+            if (m as u32) >= threshold {
+                return (m >> 32) as u32
+            }
+        }
+    }
+
+    /// a uniform integer in `[min, max)`
+    #[inline(always)]
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        assert!(min < max, "empty range");
+        min.wrapping_add(self.below(max.wrapping_sub(min) as u32) as i32)
+    }
+
+    /// a uniform float in `[min, max)`
+    #[inline(always)]
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// a normally distributed float, by the polar method
+    pub fn normal(&mut self, mean: f32, std_dev: f32) -> f32 {
+        loop {
+            let (u, v) = (self.range(-1.0, 1.0), self.range(-1.0, 1.0));
+            let s = u * u + v * v;
+            if (f32::MIN_POSITIVE..1.0).contains(&s) {
+                return mean + std_dev * u * sqrt(-2.0 * ln(s) / s)
+            }
+        }
+    }
+
+    /// an index into `weights` picked in proportion to its weight,
+    /// none if no weight is positive. negative weights count as zero
+    pub fn weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().map(|w| w.max(0.0)).sum();
+        if total <= 0.0 {
+            return None
+        }
+        let target = self.next_f32() * total;
+        let mut sum = 0.0;
+        let mut last = 0;
+        for (i, w) in weights.iter().enumerate() {
+            if *w <= 0.0 {
+                continue
+            }
+            sum += w;
+            last = i;
+            if target < sum {
+                return Some(i)
+            }
+        }
+        // rounding can leave the target just past the final sum
+        Some(last)
+    }
+
+    /// a uniformly chosen element, none if `items` is empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None
+        }
+        Some(&items[self.below(items.len() as u32) as usize])
+    }
+
+    /// shuffle `items` in place, by fisher-yates
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u32 + 1) as usize);
+        }
+    }
+
+    /// a uniform point in the unit disk
+    pub fn in_disk(&mut self) -> Vec2 {
+        loop {
+            let p = Vec2(self.range(-1.0, 1.0), self.range(-1.0, 1.0));
+            if p.magnitude_squared() < 1.0 {
+                return p
+            }
+        }
+    }
+
+    /// a uniform point in the unit ball
+    pub fn in_ball(&mut self) -> Vec3 {
+        loop {
+            let p = Vec3(self.range(-1.0, 1.0), self.range(-1.0, 1.0), self.range(-1.0, 1.0));
+            if p.magnitude_squared() < 1.0 {
+                return p
+            }
+        }
+    }
+
+    /// a uniform unit vector in two dimensions
+    pub fn on_circle(&mut self) -> Vec2 {
+        loop {
+            // too close to the center and the direction loses precision
+            let p = self.in_disk();
+            if p.magnitude_squared() > 1e-4 {
+                return p.normalize()
+            }
+        }
+    }
+
+    /// a uniform unit vector in three dimensions
+    pub fn on_sphere(&mut self) -> Vec3 {
+        loop {
+            let p = self.in_ball();
+            if p.magnitude_squared() > 1e-4 {
+                return p.normalize()
+            }
+        }
+    }
+
+    /// a uniform point in the triangle
+    pub fn in_triangle2(&mut self, triangle: &Triangle2) -> Vec2 {
+        let (u, v) = self.triangle_weights();
+        triangle.a + (triangle.b - triangle.a) * u + (triangle.c - triangle.a) * v
+    }
+
+    /// a uniform point in the triangle
+    pub fn in_triangle3(&mut self, triangle: &Triangle3) -> Vec3 {
+        let (u, v) = self.triangle_weights();
+        triangle.a + (triangle.b - triangle.a) * u + (triangle.c - triangle.a) * v
+    }
+
+    /// uniform weights of the second and third corners, by folding the unit square in half
+    fn triangle_weights(&mut self) -> (f32, f32) {
+        let (u, v) = (self.next_f32(), self.next_f32());
+        if u + v > 1.0 { (1.0 - u, 1.0 - v) } else { (u, v) }
+    }
+
+    /// a uniform point in the box
+    pub fn in_aabb2(&mut self, aabb: &Aabb2) -> Vec2 {
+        Vec2(self.range(aabb.min.x, aabb.max.x), self.range(aabb.min.y, aabb.max.y))
+    }
+
+    /// a uniform point in the box
+    pub fn in_aabb3(&mut self, aabb: &Aabb3) -> Vec3 {
+        Vec3(
+            self.range(aabb.min.x, aabb.max.x),
+            self.range(aabb.min.y, aabb.max.y),
+            self.range(aabb.min.z, aabb.max.z),
+        )
+    }
+
+    /// points in the box no closer than `radius` to each other, filling it until no more fit,
+    /// by bridson's algorithm
+    pub fn poisson_disk2(&mut self, bounds: &Aabb2, radius: f32) -> Vec<Vec2> {
+        assert!(radius > 0.0, "radius must be positive");
+        let cell = radius / SQRT_2;
+        let size = bounds.size();
+        let (w, h) = (((size.x / cell).ceil() as usize).max(1), ((size.y / cell).ceil() as usize).max(1));
+        let cell_of = |p: Vec2| {
+            let local = (p - bounds.min) / cell;
+            ((local.x as usize).min(w - 1), (local.y as usize).min(h - 1))
+        };
+
+        let mut grid = vec![u32::MAX; w * h];
+        let mut points = Vec::new();
+        let mut active = Vec::new();
+        let first = self.in_aabb2(bounds);
+        let (x, y) = cell_of(first);
+        grid[y * w + x] = 0;
+        points.push(first);
+        active.push(0);
+
+        while !active.is_empty() {
+            let slot = self.below(active.len() as u32) as usize;
+            let center = points[active[slot]];
+            let found = (0..POISSON_ATTEMPTS).map(|_| center + self.on_circle() * self.range(radius, radius * 2.0)).find(|p| {
+                if !bounds.contains_point(*p) {
+                    return false
+                }
+                let (x, y) = cell_of(*p);
+                (y.saturating_sub(2)..(y + 3).min(h)).all(|j| {
+                    (x.saturating_sub(2)..(x + 3).min(w)).all(|i| {
+                        let other = grid[j * w + i];
+                        other == u32::MAX || points[other as usize].distance(*p) >= radius
+                    })
+                })
+            });
+            match found {
+                Some(p) => {
+                    let (x, y) = cell_of(p);
+                    grid[y * w + x] = points.len() as u32;
+                    active.push(points.len());
+                    points.push(p);
+                }
+                None => {
+                    active.swap_remove(slot);
+                }
+            }
+        }
+        points
+    }
+
+    /// points in the box no closer than `radius` to each other, filling it until no more fit,
+    /// by bridson's algorithm
+    pub fn poisson_disk3(&mut self, bounds: &Aabb3, radius: f32) -> Vec<Vec3> {
+        assert!(radius > 0.0, "radius must be positive");
+        let cell = radius / sqrt(3.0);
+        let size = bounds.size();
+        let dims = [size.x, size.y, size.z].map(|s| ((s / cell).ceil() as usize).max(1));
+        let cell_of = |p: Vec3| {
+            let local = (p - bounds.min) / cell;
+            let local = [local.x, local.y, local.z];
+            std::array::from_fn::<usize, 3, _>(|axis| (local[axis] as usize).min(dims[axis] - 1))
+        };
+        let index = |c: [usize; 3]| (c[2] * dims[1] + c[1]) * dims[0] + c[0];
+
+        let mut grid = vec![u32::MAX; dims[0] * dims[1] * dims[2]];
+        let mut points = Vec::new();
+        let mut active = Vec::new();
+        let first = self.in_aabb3(bounds);
+        grid[index(cell_of(first))] = 0;
+        points.push(first);
+        active.push(0);
+
+        while !active.is_empty() {
+            let slot = self.below(active.len() as u32) as usize;
+            let center = points[active[slot]];
+            let found = (0..POISSON_ATTEMPTS).map(|_| center + self.on_sphere() * self.range(radius, radius * 2.0)).find(|p| {
+                if !bounds.contains_point(*p) {
+                    return false
+                }
+                let c = cell_of(*p);
+                let span = |axis: usize| c[axis].saturating_sub(2)..(c[axis] + 3).min(dims[axis]);
+                span(2).all(|k| span(1).all(|j| span(0).all(|i| {
+                    let other = grid[index([i, j, k])];
+                    other == u32::MAX || points[other as usize].distance(*p) >= radius
+                })))
+            });
+            match found {
+                Some(p) => {
+                    grid[index(cell_of(p))] = points.len() as u32;
+                    active.push(points.len());
+                    points.push(p);
+                }
+                None => {
+                    active.swap_remove(slot);
+                }
+            }
+        }
+        points
+    }
+}
+
+/// the natural log of a positive normal float, from a short series so it's the same everywhere
+fn ln(x: f32) -> f32 {
+    let bits = x.to_bits();
+    let mut exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mut mantissa = f32::from_bits((bits & 0x7f_ffff) | 0x3f80_0000);
+    if mantissa > SQRT_2 {
+        mantissa *= 0.5;
+        exponent += 1;
+    }
+    // ln(m) = 2 atanh((m - 1) / (m + 1)), and |t| stays below 0.172
+    let t = (mantissa - 1.0) / (mantissa + 1.0);
+    let t2 = t * t;
+    let series = 1.0 + t2 * (1.0 / 3.0 + t2 * (1.0 / 5.0 + t2 * (1.0 / 7.0 + t2 * (1.0 / 9.0))));
+    exponent as f32 * LN_2 + 2.0 * t * series
+}
@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+
+/// how `Matrix * Matrix` should be computed,
+/// passed to [`Matrix::mul_with`](crate::math::matrix::Matrix::mul_with).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatmulStrategy {
+    /// choose from the matrix shapes and the installed
+    /// [`MatmulTuning`](crate::math::tuning::MatmulTuning), as `*` does
+    Auto,
+
+    /// [`Matrix::gemm`](crate::math::matrix::Matrix::gemm)
+    Naive,
+
+    /// [`Matrix::gemm_blocked`](crate::math::matrix::Matrix::gemm_blocked),
+    /// with the block size from the installed tuning
+    Blocked,
+
+    /// Strassen's algorithm, recursing until sub-matrices are at most `cutoff` wide
+    Strassen { cutoff: usize },
+
+    /// the SIMD kernels, only available for two 2x2, 3x3 or 4x4 matrices
+    Simd,
+
+    /// [`Matrix::gemm_parallel`](crate::math::matrix::Matrix::gemm_parallel),
+    /// splitting rows across the rayon thread pool
+    Parallel,
+}
+
+/// the strategy a multiplication will use, and why it was chosen
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatmulPlan {
+    /// the resolved strategy, this is never [`MatmulStrategy::Auto`]
+    pub strategy: MatmulStrategy,
+
+    /// the padded size used to compare against the tuning thresholds
+    pub size: usize,
+
+    /// a human readable explanation of the choice
+    pub reason: String,
+}
+
+impl Display for MatmulStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatmulStrategy::Auto => write!(f, "auto"),
+            MatmulStrategy::Naive => write!(f, "naive"),
+            MatmulStrategy::Blocked => write!(f, "blocked"),
+            MatmulStrategy::Strassen { cutoff } => write!(f, "strassen (cutoff {cutoff})"),
+            MatmulStrategy::Simd => write!(f, "simd"),
+            MatmulStrategy::Parallel => write!(f, "parallel"),
+        }
+    }
+}
+
+impl Display for MatmulPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.strategy, self.reason)
+    }
+}
@@ -0,0 +1,211 @@
+use std::any::TypeId;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Sub};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use crate::math::matrix::Matrix;
+use crate::math::matrix_simd::MatrixSimd;
+
+/// tunings installed for each element type
+static INSTALLED: RwLock<Vec<(TypeId, MatmulTuning)>> = RwLock::new(Vec::new());
+
+/// the sizes timed by [`MatmulTuning::calibrate`]
+const CALIBRATION_SIZES: [usize; 5] = [32, 64, 128, 256, 512];
+
+/// crossover points used by `Matrix * Matrix` to choose an algorithm.
+///
+/// sizes are the padded dimension used by the multiplication,
+/// that is the largest dimension of either operand rounded up to a power of two.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatmulTuning {
+    /// the largest size multiplied with [`Matrix::gemm`]
+    pub gemm_max: usize,
+
+    /// the largest size multiplied with [`Matrix::gemm_blocked`],
+    /// anything larger uses [`Matrix::strassen`]
+    pub blocked_max: usize,
+
+    /// the tile size passed to [`Matrix::gemm_blocked`]
+    pub block_size: usize,
+}
+
+impl Default for MatmulTuning {
+    fn default() -> Self {
+        MatmulTuning::DEFAULT
+    }
+}
+
+impl MatmulTuning {
+    /// the thresholds used when nothing has been calibrated
+    pub const DEFAULT: MatmulTuning = MatmulTuning {
+        gemm_max: 256,
+        blocked_max: 256,
+        block_size: 64,
+    };
+
+    /// the tuning currently used for matrices of `T`
+    pub fn current<T: 'static>() -> MatmulTuning {
+        let installed = INSTALLED.read().unwrap();
+        installed.iter()
+            .find(|(id, _)| *id == TypeId::of::<T>())
+            .map(|(_, tuning)| *tuning)
+            .unwrap_or(MatmulTuning::DEFAULT)
+    }
+
+    /// use this tuning for all future multiplications of matrices of `T`
+    pub fn install<T: 'static>(self) {
+        let mut installed = INSTALLED.write().unwrap();
+        match installed.iter_mut().find(|(id, _)| *id == TypeId::of::<T>()) {
+            Some((_, tuning)) => *tuning = self,
+            None => installed.push((TypeId::of::<T>(), self)),
+        }
+    }
+
+    /// time each algorithm on this machine and find the crossover points for `T`.
+    ///
+    /// this takes a few seconds, so it is best run once and saved with [`MatmulTuning::save`].
+    pub fn calibrate<T>() -> MatmulTuning
+    where
+        T: Default + Clone + Copy
+        + Mul<Output = T> + Add<Output = T> + Sub<Output = T>
+        + AddAssign
+        + MatrixSimd,
+    {
+        Self::calibrate_sizes::<T>(&CALIBRATION_SIZES)
+    }
+
+    /// like [`MatmulTuning::calibrate`], timing only the given power of two `sizes`
+    pub fn calibrate_sizes<T>(sizes: &[usize]) -> MatmulTuning
+    where
+        T: Default + Clone + Copy
+        + Mul<Output = T> + Add<Output = T> + Sub<Output = T>
+        + AddAssign
+        + MatrixSimd,
+    {
+        assert!(!sizes.is_empty());
+        assert!(sizes.iter().all(|s| s.is_power_of_two()));
+
+        let mut sizes = sizes.to_vec();
+        sizes.sort_unstable();
+
+        let block_size = Self::DEFAULT.block_size;
+        let mut gemm_max = 0;
+        let mut blocked_max = 0;
+        let mut gemm_lost = false;
+        let mut blocked_lost = false;
+
+        for &size in &sizes {
+            let a = Matrix::<T>::new(size, size);
+            let b = Matrix::<T>::new(size, size);
+
+            let gemm = time(|| a.gemm(b.clone()));
+            let blocked = time(|| a.gemm_blocked(b.clone(), block_size));
+            let strassen = time(|| a.strassen(b.clone(), size));
+
+            // only the first crossovers count, noise at larger sizes shouldn't move them back
+            if !gemm_lost && gemm <= blocked && gemm <= strassen {
+                gemm_max = size;
+            } else {
+                gemm_lost = true;
+            }
+
+            if !blocked_lost && (blocked <= strassen || gemm <= strassen) {
+                blocked_max = size;
+            } else {
+                blocked_lost = true;
+            }
+        }
+
+        MatmulTuning {
+            gemm_max,
+            blocked_max: blocked_max.max(gemm_max),
+            block_size,
+        }
+    }
+
+    /// write this tuning to `path` in its text form
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    /// read a tuning previously written by [`MatmulTuning::save`]
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<MatmulTuning> {
+        std::fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// the fastest of a few runs of `f`
+fn time<R>(mut f: impl FnMut() -> R) -> Duration {
+    (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+impl Display for MatmulTuning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "gemm_max = {}", self.gemm_max)?;
+        writeln!(f, "blocked_max = {}", self.blocked_max)?;
+        writeln!(f, "block_size = {}", self.block_size)
+    }
+}
+
+/// an error from parsing the text form of a [`MatmulTuning`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseTuningError {
+    /// a line that isn't of the form `key = value`
+    Malformed(String),
+    /// a key that isn't a field of [`MatmulTuning`]
+    UnknownKey(String),
+    /// a value that isn't a valid size
+    InvalidValue(String),
+}
+
+impl Display for ParseTuningError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseTuningError::Malformed(line) => write!(f, "malformed line: {line:?}"),
+            ParseTuningError::UnknownKey(key) => write!(f, "unknown key: {key:?}"),
+            ParseTuningError::InvalidValue(value) => write!(f, "invalid value: {value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseTuningError {}
+
+impl FromStr for MatmulTuning {
+    type Err = ParseTuningError;
+
+    /// parse the text form written by [`Display`],
+    /// missing keys keep their [`MatmulTuning::DEFAULT`] values.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tuning = MatmulTuning::DEFAULT;
+
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (key, value) = line.split_once('=')
+                .ok_or_else(|| ParseTuningError::Malformed(line.to_string()))?;
+            let (key, value) = (key.trim(), value.trim());
+
+            let value = value.parse::<usize>()
+                .map_err(|_| ParseTuningError::InvalidValue(value.to_string()))?;
+
+            match key {
+                "gemm_max" => tuning.gemm_max = value,
+                "blocked_max" => tuning.blocked_max = value,
+                "block_size" if value > 0 => tuning.block_size = value,
+                "block_size" => return Err(ParseTuningError::InvalidValue(value.to_string())),
+                _ => return Err(ParseTuningError::UnknownKey(key.to_string())),
+            }
+        }
+
+        Ok(tuning)
+    }
+}
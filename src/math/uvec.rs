@@ -0,0 +1,11 @@
+use crate::math::vec_ops::{define_vec2, define_vec3};
+
+define_vec2!(
+    /// A two-dimensional vector of `u32`, for things like grid sizes
+    UVec2, u32
+);
+
+define_vec3!(
+    /// A three-dimensional vector of `u32`, for things like grid sizes
+    UVec3, u32
+);
@@ -0,0 +1,183 @@
+use std::fmt::{Debug, Formatter};
+use crate::math::deterministic::sqrt;
+use crate::math::vec3::Vec3;
+use crate::math::vec_ops::{impl_vec_neg, impl_vec_ops};
+
+/// A four-dimensional vector, mostly used for homogeneous coordinates
+#[derive(Clone, Copy, PartialEq)]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// wrapper function for [`Vec4::new()`]
+#[inline(always)]
+#[allow(non_snake_case)]
+pub const fn Vec4(x: f32, y: f32, z: f32, w: f32) -> Vec4 {
+    Vec4::new(x, y, z, w)
+}
+
+impl Vec4 {
+    /// construct a new [`Vec4`]
+    #[inline(always)]
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Vec4 {
+        Vec4 { x, y, z, w }
+    }
+
+    /// construct a [`Vec4`] with all components set to `v`
+    #[inline(always)]
+    pub const fn splat(v: f32) -> Vec4 {
+        Vec4(v, v, v, v)
+    }
+
+    /// a vector where all components are zero
+    pub const ZERO: Vec4 = Vec4(0.0, 0.0, 0.0, 0.0);
+
+    /// a vector where all components are one
+    pub const ONE: Vec4 = Vec4(1.0, 1.0, 1.0, 1.0);
+
+    /// a unit vector parallel to the x-axis, in the positive direction
+    pub const X: Vec4 = Vec4(1.0, 0.0, 0.0, 0.0);
+
+    /// a unit vector parallel to the y-axis, in the positive direction
+    pub const Y: Vec4 = Vec4(0.0, 1.0, 0.0, 0.0);
+
+    /// a unit vector parallel to the z-axis, in the positive direction
+    pub const Z: Vec4 = Vec4(0.0, 0.0, 1.0, 0.0);
+
+    /// a unit vector parallel to the w-axis, in the positive direction
+    pub const W: Vec4 = Vec4(0.0, 0.0, 0.0, 1.0);
+
+    /// a homogeneous point from a [`Vec3`], with `w` set to one
+    #[inline(always)]
+    pub const fn from_point(v: Vec3) -> Vec4 {
+        Vec4(v.x, v.y, v.z, 1.0)
+    }
+
+    /// a homogeneous direction from a [`Vec3`], with `w` set to zero
+    #[inline(always)]
+    pub const fn from_direction(v: Vec3) -> Vec4 {
+        Vec4(v.x, v.y, v.z, 0.0)
+    }
+
+    /// the x, y, and z components divided by `w`,
+    /// turning a homogeneous point back into a [`Vec3`]
+    #[inline(always)]
+    pub fn project(self) -> Vec3 {
+        self.xyz() / self.w
+    }
+
+    /// check whether a vectors components are finite values
+    #[inline(always)]
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite() && self.w.is_finite()
+    }
+
+    /// computes the dot product of two [`Vec4`]s
+    #[inline(always)]
+    pub fn dot(self, other: Vec4) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// computes the squared magnitude of a [`Vec4`]
+    #[inline(always)]
+    pub fn magnitude_squared(self) -> f32 {
+        self.dot(self)
+    }
+
+    /// computes the magnitude of a [`Vec4`]
+    #[inline(always)]
+    pub fn magnitude(self) -> f32 {
+        sqrt(self.dot(self))
+    }
+
+    /// normalize a [`Vec4`]
+    ///
+    /// panics if the vector has zero length or is not finite,
+    /// see [`Vec4::try_normalize`] and [`Vec4::normalize_or_zero`].
+    #[inline(always)]
+    pub fn normalize(self) -> Vec4 {
+        let res = self / self.magnitude();
+        assert!(res.is_finite());
+        res
+    }
+
+    /// normalize a [`Vec4`], returning none if the vector has zero length or is not finite
+    #[inline(always)]
+    pub fn try_normalize(self) -> Option<Vec4> {
+        let res = self / self.magnitude();
+        res.is_finite().then_some(res)
+    }
+
+    /// normalize a [`Vec4`], returning [`Vec4::ZERO`] if the vector has zero length or is not finite
+    #[inline(always)]
+    pub fn normalize_or_zero(self) -> Vec4 {
+        self.try_normalize().unwrap_or(Vec4::ZERO)
+    }
+
+    /// linearly interpolate between `self` and `other`,
+    /// where `t = 0` gives `self` and `t = 1` gives `other`
+    #[inline(always)]
+    pub fn lerp(self, other: Vec4, t: f32) -> Vec4 {
+        self + (other - self) * t
+    }
+
+    /// the component-wise minimum of two [`Vec4`]s
+    #[inline(always)]
+    pub fn min(self, other: Vec4) -> Vec4 {
+        Vec4(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+            self.w.min(other.w),
+        )
+    }
+
+    /// the component-wise maximum of two [`Vec4`]s
+    #[inline(always)]
+    pub fn max(self, other: Vec4) -> Vec4 {
+        Vec4(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+            self.w.max(other.w),
+        )
+    }
+
+    /// the absolute value of each component
+    #[inline(always)]
+    pub fn abs(self) -> Vec4 {
+        Vec4(self.x.abs(), self.y.abs(), self.z.abs(), self.w.abs())
+    }
+
+    /// clamp each component between the matching components of `min` and `max`
+    #[inline(always)]
+    pub fn clamp(self, min: Vec4, max: Vec4) -> Vec4 {
+        Vec4(
+            self.x.clamp(min.x, max.x),
+            self.y.clamp(min.y, max.y),
+            self.z.clamp(min.z, max.z),
+            self.w.clamp(min.w, max.w),
+        )
+    }
+
+    /// apply some function `f` for the x, y, z, and w components of a [`Vec4`]
+    #[inline]
+    pub fn map<F>(self, f: F) -> Vec4
+    where
+        F: Fn(f32) -> f32
+    {
+        Vec4(f(self.x), f(self.y), f(self.z), f(self.w))
+    }
+}
+
+impl Debug for Vec4 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "<{}, {}, {}, {}>", self.x, self.y, self.z, self.w)
+    }
+}
+
+impl_vec_ops!(Vec4, f32, { 0 => x, 1 => y, 2 => z, 3 => w });
+impl_vec_neg!(Vec4, { x, y, z, w });
@@ -0,0 +1,42 @@
+use crate::math::dvec::{DVec2, DVec3};
+use crate::math::ivec::{IVec2, IVec3};
+use crate::math::uvec::{UVec2, UVec3};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+use crate::math::vec_ops::{impl_vec_casts, impl_vec_extend, impl_vec_from};
+
+// lossless conversions, every component fits exactly in the target type
+impl_vec_from!(Vec2 => DVec2, { x, y });
+impl_vec_from!(IVec2 => DVec2, { x, y });
+impl_vec_from!(UVec2 => DVec2, { x, y });
+impl_vec_from!(Vec3 => DVec3, { x, y, z });
+impl_vec_from!(IVec3 => DVec3, { x, y, z });
+impl_vec_from!(UVec3 => DVec3, { x, y, z });
+
+// lossy conversions, float to integer casts truncate towards zero and saturate,
+// integer casts between signed and unsigned wrap
+impl_vec_casts!(Vec2, { x, y },
+    as_dvec2 => DVec2: f64, as_ivec2 => IVec2: i32, as_uvec2 => UVec2: u32);
+impl_vec_casts!(DVec2, { x, y },
+    as_vec2 => Vec2: f32, as_ivec2 => IVec2: i32, as_uvec2 => UVec2: u32);
+impl_vec_casts!(IVec2, { x, y },
+    as_vec2 => Vec2: f32, as_dvec2 => DVec2: f64, as_uvec2 => UVec2: u32);
+impl_vec_casts!(UVec2, { x, y },
+    as_vec2 => Vec2: f32, as_dvec2 => DVec2: f64, as_ivec2 => IVec2: i32);
+
+impl_vec_casts!(Vec3, { x, y, z },
+    as_dvec3 => DVec3: f64, as_ivec3 => IVec3: i32, as_uvec3 => UVec3: u32);
+impl_vec_casts!(DVec3, { x, y, z },
+    as_vec3 => Vec3: f32, as_ivec3 => IVec3: i32, as_uvec3 => UVec3: u32);
+impl_vec_casts!(IVec3, { x, y, z },
+    as_vec3 => Vec3: f32, as_dvec3 => DVec3: f64, as_uvec3 => UVec3: u32);
+impl_vec_casts!(UVec3, { x, y, z },
+    as_vec3 => Vec3: f32, as_dvec3 => DVec3: f64, as_ivec3 => IVec3: i32);
+
+// growing by a component and dropping it again
+impl_vec_extend!(Vec2 => Vec3, f32, { x, y } + z, xy);
+impl_vec_extend!(Vec3 => Vec4, f32, { x, y, z } + w, xyz);
+impl_vec_extend!(DVec2 => DVec3, f64, { x, y } + z, xy);
+impl_vec_extend!(IVec2 => IVec3, i32, { x, y } + z, xy);
+impl_vec_extend!(UVec2 => UVec3, u32, { x, y } + z, xy);
@@ -0,0 +1,686 @@
+/// implements the arithmetic operators for a vector type with named components.
+///
+/// this covers component-wise `+ - * /` between vectors, `* /` by a scalar on either side,
+/// the assigning versions of each, the same operators on references,
+/// indexing by component number, and [`Sum`](std::iter::Sum).
+///
+/// ```ignore
+/// impl_vec_ops!(Vec2, f32, { 0 => x, 1 => y });
+/// ```
+macro_rules! impl_vec_ops {
+    ($Vec:ident, $T:ty, { $($index:literal => $field:ident),+ }) => {
+        $crate::math::vec_ops::impl_vec_ops!(@binop $Vec, $T, Add, add, AddAssign, add_assign, +, { $($field),+ });
+        $crate::math::vec_ops::impl_vec_ops!(@binop $Vec, $T, Sub, sub, SubAssign, sub_assign, -, { $($field),+ });
+        $crate::math::vec_ops::impl_vec_ops!(@binop $Vec, $T, Mul, mul, MulAssign, mul_assign, *, { $($field),+ });
+        $crate::math::vec_ops::impl_vec_ops!(@binop $Vec, $T, Div, div, DivAssign, div_assign, /, { $($field),+ });
+
+        $crate::math::vec_ops::impl_vec_ops!(@scalar $Vec, $T, Mul, mul, MulAssign, mul_assign, *, { $($field),+ });
+        $crate::math::vec_ops::impl_vec_ops!(@scalar $Vec, $T, Div, div, DivAssign, div_assign, /, { $($field),+ });
+
+        impl std::ops::Mul<$Vec> for $T {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn mul(self, rhs: $Vec) -> Self::Output {
+                $Vec { $($field: self * rhs.$field),+ }
+            }
+        }
+
+        impl std::ops::Mul<&$Vec> for $T {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn mul(self, rhs: &$Vec) -> Self::Output {
+                self * *rhs
+            }
+        }
+
+        impl std::ops::Index<usize> for $Vec {
+            type Output = $T;
+
+            fn index(&self, index: usize) -> &Self::Output {
+                match index {
+                    $($index => &self.$field,)+
+                    _ => panic!("index {} out of bounds for {}", index, stringify!($Vec)),
+                }
+            }
+        }
+
+        impl std::ops::IndexMut<usize> for $Vec {
+            fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+                match index {
+                    $($index => &mut self.$field,)+
+                    _ => panic!("index {} out of bounds for {}", index, stringify!($Vec)),
+                }
+            }
+        }
+
+        impl std::iter::Sum for $Vec {
+            fn sum<I: Iterator<Item = $Vec>>(iter: I) -> Self {
+                iter.fold($Vec::ZERO, |acc, v| acc + v)
+            }
+        }
+
+        impl<'a> std::iter::Sum<&'a $Vec> for $Vec {
+            fn sum<I: Iterator<Item = &'a $Vec>>(iter: I) -> Self {
+                iter.fold($Vec::ZERO, |acc, v| acc + *v)
+            }
+        }
+    };
+
+    // component-wise operators between two vectors, by value and by reference
+    (@binop $Vec:ident, $T:ty, $Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $sym:tt, { $($field:ident),+ }) => {
+        impl std::ops::$Op for $Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn $op(self, rhs: $Vec) -> Self::Output {
+                $Vec { $($field: self.$field $sym rhs.$field),+ }
+            }
+        }
+
+        impl std::ops::$Op<&$Vec> for $Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn $op(self, rhs: &$Vec) -> Self::Output {
+                self $sym *rhs
+            }
+        }
+
+        impl std::ops::$Op<$Vec> for &$Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn $op(self, rhs: $Vec) -> Self::Output {
+                *self $sym rhs
+            }
+        }
+
+        impl std::ops::$Op<&$Vec> for &$Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn $op(self, rhs: &$Vec) -> Self::Output {
+                *self $sym *rhs
+            }
+        }
+
+        impl std::ops::$OpAssign for $Vec {
+            #[inline(always)]
+            fn $op_assign(&mut self, rhs: $Vec) {
+                *self = *self $sym rhs;
+            }
+        }
+
+        impl std::ops::$OpAssign<&$Vec> for $Vec {
+            #[inline(always)]
+            fn $op_assign(&mut self, rhs: &$Vec) {
+                *self = *self $sym *rhs;
+            }
+        }
+    };
+
+    // operators between a vector and a scalar on the right hand side
+    (@scalar $Vec:ident, $T:ty, $Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $sym:tt, { $($field:ident),+ }) => {
+        impl std::ops::$Op<$T> for $Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn $op(self, rhs: $T) -> Self::Output {
+                $Vec { $($field: self.$field $sym rhs),+ }
+            }
+        }
+
+        impl std::ops::$Op<$T> for &$Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn $op(self, rhs: $T) -> Self::Output {
+                *self $sym rhs
+            }
+        }
+
+        impl std::ops::$OpAssign<$T> for $Vec {
+            #[inline(always)]
+            fn $op_assign(&mut self, rhs: $T) {
+                *self = *self $sym rhs;
+            }
+        }
+    };
+}
+
+/// implements negation for a vector type, by value and by reference
+macro_rules! impl_vec_neg {
+    ($Vec:ident, { $($field:ident),+ }) => {
+        impl std::ops::Neg for $Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn neg(self) -> Self::Output {
+                $Vec { $($field: -self.$field),+ }
+            }
+        }
+
+        impl std::ops::Neg for &$Vec {
+            type Output = $Vec;
+
+            #[inline(always)]
+            fn neg(self) -> Self::Output {
+                -*self
+            }
+        }
+    };
+}
+
+/// defines a two-dimensional vector type over `$T`,
+/// along with a constructor function of the same name
+/// and the methods shared by every scalar type.
+macro_rules! define_vec2 {
+    ($(#[$meta:meta])* $Vec:ident, $T:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Default, PartialEq)]
+        pub struct $Vec {
+            pub x: $T,
+            pub y: $T,
+        }
+
+        #[doc = concat!("wrapper function for [`", stringify!($Vec), "::new()`]")]
+        #[inline(always)]
+        #[allow(non_snake_case)]
+        pub const fn $Vec(x: $T, y: $T) -> $Vec {
+            $Vec::new(x, y)
+        }
+
+        impl $Vec {
+            #[doc = concat!("construct a new [`", stringify!($Vec), "`]")]
+            #[inline(always)]
+            pub const fn new(x: $T, y: $T) -> $Vec {
+                $Vec { x, y }
+            }
+
+            #[doc = concat!("construct a [`", stringify!($Vec), "`] with both components set to `v`")]
+            #[inline(always)]
+            pub const fn splat(v: $T) -> $Vec {
+                $Vec(v, v)
+            }
+
+            /// a vector where both x and y components are zero
+            pub const ZERO: $Vec = $Vec(0 as $T, 0 as $T);
+
+            /// a vector where both x and y components are one
+            pub const ONE: $Vec = $Vec(1 as $T, 1 as $T);
+
+            /// a unit vector parallel to the x-axis, in the positive direction
+            pub const X: $Vec = $Vec(1 as $T, 0 as $T);
+
+            /// a unit vector parallel to the y-axis, in the positive direction
+            pub const Y: $Vec = $Vec(0 as $T, 1 as $T);
+
+            #[doc = concat!("computes the dot product of two [`", stringify!($Vec), "`]s")]
+            #[inline(always)]
+            pub fn dot(self, other: $Vec) -> $T {
+                self.x * other.x + self.y * other.y
+            }
+
+            #[doc = concat!("computes the squared magnitude of a [`", stringify!($Vec), "`]")]
+            #[inline(always)]
+            pub fn magnitude_squared(self) -> $T {
+                self.dot(self)
+            }
+
+            /// the component-wise minimum of two vectors
+            #[inline(always)]
+            pub fn min(self, other: $Vec) -> $Vec {
+                $Vec(self.x.min(other.x), self.y.min(other.y))
+            }
+
+            /// the component-wise maximum of two vectors
+            #[inline(always)]
+            pub fn max(self, other: $Vec) -> $Vec {
+                $Vec(self.x.max(other.x), self.y.max(other.y))
+            }
+
+            /// clamp each component between the matching components of `min` and `max`
+            #[inline(always)]
+            pub fn clamp(self, min: $Vec, max: $Vec) -> $Vec {
+                $Vec(self.x.clamp(min.x, max.x), self.y.clamp(min.y, max.y))
+            }
+
+            /// the smallest component
+            #[inline(always)]
+            pub fn min_element(self) -> $T {
+                self.x.min(self.y)
+            }
+
+            /// the largest component
+            #[inline(always)]
+            pub fn max_element(self) -> $T {
+                self.x.max(self.y)
+            }
+
+            /// apply some function `f` for both the x and y components
+            #[inline]
+            pub fn map<F>(self, f: F) -> $Vec
+            where
+                F: Fn($T) -> $T
+            {
+                $Vec(f(self.x), f(self.y))
+            }
+        }
+
+        impl std::fmt::Debug for $Vec {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                writeln!(f, "<{}, {}>", self.x, self.y)
+            }
+        }
+
+        impl From<[$T; 2]> for $Vec {
+            fn from([x, y]: [$T; 2]) -> Self {
+                $Vec(x, y)
+            }
+        }
+
+        impl From<$Vec> for [$T; 2] {
+            fn from(v: $Vec) -> Self {
+                [v.x, v.y]
+            }
+        }
+
+        $crate::math::vec_ops::impl_vec_ops!($Vec, $T, { 0 => x, 1 => y });
+    };
+}
+
+/// defines a three-dimensional vector type over `$T`,
+/// along with a constructor function of the same name
+/// and the methods shared by every scalar type.
+macro_rules! define_vec3 {
+    ($(#[$meta:meta])* $Vec:ident, $T:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Default, PartialEq)]
+        pub struct $Vec {
+            pub x: $T,
+            pub y: $T,
+            pub z: $T,
+        }
+
+        #[doc = concat!("wrapper function for [`", stringify!($Vec), "::new()`]")]
+        #[inline(always)]
+        #[allow(non_snake_case)]
+        pub const fn $Vec(x: $T, y: $T, z: $T) -> $Vec {
+            $Vec::new(x, y, z)
+        }
+
+        impl $Vec {
+            #[doc = concat!("construct a new [`", stringify!($Vec), "`]")]
+            #[inline(always)]
+            pub const fn new(x: $T, y: $T, z: $T) -> $Vec {
+                $Vec { x, y, z }
+            }
+
+            #[doc = concat!("construct a [`", stringify!($Vec), "`] with all components set to `v`")]
+            #[inline(always)]
+            pub const fn splat(v: $T) -> $Vec {
+                $Vec(v, v, v)
+            }
+
+            /// a vector where all components are zero
+            pub const ZERO: $Vec = $Vec(0 as $T, 0 as $T, 0 as $T);
+
+            /// a vector where all components are one
+            pub const ONE: $Vec = $Vec(1 as $T, 1 as $T, 1 as $T);
+
+            /// a unit vector parallel to the x-axis, in the positive direction
+            pub const X: $Vec = $Vec(1 as $T, 0 as $T, 0 as $T);
+
+            /// a unit vector parallel to the y-axis, in the positive direction
+            pub const Y: $Vec = $Vec(0 as $T, 1 as $T, 0 as $T);
+
+            /// a unit vector parallel to the z-axis, in the positive direction
+            pub const Z: $Vec = $Vec(0 as $T, 0 as $T, 1 as $T);
+
+            #[doc = concat!("computes the dot product of two [`", stringify!($Vec), "`]s")]
+            #[inline(always)]
+            pub fn dot(self, other: $Vec) -> $T {
+                self.x * other.x + self.y * other.y + self.z * other.z
+            }
+
+            #[doc = concat!("computes the squared magnitude of a [`", stringify!($Vec), "`]")]
+            #[inline(always)]
+            pub fn magnitude_squared(self) -> $T {
+                self.dot(self)
+            }
+
+            /// the component-wise minimum of two vectors
+            #[inline(always)]
+            pub fn min(self, other: $Vec) -> $Vec {
+                $Vec(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+            }
+
+            /// the component-wise maximum of two vectors
+            #[inline(always)]
+            pub fn max(self, other: $Vec) -> $Vec {
+                $Vec(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+            }
+
+            /// clamp each component between the matching components of `min` and `max`
+            #[inline(always)]
+            pub fn clamp(self, min: $Vec, max: $Vec) -> $Vec {
+                $Vec(
+                    self.x.clamp(min.x, max.x),
+                    self.y.clamp(min.y, max.y),
+                    self.z.clamp(min.z, max.z),
+                )
+            }
+
+            /// the smallest component
+            #[inline(always)]
+            pub fn min_element(self) -> $T {
+                self.x.min(self.y).min(self.z)
+            }
+
+            /// the largest component
+            #[inline(always)]
+            pub fn max_element(self) -> $T {
+                self.x.max(self.y).max(self.z)
+            }
+
+            /// apply some function `f` for the x, y, and z components
+            #[inline]
+            pub fn map<F>(self, f: F) -> $Vec
+            where
+                F: Fn($T) -> $T
+            {
+                $Vec(f(self.x), f(self.y), f(self.z))
+            }
+        }
+
+        impl std::fmt::Debug for $Vec {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                writeln!(f, "<{}, {}, {}>", self.x, self.y, self.z)
+            }
+        }
+
+        impl From<[$T; 3]> for $Vec {
+            fn from([x, y, z]: [$T; 3]) -> Self {
+                $Vec(x, y, z)
+            }
+        }
+
+        impl From<$Vec> for [$T; 3] {
+            fn from(v: $Vec) -> Self {
+                [v.x, v.y, v.z]
+            }
+        }
+
+        $crate::math::vec_ops::impl_vec_ops!($Vec, $T, { 0 => x, 1 => y, 2 => z });
+    };
+}
+
+/// adds the methods and constants that only make sense for signed components,
+/// with the perpendicular vector and perp dot product for two-dimensional types
+macro_rules! impl_vec_signed {
+    (@common $Vec:ident, { $($field:ident),+ }) => {
+        impl $Vec {
+            /// the absolute value of each component
+            #[inline(always)]
+            pub fn abs(self) -> $Vec {
+                $Vec { $($field: self.$field.abs()),+ }
+            }
+
+            /// the sign of each component
+            #[inline(always)]
+            pub fn signum(self) -> $Vec {
+                $Vec { $($field: self.$field.signum()),+ }
+            }
+        }
+
+        $crate::math::vec_ops::impl_vec_neg!($Vec, { $($field),+ });
+    };
+
+    ($Vec:ident, $T:ty, { x, y }) => {
+        impl $Vec {
+            /// a unit vector parallel to the x-axis, in the negative direction
+            pub const NEG_X: $Vec = $Vec(-1 as $T, 0 as $T);
+
+            /// a unit vector parallel to the y-axis, in the negative direction
+            pub const NEG_Y: $Vec = $Vec(0 as $T, -1 as $T);
+
+            /// computes the z component of the cross product of two vectors
+            /// extended into 3d, also known as the perp dot product
+            #[inline(always)]
+            pub fn perp_dot(self, other: $Vec) -> $T {
+                self.x * other.y - self.y * other.x
+            }
+
+            /// the vector rotated a quarter turn counterclockwise
+            #[inline(always)]
+            pub fn perp(self) -> $Vec {
+                $Vec(-self.y, self.x)
+            }
+        }
+
+        $crate::math::vec_ops::impl_vec_signed!(@common $Vec, { x, y });
+    };
+
+    ($Vec:ident, $T:ty, { x, y, z }) => {
+        impl $Vec {
+            /// a unit vector parallel to the x-axis, in the negative direction
+            pub const NEG_X: $Vec = $Vec(-1 as $T, 0 as $T, 0 as $T);
+
+            /// a unit vector parallel to the y-axis, in the negative direction
+            pub const NEG_Y: $Vec = $Vec(0 as $T, -1 as $T, 0 as $T);
+
+            /// a unit vector parallel to the z-axis, in the negative direction
+            pub const NEG_Z: $Vec = $Vec(0 as $T, 0 as $T, -1 as $T);
+        }
+
+        $crate::math::vec_ops::impl_vec_signed!(@common $Vec, { x, y, z });
+    };
+}
+
+/// adds the methods that only make sense for floating point components,
+/// taking magnitudes with `$sqrt`, which defaults to the scalar type's own square root
+macro_rules! impl_vec_float {
+    (@common $Vec:ident, $T:ty, { $($field:ident),+ }, $sqrt:expr) => {
+        impl $Vec {
+            /// check whether a vectors components are finite values
+            #[inline(always)]
+            pub fn is_finite(&self) -> bool {
+                $(self.$field.is_finite())&&+
+            }
+
+            #[doc = concat!("computes the magnitude of a [`", stringify!($Vec), "`]")]
+            #[inline(always)]
+            pub fn magnitude(self) -> $T {
+                $sqrt(self.dot(self))
+            }
+
+            /// computes the distance between two points
+            #[inline(always)]
+            pub fn distance(self, other: $Vec) -> $T {
+                (self - other).magnitude()
+            }
+
+            #[doc = concat!("normalize a [`", stringify!($Vec), "`]")]
+            ///
+            /// panics if the vector has zero length or is not finite
+            #[inline(always)]
+            pub fn normalize(self) -> $Vec {
+                let res = self / self.magnitude();
+                assert!(res.is_finite());
+                res
+            }
+
+            /// normalize a vector, returning none if it has zero length or is not finite
+            #[inline(always)]
+            pub fn try_normalize(self) -> Option<$Vec> {
+                let res = self / self.magnitude();
+                res.is_finite().then_some(res)
+            }
+
+            /// normalize a vector, returning zero if it has zero length or is not finite
+            #[inline(always)]
+            pub fn normalize_or_zero(self) -> $Vec {
+                self.try_normalize().unwrap_or($Vec::ZERO)
+            }
+
+            /// linearly interpolate between `self` and `other`,
+            /// where `t = 0` gives `self` and `t = 1` gives `other`
+            #[inline(always)]
+            pub fn lerp(self, other: $Vec, t: $T) -> $Vec {
+                self + (other - self) * t
+            }
+
+            /// reflect a vector off a surface with the given unit `normal`
+            #[inline(always)]
+            pub fn reflect(self, normal: $Vec) -> $Vec {
+                self - normal * (2.0 * self.dot(normal))
+            }
+
+            /// the projection of `self` onto the direction of `other`
+            #[inline(always)]
+            pub fn project_onto(self, other: $Vec) -> $Vec {
+                other * (self.dot(other) / other.dot(other))
+            }
+
+            /// the part of `self` perpendicular to `other`
+            #[inline(always)]
+            pub fn reject_from(self, other: $Vec) -> $Vec {
+                self - self.project_onto(other)
+            }
+
+            /// the unsigned angle between two vectors in radians, in the range `0..=PI`
+            #[inline(always)]
+            pub fn angle_between(self, other: $Vec) -> $T {
+                let cos = self.dot(other) / (self.magnitude() * other.magnitude());
+                cos.clamp(-1.0, 1.0).acos()
+            }
+
+            /// round each component to the nearest integer
+            #[inline(always)]
+            pub fn round(self) -> $Vec {
+                $Vec { $($field: self.$field.round()),+ }
+            }
+
+            /// round each component down
+            #[inline(always)]
+            pub fn floor(self) -> $Vec {
+                $Vec { $($field: self.$field.floor()),+ }
+            }
+
+            /// round each component up
+            #[inline(always)]
+            pub fn ceil(self) -> $Vec {
+                $Vec { $($field: self.$field.ceil()),+ }
+            }
+        }
+    };
+
+    ($Vec:ident, $T:ty, $fields:tt) => {
+        $crate::math::vec_ops::impl_vec_float!($Vec, $T, $fields, <$T>::sqrt);
+    };
+
+    ($Vec:ident, $T:ty, { x, y, z }, $sqrt:expr) => {
+        impl $Vec {
+            /// some unit vector perpendicular to a unit vector
+            #[inline(always)]
+            pub fn any_orthonormal(self) -> $Vec {
+                // cross with whichever axis is least aligned, to stay well conditioned
+                let axis = if self.x.abs() < 0.57 { $Vec::X } else { $Vec::Y };
+                self.cross(axis).normalize()
+            }
+        }
+
+        $crate::math::vec_ops::impl_vec_float!(@common $Vec, $T, { x, y, z }, $sqrt);
+    };
+
+    ($Vec:ident, $T:ty, { $($field:ident),+ }, $sqrt:expr) => {
+        $crate::math::vec_ops::impl_vec_float!(@common $Vec, $T, { $($field),+ }, $sqrt);
+    };
+}
+
+/// adds the cross product to a signed three-dimensional vector type
+macro_rules! impl_vec3_cross {
+    ($Vec:ident) => {
+        impl $Vec {
+            #[inline(always)]
+            pub fn cross(self, other: $Vec) -> $Vec {
+                $Vec::new(
+                    self.y * other.z - self.z * other.y,
+                    self.z * other.x - self.x * other.z,
+                    self.x * other.y - self.y * other.x,
+                )
+            }
+        }
+    };
+}
+
+/// adds `extend` to `$Vec`, appending `$extra` to make a `$Bigger`,
+/// and `$truncate` to `$Bigger`, dropping `$extra` again
+macro_rules! impl_vec_extend {
+    ($Vec:ident => $Bigger:ident, $T:ty, { $($field:ident),+ } + $extra:ident, $truncate:ident) => {
+        impl $Vec {
+            #[doc = concat!(
+                "extend a [`", stringify!($Vec), "`] into a [`", stringify!($Bigger),
+                "`] with the given `", stringify!($extra), "` component"
+            )]
+            #[inline(always)]
+            pub const fn extend(self, $extra: $T) -> $Bigger {
+                $Bigger { $($field: self.$field,)+ $extra }
+            }
+        }
+
+        impl $Bigger {
+            #[doc = concat!("the `", stringify!($truncate), "` components of a [`", stringify!($Bigger), "`], dropping `", stringify!($extra), "`")]
+            #[inline(always)]
+            pub const fn $truncate(self) -> $Vec {
+                $Vec { $($field: self.$field),+ }
+            }
+        }
+    };
+}
+
+/// adds an `as_*` method to `$Vec` for each target type,
+/// converting every component with an `as` cast.
+///
+/// these may lose precision. float to integer casts truncate towards zero and saturate,
+/// while integer to integer casts that change sign wrap, so `-1` becomes `u32::MAX`.
+macro_rules! impl_vec_casts {
+    ($Vec:ident, $fields:tt, $($as_fn:ident => $Target:ident: $T:ty),+) => {
+        $($crate::math::vec_ops::impl_vec_casts!(@one $Vec, $fields, $as_fn, $Target, $T);)+
+    };
+
+    (@one $Vec:ident, { $($field:ident),+ }, $as_fn:ident, $Target:ident, $T:ty) => {
+        impl $Vec {
+            #[doc = concat!("convert to a [`", stringify!($Target), "`], casting each component with `as`")]
+            #[inline(always)]
+            pub fn $as_fn(self) -> $Target {
+                $Target { $($field: self.$field as $T),+ }
+            }
+        }
+    };
+}
+
+/// implements [`From`] between vector types whose components convert losslessly
+macro_rules! impl_vec_from {
+    ($Src:ident => $Dst:ident, { $($field:ident),+ }) => {
+        impl From<$Src> for $Dst {
+            #[inline(always)]
+            fn from(v: $Src) -> Self {
+                $Dst { $($field: v.$field.into()),+ }
+            }
+        }
+    };
+}
+
+pub(crate) use define_vec2;
+pub(crate) use define_vec3;
+pub(crate) use impl_vec3_cross;
+pub(crate) use impl_vec_casts;
+pub(crate) use impl_vec_extend;
+pub(crate) use impl_vec_float;
+pub(crate) use impl_vec_from;
+pub(crate) use impl_vec_neg;
+pub(crate) use impl_vec_ops;
+pub(crate) use impl_vec_signed;
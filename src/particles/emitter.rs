@@ -0,0 +1,161 @@
+use std::f32::consts::TAU;
+use crate::geometry::triangle::Triangle3;
+use crate::math::deterministic::sqrt;
+use crate::math::random::Pcg32;
+use crate::math::vec3::Vec3;
+
+/// Where an emitter spawns particles and which way they head off
+#[derive(Clone, Debug, PartialEq)]
+pub enum EmitterShape {
+    /// from a single point, in every direction
+    Point,
+    /// from a single point, within `angle` radians of `direction`
+    Cone { direction: Vec3, angle: f32 },
+    /// from inside a ball, or only its surface, heading outwards
+    Sphere { radius: f32, surface: bool },
+    /// from the surface of a triangle mesh, heading out along the faces' normals
+    Mesh(MeshSurface),
+}
+
+/// Triangles to spawn on, with running totals of their areas so bigger ones get more particles
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshSurface {
+    triangles: Vec<Triangle3>,
+    areas: Vec<f32>,
+}
+
+impl MeshSurface {
+    pub fn new(triangles: Vec<Triangle3>) -> MeshSurface {
+        assert!(!triangles.is_empty(), "mesh has no triangles");
+        let mut total = 0.0;
+        let areas = triangles.iter().map(|t| {
+            total += t.area();
+            total
+        }).collect();
+        MeshSurface { triangles, areas }
+    }
+
+    #[inline(always)]
+    pub fn triangles(&self) -> &[Triangle3] {
+        &self.triangles
+    }
+
+    /// the total area of the triangles
+    #[inline(always)]
+    pub fn area(&self) -> f32 {
+        *self.areas.last().unwrap()
+    }
+
+    /// a uniform point on the surface, with the normal of the triangle it's on
+    pub fn sample(&self, rng: &mut Pcg32) -> (Vec3, Vec3) {
+        let target = rng.next_f32() * self.area();
+        let i = self.areas.partition_point(|&a| a <= target).min(self.triangles.len() - 1);
+        let triangle = &self.triangles[i];
+        (rng.in_triangle3(triangle), triangle.normal().unwrap_or(Vec3::Y))
+    }
+}
+
+/// Spawns particles at a steady rate, and in bursts
+#[derive(Clone, Debug, PartialEq)]
+pub struct Emitter {
+    pub position: Vec3,
+    pub shape: EmitterShape,
+    /// particles per second
+    pub rate: f32,
+    /// the range the initial speed, lifetime and size are picked uniformly from
+    pub speed: (f32, f32),
+    pub lifetime: (f32, f32),
+    pub size: (f32, f32),
+    /// whether the emitter is spawning at its rate
+    pub active: bool,
+    /// the fraction of a particle owed from earlier updates
+    pending: f32,
+    /// particles to spawn at the next update on top of the rate
+    burst: usize,
+}
+
+/// What an emitter hands the particle system for each particle it spawns
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Spawn {
+    pub(crate) position: Vec3,
+    pub(crate) velocity: Vec3,
+    pub(crate) lifetime: f32,
+    pub(crate) size: f32,
+}
+
+/// a uniform direction within `angle` of `axis`, by picking uniformly on the spherical cap
+fn in_cone(rng: &mut Pcg32, axis: Vec3, angle: f32) -> Vec3 {
+    let axis = axis.normalize();
+    let cos = 1.0 - rng.next_f32() * (1.0 - angle.cos());
+    let sin = sqrt((1.0 - cos * cos).max(0.0));
+    let (s, c) = (rng.next_f32() * TAU).sin_cos();
+    let u = axis.any_orthonormal();
+    let v = axis.cross(u);
+    axis * cos + (u * c + v * s) * sin
+}
+
+impl Emitter {
+    /// an emitter spawning `rate` particles a second, at 1 unit a second, living a second and sized 1
+    pub fn new(position: Vec3, shape: EmitterShape, rate: f32) -> Emitter {
+        Emitter {
+            position,
+            shape,
+            rate,
+            speed: (1.0, 1.0),
+            lifetime: (1.0, 1.0),
+            size: (1.0, 1.0),
+            active: true,
+            pending: 0.0,
+            burst: 0,
+        }
+    }
+
+    pub fn with_speed(self, min: f32, max: f32) -> Emitter {
+        Emitter { speed: (min, max), ..self }
+    }
+
+    pub fn with_lifetime(self, min: f32, max: f32) -> Emitter {
+        assert!(min > 0.0, "particles have to live for some time");
+        Emitter { lifetime: (min, max), ..self }
+    }
+
+    pub fn with_size(self, min: f32, max: f32) -> Emitter {
+        Emitter { size: (min, max), ..self }
+    }
+
+    /// spawn `count` more particles at the next update
+    pub fn burst(&mut self, count: usize) {
+        self.burst += count;
+    }
+
+    /// how many particles are due over `dt` seconds, keeping the fraction left over
+    pub(crate) fn due(&mut self, dt: f32) -> usize {
+        if self.active {
+            self.pending += self.rate * dt;
+        }
+        let count = self.pending as usize;
+        self.pending -= count as f32;
+        count + std::mem::take(&mut self.burst)
+    }
+
+    /// one particle, with its position, direction and properties picked from the emitter's ranges
+    pub(crate) fn spawn(&self, rng: &mut Pcg32) -> Spawn {
+        let (offset, direction) = match &self.shape {
+            EmitterShape::Point => (Vec3::ZERO, rng.on_sphere()),
+            EmitterShape::Cone { direction, angle } => (Vec3::ZERO, in_cone(rng, *direction, *angle)),
+            EmitterShape::Sphere { radius, surface } => {
+                let direction = rng.on_sphere();
+                let distance = if *surface { *radius } else { radius * rng.next_f32().cbrt() };
+                (direction * distance, direction)
+            }
+            EmitterShape::Mesh(mesh) => mesh.sample(rng),
+        };
+        let speed = rng.range(self.speed.0, self.speed.1);
+        Spawn {
+            position: self.position + offset,
+            velocity: direction * speed,
+            lifetime: rng.range(self.lifetime.0, self.lifetime.1),
+            size: rng.range(self.size.0, self.size.1),
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use std::simd::{Simd, StdFloat};
+use std::simd::num::SimdFloat;
+use rayon::prelude::*;
+use crate::geometry::plane::Plane3;
+use crate::math::curves::{lerp, CurvePoint};
+use crate::math::random::Pcg32;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+
+pub mod emitter;
+
+pub use emitter::{Emitter, EmitterShape, MeshSurface};
+
+/// how many particles each parallel task updates
+const CHUNK: usize = 1024;
+/// how many particles are pushed through the forces at once
+const LANES: usize = 8;
+
+/// Live particles, stored as a structure of arrays so the update can work on whole lanes at once
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Particles {
+    pub px: Vec<f32>,
+    pub py: Vec<f32>,
+    pub pz: Vec<f32>,
+    pub vx: Vec<f32>,
+    pub vy: Vec<f32>,
+    pub vz: Vec<f32>,
+    /// seconds since the particle was spawned
+    pub age: Vec<f32>,
+    pub lifetime: Vec<f32>,
+    /// the size picked by the emitter, which the size over life scales
+    pub start_size: Vec<f32>,
+    pub size: Vec<f32>,
+    pub color: Vec<Vec4>,
+}
+
+/// The same particles as [`Particles`], borrowed for a chunk of them
+struct ParticlesMut<'a> {
+    px: &'a mut [f32],
+    py: &'a mut [f32],
+    pz: &'a mut [f32],
+    vx: &'a mut [f32],
+    vy: &'a mut [f32],
+    vz: &'a mut [f32],
+    age: &'a [f32],
+    lifetime: &'a [f32],
+    start_size: &'a [f32],
+    size: &'a mut [f32],
+    color: &'a mut [Vec4],
+}
+
+impl Particles {
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.px.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.px.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn position(&self, i: usize) -> Vec3 {
+        Vec3(self.px[i], self.py[i], self.pz[i])
+    }
+
+    #[inline(always)]
+    pub fn velocity(&self, i: usize) -> Vec3 {
+        Vec3(self.vx[i], self.vy[i], self.vz[i])
+    }
+
+    pub fn clear(&mut self) {
+        *self = Particles::default();
+    }
+
+    fn push(&mut self, position: Vec3, velocity: Vec3, lifetime: f32, size: f32, color: Vec4) {
+        self.px.push(position.x);
+        self.py.push(position.y);
+        self.pz.push(position.z);
+        self.vx.push(velocity.x);
+        self.vy.push(velocity.y);
+        self.vz.push(velocity.z);
+        self.age.push(0.0);
+        self.lifetime.push(lifetime);
+        self.start_size.push(size);
+        self.size.push(size);
+        self.color.push(color);
+    }
+
+    /// remove particle `i`, moving the last one into its place
+    fn swap_remove(&mut self, i: usize) {
+        self.px.swap_remove(i);
+        self.py.swap_remove(i);
+        self.pz.swap_remove(i);
+        self.vx.swap_remove(i);
+        self.vy.swap_remove(i);
+        self.vz.swap_remove(i);
+        self.age.swap_remove(i);
+        self.lifetime.swap_remove(i);
+        self.start_size.swap_remove(i);
+        self.size.swap_remove(i);
+        self.color.swap_remove(i);
+    }
+
+    /// split the particles into chunks of `size` that can be updated independently
+    fn chunks_mut(&mut self, size: usize) -> Vec<ParticlesMut<'_>> {
+        let mut chunks = Vec::new();
+        let mut rest = ParticlesMut {
+            px: &mut self.px,
+            py: &mut self.py,
+            pz: &mut self.pz,
+            vx: &mut self.vx,
+            vy: &mut self.vy,
+            vz: &mut self.vz,
+            age: &self.age,
+            lifetime: &self.lifetime,
+            start_size: &self.start_size,
+            size: &mut self.size,
+            color: &mut self.color,
+        };
+        while rest.px.len() > size {
+            let (chunk, tail) = rest.split_at(size);
+            chunks.push(chunk);
+            rest = tail;
+        }
+        chunks.push(rest);
+        chunks
+    }
+}
+
+impl<'a> ParticlesMut<'a> {
+    fn split_at(self, mid: usize) -> (ParticlesMut<'a>, ParticlesMut<'a>) {
+        let (px, px2) = self.px.split_at_mut(mid);
+        let (py, py2) = self.py.split_at_mut(mid);
+        let (pz, pz2) = self.pz.split_at_mut(mid);
+        let (vx, vx2) = self.vx.split_at_mut(mid);
+        let (vy, vy2) = self.vy.split_at_mut(mid);
+        let (vz, vz2) = self.vz.split_at_mut(mid);
+        let (age, age2) = self.age.split_at(mid);
+        let (lifetime, lifetime2) = self.lifetime.split_at(mid);
+        let (start_size, start_size2) = self.start_size.split_at(mid);
+        let (size, size2) = self.size.split_at_mut(mid);
+        let (color, color2) = self.color.split_at_mut(mid);
+        (
+            ParticlesMut { px, py, pz, vx, vy, vz, age, lifetime, start_size, size, color },
+            ParticlesMut {
+                px: px2,
+                py: py2,
+                pz: pz2,
+                vx: vx2,
+                vy: vy2,
+                vz: vz2,
+                age: age2,
+                lifetime: lifetime2,
+                start_size: start_size2,
+                size: size2,
+                color: color2,
+            },
+        )
+    }
+}
+
+/// A value that changes over a particle's life, linear between keys at fractions of the lifetime
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverLife<P> {
+    keys: Vec<(f32, P)>,
+}
+
+impl<P: CurvePoint> OverLife<P> {
+    /// keys are `(fraction of life, value)`, in order of fraction
+    pub fn new(keys: Vec<(f32, P)>) -> OverLife<P> {
+        assert!(!keys.is_empty(), "need at least one key");
+        assert!(keys.windows(2).all(|w| w[0].0 <= w[1].0), "keys must be in order");
+        OverLife { keys }
+    }
+
+    /// the same value for the whole life
+    pub fn constant(value: P) -> OverLife<P> {
+        OverLife { keys: vec![(0.0, value)] }
+    }
+
+    /// the value at fraction `t` of the life, holding the first and last keys outside of them
+    pub fn sample(&self, t: f32) -> P {
+        let i = self.keys.partition_point(|&(k, _)| k <= t);
+        if i == 0 {
+            return self.keys[0].1
+        }
+        if i == self.keys.len() {
+            return self.keys[i - 1].1
+        }
+        let ((t0, a), (t1, b)) = (self.keys[i - 1], self.keys[i]);
+        lerp(a, b, (t - t0) / (t1 - t0))
+    }
+}
+
+/// Something pushing the particles around
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Force {
+    /// a constant acceleration
+    Gravity(Vec3),
+    /// slows particles down by a fraction `k` of their speed a second
+    Drag(f32),
+    /// swirls particles around `axis` through `center`, strongest on the axis and gone at `radius`
+    Vortex { center: Vec3, axis: Vec3, strength: f32, radius: f32 },
+    /// pulls particles in, or pushes them away with a negative strength.
+    /// `radius` softens the pull close to the middle so it doesn't blow up
+    Attractor { position: Vec3, strength: f32, radius: f32 },
+}
+
+/// A plane particles bounce off, losing speed along the normal to `restitution` and across it to `friction`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParticlePlane {
+    pub plane: Plane3,
+    pub restitution: f32,
+    pub friction: f32,
+}
+
+type Lanes<const N: usize> = Simd<f32, N>;
+
+/// three lanes of particles' coordinates
+#[derive(Clone, Copy)]
+struct Vec3s<const N: usize> {
+    x: Lanes<N>,
+    y: Lanes<N>,
+    z: Lanes<N>,
+}
+
+impl<const N: usize> Vec3s<N> {
+    #[inline(always)]
+    fn load(x: &[f32], y: &[f32], z: &[f32]) -> Vec3s<N> {
+        Vec3s { x: Lanes::from_slice(x), y: Lanes::from_slice(y), z: Lanes::from_slice(z) }
+    }
+
+    #[inline(always)]
+    fn splat(v: Vec3) -> Vec3s<N> {
+        Vec3s { x: Lanes::splat(v.x), y: Lanes::splat(v.y), z: Lanes::splat(v.z) }
+    }
+
+    #[inline(always)]
+    fn store(self, x: &mut [f32], y: &mut [f32], z: &mut [f32]) {
+        self.x.copy_to_slice(x);
+        self.y.copy_to_slice(y);
+        self.z.copy_to_slice(z);
+    }
+
+    #[inline(always)]
+    fn add(self, o: Vec3s<N>) -> Vec3s<N> {
+        Vec3s { x: self.x + o.x, y: self.y + o.y, z: self.z + o.z }
+    }
+
+    #[inline(always)]
+    fn sub(self, o: Vec3s<N>) -> Vec3s<N> {
+        Vec3s { x: self.x - o.x, y: self.y - o.y, z: self.z - o.z }
+    }
+
+    #[inline(always)]
+    fn scale(self, s: Lanes<N>) -> Vec3s<N> {
+        Vec3s { x: self.x * s, y: self.y * s, z: self.z * s }
+    }
+
+    #[inline(always)]
+    fn dot(self, o: Vec3s<N>) -> Lanes<N> {
+        self.x * o.x + self.y * o.y + self.z * o.z
+    }
+
+    #[inline(always)]
+    fn cross(self, o: Vec3s<N>) -> Vec3s<N> {
+        Vec3s {
+            x: self.y * o.z - self.z * o.y,
+            y: self.z * o.x - self.x * o.z,
+            z: self.x * o.y - self.y * o.x,
+        }
+    }
+}
+
+impl Force {
+    /// the new velocities of `N` particles after `dt` under this force
+    #[inline(always)]
+    fn apply<const N: usize>(&self, p: Vec3s<N>, v: Vec3s<N>, dt: Lanes<N>) -> Vec3s<N> {
+        match *self {
+            Force::Gravity(g) => v.add(Vec3s::splat(g).scale(dt)),
+            // implicit, so a strong drag slows particles down rather than turning them round
+            Force::Drag(k) => v.scale(Lanes::splat(1.0) / (Lanes::splat(1.0) + Lanes::splat(k) * dt)),
+            Force::Vortex { center, axis, strength, radius } => {
+                let axis = Vec3s::splat(axis.normalize());
+                let r = p.sub(Vec3s::splat(center));
+                let r = r.sub(axis.scale(r.dot(axis)));
+                let d = r.dot(r).sqrt();
+                let falloff = (Lanes::splat(1.0) - d / Lanes::splat(radius)).simd_max(Lanes::splat(0.0));
+                let s = Lanes::splat(strength) * falloff / d.simd_max(Lanes::splat(1e-6));
+                v.add(axis.cross(r).scale(s * dt))
+            }
+            Force::Attractor { position, strength, radius } => {
+                let r = Vec3s::splat(position).sub(p);
+                let d2 = r.dot(r) + Lanes::splat(radius * radius);
+                let s = Lanes::splat(strength) / (d2 * d2.sqrt());
+                v.add(r.scale(s * dt))
+            }
+        }
+    }
+}
+
+/// push `N` particles starting at `i` through the forces and move them
+#[inline(always)]
+fn integrate<const N: usize>(chunk: &mut ParticlesMut, i: usize, forces: &[Force], dt: f32) {
+    let r = i..i + N;
+    let p = Vec3s::<N>::load(&chunk.px[r.clone()], &chunk.py[r.clone()], &chunk.pz[r.clone()]);
+    let mut v = Vec3s::<N>::load(&chunk.vx[r.clone()], &chunk.vy[r.clone()], &chunk.vz[r.clone()]);
+    let dt = Lanes::splat(dt);
+    for force in forces {
+        v = force.apply(p, v, dt);
+    }
+    // semi-implicit, moving with the new velocity
+    p.add(v.scale(dt)).store(&mut chunk.px[r.clone()], &mut chunk.py[r.clone()], &mut chunk.pz[r.clone()]);
+    v.store(&mut chunk.vx[r.clone()], &mut chunk.vy[r.clone()], &mut chunk.vz[r]);
+}
+
+/// A pool of particles, spawned by emitters and moved by forces
+#[derive(Clone, Debug)]
+pub struct ParticleSystem {
+    pub emitters: Vec<Emitter>,
+    pub forces: Vec<Force>,
+    pub planes: Vec<ParticlePlane>,
+    /// scales each particle's starting size
+    pub size_over_life: OverLife<f32>,
+    pub color_over_life: OverLife<Vec4>,
+    particles: Particles,
+    max_particles: usize,
+    rng: Pcg32,
+}
+
+impl ParticleSystem {
+    /// an empty system that holds at most `max_particles`, with its randomness seeded by `seed`
+    pub fn new(max_particles: usize, seed: u64) -> ParticleSystem {
+        ParticleSystem {
+            emitters: Vec::new(),
+            forces: Vec::new(),
+            planes: Vec::new(),
+            size_over_life: OverLife::constant(1.0),
+            color_over_life: OverLife::constant(Vec4::ONE),
+            particles: Particles::default(),
+            max_particles,
+            rng: Pcg32::new(seed),
+        }
+    }
+
+    pub fn with_emitter(mut self, emitter: Emitter) -> ParticleSystem {
+        self.emitters.push(emitter);
+        self
+    }
+
+    pub fn with_force(mut self, force: Force) -> ParticleSystem {
+        self.forces.push(force);
+        self
+    }
+
+    pub fn with_plane(mut self, plane: Plane3, restitution: f32, friction: f32) -> ParticleSystem {
+        self.planes.push(ParticlePlane { plane, restitution, friction });
+        self
+    }
+
+    pub fn with_size_over_life(self, size_over_life: OverLife<f32>) -> ParticleSystem {
+        ParticleSystem { size_over_life, ..self }
+    }
+
+    pub fn with_color_over_life(self, color_over_life: OverLife<Vec4>) -> ParticleSystem {
+        ParticleSystem { color_over_life, ..self }
+    }
+
+    #[inline(always)]
+    pub fn particles(&self) -> &Particles {
+        &self.particles
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn max_particles(&self) -> usize {
+        self.max_particles
+    }
+
+    /// age the particles, remove the dead ones, spawn new ones and move everything along by `dt` seconds.
+    ///
+    /// emitters take turns in order, and stop spawning once the pool is full.
+    pub fn update(&mut self, dt: f32) {
+        let particles = &mut self.particles;
+        for age in &mut particles.age {
+            *age += dt;
+        }
+        // backwards, so the particle swapped in has already been checked
+        for i in (0..particles.len()).rev() {
+            if particles.age[i] >= particles.lifetime[i] {
+                particles.swap_remove(i);
+            }
+        }
+
+        let color = self.color_over_life.sample(0.0);
+        for emitter in &mut self.emitters {
+            let count = emitter.due(dt).min(self.max_particles - particles.len());
+            for _ in 0..count {
+                let spawn = emitter.spawn(&mut self.rng);
+                particles.push(spawn.position, spawn.velocity, spawn.lifetime, spawn.size, color);
+            }
+        }
+
+        let (forces, planes) = (&self.forces, &self.planes);
+        let (size_over_life, color_over_life) = (&self.size_over_life, &self.color_over_life);
+        particles.chunks_mut(CHUNK).into_par_iter().for_each(|mut chunk| {
+            let n = chunk.px.len();
+            let whole = n - n % LANES;
+            for i in (0..whole).step_by(LANES) {
+                integrate::<LANES>(&mut chunk, i, forces, dt);
+            }
+            for i in whole..n {
+                integrate::<1>(&mut chunk, i, forces, dt);
+            }
+
+            for i in 0..n {
+                for plane in planes {
+                    collide(&mut chunk, i, plane);
+                }
+                let t = chunk.age[i] / chunk.lifetime[i];
+                chunk.size[i] = chunk.start_size[i] * size_over_life.sample(t);
+                chunk.color[i] = color_over_life.sample(t);
+            }
+        });
+    }
+
+    /// remove every particle, leaving the emitters as they are
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+}
+
+/// push particle `i` back out of the plane, bouncing it if it's heading further in
+fn collide(chunk: &mut ParticlesMut, i: usize, plane: &ParticlePlane) {
+    let p = Vec3(chunk.px[i], chunk.py[i], chunk.pz[i]);
+    let depth = plane.plane.signed_distance(p);
+    if depth >= 0.0 {
+        return
+    }
+    let n = plane.plane.normal;
+    let p = p - n * depth;
+    let v = Vec3(chunk.vx[i], chunk.vy[i], chunk.vz[i]);
+    let vn = v.dot(n);
+    let v = if vn < 0.0 {
+        (v - n * vn) * (1.0 - plane.friction) - n * (vn * plane.restitution)
+    } else {
+        v
+    };
+    (chunk.px[i], chunk.py[i], chunk.pz[i]) = (p.x, p.y, p.z);
+    (chunk.vx[i], chunk.vy[i], chunk.vz[i]) = (v.x, v.y, v.z);
+}
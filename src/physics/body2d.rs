@@ -0,0 +1,366 @@
+use std::f32::consts::PI;
+use crate::geometry::broadphase::Handle;
+use crate::geometry::capsule::Capsule2;
+use crate::geometry::collision2d::{ConvexPolygon, Shape2};
+use crate::geometry::sphere::Circle;
+use crate::math::affine::Affine2;
+use crate::math::vec2::Vec2;
+use crate::physics::BodyType;
+
+/// `v` rotated counterclockwise by `angle` radians
+#[inline(always)]
+pub(crate) fn rotate(v: Vec2, angle: f32) -> Vec2 {
+    let (s, c) = angle.sin_cos();
+    Vec2(c * v.x - s * v.y, s * v.x + c * v.y)
+}
+
+/// The mass of a shape, where its mass is centered, and its rotational inertia about that center
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MassProperties2 {
+    pub mass: f32,
+    pub center: Vec2,
+    pub inertia: f32,
+}
+
+/// A shape attached to a body, in the body's local space
+#[derive(Clone, Debug)]
+pub struct Collider2 {
+    pub shape: Shape2,
+    /// mass per unit area
+    pub density: f32,
+    /// combined with the other collider's by the geometric mean
+    pub friction: f32,
+    /// combined with the other collider's by taking the larger
+    pub restitution: f32,
+    pub(crate) proxy: Option<Handle>,
+}
+
+impl Collider2 {
+    /// a collider with a density of 1, friction of 0.6 and no restitution
+    pub fn new(shape: Shape2) -> Collider2 {
+        Collider2 { shape, density: 1.0, friction: 0.6, restitution: 0.0, proxy: None }
+    }
+
+    /// a circle around the body's origin
+    pub fn circle(radius: f32) -> Collider2 {
+        Collider2::new(Shape2::Circle(Circle::new(Vec2::ZERO, radius)))
+    }
+
+    /// a rectangle centered on the body's origin
+    pub fn rect(half_extents: Vec2) -> Collider2 {
+        Collider2::new(Shape2::Polygon(ConvexPolygon::rect(half_extents)))
+    }
+
+    /// the convex hull of `points`
+    pub fn polygon(points: &[Vec2]) -> Collider2 {
+        Collider2::new(Shape2::Polygon(ConvexPolygon::new(points)))
+    }
+
+    pub fn capsule(a: Vec2, b: Vec2, radius: f32) -> Collider2 {
+        Collider2::new(Shape2::Capsule(Capsule2::new(a, b, radius)))
+    }
+
+    pub fn with_density(self, density: f32) -> Collider2 {
+        Collider2 { density, ..self }
+    }
+
+    pub fn with_friction(self, friction: f32) -> Collider2 {
+        Collider2 { friction, ..self }
+    }
+
+    pub fn with_restitution(self, restitution: f32) -> Collider2 {
+        Collider2 { restitution, ..self }
+    }
+
+    /// the mass properties of the shape at its density, in the body's local space
+    pub fn mass_properties(&self) -> MassProperties2 {
+        let density = self.density;
+        match &self.shape {
+            Shape2::Circle(c) => {
+                let mass = density * PI * c.radius * c.radius;
+                MassProperties2 { mass, center: c.center, inertia: mass * c.radius * c.radius * 0.5 }
+            }
+            Shape2::Capsule(c) => {
+                let (rr, length) = (c.radius * c.radius, c.segment.length());
+                let circle_mass = density * PI * rr;
+                let box_mass = density * 2.0 * c.radius * length;
+                // each half circle sits past an end of the box, with its centroid 4r / 3pi from the cut
+                let (h, lc) = (0.5 * length, 4.0 * c.radius / (3.0 * PI));
+                let circle_inertia = circle_mass * (0.5 * rr + h * h + 2.0 * h * lc);
+                let box_inertia = box_mass * (4.0 * rr + length * length) / 12.0;
+                MassProperties2 {
+                    mass: circle_mass + box_mass,
+                    center: c.segment.a.lerp(c.segment.b, 0.5),
+                    inertia: circle_inertia + box_inertia,
+                }
+            }
+            Shape2::Polygon(p) => {
+                // a fan of triangles from the first vertex, keeping the sums small near the shape
+                let vertices = p.vertices();
+                let origin = vertices[0];
+                let (mut area, mut center, mut inertia) = (0.0, Vec2::ZERO, 0.0);
+                for i in 1..vertices.len() - 1 {
+                    let (e1, e2) = (vertices[i] - origin, vertices[i + 1] - origin);
+                    let d = e1.perp_dot(e2);
+                    area += d * 0.5;
+                    center += (e1 + e2) * (d * 0.5 / 3.0);
+                    let x = e1.x * e1.x + e2.x * e1.x + e2.x * e2.x;
+                    let y = e1.y * e1.y + e2.y * e1.y + e2.y * e2.y;
+                    inertia += d / 12.0 * (x + y);
+                }
+                let mass = density * area;
+                center /= area;
+                MassProperties2 { mass, center: origin + center, inertia: density * inertia - mass * center.dot(center) }
+            }
+        }
+    }
+}
+
+/// A rigid body, positioned by its origin, which its colliders are placed relative to
+#[derive(Clone, Debug)]
+pub struct RigidBody2 {
+    pub body_type: BodyType,
+    pub position: Vec2,
+    /// counterclockwise, in radians
+    pub angle: f32,
+    pub linear_velocity: Vec2,
+    pub angular_velocity: f32,
+    /// the fraction of velocity lost per second, roughly
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub gravity_scale: f32,
+    /// whether contacts and joints can't turn the body
+    pub fixed_rotation: bool,
+    /// whether the body can fall asleep once it's come to rest
+    pub can_sleep: bool,
+    force: Vec2,
+    torque: f32,
+    pub(crate) colliders: Vec<Collider2>,
+    mass: f32,
+    inertia: f32,
+    local_center: Vec2,
+    pub(crate) awake: bool,
+    pub(crate) sleep_time: f32,
+    /// the island the body fell asleep with, which wakes up together
+    pub(crate) island: u32,
+    /// whether the colliders' bounds match the body's position
+    pub(crate) synced: bool,
+}
+
+impl RigidBody2 {
+    pub fn new(body_type: BodyType, position: Vec2) -> RigidBody2 {
+        RigidBody2 {
+            body_type,
+            position,
+            angle: 0.0,
+            linear_velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            gravity_scale: 1.0,
+            fixed_rotation: false,
+            can_sleep: true,
+            force: Vec2::ZERO,
+            torque: 0.0,
+            colliders: Vec::new(),
+            mass: 0.0,
+            inertia: 0.0,
+            local_center: Vec2::ZERO,
+            awake: true,
+            sleep_time: 0.0,
+            island: 0,
+            synced: false,
+        }
+    }
+
+    pub fn with_angle(self, angle: f32) -> RigidBody2 {
+        RigidBody2 { angle, ..self }
+    }
+
+    pub fn with_velocity(self, linear_velocity: Vec2, angular_velocity: f32) -> RigidBody2 {
+        RigidBody2 { linear_velocity, angular_velocity, ..self }
+    }
+
+    /// add a collider, updating the body's mass
+    pub fn with_collider(mut self, collider: Collider2) -> RigidBody2 {
+        self.colliders.push(collider);
+        self.update_mass();
+        self
+    }
+
+    #[inline(always)]
+    pub fn colliders(&self) -> &[Collider2] {
+        &self.colliders
+    }
+
+    #[inline(always)]
+    pub fn is_dynamic(&self) -> bool {
+        self.body_type == BodyType::Dynamic
+    }
+
+    #[inline(always)]
+    pub fn is_awake(&self) -> bool {
+        self.awake
+    }
+
+    /// the total mass, zero unless the body is dynamic
+    #[inline(always)]
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    /// the rotational inertia about the center of mass
+    #[inline(always)]
+    pub fn inertia(&self) -> f32 {
+        self.inertia
+    }
+
+    #[inline(always)]
+    pub fn inv_mass(&self) -> f32 {
+        if self.mass > 0.0 { 1.0 / self.mass } else { 0.0 }
+    }
+
+    #[inline(always)]
+    pub fn inv_inertia(&self) -> f32 {
+        if self.inertia > 0.0 && !self.fixed_rotation { 1.0 / self.inertia } else { 0.0 }
+    }
+
+    /// the center of mass relative to the origin, in local space
+    #[inline(always)]
+    pub fn local_center(&self) -> Vec2 {
+        self.local_center
+    }
+
+    #[inline(always)]
+    pub fn world_center(&self) -> Vec2 {
+        self.position + rotate(self.local_center, self.angle)
+    }
+
+    /// the rotation and translation from local space into the world
+    pub fn transform(&self) -> Affine2 {
+        let (s, c) = self.angle.sin_cos();
+        Affine2::from_cols(Vec2(c, s), Vec2(-s, c), self.position)
+    }
+
+    #[inline(always)]
+    pub fn to_world(&self, point: Vec2) -> Vec2 {
+        self.position + rotate(point, self.angle)
+    }
+
+    #[inline(always)]
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        rotate(point - self.position, -self.angle)
+    }
+
+    /// the velocity of the body at a point in the world
+    #[inline(always)]
+    pub fn velocity_at(&self, point: Vec2) -> Vec2 {
+        self.linear_velocity + (point - self.world_center()).perp() * self.angular_velocity
+    }
+
+    /// push the center of mass over the next step, which only moves dynamic bodies
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
+
+    /// push at a point in the world over the next step, turning the body too
+    pub fn apply_force_at(&mut self, force: Vec2, point: Vec2) {
+        self.force += force;
+        self.torque += (point - self.world_center()).perp_dot(force);
+    }
+
+    pub fn apply_torque(&mut self, torque: f32) {
+        self.torque += torque;
+    }
+
+    /// change the momentum at a point in the world immediately
+    pub fn apply_impulse(&mut self, impulse: Vec2, point: Vec2) {
+        if self.is_dynamic() {
+            self.linear_velocity += impulse * self.inv_mass();
+            self.angular_velocity += self.inv_inertia() * (point - self.world_center()).perp_dot(impulse);
+        }
+    }
+
+    pub fn apply_angular_impulse(&mut self, impulse: f32) {
+        if self.is_dynamic() {
+            self.angular_velocity += self.inv_inertia() * impulse;
+        }
+    }
+
+    /// the accumulated force and torque, clearing them
+    pub(crate) fn take_forces(&mut self) -> (Vec2, f32) {
+        let forces = (self.force, self.torque);
+        self.force = Vec2::ZERO;
+        self.torque = 0.0;
+        forces
+    }
+
+    /// recompute the mass, center of mass and inertia from the colliders.
+    /// bodies that aren't dynamic, or have nothing with any area, get no mass
+    pub(crate) fn update_mass(&mut self) {
+        let world_center = self.world_center();
+        let (mut mass, mut center, mut inertia) = (0.0, Vec2::ZERO, 0.0);
+        if self.is_dynamic() {
+            for collider in &self.colliders {
+                let m = collider.mass_properties();
+                mass += m.mass;
+                center += m.center * m.mass;
+                inertia += m.inertia + m.mass * m.center.dot(m.center);
+            }
+        }
+        if mass > 0.0 {
+            center /= mass;
+            self.mass = mass;
+            self.inertia = inertia - mass * center.dot(center);
+        } else {
+            self.mass = 0.0;
+            self.inertia = 0.0;
+        }
+        self.local_center = center;
+        // keep the center of mass still, so its velocity still means the same thing
+        self.linear_velocity += (self.world_center() - world_center).perp() * self.angular_velocity;
+    }
+}
+
+/// The velocity state of a body while constraints are solved
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SolverBody2 {
+    pub(crate) v: Vec2,
+    pub(crate) w: f32,
+    pub(crate) inv_mass: f32,
+    pub(crate) inv_inertia: f32,
+    pub(crate) center: Vec2,
+    pub(crate) angle: f32,
+    pub(crate) local_center: Vec2,
+}
+
+impl SolverBody2 {
+    /// stands in for empty slots, which nothing refers to
+    pub(crate) const EMPTY: SolverBody2 = SolverBody2 {
+        v: Vec2::ZERO,
+        w: 0.0,
+        inv_mass: 0.0,
+        inv_inertia: 0.0,
+        center: Vec2::ZERO,
+        angle: 0.0,
+        local_center: Vec2::ZERO,
+    };
+
+    /// the velocity at an offset from the center of mass
+    #[inline(always)]
+    pub(crate) fn velocity_at(&self, r: Vec2) -> Vec2 {
+        self.v + r.perp() * self.w
+    }
+
+    #[inline(always)]
+    pub(crate) fn apply(&mut self, impulse: Vec2, r: Vec2) {
+        self.v += impulse * self.inv_mass;
+        self.w += self.inv_inertia * r.perp_dot(impulse);
+    }
+
+    /// a point on the body given relative to its origin, as an offset from its center of mass
+    #[inline(always)]
+    pub(crate) fn offset(&self, local: Vec2) -> Vec2 {
+        rotate(local - self.local_center, self.angle)
+    }
+}
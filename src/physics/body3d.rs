@@ -0,0 +1,503 @@
+use std::f32::consts::PI;
+use crate::geometry::broadphase::Handle;
+use crate::geometry::collision3d::{ConvexHull, PlacedShape3, Shape3};
+use crate::math::matrix::Matrix;
+use crate::math::quat::Quat;
+use crate::math::vec3::Vec3;
+use crate::physics::BodyType;
+
+/// a 3x3 matrix with `d` on the diagonal
+fn diagonal(d: Vec3) -> Matrix<f32> {
+    Matrix::from_vec(3, 3, vec![d.x, 0.0, 0.0, 0.0, d.y, 0.0, 0.0, 0.0, d.z])
+}
+
+/// the inverse of a 3x3 matrix, or zero if it's singular
+pub(crate) fn inverse3(m: &Matrix<f32>) -> Matrix<f32> {
+    assert!(m.is_3x3());
+    let [a, b, c, d, e, f, g, h, i]: [f32; 9] = m.data[..].try_into().unwrap();
+    let (ca, cb, cc) = (e * i - f * h, f * g - d * i, d * h - e * g);
+    let det = a * ca + b * cb + c * cc;
+    if det.abs() <= f32::EPSILON * (a * a + e * e + i * i) {
+        return Matrix::new(3, 3)
+    }
+    let s = 1.0 / det;
+    Matrix::from_vec(3, 3, vec![
+        ca * s, (c * h - b * i) * s, (b * f - c * e) * s,
+        cb * s, (a * i - c * g) * s, (c * d - a * f) * s,
+        cc * s, (b * g - a * h) * s, (a * e - b * d) * s,
+    ])
+}
+
+fn add3(a: &Matrix<f32>, b: &Matrix<f32>) -> Matrix<f32> {
+    Matrix::from_vec(3, 3, a.data.iter().zip(&b.data).map(|(a, b)| a + b).collect())
+}
+
+/// `m` as seen from a frame rotated by `r`, that is `r m r^t`
+pub(crate) fn rotate_tensor(m: &Matrix<f32>, r: &Matrix<f32>) -> Matrix<f32> {
+    r.mul_3x3(m).mul_3x3(&r.transpose())
+}
+
+/// the inertia of a point mass at `offset`, to move an inertia tensor off the center of mass
+fn parallel_axis(mass: f32, offset: Vec3) -> Matrix<f32> {
+    let Vec3 { x, y, z } = offset;
+    let d = offset.dot(offset);
+    Matrix::from_vec(3, 3, vec![
+        mass * (d - x * x), -mass * x * y, -mass * x * z,
+        -mass * y * x, mass * (d - y * y), -mass * y * z,
+        -mass * z * x, -mass * z * y, mass * (d - z * z),
+    ])
+}
+
+/// The mass of a shape, where its mass is centered, and its inertia tensor about that center
+#[derive(Clone, Debug)]
+pub struct MassProperties3 {
+    pub mass: f32,
+    pub center: Vec3,
+    /// 3x3, in the body's local frame
+    pub inertia: Matrix<f32>,
+}
+
+/// A shape attached to a body, placed in the body's local frame
+#[derive(Clone, Debug)]
+pub struct Collider3 {
+    pub shape: Shape3,
+    /// relative to the body's origin
+    pub position: Vec3,
+    pub rotation: Quat,
+    /// mass per unit volume
+    pub density: f32,
+    /// combined with the other collider's by the geometric mean
+    pub friction: f32,
+    /// combined with the other collider's by taking the larger
+    pub restitution: f32,
+    pub(crate) proxy: Option<Handle>,
+}
+
+impl Collider3 {
+    /// a collider on the body's origin, with a density of 1, friction of 0.6 and no restitution
+    pub fn new(shape: Shape3) -> Collider3 {
+        Collider3 {
+            shape,
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            density: 1.0,
+            friction: 0.6,
+            restitution: 0.0,
+            proxy: None,
+        }
+    }
+
+    pub fn cuboid(half_extents: Vec3) -> Collider3 {
+        Collider3::new(Shape3::Cuboid { half_extents })
+    }
+
+    pub fn sphere(radius: f32) -> Collider3 {
+        Collider3::new(Shape3::Sphere { radius })
+    }
+
+    /// a capsule along the local y axis
+    pub fn capsule(half_height: f32, radius: f32) -> Collider3 {
+        Collider3::new(Shape3::Capsule { half_height, radius })
+    }
+
+    /// a cylinder along the local y axis
+    pub fn cylinder(half_height: f32, radius: f32) -> Collider3 {
+        Collider3::new(Shape3::Cylinder { half_height, radius })
+    }
+
+    pub fn hull(points: &[Vec3]) -> Collider3 {
+        Collider3::new(Shape3::Hull(ConvexHull::new(points)))
+    }
+
+    /// move the collider off the body's origin
+    pub fn with_transform(self, position: Vec3, rotation: Quat) -> Collider3 {
+        Collider3 { position, rotation, ..self }
+    }
+
+    pub fn with_density(self, density: f32) -> Collider3 {
+        Collider3 { density, ..self }
+    }
+
+    pub fn with_friction(self, friction: f32) -> Collider3 {
+        Collider3 { friction, ..self }
+    }
+
+    pub fn with_restitution(self, restitution: f32) -> Collider3 {
+        Collider3 { restitution, ..self }
+    }
+
+    /// the mass properties of the shape at its density, in the body's local frame.
+    ///
+    /// hulls are split into tetrahedra fanning out from a point inside them, and a flat hull has no mass.
+    pub fn mass_properties(&self) -> MassProperties3 {
+        let density = self.density;
+        let solid_box = |h: Vec3| {
+            let mass = density * 8.0 * h.x * h.y * h.z;
+            let (x, y, z) = (h.x * h.x, h.y * h.y, h.z * h.z);
+            (mass, diagonal(Vec3(y + z, x + z, x + y) * (mass / 3.0)))
+        };
+        let (mass, center, inertia) = match &self.shape {
+            Shape3::Cuboid { half_extents } => {
+                let (mass, inertia) = solid_box(*half_extents);
+                (mass, Vec3::ZERO, inertia)
+            }
+            Shape3::Sphere { radius } => {
+                let mass = density * 4.0 / 3.0 * PI * radius * radius * radius;
+                (mass, Vec3::ZERO, diagonal(Vec3::splat(0.4 * mass * radius * radius)))
+            }
+            Shape3::Capsule { half_height: h, radius: r } => {
+                let (h, rr) = (*h, r * r);
+                let cylinder = density * PI * rr * 2.0 * h;
+                let sphere = density * 4.0 / 3.0 * PI * rr * r;
+                // each cap's centroid sits 3r / 8 past the end of the cylinder
+                let across = cylinder * (rr / 4.0 + h * h / 3.0) + sphere * (0.4 * rr + h * h + 0.75 * h * r);
+                let along = cylinder * rr * 0.5 + sphere * 0.4 * rr;
+                (cylinder + sphere, Vec3::ZERO, diagonal(Vec3(across, along, across)))
+            }
+            Shape3::Cylinder { half_height: h, radius: r } => {
+                let mass = density * PI * r * r * 2.0 * h;
+                let across = mass * (r * r / 4.0 + h * h / 3.0);
+                (mass, Vec3::ZERO, diagonal(Vec3(across, mass * r * r * 0.5, across)))
+            }
+            Shape3::Hull(hull) => hull_mass_properties(hull, density),
+        };
+        MassProperties3 {
+            mass,
+            center: self.position + self.rotation * center,
+            inertia: rotate_tensor(&inertia, &self.rotation.to_matrix3()),
+        }
+    }
+}
+
+/// the mass, center of mass and inertia tensor about it of a solid hull, summed over
+/// tetrahedra from a point inside it to each triangle of its surface
+fn hull_mass_properties(hull: &ConvexHull, density: f32) -> (f32, Vec3, Matrix<f32>) {
+    let points = hull.points();
+    // working relative to a point inside keeps the numbers small
+    let origin = points.iter().copied().sum::<Vec3>() / points.len() as f32;
+    let mut volume = 0.0;
+    let mut moment = Vec3::ZERO;
+    // the integral of `p p^t` over the hull, split into its diagonal and its xy, xz and yz terms
+    let mut squares = Vec3::ZERO;
+    let mut products = Vec3::ZERO;
+    let cross_terms = |p: Vec3| Vec3(p.x * p.y, p.x * p.z, p.y * p.z);
+    for triangle in hull.triangles() {
+        let [a, b, c] = triangle.map(|p| p - origin);
+        let det = a.dot(b.cross(c));
+        let s = a + b + c;
+        volume += det / 6.0;
+        moment += s * (det / 24.0);
+        squares += (a * a + b * b + c * c + s * s) * (det / 120.0);
+        products += (cross_terms(a) + cross_terms(b) + cross_terms(c) + cross_terms(s)) * (det / 120.0);
+    }
+    if volume <= 0.0 {
+        return (0.0, origin, Matrix::new(3, 3))
+    }
+
+    // move the integral to the centroid, then turn it into an inertia tensor
+    let center = moment / volume;
+    let Vec3 { x: xx, y: yy, z: zz } = squares - center * center * volume;
+    let Vec3 { x: xy, y: xz, z: yz } = products - cross_terms(center) * volume;
+    let inertia = Matrix::from_vec(3, 3, vec![
+        yy + zz, -xy, -xz,
+        -xy, xx + zz, -yz,
+        -xz, -yz, xx + yy,
+    ].into_iter().map(|v| v * density).collect());
+    (volume * density, origin + center, inertia)
+}
+
+/// A rigid body, positioned by its origin, which its colliders are placed relative to
+#[derive(Clone, Debug)]
+pub struct RigidBody3 {
+    pub body_type: BodyType,
+    pub position: Vec3,
+    pub orientation: Quat,
+    pub linear_velocity: Vec3,
+    /// in radians per second about each world axis
+    pub angular_velocity: Vec3,
+    /// the fraction of velocity lost per second, roughly
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub gravity_scale: f32,
+    /// whether the body can fall asleep once it's come to rest
+    pub can_sleep: bool,
+    force: Vec3,
+    torque: Vec3,
+    pub(crate) colliders: Vec<Collider3>,
+    mass: f32,
+    /// about the center of mass, in the local frame
+    inertia: Matrix<f32>,
+    inv_inertia: Matrix<f32>,
+    local_center: Vec3,
+    pub(crate) awake: bool,
+    pub(crate) sleep_time: f32,
+    /// the island the body fell asleep with, which wakes up together
+    pub(crate) island: u32,
+    /// whether the colliders' bounds match the body's position
+    pub(crate) synced: bool,
+}
+
+impl RigidBody3 {
+    pub fn new(body_type: BodyType, position: Vec3) -> RigidBody3 {
+        RigidBody3 {
+            body_type,
+            position,
+            orientation: Quat::IDENTITY,
+            linear_velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            gravity_scale: 1.0,
+            can_sleep: true,
+            force: Vec3::ZERO,
+            torque: Vec3::ZERO,
+            colliders: Vec::new(),
+            mass: 0.0,
+            inertia: Matrix::new(3, 3),
+            inv_inertia: Matrix::new(3, 3),
+            local_center: Vec3::ZERO,
+            awake: true,
+            sleep_time: 0.0,
+            island: 0,
+            synced: false,
+        }
+    }
+
+    pub fn with_orientation(self, orientation: Quat) -> RigidBody3 {
+        RigidBody3 { orientation, ..self }
+    }
+
+    pub fn with_velocity(self, linear_velocity: Vec3, angular_velocity: Vec3) -> RigidBody3 {
+        RigidBody3 { linear_velocity, angular_velocity, ..self }
+    }
+
+    /// add a collider, updating the body's mass
+    pub fn with_collider(mut self, collider: Collider3) -> RigidBody3 {
+        self.colliders.push(collider);
+        self.update_mass();
+        self
+    }
+
+    #[inline(always)]
+    pub fn colliders(&self) -> &[Collider3] {
+        &self.colliders
+    }
+
+    #[inline(always)]
+    pub fn is_dynamic(&self) -> bool {
+        self.body_type == BodyType::Dynamic
+    }
+
+    #[inline(always)]
+    pub fn is_awake(&self) -> bool {
+        self.awake
+    }
+
+    /// the total mass, zero unless the body is dynamic
+    #[inline(always)]
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    #[inline(always)]
+    pub fn inv_mass(&self) -> f32 {
+        if self.mass > 0.0 { 1.0 / self.mass } else { 0.0 }
+    }
+
+    /// the inertia tensor about the center of mass, in the local frame
+    #[inline(always)]
+    pub fn local_inertia(&self) -> &Matrix<f32> {
+        &self.inertia
+    }
+
+    /// the inverse inertia tensor about the center of mass, rotated into the world
+    pub fn world_inv_inertia(&self) -> Matrix<f32> {
+        rotate_tensor(&self.inv_inertia, &self.orientation.to_matrix3())
+    }
+
+    /// the center of mass relative to the origin, in the local frame
+    #[inline(always)]
+    pub fn local_center(&self) -> Vec3 {
+        self.local_center
+    }
+
+    #[inline(always)]
+    pub fn world_center(&self) -> Vec3 {
+        self.position + self.orientation * self.local_center
+    }
+
+    #[inline(always)]
+    pub fn to_world(&self, point: Vec3) -> Vec3 {
+        self.position + self.orientation * point
+    }
+
+    #[inline(always)]
+    pub fn to_local(&self, point: Vec3) -> Vec3 {
+        self.orientation.inverse() * (point - self.position)
+    }
+
+    /// the velocity of the body at a point in the world
+    #[inline(always)]
+    pub fn velocity_at(&self, point: Vec3) -> Vec3 {
+        self.linear_velocity + self.angular_velocity.cross(point - self.world_center())
+    }
+
+    /// the kinetic energy of the body, from both moving and spinning
+    pub fn kinetic_energy(&self) -> f32 {
+        let w = self.orientation.inverse() * self.angular_velocity;
+        0.5 * (self.mass * self.linear_velocity.dot(self.linear_velocity) + w.dot(&self.inertia * w))
+    }
+
+    /// push the center of mass over the next step, which only moves dynamic bodies
+    pub fn apply_force(&mut self, force: Vec3) {
+        self.force += force;
+    }
+
+    /// push at a point in the world over the next step, turning the body too
+    pub fn apply_force_at(&mut self, force: Vec3, point: Vec3) {
+        self.force += force;
+        self.torque += (point - self.world_center()).cross(force);
+    }
+
+    pub fn apply_torque(&mut self, torque: Vec3) {
+        self.torque += torque;
+    }
+
+    /// change the momentum at a point in the world immediately
+    pub fn apply_impulse(&mut self, impulse: Vec3, point: Vec3) {
+        if self.is_dynamic() {
+            self.linear_velocity += impulse * self.inv_mass();
+            self.angular_velocity += &self.world_inv_inertia() * (point - self.world_center()).cross(impulse);
+        }
+    }
+
+    pub fn apply_angular_impulse(&mut self, impulse: Vec3) {
+        if self.is_dynamic() {
+            self.angular_velocity += &self.world_inv_inertia() * impulse;
+        }
+    }
+
+    /// the accumulated force and torque, clearing them
+    pub(crate) fn take_forces(&mut self) -> (Vec3, Vec3) {
+        let forces = (self.force, self.torque);
+        self.force = Vec3::ZERO;
+        self.torque = Vec3::ZERO;
+        forces
+    }
+
+    /// add the implicit gyroscopic impulse for one step, which keeps a spinning body's angular
+    /// momentum from growing without bound like the explicit term would
+    pub(crate) fn integrate_gyroscopic(&mut self, dt: f32) {
+        let rotation = self.orientation.inverse();
+        let w = rotation * self.angular_velocity;
+        let iw = &self.inertia * w;
+        let f = w.cross(iw) * dt;
+        // the jacobian of `w x (I w)` about w, stepped implicitly with one newton iteration
+        let skew = |v: Vec3| Matrix::from_vec(3, 3, vec![0.0, -v.z, v.y, v.z, 0.0, -v.x, -v.y, v.x, 0.0]);
+        let product = skew(w).mul_3x3(&self.inertia);
+        let skew_iw = skew(iw);
+        let jacobian = Matrix::from_vec(3, 3, (0..9)
+            .map(|i| self.inertia.data[i] + dt * (product.data[i] - skew_iw.data[i]))
+            .collect());
+        let w = w - &inverse3(&jacobian) * f;
+        self.angular_velocity = self.orientation * w;
+    }
+
+    /// recompute the mass, center of mass and inertia from the colliders.
+    /// bodies that aren't dynamic, or have nothing with any volume, get no mass
+    pub(crate) fn update_mass(&mut self) {
+        let world_center = self.world_center();
+        let (mut mass, mut center, mut inertia) = (0.0, Vec3::ZERO, Matrix::new(3, 3));
+        if self.is_dynamic() {
+            for collider in &self.colliders {
+                let m = collider.mass_properties();
+                mass += m.mass;
+                center += m.center * m.mass;
+                inertia = add3(&add3(&inertia, &m.inertia), &parallel_axis(m.mass, m.center));
+            }
+        }
+        if mass > 0.0 {
+            center /= mass;
+            let shift = parallel_axis(mass, center);
+            self.mass = mass;
+            self.inertia = Matrix::from_vec(3, 3, inertia.data.iter().zip(&shift.data).map(|(i, s)| i - s).collect());
+            self.inv_inertia = inverse3(&self.inertia);
+        } else {
+            self.mass = 0.0;
+            self.inertia = Matrix::new(3, 3);
+            self.inv_inertia = Matrix::new(3, 3);
+        }
+        self.local_center = center;
+        // keep the center of mass still, so its velocity still means the same thing
+        self.linear_velocity += self.angular_velocity.cross(self.world_center() - world_center);
+    }
+}
+
+/// The velocity state of a body while constraints are solved
+#[derive(Clone, Debug)]
+pub(crate) struct SolverBody3 {
+    pub(crate) v: Vec3,
+    pub(crate) w: Vec3,
+    pub(crate) inv_mass: f32,
+    /// in world space
+    pub(crate) inv_inertia: Matrix<f32>,
+    pub(crate) center: Vec3,
+    pub(crate) orientation: Quat,
+    pub(crate) local_center: Vec3,
+}
+
+impl SolverBody3 {
+    /// stands in for empty slots, which nothing refers to
+    pub(crate) fn empty() -> SolverBody3 {
+        SolverBody3 {
+            v: Vec3::ZERO,
+            w: Vec3::ZERO,
+            inv_mass: 0.0,
+            inv_inertia: Matrix::new(3, 3),
+            center: Vec3::ZERO,
+            orientation: Quat::IDENTITY,
+            local_center: Vec3::ZERO,
+        }
+    }
+
+    /// the velocity at an offset from the center of mass
+    #[inline(always)]
+    pub(crate) fn velocity_at(&self, r: Vec3) -> Vec3 {
+        self.v + self.w.cross(r)
+    }
+
+    #[inline(always)]
+    pub(crate) fn apply(&mut self, impulse: Vec3, r: Vec3) {
+        self.v += impulse * self.inv_mass;
+        self.w += &self.inv_inertia * r.cross(impulse);
+    }
+
+    #[inline(always)]
+    pub(crate) fn apply_angular(&mut self, impulse: Vec3) {
+        self.w += &self.inv_inertia * impulse;
+    }
+
+    /// how much turning a unit of angular impulse about `axis` gives, about that axis
+    #[inline(always)]
+    pub(crate) fn angular_response(&self, axis: Vec3) -> f32 {
+        axis.dot(&self.inv_inertia * axis)
+    }
+
+    /// how much a unit of impulse along `n` at an offset `r` changes the velocity there along `n`
+    #[inline(always)]
+    pub(crate) fn response(&self, r: Vec3, n: Vec3) -> f32 {
+        self.inv_mass + self.angular_response(r.cross(n))
+    }
+
+    /// a point on the body given relative to its origin, as an offset from its center of mass
+    #[inline(always)]
+    pub(crate) fn offset(&self, local: Vec3) -> Vec3 {
+        self.orientation * (local - self.local_center)
+    }
+}
+
+/// the collider placed in the world by its body's position and orientation
+pub(crate) fn placed(body: &RigidBody3, collider: &Collider3) -> PlacedShape3 {
+    let rotation = body.orientation * collider.rotation;
+    PlacedShape3::new(collider.shape.clone(), body.to_world(collider.position), &rotation.to_matrix3())
+}
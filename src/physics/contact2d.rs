@@ -0,0 +1,114 @@
+use crate::math::vec2::Vec2;
+use crate::physics::body2d::SolverBody2;
+use crate::physics::{BodyHandle, BAUMGARTE};
+
+/// how deep contacts can sink before they're pushed apart, so resting contacts don't jitter
+pub const LINEAR_SLOP: f32 = 0.005;
+
+/// the closing speed below which contacts don't bounce
+const RESTITUTION_THRESHOLD: f32 = 1.0;
+
+/// A point of a [`ContactPair2`], with the impulses that kept it apart over the last step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactPoint2 {
+    pub point: Vec2,
+    pub depth: f32,
+    pub normal_impulse: f32,
+    pub tangent_impulse: f32,
+    /// the point in the first body's local space, for matching up with the next step's points
+    pub(crate) local_a: Vec2,
+    r_a: Vec2,
+    r_b: Vec2,
+    normal_mass: f32,
+    tangent_mass: f32,
+    bias: f32,
+}
+
+impl ContactPoint2 {
+    pub(crate) fn new(point: Vec2, depth: f32, local_a: Vec2) -> ContactPoint2 {
+        ContactPoint2 {
+            point,
+            depth,
+            normal_impulse: 0.0,
+            tangent_impulse: 0.0,
+            local_a,
+            r_a: Vec2::ZERO,
+            r_b: Vec2::ZERO,
+            normal_mass: 0.0,
+            tangent_mass: 0.0,
+            bias: 0.0,
+        }
+    }
+}
+
+/// Two colliders touching, with the normal pointing from the first towards the second
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContactPair2 {
+    pub body_a: BodyHandle,
+    pub body_b: BodyHandle,
+    /// the index of each collider on its body
+    pub collider_a: usize,
+    pub collider_b: usize,
+    pub normal: Vec2,
+    pub points: Vec<ContactPoint2>,
+    pub friction: f32,
+    pub restitution: f32,
+}
+
+impl ContactPair2 {
+    /// work out each point's effective masses and target separating speed
+    pub(crate) fn prepare(&mut self, bodies: &[SolverBody2], dt: f32) {
+        let (a, b) = (&bodies[self.body_a.index()], &bodies[self.body_b.index()]);
+        let (n, t) = (self.normal, self.normal.perp());
+        let mass = a.inv_mass + b.inv_mass;
+        for p in &mut self.points {
+            p.r_a = p.point - a.center;
+            p.r_b = p.point - b.center;
+            let (rn_a, rn_b) = (p.r_a.perp_dot(n), p.r_b.perp_dot(n));
+            let (rt_a, rt_b) = (p.r_a.perp_dot(t), p.r_b.perp_dot(t));
+            let k_normal = mass + a.inv_inertia * rn_a * rn_a + b.inv_inertia * rn_b * rn_b;
+            let k_tangent = mass + a.inv_inertia * rt_a * rt_a + b.inv_inertia * rt_b * rt_b;
+            p.normal_mass = if k_normal > 0.0 { 1.0 / k_normal } else { 0.0 };
+            p.tangent_mass = if k_tangent > 0.0 { 1.0 / k_tangent } else { 0.0 };
+
+            p.bias = BAUMGARTE / dt * (p.depth - LINEAR_SLOP).max(0.0);
+            let vn = (b.velocity_at(p.r_b) - a.velocity_at(p.r_a)).dot(n);
+            if vn < -RESTITUTION_THRESHOLD {
+                p.bias = p.bias.max(-self.restitution * vn);
+            }
+        }
+    }
+
+    /// apply last step's impulses again, which is most of the way to this step's
+    pub(crate) fn warm_start(&self, bodies: &mut [SolverBody2]) {
+        let (n, t) = (self.normal, self.normal.perp());
+        for p in &self.points {
+            let impulse = n * p.normal_impulse + t * p.tangent_impulse;
+            bodies[self.body_a.index()].apply(-impulse, p.r_a);
+            bodies[self.body_b.index()].apply(impulse, p.r_b);
+        }
+    }
+
+    /// one round of sequential impulses, friction first so it's limited by the newest normal impulse
+    pub(crate) fn solve(&mut self, bodies: &mut [SolverBody2]) {
+        let (ia, ib) = (self.body_a.index(), self.body_b.index());
+        let (n, t) = (self.normal, self.normal.perp());
+        for p in &mut self.points {
+            let dv = bodies[ib].velocity_at(p.r_b) - bodies[ia].velocity_at(p.r_a);
+            let limit = self.friction * p.normal_impulse;
+            let total = (p.tangent_impulse - p.tangent_mass * dv.dot(t)).clamp(-limit, limit);
+            let impulse = t * (total - p.tangent_impulse);
+            p.tangent_impulse = total;
+            bodies[ia].apply(-impulse, p.r_a);
+            bodies[ib].apply(impulse, p.r_b);
+        }
+        for p in &mut self.points {
+            let dv = bodies[ib].velocity_at(p.r_b) - bodies[ia].velocity_at(p.r_a);
+            let total = (p.normal_impulse - p.normal_mass * (dv.dot(n) - p.bias)).max(0.0);
+            let impulse = n * (total - p.normal_impulse);
+            p.normal_impulse = total;
+            bodies[ia].apply(-impulse, p.r_a);
+            bodies[ib].apply(impulse, p.r_b);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::math::vec3::Vec3;
+use crate::physics::body3d::SolverBody3;
+use crate::physics::contact2d::LINEAR_SLOP;
+use crate::physics::{BodyHandle, BAUMGARTE};
+
+/// the closing speed below which contacts don't bounce
+const RESTITUTION_THRESHOLD: f32 = 1.0;
+
+/// A point of a [`ContactPair3`], with the impulses that kept it apart over the last step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactPoint3 {
+    pub point: Vec3,
+    pub depth: f32,
+    pub normal_impulse: f32,
+    /// along the pair's two tangents
+    pub tangent_impulse: [f32; 2],
+    /// the point in the first body's local frame, for matching up with the next step's points
+    pub(crate) local_a: Vec3,
+    r_a: Vec3,
+    r_b: Vec3,
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    bias: f32,
+}
+
+impl ContactPoint3 {
+    pub(crate) fn new(point: Vec3, depth: f32, local_a: Vec3) -> ContactPoint3 {
+        ContactPoint3 {
+            point,
+            depth,
+            normal_impulse: 0.0,
+            tangent_impulse: [0.0; 2],
+            local_a,
+            r_a: Vec3::ZERO,
+            r_b: Vec3::ZERO,
+            normal_mass: 0.0,
+            tangent_mass: [0.0; 2],
+            bias: 0.0,
+        }
+    }
+}
+
+/// Two colliders touching, with the normal pointing from the first towards the second
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContactPair3 {
+    pub body_a: BodyHandle,
+    pub body_b: BodyHandle,
+    /// the index of each collider on its body
+    pub collider_a: usize,
+    pub collider_b: usize,
+    pub normal: Vec3,
+    /// perpendicular to the normal and each other, which friction acts along
+    pub tangents: [Vec3; 2],
+    pub points: Vec<ContactPoint3>,
+    pub friction: f32,
+    pub restitution: f32,
+}
+
+#[inline(always)]
+fn inverse(k: f32) -> f32 {
+    if k > 0.0 { 1.0 / k } else { 0.0 }
+}
+
+impl ContactPair3 {
+    /// work out each point's effective masses and target separating speed
+    pub(crate) fn prepare(&mut self, bodies: &[SolverBody3], dt: f32) {
+        let (a, b) = (&bodies[self.body_a.index()], &bodies[self.body_b.index()]);
+        let n = self.normal;
+        for p in &mut self.points {
+            p.r_a = p.point - a.center;
+            p.r_b = p.point - b.center;
+            p.normal_mass = inverse(a.response(p.r_a, n) + b.response(p.r_b, n));
+            for (mass, t) in p.tangent_mass.iter_mut().zip(self.tangents) {
+                *mass = inverse(a.response(p.r_a, t) + b.response(p.r_b, t));
+            }
+
+            p.bias = BAUMGARTE / dt * (p.depth - LINEAR_SLOP).max(0.0);
+            let vn = (b.velocity_at(p.r_b) - a.velocity_at(p.r_a)).dot(n);
+            if vn < -RESTITUTION_THRESHOLD {
+                p.bias = p.bias.max(-self.restitution * vn);
+            }
+        }
+    }
+
+    /// apply last step's impulses again, which is most of the way to this step's
+    pub(crate) fn warm_start(&self, bodies: &mut [SolverBody3]) {
+        let [t1, t2] = self.tangents;
+        for p in &self.points {
+            let impulse = self.normal * p.normal_impulse + t1 * p.tangent_impulse[0] + t2 * p.tangent_impulse[1];
+            bodies[self.body_a.index()].apply(-impulse, p.r_a);
+            bodies[self.body_b.index()].apply(impulse, p.r_b);
+        }
+    }
+
+    /// one round of sequential impulses, friction first so it's limited by the newest normal impulse.
+    /// friction is clamped to a circle rather than per tangent, so it doesn't favour any direction
+    pub(crate) fn solve(&mut self, bodies: &mut [SolverBody3]) {
+        let (ia, ib) = (self.body_a.index(), self.body_b.index());
+        let [t1, t2] = self.tangents;
+        for p in &mut self.points {
+            let dv = bodies[ib].velocity_at(p.r_b) - bodies[ia].velocity_at(p.r_a);
+            let mut total = [
+                p.tangent_impulse[0] - p.tangent_mass[0] * dv.dot(t1),
+                p.tangent_impulse[1] - p.tangent_mass[1] * dv.dot(t2),
+            ];
+            let (limit, length) = (self.friction * p.normal_impulse, total[0].hypot(total[1]));
+            if length > limit {
+                let scale = limit / length;
+                total = [total[0] * scale, total[1] * scale];
+            }
+            let impulse = t1 * (total[0] - p.tangent_impulse[0]) + t2 * (total[1] - p.tangent_impulse[1]);
+            p.tangent_impulse = total;
+            bodies[ia].apply(-impulse, p.r_a);
+            bodies[ib].apply(impulse, p.r_b);
+        }
+        for p in &mut self.points {
+            let dv = bodies[ib].velocity_at(p.r_b) - bodies[ia].velocity_at(p.r_a);
+            let total = (p.normal_impulse - p.normal_mass * (dv.dot(self.normal) - p.bias)).max(0.0);
+            let impulse = self.normal * (total - p.normal_impulse);
+            p.normal_impulse = total;
+            bodies[ia].apply(-impulse, p.r_a);
+            bodies[ib].apply(impulse, p.r_b);
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use crate::math::vec2::Vec2;
+use crate::physics::body2d::{rotate, RigidBody2, SolverBody2};
+use crate::physics::{limit_impulse, pair_mut, BodyHandle, BAUMGARTE};
+
+/// Turns a joint at a target speed, with a limited torque or force
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motor2 {
+    pub speed: f32,
+    pub max_torque: f32,
+}
+
+/// Keeps two anchor points a fixed distance apart, like a massless rod
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceJoint2 {
+    /// relative to each body's origin, in its local space
+    pub local_anchor_a: Vec2,
+    pub local_anchor_b: Vec2,
+    pub length: f32,
+    impulse: f32,
+    r_a: Vec2,
+    r_b: Vec2,
+    u: Vec2,
+    mass: f32,
+    bias: f32,
+}
+
+/// Pins two bodies together at an anchor, letting them turn about it
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevoluteJoint2 {
+    pub local_anchor_a: Vec2,
+    pub local_anchor_b: Vec2,
+    /// the angle of b relative to a when the joint is at zero
+    pub reference_angle: f32,
+    /// the lowest and highest joint angle
+    pub limits: Option<(f32, f32)>,
+    pub motor: Option<Motor2>,
+    impulse: Vec2,
+    lower_impulse: f32,
+    upper_impulse: f32,
+    motor_impulse: f32,
+    r_a: Vec2,
+    r_b: Vec2,
+    /// the symmetric point constraint mass matrix, as k11, k12 and k22
+    k: [f32; 3],
+    bias: Vec2,
+    angle: f32,
+    axial_mass: f32,
+    max_motor_impulse: f32,
+    dt: f32,
+}
+
+/// Lets b slide along an axis fixed in a, without turning relative to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrismaticJoint2 {
+    pub local_anchor_a: Vec2,
+    pub local_anchor_b: Vec2,
+    /// the direction b slides in, in a's local space
+    pub local_axis_a: Vec2,
+    pub reference_angle: f32,
+    /// the lowest and highest distance along the axis between the anchors
+    pub limits: Option<(f32, f32)>,
+    perp_impulse: f32,
+    angular_impulse: f32,
+    lower_impulse: f32,
+    upper_impulse: f32,
+    axis: Vec2,
+    perp: Vec2,
+    /// the angular arms of the perpendicular and axial constraints on each body
+    s: (f32, f32),
+    a: (f32, f32),
+    perp_mass: f32,
+    axial_mass: f32,
+    angular_mass: f32,
+    perp_bias: f32,
+    angular_bias: f32,
+    translation: f32,
+    dt: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JointKind2 {
+    Distance(DistanceJoint2),
+    Revolute(RevoluteJoint2),
+    Prismatic(PrismaticJoint2),
+}
+
+/// A constraint between two bodies
+#[derive(Clone, Debug, PartialEq)]
+pub struct Joint2 {
+    pub body_a: BodyHandle,
+    pub body_b: BodyHandle,
+    /// whether the two bodies' colliders still collide with each other
+    pub collide_connected: bool,
+    pub kind: JointKind2,
+}
+
+impl DistanceJoint2 {
+    pub fn new(local_anchor_a: Vec2, local_anchor_b: Vec2, length: f32) -> DistanceJoint2 {
+        DistanceJoint2 {
+            local_anchor_a,
+            local_anchor_b,
+            length,
+            impulse: 0.0,
+            r_a: Vec2::ZERO,
+            r_b: Vec2::ZERO,
+            u: Vec2::ZERO,
+            mass: 0.0,
+            bias: 0.0,
+        }
+    }
+
+    /// a joint between anchors given in the world, keeping their current distance
+    pub fn between(a: &RigidBody2, b: &RigidBody2, anchor_a: Vec2, anchor_b: Vec2) -> DistanceJoint2 {
+        DistanceJoint2::new(a.to_local(anchor_a), b.to_local(anchor_b), anchor_a.distance(anchor_b))
+    }
+}
+
+impl RevoluteJoint2 {
+    pub fn new(local_anchor_a: Vec2, local_anchor_b: Vec2, reference_angle: f32) -> RevoluteJoint2 {
+        RevoluteJoint2 {
+            local_anchor_a,
+            local_anchor_b,
+            reference_angle,
+            limits: None,
+            motor: None,
+            impulse: Vec2::ZERO,
+            lower_impulse: 0.0,
+            upper_impulse: 0.0,
+            motor_impulse: 0.0,
+            r_a: Vec2::ZERO,
+            r_b: Vec2::ZERO,
+            k: [0.0; 3],
+            bias: Vec2::ZERO,
+            angle: 0.0,
+            axial_mass: 0.0,
+            max_motor_impulse: 0.0,
+            dt: 0.0,
+        }
+    }
+
+    /// a joint pinning the bodies at an anchor given in the world, at their current angles
+    pub fn at(a: &RigidBody2, b: &RigidBody2, anchor: Vec2) -> RevoluteJoint2 {
+        RevoluteJoint2::new(a.to_local(anchor), b.to_local(anchor), b.angle - a.angle)
+    }
+
+    pub fn with_limits(self, lower: f32, upper: f32) -> RevoluteJoint2 {
+        assert!(lower <= upper, "the lower limit is above the upper one");
+        RevoluteJoint2 { limits: Some((lower, upper)), ..self }
+    }
+
+    pub fn with_motor(self, speed: f32, max_torque: f32) -> RevoluteJoint2 {
+        RevoluteJoint2 { motor: Some(Motor2 { speed, max_torque }), ..self }
+    }
+
+    /// the joint angle as of the last step
+    #[inline(always)]
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+}
+
+impl PrismaticJoint2 {
+    pub fn new(local_anchor_a: Vec2, local_anchor_b: Vec2, local_axis_a: Vec2, reference_angle: f32) -> PrismaticJoint2 {
+        PrismaticJoint2 {
+            local_anchor_a,
+            local_anchor_b,
+            local_axis_a: local_axis_a.normalize(),
+            reference_angle,
+            limits: None,
+            perp_impulse: 0.0,
+            angular_impulse: 0.0,
+            lower_impulse: 0.0,
+            upper_impulse: 0.0,
+            axis: Vec2::ZERO,
+            perp: Vec2::ZERO,
+            s: (0.0, 0.0),
+            a: (0.0, 0.0),
+            perp_mass: 0.0,
+            axial_mass: 0.0,
+            angular_mass: 0.0,
+            perp_bias: 0.0,
+            angular_bias: 0.0,
+            translation: 0.0,
+            dt: 0.0,
+        }
+    }
+
+    /// a joint sliding along an axis through an anchor, both given in the world
+    pub fn along(a: &RigidBody2, b: &RigidBody2, anchor: Vec2, axis: Vec2) -> PrismaticJoint2 {
+        let local_axis = rotate(axis, -a.angle);
+        PrismaticJoint2::new(a.to_local(anchor), b.to_local(anchor), local_axis, b.angle - a.angle)
+    }
+
+    pub fn with_limits(self, lower: f32, upper: f32) -> PrismaticJoint2 {
+        assert!(lower <= upper, "the lower limit is above the upper one");
+        PrismaticJoint2 { limits: Some((lower, upper)), ..self }
+    }
+
+    /// the distance along the axis between the anchors as of the last step
+    #[inline(always)]
+    pub fn translation(&self) -> f32 {
+        self.translation
+    }
+}
+
+#[inline(always)]
+fn inverse(k: f32) -> f32 {
+    if k > 0.0 { 1.0 / k } else { 0.0 }
+}
+
+/// apply an impulse along a linear direction with an angular arm on each body
+#[inline(always)]
+fn apply_linear(a: &mut SolverBody2, b: &mut SolverBody2, impulse: f32, direction: Vec2, arm_a: f32, arm_b: f32) {
+    a.v -= direction * (impulse * a.inv_mass);
+    a.w -= a.inv_inertia * impulse * arm_a;
+    b.v += direction * (impulse * b.inv_mass);
+    b.w += b.inv_inertia * impulse * arm_b;
+}
+
+impl From<DistanceJoint2> for JointKind2 {
+    fn from(joint: DistanceJoint2) -> JointKind2 {
+        JointKind2::Distance(joint)
+    }
+}
+
+impl From<RevoluteJoint2> for JointKind2 {
+    fn from(joint: RevoluteJoint2) -> JointKind2 {
+        JointKind2::Revolute(joint)
+    }
+}
+
+impl From<PrismaticJoint2> for JointKind2 {
+    fn from(joint: PrismaticJoint2) -> JointKind2 {
+        JointKind2::Prismatic(joint)
+    }
+}
+
+impl Joint2 {
+    pub fn new(body_a: BodyHandle, body_b: BodyHandle, kind: JointKind2) -> Joint2 {
+        Joint2 { body_a, body_b, collide_connected: false, kind }
+    }
+
+    pub(crate) fn prepare(&mut self, bodies: &[SolverBody2], dt: f32) {
+        let (a, b) = (&bodies[self.body_a.index()], &bodies[self.body_b.index()]);
+        let mass = a.inv_mass + b.inv_mass;
+        match &mut self.kind {
+            JointKind2::Distance(j) => {
+                j.r_a = a.offset(j.local_anchor_a);
+                j.r_b = b.offset(j.local_anchor_b);
+                let d = b.center + j.r_b - a.center - j.r_a;
+                let length = d.magnitude();
+                j.u = d.try_normalize().unwrap_or(Vec2::ZERO);
+                let (cr_a, cr_b) = (j.r_a.perp_dot(j.u), j.r_b.perp_dot(j.u));
+                j.mass = inverse(mass + a.inv_inertia * cr_a * cr_a + b.inv_inertia * cr_b * cr_b);
+                j.bias = BAUMGARTE / dt * (length - j.length);
+            }
+            JointKind2::Revolute(j) => {
+                j.r_a = a.offset(j.local_anchor_a);
+                j.r_b = b.offset(j.local_anchor_b);
+                let (r_a, r_b, ia, ib) = (j.r_a, j.r_b, a.inv_inertia, b.inv_inertia);
+                j.k = [
+                    mass + ia * r_a.y * r_a.y + ib * r_b.y * r_b.y,
+                    -ia * r_a.x * r_a.y - ib * r_b.x * r_b.y,
+                    mass + ia * r_a.x * r_a.x + ib * r_b.x * r_b.x,
+                ];
+                j.bias = (b.center + r_b - a.center - r_a) * (BAUMGARTE / dt);
+                j.angle = b.angle - a.angle - j.reference_angle;
+                j.axial_mass = inverse(ia + ib);
+                j.max_motor_impulse = j.motor.map_or(0.0, |m| m.max_torque * dt);
+                j.dt = dt;
+                if j.limits.is_none() {
+                    j.lower_impulse = 0.0;
+                    j.upper_impulse = 0.0;
+                }
+                if j.motor.is_none() {
+                    j.motor_impulse = 0.0;
+                }
+            }
+            JointKind2::Prismatic(j) => {
+                let r_a = a.offset(j.local_anchor_a);
+                let r_b = b.offset(j.local_anchor_b);
+                let d = b.center + r_b - a.center - r_a;
+                j.axis = rotate(j.local_axis_a, a.angle);
+                j.perp = j.axis.perp();
+                j.s = ((d + r_a).perp_dot(j.perp), r_b.perp_dot(j.perp));
+                j.a = ((d + r_a).perp_dot(j.axis), r_b.perp_dot(j.axis));
+                let (ia, ib) = (a.inv_inertia, b.inv_inertia);
+                j.perp_mass = inverse(mass + ia * j.s.0 * j.s.0 + ib * j.s.1 * j.s.1);
+                j.axial_mass = inverse(mass + ia * j.a.0 * j.a.0 + ib * j.a.1 * j.a.1);
+                j.angular_mass = inverse(ia + ib);
+                j.perp_bias = BAUMGARTE / dt * j.perp.dot(d);
+                j.angular_bias = BAUMGARTE / dt * (b.angle - a.angle - j.reference_angle);
+                j.translation = j.axis.dot(d);
+                j.dt = dt;
+                if j.limits.is_none() {
+                    j.lower_impulse = 0.0;
+                    j.upper_impulse = 0.0;
+                }
+            }
+        }
+    }
+
+    /// apply last step's impulses again
+    pub(crate) fn warm_start(&self, bodies: &mut [SolverBody2]) {
+        let (a, b) = pair_mut(bodies, self.body_a.index(), self.body_b.index());
+        match &self.kind {
+            JointKind2::Distance(j) => {
+                a.apply(-j.u * j.impulse, j.r_a);
+                b.apply(j.u * j.impulse, j.r_b);
+            }
+            JointKind2::Revolute(j) => {
+                let axial = j.motor_impulse + j.lower_impulse - j.upper_impulse;
+                a.apply(-j.impulse, j.r_a);
+                b.apply(j.impulse, j.r_b);
+                a.w -= a.inv_inertia * axial;
+                b.w += b.inv_inertia * axial;
+            }
+            JointKind2::Prismatic(j) => {
+                let axial = j.lower_impulse - j.upper_impulse;
+                apply_linear(a, b, j.perp_impulse, j.perp, j.s.0, j.s.1);
+                apply_linear(a, b, axial, j.axis, j.a.0, j.a.1);
+                a.w -= a.inv_inertia * j.angular_impulse;
+                b.w += b.inv_inertia * j.angular_impulse;
+            }
+        }
+    }
+
+    pub(crate) fn solve(&mut self, bodies: &mut [SolverBody2]) {
+        let (a, b) = pair_mut(bodies, self.body_a.index(), self.body_b.index());
+        match &mut self.kind {
+            JointKind2::Distance(j) => {
+                let speed = (b.velocity_at(j.r_b) - a.velocity_at(j.r_a)).dot(j.u);
+                let impulse = -j.mass * (speed + j.bias);
+                j.impulse += impulse;
+                a.apply(-j.u * impulse, j.r_a);
+                b.apply(j.u * impulse, j.r_b);
+            }
+            JointKind2::Revolute(j) => {
+                if let Some(motor) = j.motor {
+                    let total = (j.motor_impulse - j.axial_mass * (b.w - a.w - motor.speed))
+                        .clamp(-j.max_motor_impulse, j.max_motor_impulse);
+                    let impulse = total - j.motor_impulse;
+                    j.motor_impulse = total;
+                    a.w -= a.inv_inertia * impulse;
+                    b.w += b.inv_inertia * impulse;
+                }
+                if let Some((lower, upper)) = j.limits {
+                    let total = limit_impulse(j.lower_impulse, j.axial_mass, b.w - a.w, j.angle - lower, j.dt);
+                    let impulse = total - j.lower_impulse;
+                    j.lower_impulse = total;
+                    a.w -= a.inv_inertia * impulse;
+                    b.w += b.inv_inertia * impulse;
+
+                    let total = limit_impulse(j.upper_impulse, j.axial_mass, a.w - b.w, upper - j.angle, j.dt);
+                    let impulse = total - j.upper_impulse;
+                    j.upper_impulse = total;
+                    a.w += a.inv_inertia * impulse;
+                    b.w -= b.inv_inertia * impulse;
+                }
+
+                let c = b.velocity_at(j.r_b) - a.velocity_at(j.r_a) + j.bias;
+                let [k11, k12, k22] = j.k;
+                let det = k11 * k22 - k12 * k12;
+                if det != 0.0 {
+                    let impulse = Vec2(k22 * c.x - k12 * c.y, k11 * c.y - k12 * c.x) * (-1.0 / det);
+                    j.impulse += impulse;
+                    a.apply(-impulse, j.r_a);
+                    b.apply(impulse, j.r_b);
+                }
+            }
+            JointKind2::Prismatic(j) => {
+                if let Some((lower, upper)) = j.limits {
+                    let speed = j.axis.dot(b.v - a.v) + j.a.1 * b.w - j.a.0 * a.w;
+                    let total = limit_impulse(j.lower_impulse, j.axial_mass, speed, j.translation - lower, j.dt);
+                    apply_linear(a, b, total - j.lower_impulse, j.axis, j.a.0, j.a.1);
+                    j.lower_impulse = total;
+
+                    let speed = j.axis.dot(b.v - a.v) + j.a.1 * b.w - j.a.0 * a.w;
+                    let total = limit_impulse(j.upper_impulse, j.axial_mass, -speed, upper - j.translation, j.dt);
+                    apply_linear(a, b, -(total - j.upper_impulse), j.axis, j.a.0, j.a.1);
+                    j.upper_impulse = total;
+                }
+
+                let speed = j.perp.dot(b.v - a.v) + j.s.1 * b.w - j.s.0 * a.w;
+                let impulse = -j.perp_mass * (speed + j.perp_bias);
+                j.perp_impulse += impulse;
+                apply_linear(a, b, impulse, j.perp, j.s.0, j.s.1);
+
+                let impulse = -j.angular_mass * (b.w - a.w + j.angular_bias);
+                j.angular_impulse += impulse;
+                a.w -= a.inv_inertia * impulse;
+                b.w += b.inv_inertia * impulse;
+            }
+        }
+    }
+}
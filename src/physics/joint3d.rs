@@ -0,0 +1,477 @@
+use crate::math::matrix::Matrix;
+use crate::math::quat::Quat;
+use crate::math::vec3::Vec3;
+use crate::physics::body3d::{inverse3, RigidBody3, SolverBody3};
+use crate::physics::{limit_impulse, pair_mut, BodyHandle, BAUMGARTE};
+
+/// Turns a joint at a target speed, with a limited torque
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motor3 {
+    pub speed: f32,
+    pub max_torque: f32,
+}
+
+/// Keeps an anchor on each body in the same place, the part every joint here shares
+#[derive(Clone, Debug)]
+struct PointConstraint {
+    impulse: Vec3,
+    r_a: Vec3,
+    r_b: Vec3,
+    mass: Matrix<f32>,
+    bias: Vec3,
+}
+
+impl PointConstraint {
+    fn new() -> PointConstraint {
+        PointConstraint { impulse: Vec3::ZERO, r_a: Vec3::ZERO, r_b: Vec3::ZERO, mass: Matrix::new(3, 3), bias: Vec3::ZERO }
+    }
+
+    fn prepare(&mut self, a: &SolverBody3, b: &SolverBody3, local_a: Vec3, local_b: Vec3, dt: f32) {
+        self.r_a = a.offset(local_a);
+        self.r_b = b.offset(local_b);
+        // how the anchors' relative velocity changes with a unit impulse along each axis
+        let column = |p: Vec3| {
+            p * (a.inv_mass + b.inv_mass)
+                + (&a.inv_inertia * self.r_a.cross(p)).cross(self.r_a)
+                + (&b.inv_inertia * self.r_b.cross(p)).cross(self.r_b)
+        };
+        let [x, y, z] = [column(Vec3::X), column(Vec3::Y), column(Vec3::Z)];
+        self.mass = inverse3(&Matrix::from_vec(3, 3, vec![x.x, y.x, z.x, x.y, y.y, z.y, x.z, y.z, z.z]));
+        self.bias = (b.center + self.r_b - a.center - self.r_a) * (BAUMGARTE / dt);
+    }
+
+    fn warm_start(&self, a: &mut SolverBody3, b: &mut SolverBody3) {
+        a.apply(-self.impulse, self.r_a);
+        b.apply(self.impulse, self.r_b);
+    }
+
+    fn solve(&mut self, a: &mut SolverBody3, b: &mut SolverBody3) {
+        let speed = b.velocity_at(self.r_b) - a.velocity_at(self.r_a);
+        let impulse = -(&self.mass * (speed + self.bias));
+        self.impulse += impulse;
+        a.apply(-impulse, self.r_a);
+        b.apply(impulse, self.r_b);
+    }
+}
+
+/// A limit on how far the bodies can turn about an axis, which only ever pushes them back
+#[derive(Clone, Debug)]
+struct AngularLimit {
+    lower_impulse: f32,
+    upper_impulse: f32,
+}
+
+impl AngularLimit {
+    fn new() -> AngularLimit {
+        AngularLimit { lower_impulse: 0.0, upper_impulse: 0.0 }
+    }
+
+    /// the total impulse about the axis, b's way
+    fn impulse(&self) -> f32 {
+        self.lower_impulse - self.upper_impulse
+    }
+
+    fn solve(&mut self, a: &mut SolverBody3, b: &mut SolverBody3, axis: Vec3, mass: f32, (angle, lower, upper): (f32, f32, f32), dt: f32) {
+        let total = limit_impulse(self.lower_impulse, mass, (b.w - a.w).dot(axis), angle - lower, dt);
+        apply_angular(a, b, axis * (total - self.lower_impulse));
+        self.lower_impulse = total;
+
+        let total = limit_impulse(self.upper_impulse, mass, (a.w - b.w).dot(axis), upper - angle, dt);
+        apply_angular(a, b, -axis * (total - self.upper_impulse));
+        self.upper_impulse = total;
+    }
+}
+
+#[inline(always)]
+fn inverse(k: f32) -> f32 {
+    if k > 0.0 { 1.0 / k } else { 0.0 }
+}
+
+/// apply an angular impulse to b, and the opposite to a
+#[inline(always)]
+fn apply_angular(a: &mut SolverBody3, b: &mut SolverBody3, impulse: Vec3) {
+    a.apply_angular(-impulse);
+    b.apply_angular(impulse);
+}
+
+/// the rotation of b's joint frame relative to a's, kept to the short way round,
+/// and a's joint frame in the world
+fn relative(a: &SolverBody3, b: &SolverBody3, frame_a: Quat, frame_b: Quat) -> (Quat, Quat) {
+    let world_a = a.orientation * frame_a;
+    let q = world_a.inverse() * (b.orientation * frame_b);
+    (if q.w < 0.0 { -q } else { q }, world_a)
+}
+
+/// the twist of a relative rotation about its frame's x axis, in radians
+fn twist(q: Quat) -> f32 {
+    2.0 * q.x.atan2(q.w)
+}
+
+/// the frame of a joint on each body, with its x axis along `axis` in the world
+fn frames(a: &RigidBody3, b: &RigidBody3, axis: Vec3) -> (Quat, Quat) {
+    let frame = Quat::from_rotation_arc(Vec3::X, axis.normalize());
+    (a.orientation.inverse() * frame, b.orientation.inverse() * frame)
+}
+
+/// Pins two bodies together at an anchor, letting them turn any way about it
+#[derive(Clone, Debug)]
+pub struct BallJoint3 {
+    /// relative to each body's origin, in its local frame
+    pub local_anchor_a: Vec3,
+    pub local_anchor_b: Vec3,
+    point: PointConstraint,
+}
+
+impl BallJoint3 {
+    pub fn new(local_anchor_a: Vec3, local_anchor_b: Vec3) -> BallJoint3 {
+        BallJoint3 { local_anchor_a, local_anchor_b, point: PointConstraint::new() }
+    }
+
+    /// a joint at an anchor given in the world
+    pub fn at(a: &RigidBody3, b: &RigidBody3, anchor: Vec3) -> BallJoint3 {
+        BallJoint3::new(a.to_local(anchor), b.to_local(anchor))
+    }
+}
+
+/// Welds two bodies together, as they were placed relative to each other
+#[derive(Clone, Debug)]
+pub struct FixedJoint3 {
+    pub local_anchor_a: Vec3,
+    pub local_anchor_b: Vec3,
+    /// the orientation of the joint in each body's frame, which the joint keeps lined up
+    pub local_frame_a: Quat,
+    pub local_frame_b: Quat,
+    point: PointConstraint,
+    angular_impulse: Vec3,
+    angular_mass: Matrix<f32>,
+    angular_bias: Vec3,
+}
+
+impl FixedJoint3 {
+    pub fn new(local_anchor_a: Vec3, local_anchor_b: Vec3, local_frame_a: Quat, local_frame_b: Quat) -> FixedJoint3 {
+        FixedJoint3 {
+            local_anchor_a,
+            local_anchor_b,
+            local_frame_a,
+            local_frame_b,
+            point: PointConstraint::new(),
+            angular_impulse: Vec3::ZERO,
+            angular_mass: Matrix::new(3, 3),
+            angular_bias: Vec3::ZERO,
+        }
+    }
+
+    /// a joint at an anchor given in the world, keeping the bodies as they are now
+    pub fn at(a: &RigidBody3, b: &RigidBody3, anchor: Vec3) -> FixedJoint3 {
+        FixedJoint3::new(a.to_local(anchor), b.to_local(anchor), a.orientation.inverse(), b.orientation.inverse())
+    }
+}
+
+/// Lets two bodies turn about a single axis through an anchor, like a door on its hinges
+#[derive(Clone, Debug)]
+pub struct HingeJoint3 {
+    pub local_anchor_a: Vec3,
+    pub local_anchor_b: Vec3,
+    /// the joint frame in each body's frame, with the hinge along its x axis
+    pub local_frame_a: Quat,
+    pub local_frame_b: Quat,
+    /// the lowest and highest hinge angle
+    pub limits: Option<(f32, f32)>,
+    pub motor: Option<Motor3>,
+    point: PointConstraint,
+    /// the two directions the bodies can't turn about, and how they're held
+    perp: [Vec3; 2],
+    perp_impulse: [f32; 2],
+    perp_mass: [f32; 2],
+    perp_bias: [f32; 2],
+    axis: Vec3,
+    axial_mass: f32,
+    angle: f32,
+    limit: AngularLimit,
+    motor_impulse: f32,
+    max_motor_impulse: f32,
+    dt: f32,
+}
+
+impl HingeJoint3 {
+    pub fn new(local_anchor_a: Vec3, local_anchor_b: Vec3, local_frame_a: Quat, local_frame_b: Quat) -> HingeJoint3 {
+        HingeJoint3 {
+            local_anchor_a,
+            local_anchor_b,
+            local_frame_a,
+            local_frame_b,
+            limits: None,
+            motor: None,
+            point: PointConstraint::new(),
+            perp: [Vec3::ZERO; 2],
+            perp_impulse: [0.0; 2],
+            perp_mass: [0.0; 2],
+            perp_bias: [0.0; 2],
+            axis: Vec3::ZERO,
+            axial_mass: 0.0,
+            angle: 0.0,
+            limit: AngularLimit::new(),
+            motor_impulse: 0.0,
+            max_motor_impulse: 0.0,
+            dt: 0.0,
+        }
+    }
+
+    /// a hinge through an anchor along an axis, both given in the world, at angle zero as placed
+    pub fn at(a: &RigidBody3, b: &RigidBody3, anchor: Vec3, axis: Vec3) -> HingeJoint3 {
+        let (frame_a, frame_b) = frames(a, b, axis);
+        HingeJoint3::new(a.to_local(anchor), b.to_local(anchor), frame_a, frame_b)
+    }
+
+    pub fn with_limits(self, lower: f32, upper: f32) -> HingeJoint3 {
+        assert!(lower <= upper, "the lower limit is above the upper one");
+        HingeJoint3 { limits: Some((lower, upper)), ..self }
+    }
+
+    pub fn with_motor(self, speed: f32, max_torque: f32) -> HingeJoint3 {
+        HingeJoint3 { motor: Some(Motor3 { speed, max_torque }), ..self }
+    }
+
+    /// the hinge angle as of the last step, counterclockwise about the axis
+    #[inline(always)]
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+}
+
+/// A ball joint whose swing away from an axis is held within a cone, and whose twist about it
+/// can be limited, like a shoulder
+#[derive(Clone, Debug)]
+pub struct ConeTwistJoint3 {
+    pub local_anchor_a: Vec3,
+    pub local_anchor_b: Vec3,
+    /// the joint frame in each body's frame, with the twist axis along its x axis
+    pub local_frame_a: Quat,
+    pub local_frame_b: Quat,
+    /// the widest angle between the two bodies' twist axes
+    pub swing_limit: f32,
+    pub twist_limits: Option<(f32, f32)>,
+    point: PointConstraint,
+    swing_axis: Vec3,
+    swing_angle: f32,
+    swing_mass: f32,
+    swing_impulse: f32,
+    twist_axis: Vec3,
+    twist_angle: f32,
+    twist_mass: f32,
+    twist: AngularLimit,
+    dt: f32,
+}
+
+impl ConeTwistJoint3 {
+    pub fn new(local_anchor_a: Vec3, local_anchor_b: Vec3, local_frame_a: Quat, local_frame_b: Quat, swing_limit: f32) -> ConeTwistJoint3 {
+        assert!(swing_limit >= 0.0, "the swing limit is negative");
+        ConeTwistJoint3 {
+            local_anchor_a,
+            local_anchor_b,
+            local_frame_a,
+            local_frame_b,
+            swing_limit,
+            twist_limits: None,
+            point: PointConstraint::new(),
+            swing_axis: Vec3::ZERO,
+            swing_angle: 0.0,
+            swing_mass: 0.0,
+            swing_impulse: 0.0,
+            twist_axis: Vec3::ZERO,
+            twist_angle: 0.0,
+            twist_mass: 0.0,
+            twist: AngularLimit::new(),
+            dt: 0.0,
+        }
+    }
+
+    /// a joint at an anchor with its twist axis along `axis`, both given in the world
+    pub fn at(a: &RigidBody3, b: &RigidBody3, anchor: Vec3, axis: Vec3, swing_limit: f32) -> ConeTwistJoint3 {
+        let (frame_a, frame_b) = frames(a, b, axis);
+        ConeTwistJoint3::new(a.to_local(anchor), b.to_local(anchor), frame_a, frame_b, swing_limit)
+    }
+
+    pub fn with_twist_limits(self, lower: f32, upper: f32) -> ConeTwistJoint3 {
+        assert!(lower <= upper, "the lower limit is above the upper one");
+        ConeTwistJoint3 { twist_limits: Some((lower, upper)), ..self }
+    }
+
+    /// the angle between the twist axes as of the last step
+    #[inline(always)]
+    pub fn swing_angle(&self) -> f32 {
+        self.swing_angle
+    }
+
+    #[inline(always)]
+    pub fn twist_angle(&self) -> f32 {
+        self.twist_angle
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum JointKind3 {
+    Ball(BallJoint3),
+    Fixed(FixedJoint3),
+    Hinge(HingeJoint3),
+    ConeTwist(ConeTwistJoint3),
+}
+
+impl From<BallJoint3> for JointKind3 {
+    fn from(joint: BallJoint3) -> JointKind3 {
+        JointKind3::Ball(joint)
+    }
+}
+
+impl From<FixedJoint3> for JointKind3 {
+    fn from(joint: FixedJoint3) -> JointKind3 {
+        JointKind3::Fixed(joint)
+    }
+}
+
+impl From<HingeJoint3> for JointKind3 {
+    fn from(joint: HingeJoint3) -> JointKind3 {
+        JointKind3::Hinge(joint)
+    }
+}
+
+impl From<ConeTwistJoint3> for JointKind3 {
+    fn from(joint: ConeTwistJoint3) -> JointKind3 {
+        JointKind3::ConeTwist(joint)
+    }
+}
+
+/// A constraint between two bodies
+#[derive(Clone, Debug)]
+pub struct Joint3 {
+    pub body_a: BodyHandle,
+    pub body_b: BodyHandle,
+    /// whether the two bodies' colliders still collide with each other
+    pub collide_connected: bool,
+    pub kind: JointKind3,
+}
+
+impl Joint3 {
+    pub fn new(body_a: BodyHandle, body_b: BodyHandle, kind: JointKind3) -> Joint3 {
+        Joint3 { body_a, body_b, collide_connected: false, kind }
+    }
+
+    pub(crate) fn prepare(&mut self, bodies: &[SolverBody3], dt: f32) {
+        let (a, b) = (&bodies[self.body_a.index()], &bodies[self.body_b.index()]);
+        let angular_mass = |axis: Vec3| inverse(a.angular_response(axis) + b.angular_response(axis));
+        match &mut self.kind {
+            JointKind3::Ball(j) => j.point.prepare(a, b, j.local_anchor_a, j.local_anchor_b, dt),
+            JointKind3::Fixed(j) => {
+                j.point.prepare(a, b, j.local_anchor_a, j.local_anchor_b, dt);
+                let k = Matrix::from_vec(3, 3, a.inv_inertia.data.iter().zip(&b.inv_inertia.data).map(|(a, b)| a + b).collect());
+                j.angular_mass = inverse3(&k);
+                let (q, world_a) = relative(a, b, j.local_frame_a, j.local_frame_b);
+                j.angular_bias = world_a * (q.xyz() * 2.0) * (BAUMGARTE / dt);
+            }
+            JointKind3::Hinge(j) => {
+                j.point.prepare(a, b, j.local_anchor_a, j.local_anchor_b, dt);
+                let (q, world_a) = relative(a, b, j.local_frame_a, j.local_frame_b);
+                let axis_a = world_a * Vec3::X;
+                let axis_b = b.orientation * j.local_frame_b * Vec3::X;
+                let p = axis_a.any_orthonormal();
+                j.perp = [p, axis_a.cross(p)];
+                let error = axis_a.cross(axis_b);
+                for i in 0..2 {
+                    j.perp_mass[i] = angular_mass(j.perp[i]);
+                    j.perp_bias[i] = BAUMGARTE / dt * error.dot(j.perp[i]);
+                }
+                j.axis = axis_a;
+                j.axial_mass = angular_mass(axis_a);
+                j.angle = twist(q);
+                j.max_motor_impulse = j.motor.map_or(0.0, |m| m.max_torque * dt);
+                j.dt = dt;
+                if j.limits.is_none() {
+                    j.limit = AngularLimit::new();
+                }
+                if j.motor.is_none() {
+                    j.motor_impulse = 0.0;
+                }
+            }
+            JointKind3::ConeTwist(j) => {
+                j.point.prepare(a, b, j.local_anchor_a, j.local_anchor_b, dt);
+                let (q, world_a) = relative(a, b, j.local_frame_a, j.local_frame_b);
+                let axis_a = world_a * Vec3::X;
+                let axis_b = b.orientation * j.local_frame_b * Vec3::X;
+                j.swing_angle = axis_a.dot(axis_b).clamp(-1.0, 1.0).acos();
+                j.swing_axis = axis_a.cross(axis_b).normalize_or_zero();
+                j.swing_mass = angular_mass(j.swing_axis);
+                j.twist_axis = (axis_a + axis_b).normalize_or_zero();
+                j.twist_mass = angular_mass(j.twist_axis);
+                // the twist left once the swing is taken out
+                let swing = Quat::from_rotation_arc(Vec3::X, q * Vec3::X);
+                j.twist_angle = twist(swing.inverse() * q);
+                j.dt = dt;
+                if j.twist_limits.is_none() {
+                    j.twist = AngularLimit::new();
+                }
+            }
+        }
+    }
+
+    /// apply last step's impulses again
+    pub(crate) fn warm_start(&self, bodies: &mut [SolverBody3]) {
+        let (a, b) = pair_mut(bodies, self.body_a.index(), self.body_b.index());
+        match &self.kind {
+            JointKind3::Ball(j) => j.point.warm_start(a, b),
+            JointKind3::Fixed(j) => {
+                j.point.warm_start(a, b);
+                apply_angular(a, b, j.angular_impulse);
+            }
+            JointKind3::Hinge(j) => {
+                j.point.warm_start(a, b);
+                let axial = j.motor_impulse + j.limit.impulse();
+                apply_angular(a, b, j.perp[0] * j.perp_impulse[0] + j.perp[1] * j.perp_impulse[1] + j.axis * axial);
+            }
+            JointKind3::ConeTwist(j) => {
+                j.point.warm_start(a, b);
+                apply_angular(a, b, -j.swing_axis * j.swing_impulse + j.twist_axis * j.twist.impulse());
+            }
+        }
+    }
+
+    pub(crate) fn solve(&mut self, bodies: &mut [SolverBody3]) {
+        let (a, b) = pair_mut(bodies, self.body_a.index(), self.body_b.index());
+        match &mut self.kind {
+            JointKind3::Ball(j) => j.point.solve(a, b),
+            JointKind3::Fixed(j) => {
+                let impulse = -(&j.angular_mass * (b.w - a.w + j.angular_bias));
+                j.angular_impulse += impulse;
+                apply_angular(a, b, impulse);
+                j.point.solve(a, b);
+            }
+            JointKind3::Hinge(j) => {
+                if let Some(motor) = j.motor {
+                    let speed = (b.w - a.w).dot(j.axis) - motor.speed;
+                    let total = (j.motor_impulse - j.axial_mass * speed).clamp(-j.max_motor_impulse, j.max_motor_impulse);
+                    apply_angular(a, b, j.axis * (total - j.motor_impulse));
+                    j.motor_impulse = total;
+                }
+                if let Some((lower, upper)) = j.limits {
+                    j.limit.solve(a, b, j.axis, j.axial_mass, (j.angle, lower, upper), j.dt);
+                }
+                for i in 0..2 {
+                    let impulse = -j.perp_mass[i] * ((b.w - a.w).dot(j.perp[i]) + j.perp_bias[i]);
+                    j.perp_impulse[i] += impulse;
+                    apply_angular(a, b, j.perp[i] * impulse);
+                }
+                j.point.solve(a, b);
+            }
+            JointKind3::ConeTwist(j) => {
+                // the swing only grows by turning b about the swing axis, away from a's twist axis
+                let speed = -(b.w - a.w).dot(j.swing_axis);
+                let total = limit_impulse(j.swing_impulse, j.swing_mass, speed, j.swing_limit - j.swing_angle, j.dt);
+                apply_angular(a, b, -j.swing_axis * (total - j.swing_impulse));
+                j.swing_impulse = total;
+                if let Some((lower, upper)) = j.twist_limits {
+                    j.twist.solve(a, b, j.twist_axis, j.twist_mass, (j.twist_angle, lower, upper), j.dt);
+                }
+                j.point.solve(a, b);
+            }
+        }
+    }
+}
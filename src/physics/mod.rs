@@ -0,0 +1,161 @@
+pub mod body2d;
+pub mod body3d;
+pub mod contact2d;
+pub mod contact3d;
+pub mod joint2d;
+pub mod joint3d;
+pub mod world2d;
+pub mod world3d;
+
+/// how much of the drift of contacts and joints is corrected each step
+pub(crate) const BAUMGARTE: f32 = 0.2;
+
+/// How a body moves
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BodyType {
+    /// moved by forces, contacts and joints
+    Dynamic,
+    /// moved only by its velocity, pushing dynamic bodies aside as if infinitely heavy
+    Kinematic,
+    /// never moves
+    Static,
+}
+
+/// A handle to a body in a physics world.
+///
+/// handles of removed bodies go stale, and their slot is reused with a new generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BodyHandle {
+    index: u32,
+    generation: u32,
+}
+
+impl BodyHandle {
+    #[inline(always)]
+    pub(crate) fn new(index: u32, generation: u32) -> BodyHandle {
+        BodyHandle { index, generation }
+    }
+
+    #[inline(always)]
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+/// A handle to a joint in a physics world, going stale like a [`BodyHandle`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JointHandle {
+    index: u32,
+    generation: u32,
+}
+
+impl JointHandle {
+    #[inline(always)]
+    pub(crate) fn new(index: u32, generation: u32) -> JointHandle {
+        JointHandle { index, generation }
+    }
+
+    #[inline(always)]
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+/// the new total of a limit's impulse, which only pushes, given how far inside the limit it
+/// is and how fast that's changing. approaching is allowed as long as it won't cross the limit
+/// this step
+#[inline(always)]
+pub(crate) fn limit_impulse(total: f32, mass: f32, speed: f32, inside: f32, dt: f32) -> f32 {
+    let bias = if inside > 0.0 { inside / dt } else { BAUMGARTE / dt * inside };
+    (total - mass * (speed + bias)).max(0.0)
+}
+
+/// two distinct elements mutably at once
+pub(crate) fn pair_mut<T>(slice: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
+    assert_ne!(a, b, "a joint can't connect a body to itself");
+    if a < b {
+        let (low, high) = slice.split_at_mut(b);
+        (&mut low[a], &mut high[0])
+    } else {
+        let (low, high) = slice.split_at_mut(a);
+        (&mut high[0], &mut low[b])
+    }
+}
+
+/// values in slots reused after removal, visited in slot order so stepping is deterministic
+#[derive(Clone, Debug)]
+pub(crate) struct Arena<T> {
+    slots: Vec<(u32, Option<T>)>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Arena<T> {
+        Arena { slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+}
+
+impl<T> Arena<T> {
+    #[inline(always)]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// one past the highest slot in use
+    #[inline(always)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// the value's slot and generation
+    pub(crate) fn insert(&mut self, value: T) -> (u32, u32) {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.0 += 1;
+                slot.1 = Some(value);
+                (index, slot.0)
+            }
+            None => {
+                self.slots.push((0, Some(value)));
+                (self.slots.len() as u32 - 1, 0)
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, index: u32, generation: u32) -> Option<T> {
+        let slot = self.slots.get_mut(index as usize).filter(|s| s.0 == generation)?;
+        let value = slot.1.take()?;
+        self.free.push(index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    #[inline(always)]
+    pub(crate) fn get(&self, index: u32, generation: u32) -> Option<&T> {
+        self.slots.get(index as usize).filter(|s| s.0 == generation)?.1.as_ref()
+    }
+
+    #[inline(always)]
+    pub(crate) fn get_mut(&mut self, index: u32, generation: u32) -> Option<&mut T> {
+        self.slots.get_mut(index as usize).filter(|s| s.0 == generation)?.1.as_mut()
+    }
+
+    /// the value in a slot whatever its generation
+    #[inline(always)]
+    pub(crate) fn slot(&self, index: usize) -> Option<&T> {
+        self.slots[index].1.as_ref()
+    }
+
+    #[inline(always)]
+    pub(crate) fn slot_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slots[index].1.as_mut()
+    }
+
+    /// every value with its slot and generation, in slot order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u32, u32, &T)> {
+        self.slots.iter().enumerate().filter_map(|(i, (g, v))| v.as_ref().map(|v| (i as u32, *g, v)))
+    }
+}
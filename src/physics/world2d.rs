@@ -0,0 +1,496 @@
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::PI;
+use crate::geometry::aabb::Aabb2;
+use crate::geometry::broadphase::SweepAndPrune;
+use crate::geometry::collision2d::{collide, Shape2};
+use crate::math::vec2::Vec2;
+use crate::physics::body2d::{rotate, Collider2, RigidBody2, SolverBody2};
+use crate::physics::contact2d::{ContactPair2, ContactPoint2};
+use crate::physics::joint2d::{Joint2, JointKind2};
+use crate::physics::{Arena, BodyHandle, BodyType, JointHandle};
+
+/// the furthest a body can move in one step, so fast bodies can't blow up the solver
+const MAX_TRANSLATION: f32 = 2.0;
+const MAX_ROTATION: f32 = 0.25 * PI;
+
+/// how slow a body has to be to count as resting
+const LINEAR_SLEEP_TOLERANCE: f32 = 0.05;
+const ANGULAR_SLEEP_TOLERANCE: f32 = 2.0 / 180.0 * PI;
+
+/// how long a whole island has to rest before it falls asleep
+const TIME_TO_SLEEP: f32 = 0.5;
+
+/// how close a contact has to stay on the first body to keep its impulses from the last step
+const MATCH_DISTANCE: f32 = 0.1;
+
+/// A collider's shape in the world, as of the last time its body was synced
+#[derive(Clone, Debug)]
+struct Proxy {
+    body: BodyHandle,
+    collider: usize,
+    shape: Shape2,
+}
+
+/// A world of 2D rigid bodies, stepped with a sequential impulse solver.
+///
+/// stepping is deterministic: the same bodies added in the same order and stepped the same
+/// way always end up in the same place, bit for bit.
+#[derive(Clone, Debug)]
+pub struct PhysicsWorld2 {
+    pub gravity: Vec2,
+    /// more iterations make stacks and chains of joints stiffer
+    pub velocity_iterations: usize,
+    bodies: Arena<RigidBody2>,
+    joints: Arena<Joint2>,
+    broadphase: SweepAndPrune<Aabb2>,
+    /// indexed by broadphase handle
+    proxies: Vec<Option<Proxy>>,
+    contacts: Vec<ContactPair2>,
+    next_island: u32,
+}
+
+impl Default for PhysicsWorld2 {
+    /// a world with earth's gravity pulling down
+    fn default() -> PhysicsWorld2 {
+        PhysicsWorld2::new(Vec2(0.0, -9.81))
+    }
+}
+
+impl PhysicsWorld2 {
+    pub fn new(gravity: Vec2) -> PhysicsWorld2 {
+        PhysicsWorld2 {
+            gravity,
+            velocity_iterations: 8,
+            bodies: Arena::default(),
+            joints: Arena::default(),
+            broadphase: SweepAndPrune::new(),
+            proxies: Vec::new(),
+            contacts: Vec::new(),
+            next_island: 1,
+        }
+    }
+
+    /// the number of bodies
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.bodies.len() == 0
+    }
+
+    pub fn add_body(&mut self, mut body: RigidBody2) -> BodyHandle {
+        body.awake = true;
+        body.sleep_time = 0.0;
+        body.synced = false;
+        for collider in &mut body.colliders {
+            collider.proxy = None;
+        }
+        let (index, generation) = self.bodies.insert(body);
+        BodyHandle::new(index, generation)
+    }
+
+    /// remove a body with its colliders and joints, waking anything it was touching
+    pub fn remove_body(&mut self, handle: BodyHandle) -> Option<RigidBody2> {
+        let mut body = self.bodies.remove(handle.index, handle.generation)?;
+        for collider in &mut body.colliders {
+            if let Some(proxy) = collider.proxy.take() {
+                self.broadphase.remove(proxy);
+                self.proxies[proxy.index()] = None;
+            }
+        }
+
+        let mut neighbours = Vec::new();
+        let joints: Vec<_> = self.joints.iter()
+            .filter(|(_, _, j)| j.body_a == handle || j.body_b == handle)
+            .map(|(i, g, j)| (i, g, if j.body_a == handle { j.body_b } else { j.body_a }))
+            .collect();
+        for (index, generation, other) in joints {
+            self.joints.remove(index, generation);
+            neighbours.push(other);
+        }
+        self.contacts.retain(|c| {
+            let touching = c.body_a == handle || c.body_b == handle;
+            if touching {
+                neighbours.push(if c.body_a == handle { c.body_b } else { c.body_a });
+            }
+            !touching
+        });
+        for other in neighbours {
+            self.wake(other);
+        }
+        Some(body)
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, handle: BodyHandle) -> bool {
+        self.body(handle).is_some()
+    }
+
+    #[inline(always)]
+    pub fn body(&self, handle: BodyHandle) -> Option<&RigidBody2> {
+        self.bodies.get(handle.index, handle.generation)
+    }
+
+    /// the body, woken up, since anything changed might need it to move
+    pub fn body_mut(&mut self, handle: BodyHandle) -> Option<&mut RigidBody2> {
+        if !self.contains(handle) {
+            return None
+        }
+        self.wake(handle);
+        let body = self.bodies.get_mut(handle.index, handle.generation)?;
+        body.synced = false;
+        Some(body)
+    }
+
+    /// every body with its handle, in a fixed order
+    pub fn bodies(&self) -> impl Iterator<Item = (BodyHandle, &RigidBody2)> {
+        self.bodies.iter().map(|(i, g, b)| (BodyHandle::new(i, g), b))
+    }
+
+    /// attach another collider, returning false if the body is gone
+    pub fn add_collider(&mut self, handle: BodyHandle, collider: Collider2) -> bool {
+        match self.body_mut(handle) {
+            Some(body) => {
+                body.colliders.push(Collider2 { proxy: None, ..collider });
+                body.update_mass();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// wake a body, along with the island it fell asleep with
+    pub fn wake(&mut self, handle: BodyHandle) {
+        let island = match self.body(handle) {
+            Some(body) if !body.awake => body.island,
+            _ => return,
+        };
+        for index in 0..self.bodies.capacity() {
+            if let Some(body) = self.bodies.slot_mut(index).filter(|b| !b.awake && b.island == island) {
+                body.awake = true;
+                body.sleep_time = 0.0;
+            }
+        }
+    }
+
+    /// join two bodies, which both have to be in the world
+    pub fn add_joint(&mut self, body_a: BodyHandle, body_b: BodyHandle, kind: impl Into<JointKind2>) -> JointHandle {
+        assert!(self.contains(body_a) && self.contains(body_b), "a joint's bodies have to be in the world");
+        assert_ne!(body_a, body_b, "a joint can't connect a body to itself");
+        self.wake(body_a);
+        self.wake(body_b);
+        let (index, generation) = self.joints.insert(Joint2::new(body_a, body_b, kind.into()));
+        JointHandle::new(index, generation)
+    }
+
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Joint2> {
+        let joint = self.joints.remove(handle.index, handle.generation)?;
+        self.wake(joint.body_a);
+        self.wake(joint.body_b);
+        Some(joint)
+    }
+
+    #[inline(always)]
+    pub fn joint(&self, handle: JointHandle) -> Option<&Joint2> {
+        self.joints.get(handle.index, handle.generation)
+    }
+
+    /// the joint, waking its bodies
+    pub fn joint_mut(&mut self, handle: JointHandle) -> Option<&mut Joint2> {
+        let (a, b) = self.joint(handle).map(|j| (j.body_a, j.body_b))?;
+        self.wake(a);
+        self.wake(b);
+        self.joints.get_mut(handle.index, handle.generation)
+    }
+
+    /// every joint with its handle, in a fixed order
+    pub fn joints(&self) -> impl Iterator<Item = (JointHandle, &Joint2)> {
+        self.joints.iter().map(|(i, g, j)| (JointHandle::new(i, g), j))
+    }
+
+    /// the touching pairs of colliders found over the last step
+    #[inline(always)]
+    pub fn contacts(&self) -> &[ContactPair2] {
+        &self.contacts
+    }
+
+    /// advance the world by `dt` seconds
+    pub fn step(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return
+        }
+        self.integrate_velocities(dt);
+        self.sync_proxies();
+        self.find_contacts();
+        self.solve(dt);
+        self.integrate_positions(dt);
+        self.update_sleep(dt);
+    }
+
+    /// apply gravity, forces and damping to every awake dynamic body
+    fn integrate_velocities(&mut self, dt: f32) {
+        for index in 0..self.bodies.capacity() {
+            let Some(body) = self.bodies.slot_mut(index) else { continue };
+            let (force, torque) = body.take_forces();
+            if !body.is_dynamic() || !body.awake {
+                continue
+            }
+            let acceleration = self.gravity * body.gravity_scale + force * body.inv_mass();
+            body.linear_velocity += acceleration * dt;
+            body.angular_velocity += body.inv_inertia() * torque * dt;
+            body.linear_velocity *= 1.0 / (1.0 + dt * body.linear_damping);
+            body.angular_velocity *= 1.0 / (1.0 + dt * body.angular_damping);
+        }
+    }
+
+    /// move the colliders of bodies that moved since they were last synced
+    fn sync_proxies(&mut self) {
+        let unsynced: Vec<BodyHandle> = self.bodies.iter()
+            .filter(|(_, _, b)| !b.synced)
+            .map(|(i, g, _)| BodyHandle::new(i, g))
+            .collect();
+        for handle in unsynced {
+            let body = self.bodies.get_mut(handle.index, handle.generation).unwrap();
+            body.synced = true;
+            let m = body.transform().to_matrix();
+            for (i, collider) in body.colliders.iter_mut().enumerate() {
+                let shape = collider.shape.transform(&m);
+                let aabb = shape.aabb();
+                let proxy = match collider.proxy {
+                    Some(proxy) => {
+                        self.broadphase.update(proxy, aabb);
+                        proxy
+                    }
+                    None => {
+                        let proxy = self.broadphase.insert(aabb);
+                        collider.proxy = Some(proxy);
+                        proxy
+                    }
+                };
+                if self.proxies.len() <= proxy.index() {
+                    self.proxies.resize(proxy.index() + 1, None);
+                }
+                self.proxies[proxy.index()] = Some(Proxy { body: handle, collider: i, shape });
+            }
+        }
+    }
+
+    /// whether a body can move this step
+    fn moving(body: &RigidBody2) -> bool {
+        match body.body_type {
+            BodyType::Dynamic => body.awake,
+            BodyType::Kinematic => body.linear_velocity != Vec2::ZERO || body.angular_velocity != 0.0,
+            BodyType::Static => false,
+        }
+    }
+
+    /// build the contact pairs for this step, keeping last step's impulses where points match
+    fn find_contacts(&mut self) {
+        let pairs = self.broadphase.pairs();
+        let mut old: HashMap<(BodyHandle, usize, BodyHandle, usize), ContactPair2> = std::mem::take(&mut self.contacts)
+            .into_iter()
+            .map(|c| ((c.body_a, c.collider_a, c.body_b, c.collider_b), c))
+            .collect();
+        let unconnected: HashSet<(BodyHandle, BodyHandle)> = self.joints.iter()
+            .filter(|(_, _, j)| !j.collide_connected)
+            .map(|(_, _, j)| (j.body_a.min(j.body_b), j.body_a.max(j.body_b)))
+            .collect();
+
+        // anything moving into a sleeping island wakes it up first
+        let mut woken = Vec::new();
+        for &(p, q) in &pairs {
+            let (Some(p), Some(q)) = (&self.proxies[p.index()], &self.proxies[q.index()]) else { continue };
+            let (Some(a), Some(b)) = (self.body(p.body), self.body(q.body)) else { continue };
+            let asleep = |body: &RigidBody2| body.is_dynamic() && !body.awake;
+            let touching = || collide(&p.shape, &q.shape).is_some();
+            if ((asleep(a) && Self::moving(b)) || (asleep(b) && Self::moving(a))) && touching() {
+                woken.push(if asleep(a) { p.body } else { q.body });
+            }
+        }
+        for handle in woken {
+            self.wake(handle);
+        }
+
+        for (p, q) in pairs {
+            let (Some(p), Some(q)) = (&self.proxies[p.index()], &self.proxies[q.index()]) else { continue };
+            if p.body == q.body || unconnected.contains(&(p.body.min(q.body), p.body.max(q.body))) {
+                continue
+            }
+            let (Some(a), Some(b)) = (self.body(p.body), self.body(q.body)) else { continue };
+            if !a.is_dynamic() && !b.is_dynamic() {
+                continue
+            }
+            let key = (p.body, p.collider, q.body, q.collider);
+            if !Self::moving(a) && !Self::moving(b) {
+                // resting pairs keep their contacts for when they wake
+                if let Some(pair) = old.remove(&key) {
+                    self.contacts.push(pair);
+                }
+                continue
+            }
+            let Some(manifold) = collide(&p.shape, &q.shape) else { continue };
+            let previous = old.remove(&key);
+            let points = manifold.contacts.iter().map(|c| {
+                let mut point = ContactPoint2::new(c.point, c.depth, a.to_local(c.point));
+                let matched = previous.as_ref().and_then(|pair| {
+                    pair.points.iter().find(|o| o.local_a.distance(point.local_a) < MATCH_DISTANCE)
+                });
+                if let Some(o) = matched {
+                    point.normal_impulse = o.normal_impulse;
+                    point.tangent_impulse = o.tangent_impulse;
+                }
+                point
+            }).collect();
+            let (ca, cb) = (&a.colliders[p.collider], &b.colliders[q.collider]);
+            self.contacts.push(ContactPair2 {
+                body_a: p.body,
+                body_b: q.body,
+                collider_a: p.collider,
+                collider_b: q.collider,
+                normal: manifold.normal,
+                points,
+                friction: (ca.friction * cb.friction).sqrt(),
+                restitution: ca.restitution.max(cb.restitution),
+            });
+        }
+    }
+
+    /// sequential impulses over the joints then the contacts, writing the velocities back
+    fn solve(&mut self, dt: f32) {
+        let mut solver: Vec<SolverBody2> = (0..self.bodies.capacity()).map(|index| {
+            match self.bodies.slot(index) {
+                Some(body) => {
+                    let moving = Self::moving(body);
+                    let dynamic = moving && body.is_dynamic();
+                    SolverBody2 {
+                        v: if moving { body.linear_velocity } else { Vec2::ZERO },
+                        w: if moving { body.angular_velocity } else { 0.0 },
+                        inv_mass: if dynamic { body.inv_mass() } else { 0.0 },
+                        inv_inertia: if dynamic { body.inv_inertia() } else { 0.0 },
+                        center: body.world_center(),
+                        angle: body.angle,
+                        local_center: body.local_center(),
+                    }
+                }
+                None => SolverBody2::EMPTY,
+            }
+        }).collect();
+
+        let joints: Vec<usize> = (0..self.joints.capacity()).filter(|&i| self.joints.slot(i).is_some()).collect();
+        for &i in &joints {
+            let joint = self.joints.slot_mut(i).unwrap();
+            joint.prepare(&solver, dt);
+            joint.warm_start(&mut solver);
+        }
+        for pair in &mut self.contacts {
+            pair.prepare(&solver, dt);
+            pair.warm_start(&mut solver);
+        }
+        for _ in 0..self.velocity_iterations {
+            for &i in &joints {
+                self.joints.slot_mut(i).unwrap().solve(&mut solver);
+            }
+            for pair in &mut self.contacts {
+                pair.solve(&mut solver);
+            }
+        }
+
+        for (index, s) in solver.iter().enumerate() {
+            if let Some(body) = self.bodies.slot_mut(index).filter(|b| b.is_dynamic() && b.awake) {
+                body.linear_velocity = s.v;
+                body.angular_velocity = s.w;
+            }
+        }
+    }
+
+    /// move every moving body by its velocity, turning it about its center of mass
+    fn integrate_positions(&mut self, dt: f32) {
+        for index in 0..self.bodies.capacity() {
+            let Some(body) = self.bodies.slot_mut(index) else { continue };
+            if !Self::moving(body) {
+                continue
+            }
+            let translation = body.linear_velocity * dt;
+            if translation.magnitude() > MAX_TRANSLATION {
+                body.linear_velocity *= MAX_TRANSLATION / translation.magnitude();
+            }
+            let rotation = body.angular_velocity * dt;
+            if rotation.abs() > MAX_ROTATION {
+                body.angular_velocity *= MAX_ROTATION / rotation.abs();
+            }
+            let center = body.world_center() + body.linear_velocity * dt;
+            body.angle += body.angular_velocity * dt;
+            body.position = center - rotate(body.local_center(), body.angle);
+            body.synced = false;
+        }
+    }
+
+    /// put islands of dynamic bodies that have all rested long enough to sleep
+    fn update_sleep(&mut self, dt: f32) {
+        let capacity = self.bodies.capacity();
+        for index in 0..capacity {
+            let Some(body) = self.bodies.slot_mut(index).filter(|b| b.is_dynamic() && b.awake) else { continue };
+            let v = body.linear_velocity;
+            let w = body.angular_velocity;
+            if !body.can_sleep || v.dot(v) > LINEAR_SLEEP_TOLERANCE * LINEAR_SLEEP_TOLERANCE
+                || w * w > ANGULAR_SLEEP_TOLERANCE * ANGULAR_SLEEP_TOLERANCE {
+                body.sleep_time = 0.0;
+            } else {
+                body.sleep_time += dt;
+            }
+        }
+
+        // islands are bodies joined by contacts and joints, through dynamic bodies only
+        let mut parent: Vec<usize> = (0..capacity).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        let links = self.contacts.iter().map(|c| (c.body_a, c.body_b))
+            .chain(self.joints.iter().map(|(_, _, j)| (j.body_a, j.body_b)))
+            .collect::<Vec<_>>();
+        for (a, b) in links {
+            let (dynamic_a, dynamic_b) = (self.body(a).unwrap().is_dynamic(), self.body(b).unwrap().is_dynamic());
+            if dynamic_a && dynamic_b {
+                let (ra, rb) = (find(&mut parent, a.index()), find(&mut parent, b.index()));
+                parent[ra.max(rb)] = ra.min(rb);
+            } else {
+                // something moving that never sleeps keeps what it touches awake
+                let (dynamic, other) = if dynamic_a { (a, b) } else { (b, a) };
+                if Self::moving(self.body(other).unwrap()) {
+                    self.bodies.get_mut(dynamic.index, dynamic.generation).unwrap().sleep_time = 0.0;
+                }
+            }
+        }
+
+        let mut rest = vec![f32::INFINITY; capacity];
+        for index in 0..capacity {
+            if let Some(body) = self.bodies.slot(index).filter(|b| b.is_dynamic() && b.awake) {
+                let root = find(&mut parent, index);
+                rest[root] = rest[root].min(body.sleep_time);
+            }
+        }
+        let mut islands = vec![0; capacity];
+        for index in 0..capacity {
+            let root = find(&mut parent, index);
+            if rest[root] < TIME_TO_SLEEP {
+                continue
+            }
+            let Some(body) = self.bodies.slot_mut(index).filter(|b| b.is_dynamic() && b.awake) else { continue };
+            if islands[root] == 0 {
+                islands[root] = self.next_island;
+                self.next_island = self.next_island.wrapping_add(1).max(1);
+            }
+            body.awake = false;
+            body.island = islands[root];
+            body.linear_velocity = Vec2::ZERO;
+            body.angular_velocity = 0.0;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::io::{self, Write};
+use std::path::Path;
+use crate::render::Framebuffer;
+
+/// the largest block deflate can store uncompressed
+const MAX_STORED: usize = 65535;
+
+/// the CRC-32 used by PNG chunks and zip, reflected with polynomial `0xEDB88320`
+pub fn crc32(bytes: &[u8]) -> u32 {
+    crc32_update(0xFFFFFFFF, bytes) ^ 0xFFFFFFFF
+}
+
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+/// the Adler-32 checksum that ends a zlib stream
+pub fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the most bytes that can be summed before `b` could overflow
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// a zlib stream holding `bytes` in stored deflate blocks, which is valid but not compressed
+pub(crate) fn zlib_stored(bytes: &[u8]) -> Vec<u8> {
+    let blocks = bytes.len().div_ceil(MAX_STORED).max(1);
+    let mut out = Vec::with_capacity(bytes.len() + blocks * 5 + 6);
+    // deflate with a 32k window, no preset dictionary and the lowest level, with the check bits set
+    out.extend_from_slice(&[0x78, 0x01]);
+    let mut chunks = bytes.chunks(MAX_STORED).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        let len = chunk.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&adler32(bytes).to_be_bytes());
+    out
+}
+
+fn write_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = crc32_update(crc32_update(0xFFFFFFFF, kind), data) ^ 0xFFFFFFFF;
+    writer.write_all(&crc.to_be_bytes())
+}
+
+/// Writing the framebuffer out as an image.
+///
+/// colours are clamped to `0..=1` and written as they are, without any gamma correction.
+impl Framebuffer {
+    /// the colours as 8 bit RGBA, row by row from the top
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let quantize = |c: f32| (c.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+        self.color().iter().flat_map(|c| [quantize(c.x), quantize(c.y), quantize(c.z), quantize(c.w)]).collect()
+    }
+
+    /// write the colours as a binary PPM, dropping alpha
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width(), self.height())?;
+        let rgb: Vec<u8> = self.to_rgba8().chunks(4).flat_map(|p| [p[0], p[1], p[2]]).collect();
+        writer.write_all(&rgb)
+    }
+
+    /// write the colours as an RGBA PNG, stored without compression
+    pub fn write_png<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width() as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height() as u32).to_be_bytes());
+        // 8 bits per channel, RGBA, then the default compression, filtering and no interlacing
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        write_chunk(&mut writer, b"IHDR", &header)?;
+
+        let rgba = self.to_rgba8();
+        let mut scanlines = Vec::with_capacity(rgba.len() + self.height());
+        for row in rgba.chunks(self.width() * 4) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+        write_chunk(&mut writer, b"IDAT", &zlib_stored(&scanlines))?;
+        write_chunk(&mut writer, b"IEND", &[])
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_ppm(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_png(&mut writer)?;
+        writer.flush()
+    }
+}
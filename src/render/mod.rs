@@ -0,0 +1,222 @@
+use crate::math::affine::Affine3;
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+
+pub mod image;
+mod raster;
+
+/// A colour and depth buffer, stored row by row from the top left
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    color: Vec<Vec4>,
+    depth: Vec<f32>,
+}
+
+impl Framebuffer {
+    /// a framebuffer cleared to transparent black and the far plane
+    pub fn new(width: usize, height: usize) -> Framebuffer {
+        assert!(width > 0 && height > 0, "framebuffer can't be empty");
+        Framebuffer {
+            width,
+            height,
+            color: vec![Vec4::ZERO; width * height],
+            depth: vec![1.0; width * height],
+        }
+    }
+
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline(always)]
+    pub fn color(&self) -> &[Vec4] {
+        &self.color
+    }
+
+    #[inline(always)]
+    pub fn depth(&self) -> &[f32] {
+        &self.depth
+    }
+
+    #[inline(always)]
+    pub fn pixel(&self, x: usize, y: usize) -> Vec4 {
+        self.color[y * self.width + x]
+    }
+
+    #[inline(always)]
+    pub fn depth_at(&self, x: usize, y: usize) -> f32 {
+        self.depth[y * self.width + x]
+    }
+
+    pub fn clear_color(&mut self, color: Vec4) {
+        self.color.fill(color);
+    }
+
+    pub fn clear_depth(&mut self, depth: f32) {
+        self.depth.fill(depth);
+    }
+}
+
+/// A corner of a triangle, in model space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vertex {
+    pub position: Vec3,
+    pub color: Vec4,
+    pub normal: Vec3,
+    pub uv: Vec2,
+}
+
+impl Vertex {
+    /// a white vertex facing the positive z-axis
+    pub fn new(position: Vec3) -> Vertex {
+        Vertex { position, color: Vec4::ONE, normal: Vec3::Z, uv: Vec2::ZERO }
+    }
+
+    pub fn with_color(self, color: Vec4) -> Vertex {
+        Vertex { color, ..self }
+    }
+
+    pub fn with_normal(self, normal: Vec3) -> Vertex {
+        Vertex { normal, ..self }
+    }
+
+    pub fn with_uv(self, uv: Vec2) -> Vertex {
+        Vertex { uv, ..self }
+    }
+}
+
+/// What gets interpolated across a triangle, with the normal in world space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Varyings {
+    pub color: Vec4,
+    pub normal: Vec3,
+    pub uv: Vec2,
+}
+
+impl Varyings {
+    #[inline(always)]
+    pub(crate) fn scale(self, s: f32) -> Varyings {
+        Varyings { color: self.color * s, normal: self.normal * s, uv: self.uv * s }
+    }
+
+    #[inline(always)]
+    pub(crate) fn add(self, other: Varyings) -> Varyings {
+        Varyings { color: self.color + other.color, normal: self.normal + other.normal, uv: self.uv + other.uv }
+    }
+
+    #[inline(always)]
+    pub(crate) fn lerp(self, other: Varyings, t: f32) -> Varyings {
+        self.scale(1.0 - t).add(other.scale(t))
+    }
+}
+
+/// A pixel covered by a triangle, handed to the shader to colour
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fragment {
+    pub x: usize,
+    pub y: usize,
+    pub depth: f32,
+    /// interpolated with perspective correction
+    pub varyings: Varyings,
+    /// whether the triangle faces the camera
+    pub front_facing: bool,
+}
+
+/// Which triangles are thrown away before rasterizing, by their winding on screen.
+///
+/// front faces wind counterclockwise, looking at them with y up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CullMode {
+    None,
+    #[default]
+    Back,
+    Front,
+}
+
+/// Which fragments pass the depth test, against the depth already in the framebuffer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DepthTest {
+    /// closer than what's there, for depth running from 0 at the near plane
+    #[default]
+    Less,
+    /// closer than what's there, for reversed projections
+    Greater,
+    /// always, without writing depth
+    Always,
+}
+
+impl DepthTest {
+    #[inline(always)]
+    pub(crate) fn passes(self, depth: f32, stored: f32) -> bool {
+        match self {
+            DepthTest::Less => depth < stored,
+            DepthTest::Greater => depth > stored,
+            DepthTest::Always => true,
+        }
+    }
+}
+
+/// A software renderer drawing triangles into its framebuffer
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    pub framebuffer: Framebuffer,
+    /// world space to view space
+    pub view: Matrix<f32>,
+    /// view space to clip space, see [`Matrix::perspective`]
+    pub projection: Matrix<f32>,
+    pub cull: CullMode,
+    pub depth_test: DepthTest,
+}
+
+impl Renderer {
+    /// a renderer with identity view and projection matrices
+    pub fn new(width: usize, height: usize) -> Renderer {
+        Renderer {
+            framebuffer: Framebuffer::new(width, height),
+            view: Affine3::IDENTITY.to_matrix(),
+            projection: Affine3::IDENTITY.to_matrix(),
+            cull: CullMode::Back,
+            depth_test: DepthTest::Less,
+        }
+    }
+
+    pub fn with_camera(self, view: Matrix<f32>, projection: Matrix<f32>) -> Renderer {
+        assert!(view.is_4x4() && projection.is_4x4());
+        Renderer { view, projection, ..self }
+    }
+
+    /// clear the colour, and the depth to the far plane of the depth test
+    pub fn clear(&mut self, color: Vec4) {
+        self.framebuffer.clear_color(color);
+        self.framebuffer.clear_depth(if self.depth_test == DepthTest::Greater { 0.0 } else { 1.0 });
+    }
+
+    /// draw the triangles in `indices` with their interpolated vertex colours
+    pub fn draw(&mut self, vertices: &[Vertex], indices: &[[u32; 3]], model: &Matrix<f32>) {
+        self.draw_with(vertices, indices, model, |fragment| fragment.varyings.color);
+    }
+
+    /// draw the triangles in `indices`, coloured by `shader`.
+    ///
+    /// normals are turned by the model matrix as directions, so non-uniform scales skew them.
+    /// triangles are drawn in order, so with the same depth the first one drawn stays on top.
+    pub fn draw_with<S>(&mut self, vertices: &[Vertex], indices: &[[u32; 3]], model: &Matrix<f32>, shader: S)
+    where
+        S: Fn(&Fragment) -> Vec4 + Sync,
+    {
+        assert!(model.is_4x4());
+        let mvp = self.projection.mul_4x4(&self.view.mul_4x4(model));
+        let triangles = raster::setup(vertices, indices, model, &mvp, self.cull, &self.framebuffer);
+        raster::rasterize(&triangles, &mut self.framebuffer, self.depth_test, &shader);
+    }
+}
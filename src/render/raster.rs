@@ -0,0 +1,261 @@
+use rayon::prelude::*;
+use crate::math::matrix::Matrix;
+use crate::math::vec4::Vec4;
+use crate::render::{CullMode, DepthTest, Fragment, Framebuffer, Varyings, Vertex};
+
+/// bits of sub-pixel precision screen positions are snapped to
+const SUBPIXEL_BITS: u32 = 8;
+const SUBPIXEL: i64 = 1 << SUBPIXEL_BITS;
+/// the side of the square tiles the framebuffer is split into, in pixels
+pub(crate) const TILE: usize = 32;
+
+/// the planes of the clip volume, with points inside where `plane.dot(position) >= 0`
+const CLIP_PLANES: [Vec4; 6] = [
+    Vec4(1.0, 0.0, 0.0, 1.0),
+    Vec4(-1.0, 0.0, 0.0, 1.0),
+    Vec4(0.0, 1.0, 0.0, 1.0),
+    Vec4(0.0, -1.0, 0.0, 1.0),
+    Vec4(0.0, 0.0, 1.0, 0.0),
+    Vec4(0.0, 0.0, -1.0, 1.0),
+];
+
+/// A vertex in clip space, before the perspective divide
+#[derive(Clone, Copy, Debug)]
+struct ClipVertex {
+    position: Vec4,
+    varyings: Varyings,
+}
+
+/// A triangle ready to rasterize, wound so its edge functions are positive inside
+#[derive(Clone, Debug)]
+pub(crate) struct ScreenTriangle {
+    /// in fixed point pixels, with y down
+    x: [i64; 3],
+    y: [i64; 3],
+    /// twice the area, in fixed point
+    area: i64,
+    depth: [f32; 3],
+    inv_w: [f32; 3],
+    /// divided by w, so they interpolate linearly on screen
+    varyings: [Varyings; 3],
+    /// the pixels whose centers might be covered, inclusive
+    min: (usize, usize),
+    max: (usize, usize),
+    front_facing: bool,
+}
+
+/// cut `polygon` down to the part inside the clip volume, leaving fewer than 3 vertices if none is
+fn clip(polygon: &mut Vec<ClipVertex>, scratch: &mut Vec<ClipVertex>) {
+    for plane in CLIP_PLANES {
+        if polygon.iter().all(|v| plane.dot(v.position) >= 0.0) {
+            continue
+        }
+        scratch.clear();
+        for (i, &a) in polygon.iter().enumerate() {
+            let b = polygon[(i + 1) % polygon.len()];
+            let (da, db) = (plane.dot(a.position), plane.dot(b.position));
+            if da >= 0.0 {
+                scratch.push(a);
+            }
+            if (da >= 0.0) != (db >= 0.0) {
+                let t = da / (da - db);
+                scratch.push(ClipVertex {
+                    position: a.position.lerp(b.position, t),
+                    varyings: a.varyings.lerp(b.varyings, t),
+                });
+            }
+        }
+        std::mem::swap(polygon, scratch);
+        if polygon.len() < 3 {
+            return
+        }
+    }
+}
+
+/// twice the signed area of `a`, `b`, `p`, positive when `p` is on the right going from `a` to `b` with y down
+#[inline(always)]
+fn edge(ax: i64, ay: i64, bx: i64, by: i64, px: i64, py: i64) -> i64 {
+    (bx - ax) * (py - ay) - (by - ay) * (px - ax)
+}
+
+/// whether the edge from `a` to `b` is a top or left edge, which own the pixels exactly on them
+#[inline(always)]
+fn is_top_left(ax: i64, ay: i64, bx: i64, by: i64) -> bool {
+    (ay == by && bx > ax) || by < ay
+}
+
+/// the first and last pixel whose centers lie within `min..=max` fixed point, clamped to `0..size`
+fn pixel_span(min: i64, max: i64, size: usize) -> Option<(usize, usize)> {
+    let half = SUBPIXEL / 2;
+    let first = (min - half + SUBPIXEL - 1).div_euclid(SUBPIXEL).max(0);
+    let last = (max - half).div_euclid(SUBPIXEL).min(size as i64 - 1);
+    (first <= last).then_some((first as usize, last as usize))
+}
+
+/// transform, clip, cull and project the triangles, ready to rasterize in order
+pub(crate) fn setup(
+    vertices: &[Vertex],
+    indices: &[[u32; 3]],
+    model: &Matrix<f32>,
+    mvp: &Matrix<f32>,
+    cull: CullMode,
+    framebuffer: &Framebuffer,
+) -> Vec<ScreenTriangle> {
+    let (width, height) = (framebuffer.width as f32, framebuffer.height as f32);
+    let transformed: Vec<ClipVertex> = vertices.iter().map(|v| ClipVertex {
+        position: mvp * Vec4::from_point(v.position),
+        varyings: Varyings { color: v.color, normal: model.transform_vector3(v.normal), uv: v.uv },
+    }).collect();
+
+    let (mut polygon, mut scratch) = (Vec::with_capacity(9), Vec::with_capacity(9));
+    let mut triangles = Vec::with_capacity(indices.len());
+    for triangle in indices {
+        polygon.clear();
+        polygon.extend(triangle.iter().map(|&i| transformed[i as usize]));
+        clip(&mut polygon, &mut scratch);
+        if polygon.len() < 3 {
+            continue
+        }
+
+        let projected: Vec<_> = polygon.iter().map(|v| {
+            let inv_w = 1.0 / v.position.w;
+            let x = (v.position.x * inv_w * 0.5 + 0.5) * width;
+            let y = (0.5 - v.position.y * inv_w * 0.5) * height;
+            let fixed = |c: f32| (c * SUBPIXEL as f32).round() as i64;
+            (fixed(x), fixed(y), v.position.z * inv_w, inv_w, v.varyings.scale(inv_w))
+        }).collect();
+
+        // clipping keeps the polygon convex and flat, so a fan covers it
+        for i in 1..projected.len() - 1 {
+            let mut corners = [projected[0], projected[i], projected[i + 1]];
+            let [a, b, c] = corners;
+            let area = edge(a.0, a.1, b.0, b.1, c.0, c.1);
+            if area == 0 {
+                continue
+            }
+            // counterclockwise with y up is clockwise with y down, which has a negative area
+            let front_facing = area < 0;
+            match cull {
+                CullMode::Back if !front_facing => continue,
+                CullMode::Front if front_facing => continue,
+                _ => {}
+            }
+            if area < 0 {
+                corners.swap(1, 2);
+            }
+
+            let x = corners.map(|c| c.0);
+            let y = corners.map(|c| c.1);
+            let spans = (
+                pixel_span(*x.iter().min().unwrap(), *x.iter().max().unwrap(), framebuffer.width),
+                pixel_span(*y.iter().min().unwrap(), *y.iter().max().unwrap(), framebuffer.height),
+            );
+            let (Some((min_x, max_x)), Some((min_y, max_y))) = spans else { continue };
+            triangles.push(ScreenTriangle {
+                x,
+                y,
+                area: area.abs(),
+                depth: corners.map(|c| c.2),
+                inv_w: corners.map(|c| c.3),
+                varyings: corners.map(|c| c.4),
+                min: (min_x, min_y),
+                max: (max_x, max_y),
+                front_facing,
+            });
+        }
+    }
+    triangles
+}
+
+/// draw the part of `triangle` in the tile spanning `x0..=x1` of a band of rows starting at `band_y`
+#[allow(clippy::too_many_arguments)]
+fn rasterize_tile<S>(
+    triangle: &ScreenTriangle,
+    (x0, x1): (usize, usize),
+    (y0, y1): (usize, usize),
+    band_y: usize,
+    width: usize,
+    color: &mut [Vec4],
+    depth: &mut [f32],
+    test: DepthTest,
+    shader: &S,
+) where
+    S: Fn(&Fragment) -> Vec4 + Sync,
+{
+    let (x0, x1) = (x0.max(triangle.min.0), x1.min(triangle.max.0));
+    let (y0, y1) = (y0.max(triangle.min.1), y1.min(triangle.max.1));
+    if x0 > x1 || y0 > y1 {
+        return
+    }
+
+    let ScreenTriangle { x, y, .. } = *triangle;
+    // edge `i` is opposite corner `i`, so its function weights that corner
+    let edges = [(1, 2), (2, 0), (0, 1)];
+    let px = x0 as i64 * SUBPIXEL + SUBPIXEL / 2;
+    let py = y0 as i64 * SUBPIXEL + SUBPIXEL / 2;
+    let mut row = [0i64; 3];
+    let (mut step_x, mut step_y, mut bias) = ([0i64; 3], [0i64; 3], [0i64; 3]);
+    for (i, &(a, b)) in edges.iter().enumerate() {
+        row[i] = edge(x[a], y[a], x[b], y[b], px, py);
+        step_x[i] = -(y[b] - y[a]) * SUBPIXEL;
+        step_y[i] = (x[b] - x[a]) * SUBPIXEL;
+        bias[i] = if is_top_left(x[a], y[a], x[b], y[b]) { 0 } else { -1 };
+    }
+
+    let inv_area = 1.0 / triangle.area as f32;
+    for fy in y0..=y1 {
+        let mut w = row;
+        for fx in x0..=x1 {
+            if (0..3).all(|i| w[i] + bias[i] >= 0) {
+                let l = w.map(|w| w as f32 * inv_area);
+                let z = l[0] * triangle.depth[0] + l[1] * triangle.depth[1] + l[2] * triangle.depth[2];
+                let index = (fy - band_y) * width + fx;
+                if test.passes(z, depth[index]) {
+                    let inv_w = l[0] * triangle.inv_w[0] + l[1] * triangle.inv_w[1] + l[2] * triangle.inv_w[2];
+                    let [a, b, c] = triangle.varyings;
+                    let varyings = a.scale(l[0]).add(b.scale(l[1])).add(c.scale(l[2])).scale(1.0 / inv_w);
+                    let fragment = Fragment { x: fx, y: fy, depth: z, varyings, front_facing: triangle.front_facing };
+                    color[index] = shader(&fragment);
+                    if test != DepthTest::Always {
+                        depth[index] = z;
+                    }
+                }
+            }
+            for i in 0..3 {
+                w[i] += step_x[i];
+            }
+        }
+        for i in 0..3 {
+            row[i] += step_y[i];
+        }
+    }
+}
+
+/// bin the triangles into tiles, then draw each band of tiles in parallel, keeping the triangles in order
+pub(crate) fn rasterize<S>(triangles: &[ScreenTriangle], framebuffer: &mut Framebuffer, test: DepthTest, shader: &S)
+where
+    S: Fn(&Fragment) -> Vec4 + Sync,
+{
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let tiles_x = width.div_ceil(TILE);
+    let mut bins = vec![Vec::new(); tiles_x * height.div_ceil(TILE)];
+    for (i, triangle) in triangles.iter().enumerate() {
+        for ty in triangle.min.1 / TILE..=triangle.max.1 / TILE {
+            for tx in triangle.min.0 / TILE..=triangle.max.0 / TILE {
+                bins[ty * tiles_x + tx].push(i);
+            }
+        }
+    }
+
+    let bands = framebuffer.color.par_chunks_mut(width * TILE).zip(framebuffer.depth.par_chunks_mut(width * TILE));
+    bands.zip(bins.par_chunks(tiles_x)).enumerate().for_each(|(ty, ((color, depth), bins))| {
+        let band_y = ty * TILE;
+        let rows = (band_y, (band_y + TILE).min(height) - 1);
+        for (tx, bin) in bins.iter().enumerate() {
+            let columns = (tx * TILE, ((tx + 1) * TILE).min(width) - 1);
+            for &i in bin {
+                rasterize_tile(&triangles[i], columns, rows, band_y, width, color, depth, test, shader);
+            }
+        }
+    });
+}
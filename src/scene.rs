@@ -0,0 +1,367 @@
+use rayon::prelude::*;
+use crate::math::affine::Affine3;
+use crate::math::matrix::Matrix;
+use crate::math::quat::Quat;
+use crate::math::vec3::Vec3;
+
+/// A translation, rotation and scale, applied scale first and translation last
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { translation: Vec3::ZERO, rotation: Quat::IDENTITY, scale: Vec3::ONE };
+
+    #[inline(always)]
+    pub const fn from_translation(translation: Vec3) -> Transform {
+        Transform { translation, ..Transform::IDENTITY }
+    }
+
+    #[inline(always)]
+    pub const fn from_rotation(rotation: Quat) -> Transform {
+        Transform { rotation, ..Transform::IDENTITY }
+    }
+
+    #[inline(always)]
+    pub const fn from_scale(scale: Vec3) -> Transform {
+        Transform { scale, ..Transform::IDENTITY }
+    }
+
+    #[inline(always)]
+    pub fn to_affine(&self) -> Affine3 {
+        Affine3::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    /// the 4x4 homogeneous matrix for this transform
+    #[inline(always)]
+    pub fn to_matrix(&self) -> Matrix<f32> {
+        self.to_affine().to_matrix()
+    }
+
+    /// split an affine transform up, losing any shear, see [`Affine3::to_scale_rotation_translation`]
+    pub fn from_affine(affine: &Affine3) -> Transform {
+        let (scale, rotation, translation) = affine.to_scale_rotation_translation();
+        Transform { translation, rotation, scale }
+    }
+
+    /// the transform for a 4x4 homogeneous matrix, losing any shear
+    pub fn from_matrix(m: &Matrix<f32>) -> Transform {
+        Transform::from_affine(&Affine3::from_matrix(m))
+    }
+}
+
+/// A handle to a node in a [`SceneGraph`].
+///
+/// handles of removed nodes go stale, and their slot is reused with a new generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+impl NodeId {
+    #[inline(always)]
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    local: Transform,
+    world: Matrix<f32>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    /// whether the local transform changed since the last propagation
+    dirty: bool,
+    generation: u32,
+    alive: bool,
+}
+
+/// A forest of nodes, each with a transform relative to its parent.
+///
+/// changing a node's local transform marks it dirty, and [`SceneGraph::propagate`] then
+/// recomputes the world matrices of dirty nodes and everything below them, multiplying
+/// down from the roots with [`Matrix::mul_4x4`]. world matrices are as of the last propagation.
+#[derive(Clone, Debug, Default)]
+pub struct SceneGraph {
+    nodes: Vec<Node>,
+    free: Vec<u32>,
+    roots: Vec<NodeId>,
+    len: usize,
+}
+
+impl SceneGraph {
+    pub fn new() -> SceneGraph {
+        SceneGraph::default()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, node: NodeId) -> bool {
+        self.node(node).is_some()
+    }
+
+    #[inline(always)]
+    fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(id.index()).filter(|n| n.alive && n.generation == id.generation)
+    }
+
+    #[inline(always)]
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.nodes.get_mut(id.index()).filter(|n| n.alive && n.generation == id.generation)
+    }
+
+    /// add a node under `parent`, or as a root if that's none or gone
+    pub fn insert(&mut self, local: Transform, parent: Option<NodeId>) -> NodeId {
+        let parent = parent.filter(|p| self.contains(*p));
+        let node = Node {
+            local,
+            world: local.to_matrix(),
+            parent,
+            children: Vec::new(),
+            dirty: true,
+            generation: 0,
+            alive: true,
+        };
+        let id = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.nodes[index as usize];
+                let generation = slot.generation + 1;
+                *slot = Node { generation, ..node };
+                NodeId { index, generation }
+            }
+            None => {
+                self.nodes.push(node);
+                NodeId { index: self.nodes.len() as u32 - 1, generation: 0 }
+            }
+        };
+        match parent {
+            Some(parent) => self.node_mut(parent).unwrap().children.push(id),
+            None => self.roots.push(id),
+        }
+        self.len += 1;
+        id
+    }
+
+    /// remove a node and everything below it, returning false if it was already gone
+    pub fn remove(&mut self, node: NodeId) -> bool {
+        let Some(parent) = self.node(node).map(|n| n.parent) else { return false };
+        self.detach(node, parent);
+        let removed: Vec<NodeId> = self.descendants(node).collect();
+        for id in removed {
+            let slot = &mut self.nodes[id.index()];
+            slot.alive = false;
+            slot.children = Vec::new();
+            self.free.push(id.index);
+            self.len -= 1;
+        }
+        true
+    }
+
+    /// take `node` out of its parent's children, or the roots
+    fn detach(&mut self, node: NodeId, parent: Option<NodeId>) {
+        let siblings = match parent {
+            Some(parent) => &mut self.node_mut(parent).unwrap().children,
+            None => &mut self.roots,
+        };
+        siblings.retain(|n| *n != node);
+    }
+
+    #[inline(always)]
+    pub fn local(&self, node: NodeId) -> Option<&Transform> {
+        self.node(node).map(|n| &n.local)
+    }
+
+    /// the local transform for changing, marking the node dirty
+    #[inline(always)]
+    pub fn local_mut(&mut self, node: NodeId) -> Option<&mut Transform> {
+        let node = self.node_mut(node)?;
+        node.dirty = true;
+        Some(&mut node.local)
+    }
+
+    /// the node's world matrix as of the last propagation
+    #[inline(always)]
+    pub fn world(&self, node: NodeId) -> Option<&Matrix<f32>> {
+        self.node(node).map(|n| &n.world)
+    }
+
+    /// the node's world position as of the last propagation
+    pub fn world_translation(&self, node: NodeId) -> Option<Vec3> {
+        self.world(node).map(|m| Vec3(m.data[3], m.data[7], m.data[11]))
+    }
+
+    /// the node's world matrix from its current local transform and its ancestors',
+    /// without propagating
+    pub fn compute_world(&self, node: NodeId) -> Option<Matrix<f32>> {
+        let mut world = self.node(node)?.local.to_matrix();
+        for ancestor in self.ancestors(node) {
+            world = self.nodes[ancestor.index()].local.to_matrix().mul_4x4(&world);
+        }
+        Some(world)
+    }
+
+    #[inline(always)]
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.node(node)?.parent
+    }
+
+    #[inline(always)]
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        self.node(node).map_or(&[], |n| &n.children)
+    }
+
+    #[inline(always)]
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    /// move `node` under `parent`, or make it a root with none, changing its local transform
+    /// so it stays where it is in the world. any shear it would need is lost.
+    ///
+    /// returns false if either node is gone or `parent` is `node` or below it.
+    pub fn set_parent(&mut self, node: NodeId, parent: Option<NodeId>) -> bool {
+        let Some(old) = self.node(node).map(|n| n.parent) else { return false };
+        if let Some(parent) = parent {
+            if !self.contains(parent) || parent == node || self.ancestors(parent).any(|a| a == node) {
+                return false
+            }
+        }
+        if old == parent {
+            return true
+        }
+
+        let world = Affine3::from_matrix(&self.compute_world(node).unwrap());
+        let local = match parent {
+            Some(parent) => Affine3::from_matrix(&self.compute_world(parent).unwrap()).inverse() * world,
+            None => world,
+        };
+
+        self.detach(node, old);
+        match parent {
+            Some(parent) => self.node_mut(parent).unwrap().children.push(node),
+            None => self.roots.push(node),
+        }
+        let node = self.node_mut(node).unwrap();
+        node.parent = parent;
+        node.local = Transform::from_affine(&local);
+        node.dirty = true;
+        true
+    }
+
+    /// the parent of `node`, its parent, and so on up to the root
+    pub fn ancestors(&self, node: NodeId) -> Ancestors<'_> {
+        Ancestors { graph: self, next: self.parent(node) }
+    }
+
+    /// `node` and everything below it, depth first with parents before children
+    pub fn descendants(&self, node: NodeId) -> Descendants<'_> {
+        let stack = if self.contains(node) { vec![node] } else { Vec::new() };
+        Descendants { graph: self, stack }
+    }
+
+    /// every node, depth first from each root in turn with parents before children
+    pub fn iter(&self) -> Descendants<'_> {
+        Descendants { graph: self, stack: self.roots.iter().rev().copied().collect() }
+    }
+
+    /// the new world matrices below `node`, whose parent's world matrix is `parent`,
+    /// for each node that's dirty or under one that is
+    fn updates(&self, node: NodeId, parent: &Matrix<f32>, parent_dirty: bool, out: &mut Vec<(u32, Matrix<f32>)>) {
+        let n = &self.nodes[node.index()];
+        let dirty = parent_dirty || n.dirty;
+        let world = if dirty {
+            let world = parent.mul_4x4(&n.local.to_matrix());
+            out.push((node.index, world.clone()));
+            world
+        } else {
+            n.world.clone()
+        };
+        for child in &n.children {
+            self.updates(*child, &world, dirty, out);
+        }
+    }
+
+    fn apply(&mut self, updates: Vec<(u32, Matrix<f32>)>) {
+        for (index, world) in updates {
+            let node = &mut self.nodes[index as usize];
+            node.world = world;
+            node.dirty = false;
+        }
+    }
+
+    /// recompute the world matrices of dirty nodes and everything below them
+    pub fn propagate(&mut self) {
+        let identity = Affine3::IDENTITY.to_matrix();
+        let mut updates = Vec::new();
+        for root in &self.roots {
+            self.updates(*root, &identity, false, &mut updates);
+        }
+        self.apply(updates);
+    }
+
+    /// [`SceneGraph::propagate`] with each root's subtree on its own rayon task
+    pub fn propagate_parallel(&mut self) {
+        let identity = Affine3::IDENTITY.to_matrix();
+        let updates: Vec<_> = self.roots.par_iter()
+            .map(|root| {
+                let mut updates = Vec::new();
+                self.updates(*root, &identity, false, &mut updates);
+                updates
+            })
+            .flatten()
+            .collect();
+        self.apply(updates);
+    }
+}
+
+/// The ancestors of a node, see [`SceneGraph::ancestors`]
+pub struct Ancestors<'a> {
+    graph: &'a SceneGraph,
+    next: Option<NodeId>,
+}
+
+impl Iterator for Ancestors<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let node = self.next?;
+        self.next = self.graph.parent(node);
+        Some(node)
+    }
+}
+
+/// A depth first walk over nodes, see [`SceneGraph::descendants`]
+pub struct Descendants<'a> {
+    graph: &'a SceneGraph,
+    stack: Vec<NodeId>,
+}
+
+impl Iterator for Descendants<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let node = self.stack.pop()?;
+        self.stack.extend(self.graph.children(node).iter().rev());
+        Some(node)
+    }
+}
@@ -0,0 +1,81 @@
+use std::f32::consts::FRAC_PI_2;
+use crate::math::affine::{Affine2, Affine3};
+use crate::math::quat::Quat;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+fn close2(a: Vec2, b: Vec2) -> bool {
+    (a - b).magnitude() < 1e-5
+}
+
+fn close3(a: Vec3, b: Vec3) -> bool {
+    (a - b).magnitude() < 1e-5
+}
+
+#[test]
+fn test_affine2() {
+    let t = Affine2::from_translation(Vec2(1.0, 2.0)) * Affine2::from_angle(FRAC_PI_2);
+
+    assert!(close2(t.transform_point2(Vec2::X), Vec2(1.0, 3.0)));
+    assert!(close2(t.transform_vector2(Vec2::X), Vec2::Y));
+    assert!(close2(t.inverse().transform_point2(Vec2(1.0, 3.0)), Vec2::X));
+    assert!(close2(t.inverse_rigid().transform_point2(Vec2(1.0, 3.0)), Vec2::X));
+
+    let m = t.to_matrix();
+    assert!(close2(m.transform_point2(Vec2(3.0, -1.0)), t.transform_point2(Vec2(3.0, -1.0))));
+    assert_eq!(Affine2::from_matrix(&m), t);
+
+    let shear = Affine2::from_shear(2.0, 0.0);
+    assert_eq!(shear.transform_point2(Vec2(1.0, 1.0)), Vec2(3.0, 1.0));
+    assert!(close2(shear.inverse().transform_point2(Vec2(3.0, 1.0)), Vec2(1.0, 1.0)));
+}
+
+#[test]
+fn test_affine3_inverse() {
+    let rotation = Quat::from_axis_angle(Vec3(1.0, -1.0, 2.0), 1.1);
+    let t = Affine3::from_scale_rotation_translation(Vec3(2.0, 0.5, 3.0), rotation, Vec3(1.0, 2.0, 3.0))
+        * Affine3::from_shear(0.5, 0.0, 0.0, 0.25, 0.0, 0.0);
+    let p = Vec3(0.3, -4.0, 1.5);
+
+    assert!(close3(t.inverse().transform_point3(t.transform_point3(p)), p));
+    assert!(close3((t * t.inverse()).transform_point3(p), p));
+
+    let rigid = Affine3::from_translation(Vec3(5.0, 0.0, -1.0)) * Affine3::from_quat(rotation);
+    assert!(close3(rigid.inverse_rigid().transform_point3(rigid.transform_point3(p)), p));
+
+    // the matrix form agrees with the affine form
+    let m = t.to_matrix();
+    assert!(close3(m.transform_point3(p), t.transform_point3(p)));
+    assert!(close3(m.transform_vector3(p), t.transform_vector3(p)));
+    assert_eq!(Affine3::from_matrix(&m), t);
+}
+
+#[test]
+fn test_decompose() {
+    let rotation = Quat::from_axis_angle(Vec3(0.0, 1.0, 1.0), 0.8);
+    let t = Affine3::from_scale_rotation_translation(Vec3(-2.0, 1.5, 0.5), rotation, Vec3(4.0, 5.0, 6.0));
+    let (scale, r, translation) = t.to_scale_rotation_translation();
+
+    assert!(close3(scale, Vec3(-2.0, 1.5, 0.5)));
+    assert!(r.dot(rotation).abs() > 1.0 - 1e-5);
+    assert_eq!(translation, Vec3(4.0, 5.0, 6.0));
+
+    let t = Affine2::from_scale_angle_translation(Vec2(3.0, -1.0), 0.6, Vec2(1.0, 1.0));
+    let (scale, angle, translation) = t.to_scale_angle_translation();
+    let back = Affine2::from_scale_angle_translation(scale, angle, translation);
+    assert!(close2(back.x_axis, t.x_axis) && close2(back.y_axis, t.y_axis));
+    assert_eq!(translation, Vec2(1.0, 1.0));
+}
+
+#[test]
+fn test_look_at() {
+    let eye = Vec3(0.0, 2.0, 5.0);
+    let view = Affine3::look_at(eye, Vec3(0.0, 2.0, 0.0), Vec3::Y);
+
+    // the camera sits at the origin, looking down -z with y up
+    assert!(close3(view.transform_point3(eye), Vec3::ZERO));
+    assert!(close3(view.transform_point3(Vec3(0.0, 2.0, 0.0)), Vec3(0.0, 0.0, -5.0)));
+    assert!(close3(view.transform_vector3(Vec3::Y), Vec3::Y));
+    assert!(close3(view.transform_vector3(Vec3::X), Vec3::X));
+    assert!((view.determinant() - 1.0).abs() < 1e-5);
+}
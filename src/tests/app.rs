@@ -0,0 +1,63 @@
+use crate::app::{App, Exit, Runner, Time};
+use crate::ecs::schedule::System;
+
+#[derive(Debug, Default, PartialEq)]
+struct Counter {
+    fixed: u32,
+    frames: u32,
+}
+
+fn counting(step: f64, max_steps: u32) -> App {
+    let mut app = App::new().with_step(step).with_max_steps(max_steps);
+    app.world.insert_resource(Counter::default());
+    app.add_fixed_system(System::new("fixed", |cx| cx.resource_mut::<Counter>().unwrap().fixed += 1).write_resource::<Counter>());
+    app.add_system(System::new("frame", |cx| cx.resource_mut::<Counter>().unwrap().frames += 1).write_resource::<Counter>());
+    app
+}
+
+#[test]
+fn test_accumulator() {
+    let mut app = counting(0.25, 8);
+    assert_eq!(app.frame(0.1), 0);
+    assert_eq!(app.frame(0.2), 1);
+    assert!((app.time().alpha - 0.2).abs() < 1e-5);
+    assert_eq!(app.frame(0.6), 2);
+    assert!((app.time().alpha - 0.6).abs() < 1e-5);
+
+    let time = app.time();
+    assert_eq!((time.ticks, time.frames), (3, 3));
+    assert_eq!(time.elapsed, 0.75);
+    assert!((time.delta - 0.6).abs() < 1e-6);
+    assert_eq!(*app.world.resource::<Counter>().unwrap(), Counter { fixed: 3, frames: 3 });
+}
+
+#[test]
+fn test_max_steps() {
+    let mut app = counting(0.125, 4);
+    // a long stall only catches up by the clamp, keeping the fraction of a step left over
+    assert_eq!(app.frame(10.0625), 4);
+    assert!((app.time().alpha - 0.5).abs() < 1e-5);
+    assert_eq!(app.frame(0.0625), 1);
+    assert_eq!(app.time().alpha, 0.0);
+    assert_eq!(app.time().ticks, 5);
+}
+
+#[test]
+fn test_headless() {
+    let mut app = counting(1.0 / 60.0, 5);
+    app.run(Runner::Headless { ticks: 600 });
+    let time = app.time();
+    assert_eq!((time.ticks, time.frames), (600, 600));
+    assert!((time.elapsed - 10.0).abs() < 1e-9);
+    assert_eq!(time.alpha, 0.0);
+
+    // exit stops the loop after the frame it was inserted in
+    app.add_fixed_system(System::new("quit", |cx| {
+        if cx.resource::<Time>().unwrap().ticks == 609 {
+            cx.commands().push(|world| { world.insert_resource(Exit); });
+        }
+    }).read_resource::<Time>());
+    app.run(Runner::Headless { ticks: 100 });
+    assert!(app.exiting());
+    assert_eq!(*app.world.resource::<Counter>().unwrap(), Counter { fixed: 610, frames: 610 });
+}
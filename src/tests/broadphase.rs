@@ -0,0 +1,151 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::broadphase::{Handle, SpatialHash, SweepAndPrune};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+fn random_box3(rng: &mut StdRng) -> Aabb3 {
+    let center = Vec3(rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0));
+    let half = Vec3(rng.gen_range(0.1..2.0), rng.gen_range(0.1..2.0), rng.gen_range(0.1..2.0));
+    Aabb3::from_center_half_extents(center, half)
+}
+
+fn random_box2(rng: &mut StdRng) -> Aabb2 {
+    let center = Vec2(rng.gen_range(-30.0..30.0), rng.gen_range(-30.0..30.0));
+    let half = Vec2(rng.gen_range(0.1..1.5), rng.gen_range(0.1..1.5));
+    Aabb2::from_center_half_extents(center, half)
+}
+
+fn brute_force<B: Copy>(objects: &[(Handle, B)], overlaps: impl Fn(&B, &B) -> bool) -> Vec<(Handle, Handle)> {
+    let mut pairs = Vec::new();
+    for (i, (a, x)) in objects.iter().enumerate() {
+        for (b, y) in &objects[i + 1..] {
+            if overlaps(x, y) {
+                pairs.push(if a < b { (*a, *b) } else { (*b, *a) });
+            }
+        }
+    }
+    pairs.sort_unstable();
+    pairs
+}
+
+#[test]
+fn test_pairs_3d() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut hash = SpatialHash::new(3.0);
+    let mut sap = SweepAndPrune::new();
+    let mut objects = Vec::new();
+    for _ in 0..3000 {
+        let aabb = random_box3(&mut rng);
+        let handle = hash.insert(aabb);
+        assert_eq!(sap.insert(aabb), handle);
+        objects.push((handle, aabb));
+    }
+
+    let expected = brute_force(&objects, |a, b| a.intersects_aabb(b));
+    assert!(!expected.is_empty());
+    assert_eq!(hash.pairs(), expected);
+    assert_eq!(hash.pairs_parallel(), expected);
+    assert_eq!(sap.pairs(), expected);
+    assert_eq!(sap.pairs_parallel(), expected);
+}
+
+#[test]
+fn test_update_remove() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let mut hash = SpatialHash::new(2.0);
+    let mut sap = SweepAndPrune::new();
+    let mut objects: Vec<(Handle, Aabb2)> = (0..1500).map(|_| {
+        let aabb = random_box2(&mut rng);
+        let handle = hash.insert(aabb);
+        sap.insert(aabb);
+        (handle, aabb)
+    }).collect();
+
+    for frame in 0..5 {
+        // nudge everything a little, drop a few and add a few in their place
+        for (handle, aabb) in objects.iter_mut() {
+            let step = Vec2(rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5));
+            *aabb = Aabb2::new(aabb.min + step, aabb.max + step);
+            assert!(hash.update(*handle, *aabb) && sap.update(*handle, *aabb));
+        }
+        for _ in 0..50 {
+            let (handle, aabb) = objects.swap_remove(rng.gen_range(0..objects.len()));
+            assert_eq!(hash.remove(handle), Some(aabb));
+            assert_eq!(sap.remove(handle), Some(aabb));
+            assert!(hash.get(handle).is_none() && !sap.update(handle, aabb));
+        }
+        for _ in 0..40 {
+            let aabb = random_box2(&mut rng);
+            let handle = hash.insert(aabb);
+            assert_eq!(sap.insert(aabb), handle);
+            objects.push((handle, aabb));
+        }
+
+        let expected = brute_force(&objects, |a, b| a.intersects_aabb(b));
+        assert_eq!(hash.pairs(), expected, "frame {frame}");
+        assert_eq!(sap.pairs(), expected, "frame {frame}");
+        assert_eq!(hash.len(), objects.len());
+        assert_eq!(sap.len(), objects.len());
+    }
+}
+
+#[test]
+fn test_stale_handles() {
+    let mut hash = SpatialHash::new(1.0);
+    let a = hash.insert(Aabb2::new(Vec2::ZERO, Vec2::ONE));
+    assert!(hash.remove(a).is_some());
+    assert!(hash.remove(a).is_none());
+
+    // the slot is reused, but the old handle doesn't reach the new object
+    let b = hash.insert(Aabb2::new(Vec2(5.0, 5.0), Vec2(6.0, 6.0)));
+    assert_eq!(a.index(), b.index());
+    assert_ne!(a, b);
+    assert!(hash.get(a).is_none() && !hash.update(a, Aabb2::new(Vec2::ZERO, Vec2::ONE)));
+    assert!(hash.get(b).is_some());
+}
+
+#[test]
+fn test_query() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut hash = SpatialHash::new(4.0);
+    let mut sap = SweepAndPrune::new();
+    let mut objects = Vec::new();
+    for _ in 0..2000 {
+        let aabb = random_box3(&mut rng);
+        objects.push((hash.insert(aabb), aabb));
+        sap.insert(aabb);
+    }
+
+    for _ in 0..50 {
+        let region = Aabb3::from_center_half_extents(random_box3(&mut rng).center(), Vec3::splat(8.0));
+        let mut expected: Vec<Handle> = objects.iter()
+            .filter(|(_, aabb)| aabb.intersects_aabb(&region))
+            .map(|(h, _)| *h)
+            .collect();
+        expected.sort_unstable();
+
+        let mut found = Vec::new();
+        hash.query(&region, |h| found.push(h));
+        found.sort_unstable();
+        assert_eq!(found, expected);
+
+        let mut found = Vec::new();
+        sap.query(&region, |h| found.push(h));
+        found.sort_unstable();
+        assert_eq!(found, expected);
+    }
+}
+
+#[test]
+fn test_sweep_axis() {
+    // objects spread along z get swept along z
+    let mut sap = SweepAndPrune::new();
+    for i in 0..100 {
+        let center = Vec3(0.0, (i % 2) as f32, i as f32 * 1.5);
+        sap.insert(Aabb3::from_center_half_extents(center, Vec3::ONE));
+    }
+    assert_eq!(sap.pairs().len(), 99);
+    assert_eq!(sap.axis(), 2);
+}
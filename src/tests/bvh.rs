@@ -0,0 +1,140 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::geometry::aabb::Aabb3;
+use crate::geometry::bvh::Bvh;
+use crate::geometry::ray::Ray3;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::triangle::Triangle3;
+use crate::geometry::EPSILON;
+use crate::math::vec3::Vec3;
+
+fn random_vec(rng: &mut StdRng, range: f32) -> Vec3 {
+    Vec3(rng.gen_range(-range..range), rng.gen_range(-range..range), rng.gen_range(-range..range))
+}
+
+fn random_triangles(count: usize, seed: u64) -> Vec<Triangle3> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count).map(|_| {
+        let center = random_vec(&mut rng, 50.0);
+        Triangle3::new(
+            center + random_vec(&mut rng, 1.0),
+            center + random_vec(&mut rng, 1.0),
+            center + random_vec(&mut rng, 1.0),
+        )
+    }).collect()
+}
+
+fn brute_force_ray(triangles: &[Triangle3], ray: &Ray3) -> Option<(usize, f32)> {
+    triangles.iter().enumerate()
+        .filter_map(|(i, tri)| ray.intersect_triangle(tri, EPSILON).map(|h| (i, h.t)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+#[test]
+fn test_ray_cast() {
+    let triangles = random_triangles(2000, 1);
+    let bvh = Bvh::build(&triangles);
+    let mut rng = StdRng::seed_from_u64(2);
+
+    for _ in 0..200 {
+        let ray = Ray3::new(random_vec(&mut rng, 60.0), random_vec(&mut rng, 1.0));
+        let hit = |i: usize| ray.intersect_triangle(&triangles[i], EPSILON);
+
+        let closest = bvh.ray_cast(&ray, f32::INFINITY, hit);
+        assert_eq!(closest.map(|(i, h)| (i, h.t)), brute_force_ray(&triangles, &ray));
+
+        let any = bvh.ray_cast_any(&ray, f32::INFINITY, hit);
+        assert_eq!(any.is_some(), closest.is_some());
+        if let Some(i) = any {
+            assert!(hit(i).is_some());
+        }
+
+        // a max distance short of the closest hit finds nothing
+        if let Some((_, h)) = closest {
+            assert!(bvh.ray_cast(&ray, h.t * 0.5, hit).is_none());
+        }
+    }
+}
+
+#[test]
+fn test_overlap() {
+    let triangles = random_triangles(1000, 3);
+    let bvh = Bvh::build(&triangles);
+    let query = Aabb3::new(Vec3(-10.0, -10.0, -10.0), Vec3(15.0, 5.0, 20.0));
+    let sphere = Sphere::new(Vec3(5.0, -5.0, 0.0), 20.0);
+
+    let mut found = Vec::new();
+    bvh.query_aabb(&query, |i| found.push(i));
+    found.sort();
+    let expected: Vec<usize> = (0..triangles.len()).filter(|&i| triangles[i].aabb().intersects_aabb(&query)).collect();
+    assert_eq!(found, expected);
+
+    let mut found = Vec::new();
+    bvh.query_sphere(&sphere, |i| found.push(i));
+    found.sort();
+    let expected: Vec<usize> = (0..triangles.len()).filter(|&i| sphere.intersects_aabb(&triangles[i].aabb())).collect();
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn test_nearest() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let points: Vec<Vec3> = (0..5000).map(|_| random_vec(&mut rng, 100.0)).collect();
+    let bvh = Bvh::build(&points);
+
+    for _ in 0..100 {
+        let query = random_vec(&mut rng, 120.0);
+        let (index, distance) = bvh.nearest(query, |i| points[i].distance(query)).unwrap();
+        let expected = points.iter().map(|p| p.distance(query)).fold(f32::INFINITY, f32::min);
+        assert_eq!(distance, expected);
+        assert_eq!(points[index].distance(query), expected);
+    }
+
+    assert!(Bvh::build::<Vec3>(&[]).nearest(Vec3::ZERO, |_| 0.0).is_none());
+}
+
+#[test]
+fn test_refit_and_update() {
+    let mut triangles = random_triangles(500, 5);
+    let mut bvh = Bvh::build(&triangles);
+    let offset = Vec3(0.0, 200.0, 0.0);
+
+    // move one triangle far away and grow the path to it
+    let moved = 17;
+    let t = &mut triangles[moved];
+    *t = Triangle3::new(t.a + offset, t.b + offset, t.c + offset);
+    bvh.update(moved, triangles[moved].aabb());
+    assert!(bvh.bounds().contains_aabb(&triangles[moved].aabb()));
+
+    let ray = Ray3::new(triangles[moved].centroid() + Vec3(0.0, 0.0, 10.0), Vec3::NEG_Z);
+    let hit = |i: usize| ray.intersect_triangle(&triangles[i], EPSILON);
+    assert_eq!(bvh.ray_cast(&ray, f32::INFINITY, hit).map(|(i, _)| i), Some(moved));
+
+    // move everything and refit
+    for t in triangles.iter_mut() {
+        *t = Triangle3::new(t.a * 2.0, t.b * 2.0, t.c * 2.0);
+    }
+    bvh.refit(&triangles);
+    assert_eq!(bvh.bounds(), triangles.iter().fold(Aabb3::EMPTY, |b, t| b.union(t.aabb())));
+
+    let ray = Ray3::new(Vec3(-200.0, 1.0, 2.0), Vec3(1.0, 0.01, 0.02));
+    let hit = |i: usize| ray.intersect_triangle(&triangles[i], EPSILON);
+    assert_eq!(bvh.ray_cast(&ray, f32::INFINITY, hit).map(|(i, h)| (i, h.t)), brute_force_ray(&triangles, &ray));
+}
+
+#[test]
+fn test_parallel_build() {
+    let triangles = random_triangles(20000, 6);
+    let serial = Bvh::build(&triangles);
+    let parallel = Bvh::build_parallel(&triangles);
+    let mut rng = StdRng::seed_from_u64(7);
+
+    assert_eq!(serial.bounds(), parallel.bounds());
+    assert_eq!(parallel.len(), triangles.len());
+    for _ in 0..50 {
+        let ray = Ray3::new(random_vec(&mut rng, 60.0), random_vec(&mut rng, 1.0));
+        let hit = |i: usize| ray.intersect_triangle(&triangles[i], EPSILON);
+        let a = serial.ray_cast(&ray, f32::INFINITY, hit).map(|(i, h)| (i, h.t));
+        assert_eq!(a, parallel.ray_cast(&ray, f32::INFINITY, hit).map(|(i, h)| (i, h.t)));
+    }
+}
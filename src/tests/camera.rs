@@ -0,0 +1,195 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_3};
+use crate::camera::{Camera, CameraShake, FlyController, Follow2d, OrbitController};
+use crate::geometry::aabb::Aabb3;
+use crate::geometry::frustum::{Containment, Frustum};
+use crate::geometry::sphere::Sphere;
+use crate::math::affine::Affine3;
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+const VIEWPORT: Vec2 = Vec2(320.0, 200.0);
+
+fn close3(a: Vec3, b: Vec3, tolerance: f32) -> bool {
+    (a - b).magnitude() < tolerance
+}
+
+#[test]
+fn test_matrices_and_frustum() {
+    let eye = Vec3(0.0, 2.0, 10.0);
+    let camera = Camera::perspective(FRAC_PI_3, 0.5, 100.0, VIEWPORT).with_position(eye).looking_at(Vec3::ZERO, Vec3::Y);
+    let look_at = Affine3::look_at(eye, Vec3::ZERO, Vec3::Y).to_matrix();
+    for (a, b) in camera.view_matrix().data.iter().zip(&look_at.data) {
+        assert!((a - b).abs() < 1e-5);
+    }
+    assert!(close3(camera.forward(), -eye.normalize(), 1e-5));
+
+    let frustum = camera.frustum();
+    assert_eq!(frustum.planes.len(), 6);
+    assert_eq!(frustum.classify_sphere(&Sphere::new(Vec3::ZERO, 1.0)), Containment::Inside);
+    assert_eq!(frustum.classify_sphere(&Sphere::new(Vec3(0.0, 2.0, 12.0), 1.0)), Containment::Outside);
+    assert_eq!(frustum.classify_sphere(&Sphere::new(Vec3(0.0, 0.0, -95.0), 1.0)), Containment::Outside);
+    assert_eq!(frustum.classify_sphere(&Sphere::new(Vec3(0.0, 2.0, 9.5), 0.2)), Containment::Intersecting);
+    assert!(!frustum.intersects_sphere(&Sphere::new(Vec3(30.0, 0.0, 0.0), 1.0)));
+    assert!(frustum.contains_point(Vec3(0.0, 0.0, -50.0)));
+
+    // the box pokes out past the right edge of the view, and then sits just beyond it
+    let edge = camera.screen_to_world(Vec2(VIEWPORT.x, 100.0), camera.world_to_screen(Vec3::ZERO).unwrap().z);
+    let across = Aabb3::from_center_half_extents(edge, Vec3::splat(0.5));
+    assert_eq!(frustum.classify_aabb(&across), Containment::Intersecting);
+    let beyond = Aabb3::from_center_half_extents(edge + camera.right() * 2.0, Vec3::splat(0.5));
+    assert!(!frustum.intersects_aabb(&beyond));
+
+    // an orthographic camera's frustum is a box, and one at infinity loses its far plane
+    let ortho = Camera::orthographic(10.0, 1.0, 20.0, Vec2(100.0, 100.0));
+    let frustum = ortho.frustum();
+    assert!(frustum.contains_point(Vec3(4.9, -4.9, -19.9)));
+    assert!(!frustum.contains_point(Vec3(5.1, 0.0, -10.0)) && !frustum.contains_point(Vec3(0.0, 0.0, -0.9)));
+    let infinite = Frustum::from_matrix(&Matrix::perspective_infinite(FRAC_PI_2, 1.0, 0.1));
+    assert_eq!(infinite.planes.len(), 5);
+    assert!(infinite.contains_point(Vec3(0.0, 0.0, -1e6)));
+}
+
+#[test]
+fn test_screen_and_world() {
+    let perspective = Camera::perspective(FRAC_PI_3, 0.5, 100.0, VIEWPORT)
+        .with_position(Vec3(3.0, 1.0, -2.0))
+        .looking_at(Vec3(0.0, 0.0, -20.0), Vec3::Y);
+    let orthographic = Camera::orthographic(8.0, -5.0, 30.0, VIEWPORT)
+        .with_position(Vec3(1.0, 5.0, 0.0))
+        .looking_at(Vec3(1.0, 0.0, -3.0), Vec3::Y);
+    for camera in [perspective, orthographic] {
+        for pixel in [Vec2(0.0, 0.0), Vec2(160.0, 100.0), Vec2(17.5, 190.25), Vec2(320.0, 3.0)] {
+            for depth in [0.0, 0.3, 0.9, 1.0] {
+                let world = camera.screen_to_world(pixel, depth);
+                let screen = camera.world_to_screen(world).unwrap();
+                assert!((screen.x - pixel.x).abs() < 0.01 && (screen.y - pixel.y).abs() < 0.01, "{screen:?}");
+                assert!((screen.z - depth).abs() < 1e-3, "{} != {depth}", screen.z);
+            }
+        }
+    }
+
+    // the middle of the screen at the near plane is straight ahead
+    let center = perspective.screen_to_world(VIEWPORT * 0.5, 0.0);
+    assert!(close3(center, perspective.position + perspective.forward() * 0.5, 1e-4));
+    assert!(perspective.world_to_screen(perspective.position - perspective.forward()).is_none());
+    // up in the world is up on the screen, which is towards smaller y
+    let above = perspective.world_to_screen(center + perspective.forward() + Vec3::Y).unwrap();
+    assert!(above.y < 100.0);
+}
+
+#[test]
+fn test_picking() {
+    let camera = Camera::perspective(FRAC_PI_2, 0.1, 100.0, VIEWPORT)
+        .with_position(Vec3(0.0, 5.0, 5.0))
+        .looking_at(Vec3::ZERO, Vec3::Y);
+    let spheres = [Vec3(-3.0, 0.0, 0.0), Vec3(0.0, 0.0, -4.0), Vec3(2.0, 1.0, 1.0)].map(|c| Sphere::new(c, 0.5));
+    for (i, sphere) in spheres.iter().enumerate() {
+        let pixel = camera.world_to_screen(sphere.center).unwrap();
+        let ray = camera.pick_ray(Vec2(pixel.x, pixel.y));
+        let nearest = spheres.iter().enumerate()
+            .filter_map(|(j, s)| ray.intersect_sphere(s).map(|hit| (j, hit.t)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        assert_eq!(nearest.map(|(j, _)| j), Some(i));
+    }
+    assert!(spheres.iter().all(|s| camera.pick_ray(Vec2(1.0, 1.0)).intersect_sphere(s).is_none()));
+
+    // an orthographic camera's rays all point the same way, starting from across the near plane
+    let ortho = Camera::orthographic(10.0, 0.5, 50.0, VIEWPORT).with_position(Vec3(0.0, 0.0, 10.0));
+    let ray = ortho.pick_ray(Vec2(0.0, 200.0));
+    assert!(close3(ray.direction, Vec3::NEG_Z, 1e-5));
+    assert!(close3(ray.origin, Vec3(-8.0, -5.0, 9.5), 1e-4));
+}
+
+#[test]
+fn test_orbit_and_fly() {
+    let mut orbit = OrbitController::new(Vec3(1.0, 0.0, 0.0), 5.0).with_distance_limits(1.0, 10.0);
+    let mut camera = Camera::perspective(FRAC_PI_3, 0.1, 100.0, VIEWPORT);
+    orbit.rotate(0.7, -0.4);
+    orbit.apply(&mut camera);
+    assert!((camera.position.distance(orbit.target) - 5.0).abs() < 1e-5);
+    assert!(close3(camera.forward(), (orbit.target - camera.position).normalize(), 1e-5));
+    // looking down from above, so the camera sits above the target
+    assert!(camera.position.y > 0.0);
+
+    orbit.rotate(0.0, -10.0);
+    assert!(orbit.pitch > -FRAC_PI_2);
+    orbit.zoom(0.01);
+    assert_eq!(orbit.distance, 1.0);
+    orbit.zoom(100.0);
+    assert_eq!(orbit.distance, 10.0);
+    let (before, forward) = (orbit.target, orbit.rotation() * Vec3::NEG_Z);
+    orbit.pan(0.1, 0.2);
+    assert!((orbit.target - before).dot(forward).abs() < 1e-5);
+    assert!(((orbit.target - before).magnitude() - 10.0 * 0.05f32.sqrt()).abs() < 1e-4);
+
+    // the fly controller picks up where the camera is looking, then flies along it
+    let mut fly = FlyController::from_camera(&camera, 4.0, 0.01);
+    let (position, forward) = (camera.position, camera.forward());
+    fly.update(&mut camera, Vec2::ZERO, Vec3(0.0, 0.0, 1.0), 0.5);
+    assert!(close3(camera.forward(), forward, 1e-4));
+    assert!(close3(camera.position, position + forward * 2.0, 1e-4));
+
+    // moving diagonally is no faster, and looking right and up turns that way
+    let position = camera.position;
+    fly.update(&mut camera, Vec2(10.0, 1000.0), Vec3(1.0, 0.0, 1.0), 0.5);
+    assert!((camera.position.distance(position) - 2.0).abs() < 1e-4);
+    assert!(fly.pitch < FRAC_PI_2 && camera.forward().y > 0.99);
+    let mut level = Camera::perspective(FRAC_PI_3, 0.1, 100.0, VIEWPORT);
+    FlyController::new(1.0, 0.1).update(&mut level, Vec2(1.0, 0.0), Vec3::ZERO, 0.1);
+    assert!(level.forward().x > 0.0);
+}
+
+#[test]
+fn test_follow_and_shake() {
+    let mut follow = Follow2d::new(Vec2::ZERO, Vec2(2.0, 1.0), 5.0);
+    assert_eq!(follow.update(Vec2(1.5, -0.5), 0.1), Vec2::ZERO);
+
+    // chasing a target outside the dead zone settles with it on the zone's edge, whatever the step size
+    let mut halves = follow;
+    for _ in 0..100 {
+        follow.update(Vec2(10.0, -4.0), 0.1);
+        halves.update(Vec2(10.0, -4.0), 0.05);
+        halves.update(Vec2(10.0, -4.0), 0.05);
+        assert!((follow.position - halves.position).magnitude() < 1e-4);
+    }
+    assert!((follow.position - Vec2(8.0, -3.0)).magnitude() < 1e-3);
+    let mut snapping = Follow2d::new(Vec2::ZERO, Vec2::ZERO, f32::INFINITY);
+    assert_eq!(snapping.update(Vec2(3.0, 4.0), 0.0), Vec2(3.0, 4.0));
+    let mut camera = Camera::orthographic(10.0, 0.1, 10.0, VIEWPORT).with_position(Vec3(0.0, 0.0, 5.0));
+    snapping.apply(&mut camera);
+    assert_eq!(camera.position, Vec3(3.0, 4.0, 5.0));
+
+    // no trauma leaves the camera alone
+    let mut shake = CameraShake::new(Vec3(0.5, 0.5, 0.0), Vec3(0.1, 0.1, 0.05), 9).with_decay(0.5);
+    assert_eq!(shake.shaken(&camera), camera);
+
+    // half the trauma shakes a quarter as hard, smoothly, and the same way each time for the same seed
+    shake.add_trauma(0.5);
+    let mut previous = shake.shake().0;
+    let mut moved = 0.0f32;
+    for _ in 0..30 {
+        shake.update(1.0 / 240.0);
+        let (offset, rotation) = shake.shake();
+        assert!(offset.x.abs() <= 0.125 && offset.y.abs() <= 0.125 && offset.z == 0.0);
+        assert!((rotation.magnitude() - 1.0).abs() < 1e-5);
+        assert!((offset - previous).magnitude() < 0.02);
+        moved = moved.max((offset - previous).magnitude());
+        previous = offset;
+    }
+    assert!(moved > 0.0);
+    let mut again = CameraShake::new(Vec3(0.5, 0.5, 0.0), Vec3(0.1, 0.1, 0.05), 9).with_decay(0.5);
+    again.add_trauma(0.5);
+    for _ in 0..30 {
+        again.update(1.0 / 240.0);
+    }
+    assert_eq!(again.shake(), shake.shake());
+
+    // and it wears off, then stays off
+    shake.add_trauma(2.0);
+    assert_eq!(shake.trauma, 1.0);
+    for _ in 0..130 {
+        shake.update(1.0 / 60.0);
+    }
+    assert_eq!(shake.shaken(&camera), camera);
+}
@@ -0,0 +1,179 @@
+use std::f32::consts::FRAC_PI_4;
+use crate::geometry::capsule::Capsule2;
+use crate::geometry::collision2d::{
+    collide, collide_polygons, epa_penetration, gjk_distance, gjk_intersects, ConvexPolygon, Shape2,
+};
+use crate::geometry::obb::Obb2;
+use crate::geometry::sphere::Circle;
+use crate::math::affine::Affine2;
+use crate::math::vec2::Vec2;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+fn close2(a: Vec2, b: Vec2) -> bool {
+    (a - b).magnitude() < 1e-4
+}
+
+fn square(center: Vec2, half: f32) -> ConvexPolygon {
+    let m = Affine2::from_translation(center).to_matrix();
+    ConvexPolygon::rect(Vec2::splat(half)).transform(&m)
+}
+
+#[test]
+fn test_hull() {
+    let points = [
+        Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(0.5, 0.5), Vec2(1.0, 1.0),
+        Vec2(0.0, 1.0), Vec2(0.5, 0.0), Vec2(0.0, 1.0),
+    ];
+    let hull = ConvexPolygon::new(&points);
+
+    // interior, collinear and repeated points are dropped, leaving counterclockwise corners
+    assert_eq!(hull.vertices(), &[Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(1.0, 1.0), Vec2(0.0, 1.0)]);
+    assert_eq!(hull.normals(), &[Vec2::NEG_Y, Vec2::X, Vec2::Y, Vec2::NEG_X]);
+}
+
+#[test]
+fn test_gjk_distance() {
+    let a = square(Vec2::ZERO, 1.0);
+    let b = square(Vec2(4.0, 0.5), 1.0);
+    let d = gjk_distance(&a, &b).unwrap();
+    assert!(close(d.distance, 2.0));
+    assert!(close(d.point_a.x, 1.0) && close(d.point_b.x, 3.0));
+
+    // corner to corner
+    let c = square(Vec2(3.0, 3.0), 1.0);
+    let d = gjk_distance(&a, &c).unwrap();
+    assert!(close(d.distance, 2f32.sqrt()));
+    assert!(close2(d.point_a, Vec2(1.0, 1.0)) && close2(d.point_b, Vec2(2.0, 2.0)));
+
+    let circle = Circle::new(Vec2(0.0, 5.0), 1.0);
+    assert!(close(gjk_distance(&a, &circle).unwrap().distance, 3.0));
+    let capsule = Capsule2::new(Vec2(-5.0, -3.0), Vec2(5.0, -3.0), 0.5);
+    assert!(close(gjk_distance(&a, &capsule).unwrap().distance, 1.5));
+
+    assert!(gjk_intersects(&a, &square(Vec2(1.5, 1.5), 1.0)));
+    assert!(gjk_intersects(&a, &square(Vec2(2.0, 0.0), 1.0)));
+    assert!(!gjk_intersects(&a, &square(Vec2(2.01, 0.0), 1.0)));
+    assert!(gjk_distance(&a, &Circle::new(Vec2::ZERO, 0.1)).is_none());
+}
+
+#[test]
+fn test_epa() {
+    let a = square(Vec2::ZERO, 1.0);
+
+    let p = epa_penetration(&a, &square(Vec2(1.5, 0.2), 1.0)).unwrap();
+    assert!(close(p.depth, 0.5) && close2(p.normal, Vec2::X));
+
+    let p = epa_penetration(&a, &Circle::new(Vec2(0.0, -1.5), 1.0)).unwrap();
+    assert!(close(p.depth, 0.5) && close2(p.normal, Vec2::NEG_Y));
+    // the witness on a curved surface is only as exact as the polytope around it
+    assert!(p.point_b.distance(Vec2(0.0, -0.5)) < 1e-2);
+
+    // identical shapes on top of each other still give a way out
+    let p = epa_penetration(&a, &a).unwrap();
+    assert!(close(p.depth, 2.0));
+
+    let obb = Obb2::new(Vec2(2.2, 0.0), Vec2(1.0, 1.0), FRAC_PI_4);
+    let p = epa_penetration(&a, &obb).unwrap();
+    assert!(close(p.depth, 2f32.sqrt() - 1.2) && close2(p.normal, Vec2::X));
+
+    assert!(epa_penetration(&a, &square(Vec2(3.0, 0.0), 1.0)).is_none());
+}
+
+#[test]
+fn test_manifold_face() {
+    // a box resting on a wider box gets a contact at each of its bottom corners
+    let ground = ConvexPolygon::rect(Vec2(5.0, 1.0));
+    let box_ = square(Vec2(0.5, 1.9), 1.0);
+    let m = collide_polygons(&ground, &box_).unwrap();
+
+    assert!(close2(m.normal, Vec2::Y));
+    assert_eq!(m.contacts.len(), 2);
+    for c in &m.contacts {
+        assert!(close(c.depth, 0.1));
+        assert!(close(c.point.y, 0.95));
+    }
+    let mut xs: Vec<f32> = m.contacts.iter().map(|c| c.point.x).collect();
+    xs.sort_by(f32::total_cmp);
+    assert!(close(xs[0], -0.5) && close(xs[1], 1.5));
+
+    // swapping the shapes flips the normal but keeps the contacts
+    let flipped = collide_polygons(&box_, &ground).unwrap();
+    assert!(close2(flipped.normal, Vec2::NEG_Y));
+    assert_eq!(flipped.contacts.len(), 2);
+
+    // and an overhanging box is clipped to the ground's edge
+    let m = collide_polygons(&ground, &square(Vec2(5.5, 1.9), 1.0)).unwrap();
+    let mut xs: Vec<f32> = m.contacts.iter().map(|c| c.point.x).collect();
+    xs.sort_by(f32::total_cmp);
+    assert!(close(xs[0], 4.5) && close(xs[1], 5.0));
+}
+
+#[test]
+fn test_manifold_corner() {
+    // a diamond pokes one corner into the ground
+    let ground = ConvexPolygon::rect(Vec2(5.0, 1.0));
+    let diamond = Shape2::Polygon(ConvexPolygon::from_obb(&Obb2::new(Vec2(0.0, 2.3), Vec2(1.0, 1.0), FRAC_PI_4)));
+    let m = collide(&Shape2::Polygon(ground.clone()), &diamond).unwrap();
+
+    let depth = 1.0 - (2.3 - 2f32.sqrt());
+    assert!(close2(m.normal, Vec2::Y));
+    assert_eq!(m.contacts.len(), 1);
+    assert!(close(m.contacts[0].depth, depth));
+    assert!(close2(m.contacts[0].point, Vec2(0.0, 1.0 - depth * 0.5)));
+
+    assert!(collide(&Shape2::Polygon(ground), &Shape2::Polygon(square(Vec2(0.0, 3.1), 1.0))).is_none());
+}
+
+#[test]
+fn test_manifold_circles() {
+    let a = Shape2::Circle(Circle::new(Vec2::ZERO, 1.0));
+    let b = Shape2::Circle(Circle::new(Vec2(1.5, 0.0), 1.0));
+    let m = collide(&a, &b).unwrap();
+    assert!(close2(m.normal, Vec2::X) && close(m.contacts[0].depth, 0.5));
+    assert!(close2(m.contacts[0].point, Vec2(0.75, 0.0)));
+
+    // circle against a face, a corner, and from inside
+    let box_ = Shape2::Polygon(square(Vec2::ZERO, 1.0));
+    let m = collide(&Shape2::Circle(Circle::new(Vec2(0.0, 1.5), 1.0)), &box_).unwrap();
+    assert!(close2(m.normal, Vec2::NEG_Y) && close(m.contacts[0].depth, 0.5));
+    let m = collide(&box_, &Shape2::Circle(Circle::new(Vec2(1.5, 1.5), 1.0))).unwrap();
+    assert!(close2(m.normal, Vec2(1.0, 1.0).normalize()));
+    assert!(close(m.contacts[0].depth, 1.0 - 0.5 * 2f32.sqrt()));
+    let m = collide(&box_, &Shape2::Circle(Circle::new(Vec2(0.8, 0.0), 0.5))).unwrap();
+    assert!(close2(m.normal, Vec2::X) && close(m.contacts[0].depth, 0.7));
+
+    assert!(collide(&box_, &Shape2::Circle(Circle::new(Vec2(2.0, 2.0), 1.0))).is_none());
+}
+
+#[test]
+fn test_manifold_capsules() {
+    let ground = Shape2::Polygon(square(Vec2(0.0, -1.0), 1.0));
+
+    // lying flat on a box gives a contact under each end
+    let lying = Shape2::Capsule(Capsule2::new(Vec2(-0.5, 0.4), Vec2(0.5, 0.4), 0.5));
+    let m = collide(&ground, &lying).unwrap();
+    assert!(close2(m.normal, Vec2::Y));
+    assert_eq!(m.contacts.len(), 2);
+    assert!(m.contacts.iter().all(|c| close(c.depth, 0.1) && close(c.point.y, -0.05)));
+
+    // a rounded end over the box's corner only touches at one point, along the diagonal
+    let corner = Shape2::Capsule(Capsule2::new(Vec2(1.3, 0.3), Vec2(3.0, 2.0), 0.5));
+    let m = collide(&ground, &corner).unwrap();
+    assert_eq!(m.contacts.len(), 1);
+    assert!(close2(m.normal, Vec2(1.0, 1.0).normalize()));
+    assert!(close(m.contacts[0].depth, 0.5 - 0.3 * 2f32.sqrt()));
+    let apart = Shape2::Capsule(Capsule2::new(Vec2(1.4, 0.4), Vec2(3.0, 2.0), 0.5));
+    assert!(collide(&ground, &apart).is_none());
+
+    // crossed capsules, and a circle against the side of one
+    let a = Shape2::Capsule(Capsule2::new(Vec2(-1.0, 0.0), Vec2(1.0, 0.0), 0.25));
+    let b = Shape2::Capsule(Capsule2::new(Vec2(0.0, 0.4), Vec2(0.0, 2.0), 0.25));
+    let m = collide(&a, &b).unwrap();
+    assert!(close2(m.normal, Vec2::Y) && close(m.contacts[0].depth, 0.1));
+    let circle = Shape2::Circle(Circle::new(Vec2(0.5, -0.5), 0.5));
+    let m = collide(&circle, &a).unwrap();
+    assert!(close2(m.normal, Vec2::Y) && close(m.contacts[0].depth, 0.25));
+}
@@ -0,0 +1,155 @@
+use std::f32::consts::FRAC_PI_4;
+use crate::geometry::collision3d::{
+    collide, collide_boxes, epa_penetration, gjk_distance, gjk_intersects, ConvexHull, PlacedShape3, Shape3,
+};
+use crate::geometry::obb::Obb3;
+use crate::geometry::sphere::Sphere;
+use crate::math::quat::Quat;
+use crate::math::random::Pcg32;
+use crate::math::vec3::Vec3;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+fn close3(a: Vec3, b: Vec3) -> bool {
+    (a - b).magnitude() < 1e-3
+}
+
+fn cuboid(position: Vec3, half_extents: Vec3) -> PlacedShape3 {
+    PlacedShape3::from_position(Shape3::Cuboid { half_extents }, position)
+}
+
+#[test]
+fn test_gjk_distance() {
+    let a = cuboid(Vec3::ZERO, Vec3::ONE);
+    let b = Sphere::new(Vec3(3.0, 0.5, -0.5), 1.0);
+    let d = gjk_distance(&a, &b).unwrap();
+    assert!(close(d.distance, 1.0));
+    assert!(close3(d.point_a, Vec3(1.0, 0.5, -0.5)) && close3(d.point_b, Vec3(2.0, 0.5, -0.5)));
+
+    // a cylinder standing on its end above a tetrahedron of points
+    let cylinder = PlacedShape3::from_position(Shape3::Cylinder { half_height: 1.0, radius: 0.5 }, Vec3(0.0, 3.0, 0.0));
+    let hull = ConvexHull::new(&[Vec3(-1.0, 0.0, -1.0), Vec3(1.0, 0.0, -1.0), Vec3(0.0, 0.0, 1.0), Vec3(0.0, 1.5, 0.0)]);
+    assert_eq!(hull.triangles().len(), 4);
+    assert!(hull.triangles().iter().all(|[a, b, c]| (b - a).cross(c - a).dot(*a - Vec3(0.0, 0.5, -0.25)) > 0.0));
+    let d = gjk_distance(&cylinder, &hull).unwrap();
+    assert!(close(d.distance, 0.5) && close3(d.point_b, Vec3(0.0, 1.5, 0.0)));
+
+    assert!(gjk_intersects(&a, &Sphere::new(Vec3(1.5, 0.0, 0.0), 0.6)));
+    assert!(!gjk_intersects(&a, &Sphere::new(Vec3(1.5, 1.5, 1.5), 0.6)));
+    assert!(gjk_distance(&a, &cuboid(Vec3(1.0, 1.0, 1.0), Vec3::ONE)).is_none());
+}
+
+#[test]
+fn test_rotation_matrix() {
+    // a long box turned a quarter about z now reaches along y instead of x
+    let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2).to_matrix3();
+    let a = PlacedShape3::new(Shape3::Cuboid { half_extents: Vec3(2.0, 0.5, 0.5) }, Vec3::ZERO, &rotation);
+    let aabb = a.aabb();
+    assert!(close3(aabb.min, Vec3(-0.5, -2.0, -0.5)) && close3(aabb.max, Vec3(0.5, 2.0, 0.5)));
+    assert!(close3(a.to_local(Vec3(0.0, 1.0, 0.0)), Vec3(1.0, 0.0, 0.0)));
+
+    // a 4x4 matrix is read for its rotation too
+    let b = PlacedShape3::new(a.shape.clone(), Vec3::ZERO, &Quat::from_rotation_z(std::f32::consts::FRAC_PI_2).to_matrix4());
+    assert!(close3(b.axes()[0], a.axes()[0]) && close3(b.axes()[1], a.axes()[1]));
+    assert!(close3(a.rotation() * Vec3::X, Vec3::Y));
+}
+
+#[test]
+fn test_epa() {
+    let a = cuboid(Vec3::ZERO, Vec3::ONE);
+
+    let p = epa_penetration(&a, &cuboid(Vec3(1.5, 0.2, 0.1), Vec3::ONE)).unwrap();
+    assert!(close(p.depth, 0.5) && close3(p.normal, Vec3::X));
+
+    let p = epa_penetration(&a, &Sphere::new(Vec3(0.0, -1.5, 0.0), 1.0)).unwrap();
+    assert!(close(p.depth, 0.5) && close3(p.normal, Vec3::NEG_Y));
+
+    // a capsule lying across the top of the box
+    let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2).to_matrix3();
+    let capsule = PlacedShape3::new(Shape3::Capsule { half_height: 2.0, radius: 0.5 }, Vec3(0.0, 1.25, 0.0), &rotation);
+    let p = epa_penetration(&a, &capsule).unwrap();
+    assert!(close(p.depth, 0.25) && close3(p.normal, Vec3::Y));
+
+    // identical shapes on top of each other still give a way out
+    let p = epa_penetration(&a, &a).unwrap();
+    assert!(close(p.depth, 2.0));
+}
+
+#[test]
+fn test_box_face_manifold() {
+    // a box resting slightly sunk into a wider one gives its four bottom corners
+    let a = Obb3::new(Vec3::ZERO, Vec3(2.0, 0.5, 2.0), Quat::IDENTITY);
+    let b = Obb3::new(Vec3(0.2, 0.95, -0.1), Vec3(0.5, 0.5, 0.5), Quat::from_rotation_y(0.3));
+    let m = collide_boxes(&a, &b).unwrap();
+    assert!(close3(m.normal, Vec3::Y));
+    assert_eq!(m.contacts.len(), 4);
+    for contact in &m.contacts {
+        assert!(close(contact.depth, 0.05) && close(contact.point.y, 0.475));
+    }
+
+    // the same from the other side flips the normal and keeps the points
+    let m = collide_boxes(&b, &a).unwrap();
+    assert!(close3(m.normal, Vec3::NEG_Y) && m.contacts.len() == 4);
+
+    // overhanging boxes are clipped to the reference face
+    let c = Obb3::new(Vec3(1.8, 0.95, 0.0), Vec3(0.5, 0.5, 0.5), Quat::IDENTITY);
+    let m = collide_boxes(&a, &c).unwrap();
+    assert_eq!(m.contacts.len(), 4);
+    assert!(m.contacts.iter().all(|c| c.point.x <= 2.0 + 1e-4 && c.point.x >= 1.3 - 1e-4));
+
+    assert!(collide_boxes(&a, &Obb3::new(Vec3(0.0, 1.01, 0.0), Vec3(0.5, 0.5, 0.5), Quat::IDENTITY)).is_none());
+}
+
+#[test]
+fn test_box_edge_manifold() {
+    // two boxes turned so their edges cross
+    let a = Obb3::new(Vec3::ZERO, Vec3::ONE, Quat::from_rotation_x(FRAC_PI_4));
+    let b = Obb3::new(Vec3(0.0, 2f32.sqrt() * 2.0 - 0.1, 0.0), Vec3::ONE, Quat::from_rotation_z(FRAC_PI_4));
+    let m = collide_boxes(&a, &b).unwrap();
+    assert!(close3(m.normal, Vec3::Y));
+    assert_eq!(m.contacts.len(), 1);
+    assert!(close(m.contacts[0].depth, 0.1));
+    assert!(close3(m.contacts[0].point, Vec3(0.0, 2f32.sqrt() - 0.05, 0.0)));
+}
+
+#[test]
+fn test_box_manifolds_never_empty() {
+    // a face that clips away every point falls back to the edges, so a hit always has a contact
+    let mut rng = Pcg32::new(7);
+    let random_box = |rng: &mut Pcg32| {
+        let axis = Vec3(rng.range(-1.0, 1.0), rng.range(-1.0, 1.0), rng.range(-1.0, 1.0));
+        let rotation = Quat::from_axis_angle(axis.try_normalize().unwrap_or(Vec3::Y), rng.range(0.0, 3.0));
+        let half_extents = Vec3(rng.range(0.05, 1.5), rng.range(0.05, 1.5), rng.range(0.05, 1.5));
+        let center = Vec3(rng.range(-1.5, 1.5), rng.range(-1.5, 1.5), rng.range(-1.5, 1.5));
+        Obb3::new(center, half_extents, rotation)
+    };
+    for _ in 0..2000 {
+        let (a, b) = (random_box(&mut rng), random_box(&mut rng));
+        if let Some(m) = collide_boxes(&a, &b) {
+            assert!(!m.contacts.is_empty(), "{a:?} {b:?}");
+        }
+    }
+}
+
+#[test]
+fn test_collide() {
+    let ball = |position, radius| PlacedShape3::from_position(Shape3::Sphere { radius }, position);
+
+    let m = collide(&ball(Vec3::ZERO, 1.0), &ball(Vec3(0.0, 0.0, 1.5), 1.0)).unwrap();
+    assert!(close3(m.normal, Vec3::Z) && close(m.contacts[0].depth, 0.5));
+    assert!(close3(m.contacts[0].point, Vec3(0.0, 0.0, 0.75)));
+
+    let ground = cuboid(Vec3(0.0, -1.0, 0.0), Vec3(5.0, 1.0, 5.0));
+    let m = collide(&ground, &ball(Vec3(1.0, 0.9, 0.0), 1.0)).unwrap();
+    assert!(close3(m.normal, Vec3::Y) && close(m.contacts[0].depth, 0.1));
+    let m = collide(&ball(Vec3(1.0, 0.9, 0.0), 1.0), &ground).unwrap();
+    assert!(close3(m.normal, Vec3::NEG_Y));
+
+    // shapes without a special case go through epa
+    let cylinder = PlacedShape3::from_position(Shape3::Cylinder { half_height: 1.0, radius: 0.5 }, Vec3(0.0, 0.8, 0.0));
+    let m = collide(&ground, &cylinder).unwrap();
+    assert!(close3(m.normal, Vec3::Y) && close(m.contacts[0].depth, 0.2));
+    assert!(collide(&ground, &ball(Vec3(0.0, 2.0, 0.0), 1.0)).is_none());
+}
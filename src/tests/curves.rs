@@ -0,0 +1,125 @@
+use crate::math::curves::{
+    closest_point, inverse_lerp, lerp, remap, smoothstep, ArcLength, BSpline, CatmullRom,
+    CubicBezier, Curve, Ease, Hermite, QuadraticBezier,
+};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+fn close2(a: Vec2, b: Vec2) -> bool {
+    (a - b).magnitude() < 1e-4
+}
+
+/// compare a curves derivative against a central difference
+fn check_derivative<C: Curve<Vec3>>(curve: &C) {
+    let h = 1e-3;
+    for t in [0.1, 0.35, 0.6, 0.9] {
+        let numeric = (curve.point(t + h) - curve.point(t - h)) / (2.0 * h);
+        assert!((numeric - curve.derivative(t)).magnitude() < 1e-2, "{t}");
+    }
+}
+
+#[test]
+fn test_scalar() {
+    assert_eq!(lerp(2.0, 4.0, 0.25), 2.5);
+    assert_eq!(lerp(Vec2::ZERO, Vec2(2.0, 4.0), 0.5), Vec2(1.0, 2.0));
+    assert_eq!(inverse_lerp(2.0, 4.0, 2.5), 0.25);
+    assert_eq!(remap(5.0, (0.0, 10.0), (100.0, 200.0)), 150.0);
+
+    assert_eq!(smoothstep(0.0, 1.0, -1.0), 0.0);
+    assert_eq!(smoothstep(0.0, 1.0, 0.5), 0.5);
+    assert_eq!(smoothstep(0.0, 1.0, 2.0), 1.0);
+}
+
+#[test]
+fn test_easing() {
+    let all = [
+        Ease::Linear, Ease::Quad, Ease::Cubic, Ease::Quart, Ease::Quint, Ease::Sine,
+        Ease::Expo, Ease::Circ, Ease::Back, Ease::Elastic, Ease::Bounce,
+    ];
+
+    for ease in all {
+        for f in [Ease::ease_in, Ease::ease_out, Ease::ease_in_out] {
+            assert!(close(f(ease, 0.0), 0.0), "{ease:?}");
+            assert!(close(f(ease, 1.0), 1.0), "{ease:?}");
+        }
+        assert!(close(ease.ease_in_out(0.5), 0.5), "{ease:?}");
+    }
+
+    assert_eq!(Ease::Quad.ease_in(0.5), 0.25);
+    assert_eq!(Ease::Quad.ease_out(0.5), 0.75);
+    assert!(Ease::Back.ease_in(0.2) < 0.0);
+}
+
+#[test]
+fn test_endpoints() {
+    let (a, b, c, d) = (Vec2(0.0, 0.0), Vec2(1.0, 2.0), Vec2(3.0, 2.0), Vec2(4.0, 0.0));
+
+    let quad = QuadraticBezier { p0: a, p1: b, p2: d };
+    assert_eq!(quad.point(0.0), a);
+    assert_eq!(quad.point(1.0), d);
+
+    let cubic = CubicBezier { p0: a, p1: b, p2: c, p3: d };
+    assert_eq!(cubic.point(0.0), a);
+    assert_eq!(cubic.point(1.0), d);
+    assert!(close2(cubic.point(0.5), Vec2(2.0, 1.5)));
+
+    let hermite = Hermite { p0: a, m0: Vec2::X, p1: d, m1: Vec2::X };
+    assert_eq!(hermite.point(1.0), d);
+    assert_eq!(hermite.derivative(0.0), Vec2::X);
+
+    // catmull-rom passes through every point
+    let spline = CatmullRom::new(vec![a, b, c, d]);
+    assert!(close2(spline.point(0.0), a));
+    assert!(close2(spline.point(1.0 / 3.0), b));
+    assert!(close2(spline.point(2.0 / 3.0), c));
+    assert!(close2(spline.point(1.0), d));
+
+    // a b-spline of collinear evenly spaced points is the line between the middle two
+    let line = BSpline::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+    assert!(close(line.point(0.0), 1.0));
+    assert!(close(line.point(1.0), 3.0));
+}
+
+#[test]
+fn test_derivatives() {
+    let (a, b, c, d) = (Vec3(0.0, 0.0, 1.0), Vec3(1.0, 2.0, 0.0), Vec3(3.0, 2.0, -1.0), Vec3(4.0, 0.0, 2.0));
+
+    check_derivative(&QuadraticBezier { p0: a, p1: b, p2: c });
+    check_derivative(&CubicBezier { p0: a, p1: b, p2: c, p3: d });
+    check_derivative(&Hermite { p0: a, m0: b, p1: c, m1: d });
+    check_derivative(&CatmullRom::new(vec![a, b, c, d]));
+    check_derivative(&BSpline::new(vec![a, b, c, d, a]));
+}
+
+#[test]
+fn test_arc_length() {
+    // control points evenly spaced along a line, but pulled so t isn't uniform
+    let curve = CubicBezier { p0: Vec2::ZERO, p1: Vec2(0.1, 0.0), p2: Vec2(0.2, 0.0), p3: Vec2(3.0, 0.0) };
+    let table = ArcLength::new(&curve, 256);
+
+    assert!(close(table.length(), 3.0));
+    assert_eq!(table.parameter_at(-1.0), 0.0);
+    assert_eq!(table.parameter_at(10.0), 1.0);
+
+    for distance in [0.5, 1.0, 2.0, 2.9] {
+        let point = curve.point(table.parameter_at(distance));
+        assert!((point.x - distance).abs() < 1e-2, "{distance}");
+    }
+    assert!((curve.point(table.parameter_at_fraction(0.5)).x - 1.5).abs() < 1e-2);
+}
+
+#[test]
+fn test_closest_point() {
+    let curve = QuadraticBezier { p0: Vec2(-1.0, 1.0), p1: Vec2(0.0, -1.0), p2: Vec2(1.0, 1.0) };
+
+    // the curve is y = x^2 with x = 2t - 1, so the bottom is at t = 0.5
+    let t = closest_point(&curve, Vec2(0.0, -5.0), 16);
+    assert!(close(t, 0.5));
+
+    let t = closest_point(&curve, Vec2(3.0, 3.0), 16);
+    assert!(close(t, 1.0));
+}
@@ -0,0 +1,78 @@
+use crate::math::deterministic::soft_sqrt;
+use crate::math::matrix::Matrix;
+
+#[test]
+fn test_soft_sqrt_golden() {
+    let golden = [
+        (2.0f32, 0x3fb504f3u32),
+        (0.1, 0x3ea1e89b),
+        (1e-40, 0x1e3ce4e7),
+        (3.4e38, 0x5f7fe4ce),
+        (12345.678, 0x42de38e3),
+    ];
+
+    for (x, bits) in golden {
+        assert_eq!(soft_sqrt(x).to_bits(), bits, "sqrt({x})");
+    }
+}
+
+#[test]
+fn test_soft_sqrt_special() {
+    assert_eq!(soft_sqrt(0.0).to_bits(), 0.0f32.to_bits());
+    assert_eq!(soft_sqrt(-0.0).to_bits(), (-0.0f32).to_bits());
+    assert_eq!(soft_sqrt(f32::INFINITY), f32::INFINITY);
+    assert!(soft_sqrt(-1.0).is_nan());
+    assert!(soft_sqrt(f32::NAN).is_nan());
+}
+
+#[test]
+fn test_soft_sqrt_correctly_rounded() {
+    // ieee 754 requires sqrt to be correctly rounded, so these must agree exactly
+    let mut bits = 1u32;
+    while bits < 0x7f80_0000 {
+        let x = f32::from_bits(bits);
+        assert_eq!(soft_sqrt(x).to_bits(), x.sqrt().to_bits(), "sqrt({x})");
+        bits += 0x1337;
+    }
+}
+
+#[test]
+fn test_mul_deterministic_golden() {
+    let a = Matrix::from_vec(3, 5, (0..15).map(|i| (i as f32 - 7.0) / 3.0).collect::<Vec<f32>>());
+    let b = Matrix::from_vec(5, 4, (0..20).map(|i| (11.0 - i as f32) / 7.0).collect::<Vec<f32>>());
+
+    let golden = [
+        0xc0af3cf3u32, 0xc0892492, 0xc0461860, 0xbff3cf3f,
+        0xbff3cf3e, 0xbff3cf3e, 0xbff3cf3e, 0xbff3cf3e,
+        0x3fd55555, 0x3ef3cf48, 0xbf36db6c, 0xbff3cf3d,
+    ];
+
+    let c = a.mul_deterministic(&b);
+    assert_eq!(c.data.iter().map(|v| v.to_bits()).collect::<Vec<u32>>(), golden);
+}
+
+#[test]
+fn test_mul_deterministic_4x4_golden() {
+    // the simd 4x4 kernel sums pairwise, so it differs from this in the last bit
+    let a = Matrix::from_vec(4, 4, (0..16).map(|i| (i as f32 - 7.0) / 3.0).collect::<Vec<f32>>());
+    let b = Matrix::from_vec(4, 4, (0..16).map(|i| (11.0 - i as f32) / 7.0).collect::<Vec<f32>>());
+
+    let golden = [
+        0xc0c61861u32, 0xc0a49249, 0xc0830c30, 0xc0430c32,
+        0xc0186186, 0xc0061862, 0xbfe79e7a, 0xbfc30c32,
+        0x3fb6db6e, 0x3f73cf3d, 0x3ef3cf3e, 0x00000000,
+        0x40a79e79, 0x40800000, 0x4030c30c, 0x3fc30c30,
+    ];
+
+    let c = a.mul_deterministic(&b);
+    assert_eq!(c.data.iter().map(|v| v.to_bits()).collect::<Vec<u32>>(), golden);
+}
+
+#[test]
+#[cfg(feature = "deterministic")]
+fn test_mul_uses_deterministic() {
+    let a = Matrix::from_vec(4, 4, (0..16).map(|i| (i as f32 - 7.0) / 3.0).collect::<Vec<f32>>());
+    let b = Matrix::from_vec(4, 4, (0..16).map(|i| (11.0 - i as f32) / 7.0).collect::<Vec<f32>>());
+
+    assert_eq!((a.clone() * b.clone()).data, a.mul_deterministic(&b).data);
+}
@@ -0,0 +1,157 @@
+use crate::ecs::command::CommandBuffer;
+use crate::ecs::entity::Entity;
+use crate::ecs::query::{Added, Changed, With, Without};
+use crate::ecs::world::World;
+use crate::math::vec2::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position(Vec2);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Velocity(Vec2);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Frozen;
+
+#[test]
+fn test_spawn_despawn() {
+    let mut world = World::new();
+    let a = world.spawn((Position(Vec2::ZERO), Velocity(Vec2::X)));
+    let b = world.spawn((Position(Vec2::ONE),));
+    let c = world.spawn((Velocity(Vec2::Y), Position(Vec2(2.0, 2.0))));
+    assert_eq!(world.len(), 3);
+    // component order doesn't matter to which archetype an entity lands in
+    assert_eq!(world.archetypes().len(), 3);
+
+    assert!(world.despawn(a));
+    assert!(!world.despawn(a) && !world.contains(a));
+    assert_eq!(*world.get::<Position>(c).unwrap(), Position(Vec2(2.0, 2.0)));
+    assert_eq!(*world.get::<Position>(b).unwrap(), Position(Vec2::ONE));
+
+    // the slot comes back with a new generation
+    let d = world.spawn((Frozen,));
+    assert_eq!(d.index(), a.index());
+    assert_ne!(d, a);
+    assert!(world.get::<Frozen>(a).is_none() && world.get::<Frozen>(d).is_some());
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut world = World::new();
+    let others: Vec<Entity> = (0..5).map(|i| world.spawn((Position(Vec2::splat(i as f32)),))).collect();
+    let e = others[2];
+
+    assert!(world.insert(e, (Velocity(Vec2::X),)));
+    assert!(world.has::<Velocity>(e));
+    assert_eq!(*world.get::<Position>(e).unwrap(), Position(Vec2::splat(2.0)));
+
+    // inserting a component it already has replaces it in place
+    world.insert(e, (Velocity(Vec2::Y), Frozen));
+    assert_eq!(*world.get::<Velocity>(e).unwrap(), Velocity(Vec2::Y));
+
+    assert_eq!(world.remove::<Velocity>(e), Some(Velocity(Vec2::Y)));
+    assert_eq!(world.remove::<Velocity>(e), None);
+    assert!(world.has::<Frozen>(e) && world.has::<Position>(e));
+
+    // every entity that was swapped around along the way is still intact
+    for (i, other) in others.iter().enumerate() {
+        assert_eq!(*world.get::<Position>(*other).unwrap(), Position(Vec2::splat(i as f32)));
+    }
+    world.get_mut::<Position>(others[4]).unwrap().0 = Vec2::ZERO;
+    assert_eq!(*world.get::<Position>(others[4]).unwrap(), Position(Vec2::ZERO));
+}
+
+#[test]
+fn test_query() {
+    let mut world = World::new();
+    for i in 0..10 {
+        let e = world.spawn((Position(Vec2::ZERO), Velocity(Vec2(i as f32, 1.0))));
+        if i % 3 == 0 {
+            world.insert(e, (Frozen,));
+        }
+    }
+    world.spawn((Position(Vec2::ONE),));
+
+    world.query_filtered::<(&mut Position, &Velocity), Without<Frozen>>().for_each(|(mut p, v)| {
+        p.0 += v.0;
+    });
+
+    let mut moved = 0;
+    world.query::<(Entity, &Position, Option<&Velocity>)>().for_each(|(e, p, v)| {
+        match v {
+            Some(v) if !world.has::<Frozen>(e) => {
+                assert_eq!(p.0, v.0);
+                moved += 1;
+            }
+            Some(_) => assert_eq!(p.0, Vec2::ZERO),
+            None => assert_eq!(p.0, Vec2::ONE),
+        }
+    });
+    assert_eq!(moved, 6);
+    assert_eq!(world.query::<&Position>().count(), 11);
+    assert_eq!(world.query_filtered::<&Position, With<Frozen>>().count(), 4);
+    assert_eq!(world.query_filtered::<Entity, (With<Velocity>, Without<Frozen>)>().entities().len(), 6);
+
+    let first = world.query::<Entity>().entities()[0];
+    assert_eq!(world.query::<&Velocity>().get(first, |v| v.0.y), Some(1.0));
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn test_conflicting_query() {
+    let mut world = World::new();
+    world.spawn((Position(Vec2::ZERO),));
+    world.query::<(&mut Position, &Position)>().for_each(|_| {});
+}
+
+#[test]
+fn test_change_detection() {
+    let mut world = World::new();
+    let a = world.spawn((Position(Vec2::ZERO), Velocity(Vec2::X)));
+    let b = world.spawn((Position(Vec2::ZERO), Velocity(Vec2::ZERO)));
+    let since = world.change_tick();
+    world.increment_change_tick();
+
+    assert_eq!(world.query_filtered::<Entity, Changed<Position>>().since(since).count(), 0);
+    assert_eq!(world.query_filtered::<Entity, Changed<Position>>().count(), 2);
+
+    // only writes through a mutable reference count
+    world.query::<(&mut Position, &Velocity)>().for_each(|(mut p, v)| {
+        if v.0 != Vec2::ZERO {
+            p.0 += v.0;
+        }
+    });
+    let c = world.spawn((Position(Vec2::ONE),));
+
+    assert_eq!(world.query_filtered::<Entity, Changed<Position>>().since(since).entities(), vec![a, c]);
+    assert_eq!(world.query_filtered::<Entity, Added<Position>>().since(since).entities(), vec![c]);
+    assert!(world.query_filtered::<Entity, Changed<Position>>().since(since).get(b, |_| ()).is_none());
+}
+
+#[test]
+fn test_commands() {
+    let mut world = World::new();
+    let a = world.spawn((Position(Vec2::ZERO),));
+    let b = world.spawn((Position(Vec2::ONE),));
+
+    let mut commands = CommandBuffer::new();
+    world.query::<(Entity, &Position)>().for_each(|(e, p)| {
+        if p.0 == Vec2::ZERO {
+            commands.despawn(e);
+        } else {
+            commands.insert(e, (Velocity(Vec2::X),));
+            commands.spawn((Position(p.0 * 2.0),));
+        }
+    });
+    commands.remove::<Position>(b);
+    // a despawned entity is skipped quietly
+    commands.insert(a, (Frozen,));
+    assert_eq!(commands.len(), 5);
+    commands.apply(&mut world);
+
+    assert!(commands.is_empty());
+    assert!(!world.contains(a));
+    assert!(world.has::<Velocity>(b) && !world.has::<Position>(b));
+    assert_eq!(world.query::<&Position>().count(), 1);
+    assert_eq!(world.len(), 2);
+}
@@ -0,0 +1,110 @@
+use std::f32::consts::FRAC_PI_4;
+use crate::geometry::aabb::Aabb2;
+use crate::geometry::capsule::Capsule2;
+use crate::geometry::obb::Obb2;
+use crate::geometry::plane::Plane2;
+use crate::geometry::ray::Ray2;
+use crate::geometry::segment::Segment2;
+use crate::geometry::sphere::Circle;
+use crate::geometry::triangle::Triangle2;
+use crate::geometry::EPSILON;
+use crate::math::affine::Affine2;
+use crate::math::vec2::Vec2;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+#[test]
+fn test_overlap() {
+    let aabb = Aabb2::new(Vec2(0.0, 0.0), Vec2(2.0, 1.0));
+
+    assert!(aabb.intersects_aabb(&Aabb2::new(Vec2(2.0, 1.0), Vec2(3.0, 3.0))));
+    assert!(!aabb.intersects_aabb(&Aabb2::new(Vec2(2.1, 0.0), Vec2(3.0, 3.0))));
+    assert!(Circle::new(Vec2(3.0, 0.5), 1.0).intersects_aabb(&aabb));
+    assert!(!Circle::new(Vec2(3.0, 2.0), 1.0).intersects_aabb(&aabb));
+
+    // rotated a quarter turn the corner of one box reaches into the other
+    let a = Obb2::new(Vec2::ZERO, Vec2(1.0, 1.0), 0.0);
+    assert!(a.intersects_obb(&Obb2::new(Vec2(2.3, 0.0), Vec2(1.0, 1.0), FRAC_PI_4)));
+    assert!(!a.intersects_obb(&Obb2::new(Vec2(2.5, 0.0), Vec2(1.0, 1.0), FRAC_PI_4)));
+
+    let capsule = Capsule2::new(Vec2(0.0, 0.0), Vec2(4.0, 0.0), 0.5);
+    assert!(capsule.intersects_capsule(&Capsule2::new(Vec2(2.0, 0.9), Vec2(2.0, 3.0), 0.5)));
+    assert!(!capsule.intersects_circle(&Circle::new(Vec2(5.0, 1.0), 0.5)));
+}
+
+#[test]
+fn test_closest_and_contains() {
+    let triangle = Triangle2::new(Vec2(0.0, 0.0), Vec2(2.0, 0.0), Vec2(0.0, 2.0));
+
+    assert!(triangle.contains_point(Vec2(0.5, 0.5)));
+    assert!(!triangle.contains_point(Vec2(1.5, 1.5)));
+    assert_eq!(triangle.closest_point(Vec2(2.0, 2.0)), Vec2(1.0, 1.0));
+    assert_eq!(triangle.closest_point(Vec2(-1.0, -1.0)), Vec2::ZERO);
+    assert_eq!(triangle.signed_area(), 2.0);
+    assert_eq!(triangle.barycentric(Vec2(0.0, 0.0), EPSILON).unwrap().x, 1.0);
+
+    let obb = Obb2::new(Vec2(1.0, 1.0), Vec2(1.0, 0.5), FRAC_PI_4);
+    assert!(obb.contains_point(Vec2(1.5, 1.5)));
+    assert!(!obb.contains_point(Vec2(1.5, 0.5)));
+    assert!(obb.contains_point(obb.closest_point(Vec2(5.0, -3.0)) * 0.999 + obb.center * 0.001));
+
+    let circle = Circle::new(Vec2::ZERO, 1.0);
+    assert!(circle.contains_circle(&Circle::new(Vec2(0.5, 0.0), 0.5)));
+    assert!(close(circle.distance_to_point(Vec2(3.0, 0.0)), 2.0));
+
+    let line = Plane2::from_points(Vec2(0.0, 1.0), Vec2(1.0, 1.0), EPSILON).unwrap();
+    assert_eq!(line.signed_distance(Vec2(5.0, 0.0)), 1.0);
+    assert!(Plane2::from_points(Vec2::ONE, Vec2::ONE, EPSILON).is_none());
+}
+
+#[test]
+fn test_segments() {
+    let a = Segment2::new(Vec2(0.0, 0.0), Vec2(2.0, 2.0));
+    let b = Segment2::new(Vec2(0.0, 2.0), Vec2(2.0, 0.0));
+    assert_eq!(a.intersection(&b), Some(Vec2(1.0, 1.0)));
+    assert_eq!(a.intersection(&Segment2::new(Vec2(1.0, 0.0), Vec2(3.0, 2.0))), None);
+
+    // parallel and degenerate segments still give sensible closest points
+    let c = Segment2::new(Vec2(0.0, 1.0), Vec2(2.0, 3.0));
+    assert!(close(a.distance_to_segment(&c), 0.5f32.sqrt()));
+    let point = Segment2::new(Vec2(3.0, 0.0), Vec2(3.0, 0.0));
+    let (p, q) = a.closest_points(&point, EPSILON);
+    assert_eq!((p, q), (Vec2(1.5, 1.5), Vec2(3.0, 0.0)));
+}
+
+#[test]
+fn test_ray() {
+    let ray = Ray2::new(Vec2(-5.0, 0.5), Vec2::X);
+
+    let hit = ray.intersect_aabb(&Aabb2::new(Vec2::ZERO, Vec2::ONE)).unwrap();
+    assert_eq!((hit.t, hit.normal), (5.0, Vec2::NEG_X));
+    let hit = ray.intersect_circle(&Circle::new(Vec2(0.0, 0.5), 1.0)).unwrap();
+    assert_eq!((hit.t, hit.normal), (4.0, Vec2::NEG_X));
+    let hit = ray.intersect_capsule(&Capsule2::new(Vec2(0.0, -2.0), Vec2(0.0, 2.0), 1.0)).unwrap();
+    assert!(close(hit.t, 4.0) && hit.normal == Vec2::NEG_X);
+    let hit = ray.intersect_triangle(&Triangle2::new(Vec2(0.0, 0.0), Vec2(1.0, 0.0), Vec2(0.0, 1.0))).unwrap();
+    assert_eq!(hit.t, 5.0);
+    let hit = ray.intersect_obb(&Obb2::new(Vec2::ZERO, Vec2(1.0, 1.0), FRAC_PI_4)).unwrap();
+    assert!(close(hit.normal.dot(Vec2::NEG_X), FRAC_PI_4.cos()));
+
+    // starting inside, pointing away, and parallel
+    assert_eq!(ray.intersect_circle(&Circle::new(Vec2(-5.0, 0.0), 1.0)).unwrap().t, 0.0);
+    assert!(ray.intersect_circle(&Circle::new(Vec2(-8.0, 0.0), 1.0)).is_none());
+    assert!(ray.intersect_plane(&Plane2::from_point_normal(Vec2::ZERO, Vec2::Y)).is_none());
+    assert!(ray.intersect_segment(&Segment2::new(Vec2(0.0, 0.5), Vec2(1.0, 0.5))).is_none());
+}
+
+#[test]
+fn test_transform() {
+    let m = (Affine2::from_translation(Vec2(1.0, 0.0)) * Affine2::from_scale(Vec2(2.0, 3.0))).to_matrix();
+
+    assert_eq!(Aabb2::new(Vec2::ZERO, Vec2::ONE).transform(&m), Aabb2::new(Vec2(1.0, 0.0), Vec2(3.0, 3.0)));
+    assert_eq!(Circle::new(Vec2::ZERO, 1.0).transform(&m), Circle::new(Vec2(1.0, 0.0), 3.0));
+    let line = Plane2::from_point_normal(Vec2(1.0, 1.0), Vec2::ONE).transform(&m);
+    assert!(close(line.signed_distance(Vec2(3.0, 3.0)), 0.0));
+    let rotated = Aabb2::new(-Vec2::ONE, Vec2::ONE).transform(&Affine2::from_angle(FRAC_PI_4).to_matrix());
+    assert!(close(rotated.max.x, 2f32.sqrt()));
+    assert_eq!(Aabb2::EMPTY.transform(&m), Aabb2::EMPTY);
+}
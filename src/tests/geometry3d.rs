@@ -0,0 +1,141 @@
+use std::f32::consts::FRAC_PI_4;
+use crate::geometry::aabb::Aabb3;
+use crate::geometry::capsule::Capsule3;
+use crate::geometry::obb::Obb3;
+use crate::geometry::plane::Plane3;
+use crate::geometry::ray::Ray3;
+use crate::geometry::segment::Segment3;
+use crate::geometry::sphere::Sphere;
+use crate::geometry::triangle::Triangle3;
+use crate::geometry::EPSILON;
+use crate::math::affine::Affine3;
+use crate::math::quat::Quat;
+use crate::math::vec3::Vec3;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+fn close3(a: Vec3, b: Vec3) -> bool {
+    (a - b).magnitude() < 1e-5
+}
+
+#[test]
+fn test_overlap() {
+    let aabb = Aabb3::new(Vec3::ZERO, Vec3::ONE);
+
+    assert!(aabb.intersects_aabb(&Aabb3::new(Vec3::ONE, Vec3::splat(2.0))));
+    assert!(!aabb.intersects_aabb(&Aabb3::new(Vec3(0.0, 0.0, 1.1), Vec3::splat(2.0))));
+    assert!(aabb.contains_aabb(&Aabb3::new(Vec3::splat(0.25), Vec3::splat(0.75))));
+    assert!(Sphere::new(Vec3(2.0, 0.5, 0.5), 1.0).intersects_aabb(&aabb));
+    assert!(!Sphere::new(Vec3(2.0, 2.0, 2.0), 1.0).intersects_aabb(&aabb));
+    assert_eq!(aabb.surface_area(), 6.0);
+
+    // only the edge cross product axes separate these two boxes
+    let a = Obb3::new(Vec3::ZERO, Vec3::ONE, Quat::from_axis_angle(Vec3::Z, FRAC_PI_4));
+    let b = Obb3::new(Vec3(2.0, 2.0, 0.0), Vec3::ONE, Quat::from_axis_angle(Vec3::X, FRAC_PI_4));
+    assert!(!a.intersects_obb(&b));
+    assert!(a.intersects_obb(&Obb3::new(Vec3(1.5, 1.5, 0.0), Vec3::ONE, Quat::IDENTITY)));
+    assert!(a.intersects_sphere(&Sphere::new(Vec3(2.0, 0.0, 0.0), 0.6)));
+
+    let capsule = Capsule3::new(Vec3::ZERO, Vec3(0.0, 4.0, 0.0), 0.5);
+    assert!(capsule.intersects_capsule(&Capsule3::new(Vec3(-2.0, 2.0, 0.9), Vec3(2.0, 2.0, 0.9), 0.5)));
+    assert!(!capsule.intersects_capsule(&Capsule3::new(Vec3(-2.0, 2.0, 1.1), Vec3(2.0, 2.0, 1.1), 0.5)));
+    assert!(capsule.intersects_sphere(&Sphere::new(Vec3(0.0, 5.0, 0.0), 0.6)));
+
+    let plane = Plane3::from_point_normal(Vec3(0.0, 0.5, 0.0), Vec3::Y);
+    assert!(plane.intersects_aabb(&aabb));
+    assert!(!plane.intersects_sphere(&Sphere::new(Vec3(0.0, 2.0, 0.0), 1.0)));
+}
+
+#[test]
+fn test_closest() {
+    let triangle = Triangle3::new(Vec3(0.0, 0.0, 0.0), Vec3(2.0, 0.0, 0.0), Vec3(0.0, 2.0, 0.0));
+
+    assert_eq!(triangle.closest_point(Vec3(0.5, 0.5, 3.0)), Vec3(0.5, 0.5, 0.0));
+    assert_eq!(triangle.closest_point(Vec3(2.0, 2.0, 1.0)), Vec3(1.0, 1.0, 0.0));
+    assert_eq!(triangle.closest_point(Vec3(3.0, -1.0, 0.0)), Vec3(2.0, 0.0, 0.0));
+    assert_eq!(triangle.normal(), Some(Vec3::Z));
+    assert!(close3(triangle.barycentric(Vec3(0.5, 0.5, 1.0), EPSILON).unwrap(), Vec3(0.5, 0.25, 0.25)));
+
+    let degenerate = Triangle3::new(Vec3::ZERO, Vec3::X, Vec3::X * 2.0);
+    assert!(degenerate.normal().is_none() && degenerate.plane().is_none());
+    assert!(degenerate.barycentric(Vec3::ZERO, EPSILON).is_none());
+    assert_eq!(degenerate.closest_point(Vec3(1.5, 1.0, 0.0)), Vec3(1.5, 0.0, 0.0));
+
+    // skew segments, and a pair of points
+    let a = Segment3::new(Vec3(-1.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0));
+    let b = Segment3::new(Vec3(0.0, -1.0, 1.0), Vec3(0.0, 1.0, 1.0));
+    assert_eq!(a.closest_points(&b, EPSILON), (Vec3::ZERO, Vec3(0.0, 0.0, 1.0)));
+    let p = Segment3::new(Vec3::ONE, Vec3::ONE);
+    assert_eq!(p.closest_points(&p, EPSILON), (Vec3::ONE, Vec3::ONE));
+
+    let obb = Obb3::new(Vec3::ZERO, Vec3(1.0, 2.0, 3.0), Quat::IDENTITY);
+    assert_eq!(obb.closest_point(Vec3(5.0, 0.5, -5.0)), Vec3(1.0, 0.5, -3.0));
+    assert!(close(Capsule3::new(Vec3::ZERO, Vec3::Y, 0.5).distance_to_point(Vec3(0.0, 3.0, 0.0)), 1.5));
+
+    let (point, direction) = Plane3::from_point_normal(Vec3::ZERO, Vec3::X)
+        .intersect_plane(&Plane3::from_point_normal(Vec3(0.0, 2.0, 0.0), Vec3::Y))
+        .unwrap();
+    assert!(close3(point, Vec3(0.0, 2.0, 0.0)) && close3(direction, Vec3::Z));
+}
+
+#[test]
+fn test_ray() {
+    let ray = Ray3::new(Vec3(0.25, 0.25, 5.0), Vec3::NEG_Z);
+
+    let hit = ray.intersect_aabb(&Aabb3::new(Vec3::ZERO, Vec3::ONE)).unwrap();
+    assert_eq!((hit.t, hit.normal), (4.0, Vec3::Z));
+    let hit = ray.intersect_sphere(&Sphere::new(Vec3(0.25, 0.25, 0.0), 1.0)).unwrap();
+    assert_eq!((hit.t, hit.normal), (4.0, Vec3::Z));
+    let hit = ray.intersect_plane(&Plane3::from_point_normal(Vec3::ZERO, Vec3::NEG_Z)).unwrap();
+    assert_eq!((hit.t, hit.normal), (5.0, Vec3::Z));
+
+    // either side of the triangle is hit, with the normal facing the ray
+    let triangle = Triangle3::new(Vec3::ZERO, Vec3::X, Vec3::Y);
+    let hit = ray.intersect_triangle(&triangle, EPSILON).unwrap();
+    assert_eq!((hit.t, hit.normal), (5.0, Vec3::Z));
+    let flipped = Triangle3::new(Vec3::ZERO, Vec3::Y, Vec3::X);
+    assert_eq!(ray.intersect_triangle(&flipped, EPSILON).unwrap().normal, Vec3::Z);
+    let sideways = Ray3::new(Vec3(-1.0, 0.25, 0.0), Vec3::X);
+    assert!(sideways.intersect_triangle(&triangle, EPSILON).is_none());
+
+    // the body of the capsule, then a cap
+    let capsule = Capsule3::new(Vec3(0.0, -1.0, 0.0), Vec3(0.0, 1.0, 0.0), 0.5);
+    let hit = Ray3::new(Vec3(5.0, 0.0, 0.0), Vec3::NEG_X).intersect_capsule(&capsule).unwrap();
+    assert!(close(hit.t, 4.5) && close3(hit.normal, Vec3::X));
+    let hit = Ray3::new(Vec3(0.0, 5.0, 0.0), Vec3::NEG_Y).intersect_capsule(&capsule).unwrap();
+    assert!(close(hit.t, 3.5) && close3(hit.normal, Vec3::Y));
+
+    let obb = Obb3::new(Vec3::ZERO, Vec3::ONE, Quat::from_axis_angle(Vec3::Y, FRAC_PI_4));
+    let hit = Ray3::new(Vec3(5.0, 0.0, 0.0), Vec3::NEG_X).intersect_obb(&obb).unwrap();
+    assert!(close(hit.t, 5.0 - 2f32.sqrt()));
+
+    assert_eq!(ray.intersect_sphere(&Sphere::new(Vec3(0.0, 0.0, 5.0), 1.0)).unwrap().t, 0.0);
+    assert!(ray.intersect_sphere(&Sphere::new(Vec3(0.0, 0.0, 8.0), 1.0)).is_none());
+}
+
+#[test]
+fn test_transform() {
+    let rotation = Quat::from_axis_angle(Vec3(1.0, 2.0, 0.5), 0.7);
+    let affine = Affine3::from_scale_rotation_translation(Vec3::splat(2.0), rotation, Vec3(1.0, 2.0, 3.0));
+    let m = affine.to_matrix();
+    let p = Vec3(0.3, -0.2, 0.1);
+
+    let sphere = Sphere::new(Vec3::ZERO, 1.0).transform(&m);
+    assert!(close3(sphere.center, Vec3(1.0, 2.0, 3.0)) && close(sphere.radius, 2.0));
+
+    let obb = Obb3::from_aabb(&Aabb3::new(-Vec3::ONE, Vec3::ONE)).transform(&m);
+    assert!(obb.contains_point(affine.transform_point3(p)));
+    assert!(close3(obb.half_extents, Vec3::splat(2.0)));
+    assert!(obb.aabb().grow(1e-5).contains_aabb(&Aabb3::from_points(&obb.corners())));
+    assert!(Aabb3::new(-Vec3::ONE, Vec3::ONE).transform(&m).contains_point(affine.transform_point3(Vec3::ONE)));
+    assert_eq!(Aabb3::EMPTY.transform(&m), Aabb3::EMPTY);
+
+    let plane = Plane3::from_point_normal(p, Vec3(1.0, 1.0, 0.0)).transform(&m);
+    assert!(close(plane.signed_distance(affine.transform_point3(p)), 0.0));
+    assert!(close(plane.signed_distance(affine.transform_point3(p + Vec3(1.0, -1.0, 4.0))), 0.0));
+
+    let ray = Ray3::new(Vec3::ZERO, Vec3::X).transform(&m);
+    assert!(close3(ray.direction, rotation * Vec3::X));
+}
@@ -0,0 +1,42 @@
+use crate::math::half::{hgemm, Bf16, F16, HalfFloat, HalfMatrix};
+use crate::math::matrix::Matrix;
+
+#[test]
+fn test_f16_conversion() {
+    assert_eq!(F16::from_f32(1.0), F16(0x3c00));
+    assert_eq!(F16::from_f32(-2.0), F16(0xc000));
+    assert_eq!(F16::from_f32(65504.0), F16(0x7bff));
+    assert_eq!(F16::from_f32(1.0e6), F16(0x7c00));
+    assert_eq!(F16(0x0001).to_f32(), 2.0f32.powi(-24));
+    assert_eq!(F16::from_f32(2.0f32.powi(-24)), F16(0x0001));
+    assert!(F16::from_f32(f32::NAN).to_f32().is_nan());
+
+    // ties round to even
+    assert_eq!(F16::from_f32(1.0 + 2.0f32.powi(-11)), F16(0x3c00));
+    assert_eq!(F16::from_f32(1.0 + 3.0 * 2.0f32.powi(-11)), F16(0x3c02));
+}
+
+#[test]
+fn test_bf16_conversion() {
+    assert_eq!(Bf16::from_f32(1.0), Bf16(0x3f80));
+    assert_eq!(Bf16(0x3f80).to_f32(), 1.0);
+    assert_eq!(Bf16::from_f32(1.0 + 2.0f32.powi(-8)), Bf16(0x3f80));
+    assert!(Bf16::from_f32(f32::NAN).to_f32().is_nan());
+}
+
+#[test]
+fn test_hgemm_matches_f32() {
+    let a = Matrix::from_vec(3, 4, (0..12).map(|i| i as f32 * 0.3 - 1.0).collect());
+    let b = Matrix::from_vec(4, 2, (0..8).map(|i| i as f32 * -0.7 + 2.0).collect());
+    let reference = a.gemm(b.clone());
+
+    let res = hgemm(&HalfMatrix::<F16>::from_f32(&a), &HalfMatrix::<F16>::from_f32(&b));
+    for (x, y) in reference.data.iter().zip(res.data.iter()) {
+        assert!((x - y).abs() < 1e-2, "{x} vs {y}");
+    }
+
+    let res = hgemm(&HalfMatrix::<Bf16>::from_f32(&a), &HalfMatrix::<Bf16>::from_f32(&b));
+    for (x, y) in reference.data.iter().zip(res.data.iter()) {
+        assert!((x - y).abs() < 1e-1, "{x} vs {y}");
+    }
+}
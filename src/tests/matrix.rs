@@ -1,4 +1,8 @@
 use crate::math::matrix::Matrix;
+use crate::math::matrix_simd::transform_points;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
 
 #[test]
 fn test_transpose() {
@@ -86,6 +90,14 @@ fn test_mul_non_square() {
                             48, 37, 26]);
 }
 
+#[test]
+fn test_gemm_blocked() {
+    let a = Matrix::from_vec(5, 7, (0..35).collect::<Vec<i32>>());
+    let b = Matrix::from_vec(7, 3, (0..21).rev().collect::<Vec<i32>>());
+
+    assert_eq!(a.gemm_blocked(b.clone(), 2).data, a.gemm(b).data);
+}
+
 #[test]
 fn test_pad() {
     let a_data = vec![1, 2,
@@ -109,4 +121,66 @@ fn test_pad() {
                                 0, 0, 0, 0,
                                 0, 0, 0, 0]
     );
+}
+
+#[test]
+fn test_mul_vec() {
+    let m2 = Matrix::from_vec(2, 2, vec![
+        1.0, 2.0,
+        3.0, 4.0]);
+    assert_eq!(&m2 * Vec2(1.0, 1.0), Vec2(3.0, 7.0));
+
+    let m3 = Matrix::from_vec(3, 3, vec![
+        1.0, 2.0, 3.0,
+        4.0, 5.0, 6.0,
+        7.0, 8.0, 9.0]);
+    assert_eq!(&m3 * Vec3::Y, Vec3(2.0, 5.0, 8.0));
+
+    let m4 = Matrix::from_vec(4, 4, vec![
+        1.0, 0.0, 0.0, 5.0,
+        0.0, 1.0, 0.0, 6.0,
+        0.0, 0.0, 1.0, 7.0,
+        0.0, 0.0, 0.0, 1.0]);
+    assert_eq!(m4 * Vec4(1.0, 2.0, 3.0, 1.0), Vec4(6.0, 8.0, 10.0, 1.0));
+}
+
+#[test]
+fn test_transform_point_direction() {
+    let translate = Matrix::from_vec(4, 4, vec![
+        1.0, 0.0, 0.0, 5.0,
+        0.0, 1.0, 0.0, 6.0,
+        0.0, 0.0, 1.0, 7.0,
+        0.0, 0.0, 0.0, 1.0]);
+
+    assert_eq!(translate.transform_point3(Vec3::ZERO), Vec3(5.0, 6.0, 7.0));
+    assert_eq!(translate.transform_vector3(Vec3::X), Vec3::X);
+
+    let translate_2d = Matrix::from_vec(3, 3, vec![
+        1.0, 0.0, 2.0,
+        0.0, 1.0, 3.0,
+        0.0, 0.0, 1.0]);
+
+    assert_eq!(translate_2d.transform_point2(Vec2::X), Vec2(3.0, 3.0));
+    assert_eq!(translate_2d.transform_vector2(Vec2::X), Vec2::X);
+}
+
+#[test]
+fn test_transform_points() {
+    let m = Matrix::from_vec(4, 4, vec![
+        0.0, -1.0, 0.0, 1.0,
+        1.0, 0.0, 0.0, 2.0,
+        0.0, 0.0, 2.0, 3.0,
+        0.0, 0.0, 0.0, 1.0]);
+
+    // more than one full set of lanes, plus a remainder
+    let points = (0..7)
+        .map(|i| Vec3(i as f32, (i * 2) as f32, (i * 3) as f32))
+        .collect::<Vec<Vec3>>();
+    let mut out = vec![Vec3::ZERO; points.len()];
+
+    transform_points(&m, &points, &mut out);
+
+    for (p, o) in points.iter().zip(out.iter()) {
+        assert_eq!(m.transform_point3(*p), *o);
+    }
 }
\ No newline at end of file
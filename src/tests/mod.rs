@@ -1,4 +1,31 @@
 #![cfg(test)]
+mod affine;
+mod app;
+mod broadphase;
+mod bvh;
+mod camera;
+mod collision2d;
+mod collision3d;
+mod curves;
+mod ecs;
+mod deterministic;
+mod geometry2d;
+mod geometry3d;
+mod half;
 mod matrix;
+mod particles;
+mod physics2d;
+mod physics3d;
+mod projection;
+mod quantize;
+mod quat;
+mod random;
+mod render;
+mod scene;
+mod schedule;
+mod strategy;
+mod tuning;
 mod vec2;
-mod vec3;
\ No newline at end of file
+mod vec3;
+mod vec4;
+mod vec_family;
\ No newline at end of file
@@ -0,0 +1,190 @@
+use crate::geometry::plane::Plane3;
+use crate::geometry::triangle::Triangle3;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+use crate::particles::{Emitter, EmitterShape, Force, MeshSurface, OverLife, ParticleSystem};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// a system with a point emitter at the origin that only spawns in bursts, of particles that start still
+fn still(force: Force) -> ParticleSystem {
+    let emitter = Emitter::new(Vec3::ZERO, EmitterShape::Point, 0.0)
+        .with_speed(0.0, 0.0)
+        .with_lifetime(100.0, 100.0);
+    ParticleSystem::new(1000, 1).with_emitter(emitter).with_force(force)
+}
+
+#[test]
+fn test_emission_and_lifetime() {
+    let emitter = Emitter::new(Vec3::ZERO, EmitterShape::Point, 120.0).with_lifetime(0.5, 0.5);
+    let mut system = ParticleSystem::new(1000, 7).with_emitter(emitter);
+    for _ in 0..120 {
+        system.update(DT);
+    }
+    // two a frame, for the 30 frames a particle lives
+    assert_eq!(system.len(), 60);
+    assert!(system.particles().age.iter().all(|&age| age < 0.5));
+
+    system.emitters[0].burst(500);
+    system.update(DT);
+    assert_eq!(system.len(), 560);
+
+    // the pool stops filling at its limit, and emptying it leaves the emitters running
+    system.emitters[0].burst(1000);
+    system.update(DT);
+    assert_eq!(system.len(), system.max_particles());
+    system.clear();
+    system.emitters[0].active = false;
+    system.update(DT);
+    assert!(system.is_empty());
+}
+
+#[test]
+fn test_forces() {
+    // 13 particles, so both whole lanes and the leftovers get updated
+    let mut falling = still(Force::Gravity(Vec3(0.0, -9.81, 0.0)));
+    falling.emitters[0].burst(13);
+    let steps = 30;
+    for _ in 0..steps {
+        falling.update(DT);
+    }
+    let n = steps as f32;
+    let particles = falling.particles();
+    for i in 0..particles.len() {
+        assert!((particles.velocity(i).y + 9.81 * DT * n).abs() < 1e-4);
+        assert!((particles.position(i).y + 9.81 * DT * DT * n * (n + 1.0) / 2.0).abs() < 1e-4);
+    }
+
+    let mut dragged = still(Force::Drag(2.0));
+    dragged.emitters[0].speed = (10.0, 10.0);
+    dragged.emitters[0].burst(9);
+    for _ in 0..60 {
+        dragged.update(DT);
+    }
+    let expected = 10.0 * (1.0 / (1.0 + 2.0 * DT)).powi(60);
+    for i in 0..dragged.len() {
+        assert!((dragged.particles().velocity(i).magnitude() - expected).abs() < 1e-3);
+    }
+
+    // particles off to the side of the attractor head straight for it
+    let mut attracted = still(Force::Attractor { position: Vec3(5.0, 0.0, 0.0), strength: 10.0, radius: 0.5 });
+    attracted.emitters[0].burst(3);
+    attracted.update(DT);
+    let v = attracted.particles().velocity(0);
+    assert!(v.x > 0.0 && v.y.abs() < 1e-6 && v.z.abs() < 1e-6);
+
+    // and the vortex spins them round its axis, without pulling them in or along it
+    let vortex = Force::Vortex { center: Vec3(-1.0, 0.0, 0.0), axis: Vec3::Y, strength: 4.0, radius: 2.0 };
+    let mut swirled = still(vortex);
+    swirled.emitters[0].burst(3);
+    swirled.update(DT);
+    let v = swirled.particles().velocity(0);
+    assert!((v - Vec3(0.0, 0.0, -2.0 * DT)).magnitude() < 1e-5, "{v:?}");
+}
+
+#[test]
+fn test_emitter_shapes() {
+    let spawn = |shape: EmitterShape| {
+        let mut system = ParticleSystem::new(500, 3)
+            .with_emitter(Emitter::new(Vec3(1.0, 2.0, 3.0), shape, 0.0).with_speed(2.0, 2.0));
+        system.emitters[0].burst(500);
+        system.update(0.0);
+        let particles = system.particles().clone();
+        (0..500).map(move |i| (particles.position(i) - Vec3(1.0, 2.0, 3.0), particles.velocity(i)))
+    };
+
+    for (p, v) in spawn(EmitterShape::Point) {
+        assert!(p.magnitude() < 1e-6 && (v.magnitude() - 2.0).abs() < 1e-4);
+    }
+    let cone = EmitterShape::Cone { direction: Vec3(0.0, 0.0, 3.0), angle: 0.3 };
+    for (_, v) in spawn(cone) {
+        assert!(v.z / v.magnitude() >= 0.3f32.cos() - 1e-5);
+    }
+    for (p, v) in spawn(EmitterShape::Sphere { radius: 0.5, surface: false }) {
+        assert!(p.magnitude() <= 0.5 + 1e-5 && p.dot(v) >= 0.0);
+    }
+    for (p, _) in spawn(EmitterShape::Sphere { radius: 0.5, surface: true }) {
+        assert!((p.magnitude() - 0.5).abs() < 1e-5);
+    }
+
+    // a unit square in the xz plane and a big triangle below it, which should get most of the particles
+    let mesh = MeshSurface::new(vec![
+        Triangle3::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, 1.0), Vec3(1.0, 0.0, 1.0)),
+        Triangle3::new(Vec3(0.0, 0.0, 0.0), Vec3(1.0, 0.0, 1.0), Vec3(1.0, 0.0, 0.0)),
+        Triangle3::new(Vec3(0.0, -1.0, 0.0), Vec3(0.0, -1.0, 4.0), Vec3(4.0, -1.0, 0.0)),
+    ]);
+    assert!((mesh.area() - 9.0).abs() < 1e-5);
+    let (mut top, mut bottom) = (0, 0);
+    for (p, v) in spawn(EmitterShape::Mesh(mesh)) {
+        assert!((v - Vec3(0.0, 2.0, 0.0)).magnitude() < 1e-4);
+        if p.y.abs() < 1e-5 {
+            top += 1;
+        } else {
+            assert!((p.y + 1.0).abs() < 1e-5 && p.x + p.z <= 4.0 + 1e-4);
+            bottom += 1;
+        }
+    }
+    assert!((40..90).contains(&top) && top + bottom == 500, "{top}");
+}
+
+#[test]
+fn test_plane_collision() {
+    let mut system = still(Force::Gravity(Vec3(0.0, -10.0, 0.0)))
+        .with_plane(Plane3::from_point_normal(Vec3(0.0, -1.0, 0.0), Vec3::Y), 0.5, 0.0);
+    system.emitters[0].speed = (0.0, 0.0);
+    system.emitters[0].burst(1);
+    system.update(DT);
+    let mut bounced = None;
+    for _ in 0..300 {
+        let before = system.particles().velocity(0).y;
+        system.update(DT);
+        let after = system.particles().velocity(0).y;
+        assert!(system.particles().position(0).y >= -1.0 - 1e-6);
+        if bounced.is_none() && after > 0.0 {
+            bounced = Some((before, after));
+        }
+    }
+    let (before, after) = bounced.expect("the particle should have bounced");
+    assert!((after + 0.5 * (before - 10.0 * DT)).abs() < 1e-3, "{before} {after}");
+    assert!((system.particles().position(0).y + 1.0).abs() < 0.01);
+}
+
+#[test]
+fn test_over_life_and_determinism() {
+    let size = OverLife::new(vec![(0.0, 1.0), (0.5, 3.0), (1.0, 0.0)]);
+    assert_eq!(size.sample(-1.0), 1.0);
+    assert_eq!(size.sample(0.25), 2.0);
+    assert_eq!(size.sample(0.75), 1.5);
+    assert_eq!(size.sample(2.0), 0.0);
+
+    // enough particles to spread over several parallel chunks
+    let build = || {
+        let emitter = Emitter::new(Vec3::ZERO, EmitterShape::Cone { direction: Vec3::Y, angle: 0.5 }, 6000.0)
+            .with_speed(4.0, 6.0)
+            .with_lifetime(1.0, 2.0)
+            .with_size(0.5, 1.0);
+        ParticleSystem::new(20_000, 42)
+            .with_emitter(emitter)
+            .with_force(Force::Gravity(Vec3(0.0, -9.81, 0.0)))
+            .with_force(Force::Drag(0.1))
+            .with_force(Force::Vortex { center: Vec3::ZERO, axis: Vec3::Y, strength: 3.0, radius: 5.0 })
+            .with_plane(Plane3::from_point_normal(Vec3::ZERO, Vec3::Y), 0.3, 0.2)
+            .with_size_over_life(size.clone())
+            .with_color_over_life(OverLife::new(vec![(0.0, Vec4::ONE), (1.0, Vec4(1.0, 0.0, 0.0, 0.0))]))
+    };
+    let (mut a, mut b) = (build(), build());
+    for _ in 0..90 {
+        a.update(DT);
+        b.update(DT);
+    }
+    assert!(a.len() > 5000);
+    assert_eq!(a.particles(), b.particles());
+
+    let particles = a.particles();
+    for i in 0..particles.len() {
+        let t = particles.age[i] / particles.lifetime[i];
+        assert_eq!(particles.size[i], particles.start_size[i] * size.sample(t));
+        assert!((particles.color[i].w - (1.0 - t)).abs() < 1e-5);
+        assert!(particles.position(i).y >= -1e-6);
+    }
+}
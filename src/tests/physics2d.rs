@@ -0,0 +1,180 @@
+use std::f32::consts::{FRAC_PI_4, PI};
+use crate::math::vec2::Vec2;
+use crate::physics::body2d::{Collider2, RigidBody2};
+use crate::physics::joint2d::{DistanceJoint2, JointKind2, PrismaticJoint2, RevoluteJoint2};
+use crate::physics::world2d::PhysicsWorld2;
+use crate::physics::{BodyHandle, BodyType};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// a world with a wide static floor whose top is at y = 0
+fn world_with_ground() -> (PhysicsWorld2, BodyHandle) {
+    let mut world = PhysicsWorld2::default();
+    let ground = world.add_body(RigidBody2::new(BodyType::Static, Vec2(0.0, -0.5))
+        .with_collider(Collider2::rect(Vec2(50.0, 0.5))));
+    (world, ground)
+}
+
+fn dynamic(position: Vec2, collider: Collider2) -> RigidBody2 {
+    RigidBody2::new(BodyType::Dynamic, position).with_collider(collider)
+}
+
+#[test]
+fn test_mass_properties() {
+    let circle = Collider2::circle(2.0).with_density(3.0).mass_properties();
+    assert!((circle.mass - 12.0 * PI).abs() < 1e-4);
+    assert!((circle.inertia - circle.mass * 2.0).abs() < 1e-3);
+
+    let rect = Collider2::rect(Vec2(2.0, 1.0)).mass_properties();
+    assert!((rect.mass - 8.0).abs() < 1e-5 && rect.center.magnitude() < 1e-6);
+    assert!((rect.inertia - 8.0 * (16.0 + 4.0) / 12.0).abs() < 1e-4);
+
+    // a capsule weighs a box plus a circle
+    let capsule = Collider2::capsule(Vec2(-1.0, 0.0), Vec2(1.0, 0.0), 0.5).mass_properties();
+    assert!((capsule.mass - (2.0 + 0.25 * PI)).abs() < 1e-5);
+    assert!(capsule.center.magnitude() < 1e-6);
+
+    // an offset collider moves the center of mass, and the inertia about it stays the same
+    let body = dynamic(Vec2(5.0, 0.0), Collider2::polygon(&[Vec2(1.0, -1.0), Vec2(3.0, -1.0), Vec2(3.0, 1.0), Vec2(1.0, 1.0)]));
+    assert!((body.local_center() - Vec2(2.0, 0.0)).magnitude() < 1e-5);
+    assert!((body.world_center() - Vec2(7.0, 0.0)).magnitude() < 1e-5);
+    assert!((body.inertia() - 4.0 * 8.0 / 12.0).abs() < 1e-4);
+    assert_eq!(RigidBody2::new(BodyType::Static, Vec2::ZERO).with_collider(Collider2::circle(1.0)).inv_mass(), 0.0);
+}
+
+#[test]
+fn test_resting_and_sleeping() {
+    let (mut world, _) = world_with_ground();
+    let crate_ = world.add_body(dynamic(Vec2(0.0, 3.0), Collider2::rect(Vec2(0.5, 0.5))));
+    let ball = world.add_body(dynamic(Vec2(3.0, 2.0), Collider2::circle(0.5)));
+    let pill = world.add_body(dynamic(Vec2(-3.0, 2.0), Collider2::capsule(Vec2(-0.5, 0.0), Vec2(0.5, 0.0), 0.25)));
+    for _ in 0..240 {
+        world.step(DT);
+    }
+    let resting = |world: &PhysicsWorld2, body, height: f32| {
+        let body: &RigidBody2 = world.body(body).unwrap();
+        assert!((body.position.y - height).abs() < 0.03, "{:?}", body.position);
+        assert!(body.angle.abs() < 0.01);
+    };
+    resting(&world, crate_, 0.5);
+    resting(&world, ball, 0.5);
+    resting(&world, pill, 0.25);
+    assert!(world.bodies().all(|(_, b)| !b.is_dynamic() || !b.is_awake()));
+
+    // a push wakes it, and a sleeping body stays put however long the world runs
+    let before = world.body(ball).unwrap().position;
+    for _ in 0..60 {
+        world.step(DT);
+    }
+    assert_eq!(world.body(ball).unwrap().position, before);
+    world.body_mut(ball).unwrap().linear_velocity = Vec2(2.0, 0.0);
+    world.step(DT);
+    assert!(world.body(ball).unwrap().is_awake() && world.body(ball).unwrap().position.x > before.x);
+    assert!(!world.body(crate_).unwrap().is_awake());
+}
+
+#[test]
+fn test_restitution_and_friction() {
+    let (mut world, _) = world_with_ground();
+    let bouncy = world.add_body(dynamic(Vec2(0.0, 5.0), Collider2::circle(0.5).with_restitution(0.8)));
+    let dull = world.add_body(dynamic(Vec2(4.0, 5.0), Collider2::circle(0.5)));
+    let mut highest = 0.0f32;
+    let mut fell = false;
+    for _ in 0..120 {
+        world.step(DT);
+        let body = world.body(bouncy).unwrap();
+        fell |= body.position.y < 1.0;
+        if fell {
+            highest = highest.max(body.position.y);
+        }
+    }
+    // dropped 4.5, it should bounce back about 0.64 of the way
+    assert!(highest > 0.5 + 4.5 * 0.5 && highest < 0.5 + 4.5 * 0.8, "{highest}");
+    assert!(world.body(dull).unwrap().position.y < 0.6);
+
+    // a block slides to a stop on the floor, but keeps sliding on ice
+    let (mut world, _) = world_with_ground();
+    let rough = world.add_body(dynamic(Vec2(0.0, 0.5), Collider2::rect(Vec2(0.5, 0.5)))
+        .with_velocity(Vec2(3.0, 0.0), 0.0));
+    let icy = world.add_body(dynamic(Vec2(-20.0, 0.5), Collider2::rect(Vec2(0.5, 0.5)).with_friction(0.0))
+        .with_velocity(Vec2(3.0, 0.0), 0.0));
+    for _ in 0..120 {
+        world.step(DT);
+    }
+    // friction of 0.6 stops it after 3^2 / (2 * 0.6 * 9.81), about 0.76
+    let stopped = world.body(rough).unwrap();
+    assert!(stopped.linear_velocity.magnitude() < 0.05);
+    assert!((stopped.position.x - 0.76).abs() < 0.1, "{:?}", stopped.position);
+    let icy = world.body(icy).unwrap();
+    assert!((icy.linear_velocity.x - 3.0).abs() < 0.01 && icy.position.x > -20.0 + 5.5);
+}
+
+#[test]
+fn test_joints() {
+    let mut world = PhysicsWorld2::default();
+    let anchor = world.add_body(RigidBody2::new(BodyType::Static, Vec2(0.0, 10.0)));
+
+    // a pendulum on a rod keeps its length as it swings
+    let bob = world.add_body(dynamic(Vec2(3.0, 10.0), Collider2::circle(0.25)));
+    let rod = DistanceJoint2::between(world.body(anchor).unwrap(), world.body(bob).unwrap(), Vec2(0.0, 10.0), Vec2(3.0, 10.0));
+    world.add_joint(anchor, bob, rod);
+
+    // a door hinged at its left edge can only swing down a quarter turn
+    let door = world.add_body(dynamic(Vec2(11.0, 10.0), Collider2::rect(Vec2(1.0, 0.1))));
+    let hinge = RevoluteJoint2::at(world.body(anchor).unwrap(), world.body(door).unwrap(), Vec2(10.0, 10.0))
+        .with_limits(-FRAC_PI_4, FRAC_PI_4);
+    let hinge = world.add_joint(anchor, door, hinge);
+
+    // a slider on a diagonal rail stops at the end of it
+    let slider = world.add_body(dynamic(Vec2(-10.0, 10.0), Collider2::rect(Vec2(0.2, 0.2))));
+    let rail = PrismaticJoint2::along(world.body(anchor).unwrap(), world.body(slider).unwrap(), Vec2(-10.0, 10.0), Vec2(1.0, 1.0))
+        .with_limits(-2.0, 2.0);
+    let rail = world.add_joint(anchor, slider, rail);
+
+    for i in 0..300 {
+        world.step(DT);
+        let bob = world.body(bob).unwrap();
+        assert!((bob.position.distance(Vec2(0.0, 10.0)) - 3.0).abs() < 0.05, "step {i}: {:?}", bob.position);
+    }
+    let door = world.body(door).unwrap();
+    assert!((door.angle + FRAC_PI_4).abs() < 0.03, "{}", door.angle);
+    assert!((door.to_world(Vec2(-1.0, 0.0)) - Vec2(10.0, 10.0)).magnitude() < 0.02);
+    let JointKind2::Revolute(hinge) = &world.joint(hinge).unwrap().kind else { unreachable!() };
+    assert!((hinge.angle() + FRAC_PI_4).abs() < 0.03);
+
+    let slider = world.body(slider).unwrap();
+    let offset = slider.position - Vec2(-10.0, 10.0);
+    assert!((offset - Vec2(-2.0, -2.0) * 0.5f32.sqrt()).magnitude() < 0.03, "{offset:?}");
+    assert!(slider.angle.abs() < 1e-3);
+    let JointKind2::Prismatic(rail) = &world.joint(rail).unwrap().kind else { unreachable!() };
+    assert!((rail.translation() + 2.0).abs() < 0.03);
+}
+
+#[test]
+fn test_determinism() {
+    let build = || {
+        let (mut world, _) = world_with_ground();
+        for i in 0..30 {
+            let position = Vec2((i % 5) as f32 * 1.1 - 2.0 + (i / 5) as f32 * 0.05, 0.5 + (i / 5) as f32 * 1.2);
+            let collider = match i % 3 {
+                0 => Collider2::circle(0.5),
+                1 => Collider2::rect(Vec2(0.5, 0.5)),
+                _ => Collider2::capsule(Vec2(-0.3, 0.0), Vec2(0.3, 0.0), 0.3),
+            };
+            world.add_body(dynamic(position, collider).with_angle(i as f32 * 0.1));
+        }
+        world
+    };
+    let (mut a, mut b) = (build(), build());
+    for _ in 0..200 {
+        a.step(DT);
+        b.step(DT);
+    }
+    for ((_, a), (_, b)) in a.bodies().zip(b.bodies()) {
+        assert_eq!(a.position.x.to_bits(), b.position.x.to_bits());
+        assert_eq!(a.position.y.to_bits(), b.position.y.to_bits());
+        assert_eq!(a.angle.to_bits(), b.angle.to_bits());
+    }
+    // everything landed on the floor rather than falling through it
+    assert!(a.bodies().all(|(_, b)| !b.is_dynamic() || b.position.y > 0.0));
+}
@@ -0,0 +1,214 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_6};
+use crate::math::quat::Quat;
+use crate::math::vec3::Vec3;
+use crate::physics::body3d::{inverse3, Collider3, RigidBody3};
+use crate::physics::joint3d::{BallJoint3, ConeTwistJoint3, FixedJoint3, HingeJoint3, JointKind3};
+use crate::physics::world3d::PhysicsWorld3;
+use crate::physics::{BodyHandle, BodyType};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn assert_close(a: Vec3, b: Vec3, tolerance: f32) {
+    assert!((a - b).magnitude() < tolerance, "{a:?} != {b:?}");
+}
+
+/// a world with a wide static floor whose top is at y = 0
+fn world_with_ground() -> (PhysicsWorld3, BodyHandle) {
+    let mut world = PhysicsWorld3::default();
+    let ground = world.add_body(RigidBody3::new(BodyType::Static, Vec3(0.0, -0.5, 0.0))
+        .with_collider(Collider3::cuboid(Vec3(50.0, 0.5, 50.0))));
+    (world, ground)
+}
+
+fn dynamic(position: Vec3, collider: Collider3) -> RigidBody3 {
+    RigidBody3::new(BodyType::Dynamic, position).with_collider(collider)
+}
+
+#[test]
+fn test_inertia_tensors() {
+    let block = dynamic(Vec3::ZERO, Collider3::cuboid(Vec3(2.0, 1.0, 0.5)));
+    assert!((block.mass() - 8.0).abs() < 1e-5);
+    let expected = [8.0 / 3.0 * 1.25, 8.0 / 3.0 * 4.25, 8.0 / 3.0 * 5.0];
+    for (i, e) in expected.iter().enumerate() {
+        assert!((block.local_inertia().data[i * 4] - e).abs() < 1e-4);
+    }
+
+    // turned a quarter about z, the long side now runs along y, so spinning about x is hardest
+    let turned = block.clone().with_orientation(Quat::from_rotation_z(FRAC_PI_2));
+    let inv = turned.world_inv_inertia();
+    assert!((inv.data[0] - 1.0 / expected[1]).abs() < 1e-4 && (inv.data[4] - 1.0 / expected[0]).abs() < 1e-4);
+
+    // two spheres off to each side: the center stays put and the parallel axis term shows up
+    let sphere = Collider3::sphere(0.5);
+    let m = sphere.mass_properties().mass;
+    let dumbbell = dynamic(Vec3::ZERO, sphere.clone().with_transform(Vec3(-2.0, 0.0, 0.0), Quat::IDENTITY))
+        .with_collider(sphere.with_transform(Vec3(2.0, 0.0, 0.0), Quat::IDENTITY));
+    assert_close(dumbbell.local_center(), Vec3::ZERO, 1e-6);
+    let i = dumbbell.local_inertia();
+    assert!((i.data[0] - 2.0 * 0.4 * m * 0.25).abs() < 1e-4);
+    assert!((i.data[4] - 2.0 * (0.4 * m * 0.25 + 4.0 * m)).abs() < 1e-3);
+}
+
+#[test]
+fn test_hull_mass_properties() {
+    // a box given as a hull, with some points inside it, weighs the same as the box itself
+    let h = Vec3(2.0, 1.0, 0.5);
+    let mut points: Vec<Vec3> = (0..8).map(|i| Vec3(
+        if i & 1 == 0 { -h.x } else { h.x },
+        if i & 2 == 0 { -h.y } else { h.y },
+        if i & 4 == 0 { -h.z } else { h.z },
+    ) + Vec3(1.0, 2.0, 3.0)).collect();
+    points.extend([Vec3(1.0, 2.0, 3.0), Vec3(1.5, 2.5, 3.0), Vec3(0.0, 1.5, 3.25)]);
+    let hull = Collider3::hull(&points).with_density(2.0).mass_properties();
+    let cuboid = Collider3::cuboid(h).with_density(2.0).mass_properties();
+    assert!((hull.mass - cuboid.mass).abs() < 1e-4);
+    assert_close(hull.center, Vec3(1.0, 2.0, 3.0), 1e-5);
+    for (a, b) in hull.inertia.data.iter().zip(&cuboid.inertia.data) {
+        assert!((a - b).abs() < 1e-3, "{:?} != {:?}", hull.inertia, cuboid.inertia);
+    }
+
+    // the corner of a unit cube is lopsided, so it has products of inertia
+    let corner = Collider3::hull(&[Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z]).mass_properties();
+    assert!((corner.mass - 1.0 / 6.0).abs() < 1e-6);
+    assert_close(corner.center, Vec3::splat(0.25), 1e-6);
+    assert!((corner.inertia.data[0] - 1.0 / 80.0).abs() < 1e-6);
+    assert!((corner.inertia.data[1] - 1.0 / 480.0).abs() < 1e-6);
+
+    // a flat hull has no volume to weigh anything
+    let flat = Collider3::hull(&[Vec3::ZERO, Vec3::X, Vec3::Y, Vec3(1.0, 1.0, 0.0)]).mass_properties();
+    assert_eq!(flat.mass, 0.0);
+}
+
+#[test]
+fn test_gyroscopic_spin() {
+    // a brick spun about its middle axis tumbles, but keeps its energy and angular momentum
+    let mut world = PhysicsWorld3::new(Vec3::ZERO);
+    let brick = world.add_body(dynamic(Vec3::ZERO, Collider3::cuboid(Vec3(1.5, 0.5, 1.0)))
+        .with_velocity(Vec3::ZERO, Vec3(0.01, 0.0, 4.0)));
+    let momentum = |body: &RigidBody3| {
+        let inertia = inverse3(&body.world_inv_inertia());
+        &inertia * body.angular_velocity
+    };
+    let (energy, start) = {
+        let body = world.body(brick).unwrap();
+        (body.kinetic_energy(), momentum(body))
+    };
+    let mut flipped = false;
+    for _ in 0..600 {
+        world.step(DT);
+        let body = world.body(brick).unwrap();
+        assert!(body.kinetic_energy() <= energy * 1.001);
+        assert!((body.orientation.magnitude() - 1.0).abs() < 1e-5);
+        flipped |= (body.orientation.inverse() * body.angular_velocity).z < 0.0;
+    }
+    let body = world.body(brick).unwrap();
+    assert!(flipped, "spinning about its middle axis should flip it over");
+    assert!(body.kinetic_energy() > energy * 0.9);
+    assert!((momentum(body) - start).magnitude() < start.magnitude() * 0.1);
+    assert_close(body.position, Vec3::ZERO, 1e-6);
+}
+
+#[test]
+fn test_resting_and_sleeping() {
+    let (mut world, _) = world_with_ground();
+    let crate_ = world.add_body(dynamic(Vec3(0.0, 2.0, 0.0), Collider3::cuboid(Vec3::splat(0.5)))
+        .with_orientation(Quat::from_rotation_y(0.3)));
+    let on_top = world.add_body(dynamic(Vec3(0.1, 3.5, 0.0), Collider3::cuboid(Vec3::splat(0.5))));
+    let ball = world.add_body(dynamic(Vec3(3.0, 1.0, 0.0), Collider3::sphere(0.5)));
+    for _ in 0..300 {
+        world.step(DT);
+    }
+    assert!((world.body(crate_).unwrap().position.y - 0.5).abs() < 0.03);
+    assert!((world.body(on_top).unwrap().position.y - 1.5).abs() < 0.05);
+    assert!((world.body(ball).unwrap().position.y - 0.5).abs() < 0.03);
+    assert!(world.bodies().all(|(_, b)| !b.is_dynamic() || !b.is_awake()));
+
+    // knocking the bottom box wakes the one resting on it, but not the ball
+    world.body_mut(crate_).unwrap().linear_velocity = Vec3(1.0, 0.0, 0.0);
+    world.step(DT);
+    assert!(world.body(on_top).unwrap().is_awake());
+    assert!(!world.body(ball).unwrap().is_awake());
+}
+
+#[test]
+fn test_joints() {
+    let mut world = PhysicsWorld3::default();
+    let anchor = world.add_body(RigidBody3::new(BodyType::Static, Vec3(0.0, 10.0, 0.0)));
+    let body = |world: &PhysicsWorld3, handle| world.body(handle).unwrap().clone();
+
+    // a ball joint pendulum swings out of plane but stays on its anchor
+    let bob = world.add_body(dynamic(Vec3(2.0, 10.0, 1.0), Collider3::sphere(0.25)));
+    let ball = BallJoint3::at(&body(&world, anchor), &body(&world, bob), Vec3(0.0, 10.0, 0.0));
+    world.add_joint(anchor, bob, ball);
+
+    // a door hinged about y, pushed round until it hits its limit
+    let door = world.add_body(dynamic(Vec3(11.0, 10.0, 0.0), Collider3::cuboid(Vec3(1.0, 1.0, 0.05))));
+    let hinge = HingeJoint3::at(&body(&world, anchor), &body(&world, door), Vec3(10.0, 10.0, 0.0), Vec3::Y)
+        .with_limits(-FRAC_PI_4, FRAC_PI_4)
+        .with_motor(2.0, 50.0);
+    let hinge = world.add_joint(anchor, door, hinge);
+
+    // a plank welded sideways to the anchor doesn't sag or turn
+    let plank = world.add_body(dynamic(Vec3(-11.0, 10.0, 0.0), Collider3::cuboid(Vec3(1.0, 0.1, 0.1)))
+        .with_orientation(Quat::from_rotation_x(0.5)));
+    let weld = FixedJoint3::at(&body(&world, anchor), &body(&world, plank), Vec3(-10.0, 10.0, 0.0));
+    world.add_joint(anchor, plank, weld);
+
+    // an arm hanging off a shoulder pointing sideways drops to the edge of its cone
+    let arm = world.add_body(dynamic(Vec3(0.0, 10.0, -11.0), Collider3::capsule(0.8, 0.1)
+        .with_transform(Vec3::ZERO, Quat::from_rotation_x(FRAC_PI_2))));
+    let shoulder = ConeTwistJoint3::at(&body(&world, anchor), &body(&world, arm), Vec3(0.0, 10.0, -10.0), Vec3::NEG_Z, FRAC_PI_6)
+        .with_twist_limits(-0.1, 0.1);
+    let shoulder = world.add_joint(anchor, arm, shoulder);
+
+    for _ in 0..300 {
+        world.step(DT);
+        let bob = world.body(bob).unwrap();
+        assert!((bob.position.distance(Vec3(0.0, 10.0, 0.0)) - 5f32.sqrt()).abs() < 0.05, "{:?}", bob.position);
+    }
+
+    let JointKind3::Hinge(hinge) = &world.joint(hinge).unwrap().kind else { unreachable!() };
+    assert!((hinge.angle() - FRAC_PI_4).abs() < 0.03, "{}", hinge.angle());
+    let door = world.body(door).unwrap();
+    assert_close(door.to_world(Vec3(-1.0, 0.0, 0.0)), Vec3(10.0, 10.0, 0.0), 0.03);
+    assert_close(door.orientation * Vec3::Y, Vec3::Y, 0.02);
+
+    let plank = world.body(plank).unwrap();
+    assert_close(plank.position, Vec3(-11.0, 10.0, 0.0), 0.03);
+    assert!(plank.orientation.dot(Quat::from_rotation_x(0.5)).abs() > 0.9995);
+
+    let JointKind3::ConeTwist(shoulder) = &world.joint(shoulder).unwrap().kind else { unreachable!() };
+    assert!((shoulder.swing_angle() - FRAC_PI_6).abs() < 0.03, "{}", shoulder.swing_angle());
+    assert!(shoulder.twist_angle().abs() < 0.12);
+    let arm = world.body(arm).unwrap();
+    assert_close(arm.to_world(Vec3(0.0, 0.0, 1.0)), Vec3(0.0, 10.0, -10.0), 0.03);
+}
+
+#[test]
+fn test_determinism() {
+    let build = || {
+        let (mut world, _) = world_with_ground();
+        for i in 0..24 {
+            let position = Vec3((i % 4) as f32 * 1.2 - 1.8, 0.6 + (i / 4) as f32 * 1.3, (i % 3) as f32 * 0.3);
+            let collider = match i % 3 {
+                0 => Collider3::sphere(0.5),
+                1 => Collider3::cuboid(Vec3::splat(0.5)),
+                _ => Collider3::capsule(0.3, 0.3),
+            };
+            world.add_body(dynamic(position, collider).with_orientation(Quat::from_rotation_y(i as f32 * 0.2)));
+        }
+        world
+    };
+    let (mut a, mut b) = (build(), build());
+    for _ in 0..200 {
+        a.step(DT);
+        b.step(DT);
+    }
+    for ((_, a), (_, b)) in a.bodies().zip(b.bodies()) {
+        assert_eq!(a.position.x.to_bits(), b.position.x.to_bits());
+        assert_eq!(a.position.y.to_bits(), b.position.y.to_bits());
+        assert_eq!(a.position.z.to_bits(), b.position.z.to_bits());
+        assert_eq!(a.orientation.w.to_bits(), b.orientation.w.to_bits());
+    }
+    assert!(a.bodies().all(|(_, b)| !b.is_dynamic() || b.position.y > 0.0));
+}
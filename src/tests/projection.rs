@@ -0,0 +1,44 @@
+use std::f32::consts::FRAC_PI_2;
+use crate::math::matrix::Matrix;
+use crate::math::vec3::Vec3;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+#[test]
+fn test_perspective_depth() {
+    let (near, far) = (0.5, 100.0);
+    let p = Matrix::perspective(FRAC_PI_2, 2.0, near, far);
+    let r = Matrix::perspective_reversed(FRAC_PI_2, 2.0, near, far);
+
+    assert!(close(p.transform_point3(Vec3(0.0, 0.0, -near)).z, 0.0));
+    assert!(close(p.transform_point3(Vec3(0.0, 0.0, -far)).z, 1.0));
+    assert!(close(r.transform_point3(Vec3(0.0, 0.0, -near)).z, 1.0));
+    assert!(close(r.transform_point3(Vec3(0.0, 0.0, -far)).z, 0.0));
+
+    // a 90 degree field of view puts the top edge at 45 degrees, aspect squeezes x
+    let edge = p.transform_point3(Vec3(2.0, 1.0, -1.0));
+    assert!(close(edge.x, 1.0) && close(edge.y, 1.0));
+}
+
+#[test]
+fn test_perspective_infinite() {
+    let p = Matrix::perspective_infinite(1.0, 1.0, 0.1);
+    let r = Matrix::perspective_infinite_reversed(1.0, 1.0, 0.1);
+
+    assert!(close(p.transform_point3(Vec3(0.0, 0.0, -0.1)).z, 0.0));
+    assert!(close(r.transform_point3(Vec3(0.0, 0.0, -0.1)).z, 1.0));
+    assert!(p.transform_point3(Vec3(0.0, 0.0, -1e6)).z > 0.999);
+    assert!(r.transform_point3(Vec3(0.0, 0.0, -1e6)).z < 0.001);
+}
+
+#[test]
+fn test_orthographic() {
+    let o = Matrix::orthographic(-2.0, 2.0, -1.0, 1.0, 1.0, 11.0);
+
+    let a = o.transform_point3(Vec3(-2.0, -1.0, -1.0));
+    let b = o.transform_point3(Vec3(2.0, 1.0, -11.0));
+    assert!(close(a.x, -1.0) && close(a.y, -1.0) && close(a.z, 0.0));
+    assert!(close(b.x, 1.0) && close(b.y, 1.0) && close(b.z, 1.0));
+}
@@ -0,0 +1,71 @@
+use crate::math::matrix::Matrix;
+use crate::math::quantize::{qgemm_f32, QuantParams, QuantizedMatrix};
+
+fn sample(rows: usize, cols: usize, seed: f32) -> Matrix<f32> {
+    let data = (0..rows * cols)
+        .map(|i| ((i as f32 + seed) * 0.37).sin() * 4.0)
+        .collect();
+    Matrix::from_vec(rows, cols, data)
+}
+
+#[test]
+fn test_zero_is_exact() {
+    let params = QuantParams::from_range::<u8>(-1.0, 3.0);
+    let q: u8 = params.quantize(0.0);
+    assert_eq!(params.dequantize(q), 0.0);
+
+    let params = QuantParams::from_range::<i8>(0.5, 2.0);
+    let q: i8 = params.quantize(0.0);
+    assert_eq!(params.dequantize(q), 0.0);
+}
+
+#[test]
+fn test_saturation() {
+    // far outside the calibrated range, both ends saturate rather than wrapping around
+    let params = QuantParams::new(1e-6, 10);
+    assert_eq!(params.quantize::<i8>(1e4), i8::MAX);
+    assert_eq!(params.quantize::<i8>(-1e4), i8::MIN);
+    assert_eq!(params.quantize::<u8>(f32::MAX), u8::MAX);
+    assert_eq!(params.quantize::<u8>(f32::MIN), u8::MIN);
+
+    let params = QuantParams::new(1e-6, -10);
+    assert_eq!(params.quantize::<i8>(1e4), i8::MAX);
+    assert_eq!(params.quantize::<i8>(-1e4), i8::MIN);
+}
+
+#[test]
+fn test_round_trip() {
+    let m = sample(5, 7, 1.0);
+    let q = QuantizedMatrix::<i8>::quantize(&m);
+    let back = q.dequantize();
+
+    for (a, b) in m.data.iter().zip(back.data.iter()) {
+        assert!((a - b).abs() <= q.params.scale * 0.5 + f32::EPSILON);
+    }
+}
+
+#[test]
+fn test_qgemm_matches_f32() {
+    let a = sample(9, 21, 0.0);
+    let b = sample(21, 6, 3.0);
+    let reference = a.gemm(b.clone());
+
+    let qa = QuantizedMatrix::<i8>::quantize(&a);
+    let qb = QuantizedMatrix::<i8>::quantize(&b);
+    let res = qgemm_f32(&qa, &qb);
+
+    // each term is off by at most half a step in each operand
+    let tolerance = 21.0 * 4.0 * (qa.params.scale + qb.params.scale);
+    for (x, y) in reference.data.iter().zip(res.data.iter()) {
+        assert!((x - y).abs() < tolerance, "{x} vs {y}");
+    }
+
+    let qa = QuantizedMatrix::<u8>::quantize(&a);
+    let qb = QuantizedMatrix::<u8>::quantize(&b);
+    let res = qgemm_f32(&qa, &qb);
+
+    let tolerance = 21.0 * 4.0 * (qa.params.scale + qb.params.scale);
+    for (x, y) in reference.data.iter().zip(res.data.iter()) {
+        assert!((x - y).abs() < tolerance, "{x} vs {y}");
+    }
+}
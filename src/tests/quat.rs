@@ -0,0 +1,108 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use crate::math::matrix::Matrix;
+use crate::math::quat::{EulerOrder, Quat};
+use crate::math::vec3::Vec3;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+fn close_vec(a: Vec3, b: Vec3) -> bool {
+    (a - b).magnitude() < 1e-5
+}
+
+fn close_quat(a: Quat, b: Quat) -> bool {
+    // q and -q are the same rotation
+    a.dot(b).abs() > 1.0 - 1e-5
+}
+
+fn close_matrix(a: &Matrix<f32>, b: &Matrix<f32>) -> bool {
+    a.data.iter().zip(&b.data).all(|(x, y)| close(*x, *y))
+}
+
+#[test]
+fn test_rotate() {
+    let q = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+
+    assert!(close_vec(q * Vec3::X, Vec3::Y));
+    assert!(close_vec(q.inverse() * Vec3::Y, Vec3::X));
+    assert!(close_vec((q * q) * Vec3::X, Vec3::NEG_X));
+    assert!(close_vec(q.conjugate() * (q * Vec3(1.0, 2.0, 3.0)), Vec3(1.0, 2.0, 3.0)));
+
+    let (axis, angle) = q.to_axis_angle();
+    assert!(close_vec(axis, Vec3::Z));
+    assert!(close(angle, FRAC_PI_2));
+}
+
+#[test]
+fn test_matrix_round_trip() {
+    let a = Quat::from_axis_angle(Vec3(1.0, 2.0, 3.0), 0.7);
+    let b = Quat::from_axis_angle(Vec3(-2.0, 0.5, 1.0), 2.9);
+    let v = Vec3(0.3, -1.2, 2.5);
+
+    // the hamilton product matches the matrix product
+    let product = (a * b).to_matrix3();
+    assert!(close_matrix(&product, &a.to_matrix3().mul_3x3(&b.to_matrix3())));
+    assert!(close_vec(&product * v, a * (b * v)));
+
+    assert!(close_quat(Quat::from_matrix(&a.to_matrix3()), a));
+    assert!(close_quat(Quat::from_matrix(&b.to_matrix4()), b));
+    assert!(close_quat(Quat::from_matrix(&product), a * b));
+
+    // half turns hit the branches that don't divide by w
+    for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+        let q = Quat::from_axis_angle(axis, PI);
+        assert!(close_quat(Quat::from_matrix(&q.to_matrix3()), q));
+    }
+}
+
+#[test]
+fn test_euler() {
+    let orders = [
+        EulerOrder::XYZ, EulerOrder::XZY, EulerOrder::YXZ,
+        EulerOrder::YZX, EulerOrder::ZXY, EulerOrder::ZYX,
+    ];
+
+    for order in orders {
+        let q = Quat::from_euler(order, 0.4, -0.9, 2.1);
+        let (a, b, c) = q.to_euler(order);
+        assert!(close(a, 0.4) && close(b, -0.9) && close(c, 2.1), "{order:?}");
+
+        // gimbal lock still gives back the same rotation
+        let q = Quat::from_euler(order, 0.3, FRAC_PI_2, 0.5);
+        let (a, b, c) = q.to_euler(order);
+        assert!(close_quat(Quat::from_euler(order, a, b, c), q), "{order:?}");
+    }
+
+    let q = Quat::from_euler(EulerOrder::XYZ, 0.1, 0.2, 0.3);
+    let expected = Quat::from_rotation_x(0.1) * Quat::from_rotation_y(0.2) * Quat::from_rotation_z(0.3);
+    assert!(close_quat(q, expected));
+}
+
+#[test]
+fn test_rotation_arc() {
+    let from = Vec3(1.0, 1.0, 0.0);
+    let to = Vec3(0.0, -2.0, 3.0);
+    let q = Quat::from_rotation_arc(from, to);
+    assert!(close_vec(q * from.normalize(), to.normalize()));
+
+    let q = Quat::from_rotation_arc(Vec3::X, Vec3::NEG_X);
+    assert!(close_vec(q * Vec3::X, Vec3::NEG_X));
+
+    assert!(close_quat(Quat::from_rotation_arc(Vec3::Y, Vec3::Y), Quat::IDENTITY));
+}
+
+#[test]
+fn test_interpolate() {
+    let a = Quat::IDENTITY;
+    let b = Quat::from_axis_angle(Vec3::Y, FRAC_PI_2);
+
+    assert!(close_quat(a.slerp(b, 0.0), a));
+    assert!(close_quat(a.slerp(b, 1.0), b));
+    assert!(close_quat(a.slerp(b, 0.5), Quat::from_axis_angle(Vec3::Y, FRAC_PI_4)));
+    assert!(close_quat(a.nlerp(b, 0.5), Quat::from_axis_angle(Vec3::Y, FRAC_PI_4)));
+
+    // the shorter path is taken even when the signs disagree
+    assert!(close_quat(a.slerp(-b, 0.5), Quat::from_axis_angle(Vec3::Y, FRAC_PI_4)));
+    assert!(close(Quat(1.0, 2.0, 3.0, 4.0).normalize().magnitude(), 1.0));
+}
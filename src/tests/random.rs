@@ -0,0 +1,101 @@
+use crate::geometry::aabb::{Aabb2, Aabb3};
+use crate::geometry::triangle::Triangle2;
+use crate::math::random::Pcg32;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+#[test]
+fn test_sequence() {
+    // the reference pcg32 outputs for seed 42 on stream 54, which must never change
+    let mut rng = Pcg32::with_stream(42, 54);
+    let expected = [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+    for value in expected {
+        assert_eq!(rng.next_u32(), value);
+    }
+
+    let (mut a, mut b) = (Pcg32::new(7), Pcg32::new(7));
+    assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+    assert_ne!(Pcg32::new(7).next_u32(), Pcg32::new(8).next_u32());
+    assert_ne!(Pcg32::with_stream(7, 1).next_u32(), Pcg32::with_stream(7, 2).next_u32());
+}
+
+#[test]
+fn test_uniform() {
+    let mut rng = Pcg32::new(1);
+    let mut counts = [0; 6];
+    for _ in 0..60000 {
+        let x = rng.next_f32();
+        assert!((0.0..1.0).contains(&x));
+        let r = rng.range(-2.0, 3.0);
+        assert!((-2.0..3.0).contains(&r));
+        let i = rng.range_i32(-3, 3);
+        assert!((-3..3).contains(&i));
+        counts[rng.below(6) as usize] += 1;
+    }
+    assert!(counts.iter().all(|c| (9500..10500).contains(c)));
+
+    let mut items: Vec<u32> = (0..50).collect();
+    rng.shuffle(&mut items);
+    assert_ne!(items, (0..50).collect::<Vec<_>>());
+    items.sort_unstable();
+    assert_eq!(items, (0..50).collect::<Vec<_>>());
+    assert!(rng.choose::<u32>(&[]).is_none());
+}
+
+#[test]
+fn test_normal_and_weighted() {
+    let mut rng = Pcg32::new(2);
+    let samples: Vec<f32> = (0..50000).map(|_| rng.normal(3.0, 2.0)).collect();
+    let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+    let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / samples.len() as f32;
+    assert!((mean - 3.0).abs() < 0.05, "mean {mean}");
+    assert!((variance - 4.0).abs() < 0.1, "variance {variance}");
+
+    let weights = [1.0, 0.0, 3.0, -2.0];
+    let mut counts = [0; 4];
+    for _ in 0..40000 {
+        counts[rng.weighted_index(&weights).unwrap()] += 1;
+    }
+    assert_eq!(counts[1] + counts[3], 0);
+    assert!((9500..10500).contains(&counts[0]));
+    assert!(rng.weighted_index(&[0.0, -1.0]).is_none());
+}
+
+#[test]
+fn test_geometric() {
+    let mut rng = Pcg32::new(3);
+    let triangle = Triangle2::new(Vec2(0.0, 0.0), Vec2(4.0, 0.0), Vec2(0.0, 2.0));
+    let aabb = Aabb3::new(Vec3(-1.0, 2.0, 3.0), Vec3(1.0, 3.0, 5.0));
+    let mut mean = Vec3::ZERO;
+    for _ in 0..10000 {
+        assert!((rng.on_circle().magnitude() - 1.0).abs() < 1e-5);
+        let direction = rng.on_sphere();
+        assert!((direction.magnitude() - 1.0).abs() < 1e-5);
+        mean += direction;
+        assert!(rng.in_disk().magnitude() < 1.0 && rng.in_ball().magnitude() < 1.0);
+        assert!(triangle.contains_point(rng.in_triangle2(&triangle)));
+        assert!(aabb.contains_point(rng.in_aabb3(&aabb)));
+    }
+    // directions don't lean any particular way
+    assert!((mean / 10000.0).magnitude() < 0.03);
+}
+
+#[test]
+fn test_poisson_disk() {
+    let bounds = Aabb2::new(Vec2::ZERO, Vec2(20.0, 10.0));
+    let points = Pcg32::new(4).poisson_disk2(&bounds, 1.0);
+    assert_eq!(points, Pcg32::new(4).poisson_disk2(&bounds, 1.0));
+    assert!(points.iter().all(|p| bounds.contains_point(*p)));
+    for (i, p) in points.iter().enumerate() {
+        assert!(points[i + 1..].iter().all(|q| p.distance(*q) >= 1.0));
+    }
+    // packed densely enough that no gap is big enough for another point
+    assert!(points.len() > 100, "{}", points.len());
+
+    let bounds = Aabb3::new(Vec3::ZERO, Vec3(6.0, 6.0, 6.0));
+    let points = Pcg32::new(5).poisson_disk3(&bounds, 1.0);
+    for (i, p) in points.iter().enumerate() {
+        assert!(points[i + 1..].iter().all(|q| p.distance(*q) >= 1.0));
+    }
+    assert!(points.len() > 150, "{}", points.len());
+}
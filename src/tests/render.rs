@@ -0,0 +1,200 @@
+use std::f32::consts::FRAC_PI_2;
+use std::sync::atomic::{AtomicU32, Ordering};
+use crate::math::affine::Affine3;
+use crate::math::matrix::Matrix;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+use crate::render::image::{adler32, crc32};
+use crate::render::{CullMode, DepthTest, Framebuffer, Renderer, Vertex};
+
+const RED: Vec4 = Vec4(1.0, 0.0, 0.0, 1.0);
+const BLUE: Vec4 = Vec4(0.0, 0.0, 1.0, 1.0);
+
+fn identity() -> Matrix<f32> {
+    Affine3::IDENTITY.to_matrix()
+}
+
+/// a counterclockwise square in the xy plane at `z`, spanning `-size..=size`
+fn square(size: f32, z: f32, color: Vec4) -> Vec<Vertex> {
+    [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .map(|(x, y)| Vertex::new(Vec3(x * size, y * size, z)).with_color(color))
+        .to_vec()
+}
+
+const SQUARE: [[u32; 3]; 2] = [[0, 1, 2], [0, 2, 3]];
+
+#[test]
+fn test_fill_rule() {
+    // a fan of triangles meeting off pixel centers and on them, covering the whole screen
+    let (width, height) = (67, 45);
+    let counts: Vec<AtomicU32> = (0..width * height).map(|_| AtomicU32::new(0)).collect();
+    let mut renderer = Renderer::new(width, height);
+    renderer.depth_test = DepthTest::Always;
+    let mut vertices = vec![Vertex::new(Vec3(0.123, -0.31, 0.5))];
+    let rim = [(-1.0, -1.0), (-0.2, -1.0), (1.0, -1.0), (1.0, 0.0), (1.0, 1.0), (0.3, 1.0), (-1.0, 1.0), (-1.0, 0.1)];
+    vertices.extend(rim.map(|(x, y)| Vertex::new(Vec3(x, y, 0.5))));
+    let indices: Vec<[u32; 3]> = (0..8).map(|i| [0, i + 1, (i + 1) % 8 + 1]).collect();
+    renderer.draw_with(&vertices, &indices, &identity(), |fragment| {
+        counts[fragment.y * width + fragment.x].fetch_add(1, Ordering::Relaxed);
+        RED
+    });
+    assert!(counts.iter().all(|c| c.load(Ordering::Relaxed) == 1));
+
+    // and two triangles sharing an edge straight through pixel centers still touch each pixel once
+    let counts: Vec<AtomicU32> = (0..16).map(|_| AtomicU32::new(0)).collect();
+    let mut renderer = Renderer::new(4, 4);
+    let vertices = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0), (-0.25, 1.0), (-0.25, -1.0)]
+        .map(|(x, y)| Vertex::new(Vec3(x, y, 0.5)));
+    renderer.draw_with(&vertices, &[[5, 1, 2], [5, 2, 4], [0, 5, 4], [0, 4, 3]], &identity(), |fragment| {
+        counts[fragment.y * 4 + fragment.x].fetch_add(1, Ordering::Relaxed);
+        RED
+    });
+    assert!(counts.iter().all(|c| c.load(Ordering::Relaxed) == 1));
+}
+
+#[test]
+fn test_depth_and_culling() {
+    let perspective = Matrix::perspective(FRAC_PI_2, 1.0, 0.1, 100.0);
+    let view = Affine3::look_at(Vec3(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y).to_matrix();
+    let mut renderer = Renderer::new(64, 64).with_camera(view, perspective);
+
+    // the nearer square wins whichever order they're drawn in
+    for near_first in [false, true] {
+        renderer.clear(Vec4::ZERO);
+        let (near, far) = (square(1.0, 1.0, RED), square(3.0, -1.0, BLUE));
+        let order = if near_first { [&near, &far] } else { [&far, &near] };
+        for vertices in order {
+            renderer.draw(vertices, &SQUARE, &identity());
+        }
+        assert_eq!(renderer.framebuffer.pixel(32, 32), RED);
+        assert_eq!(renderer.framebuffer.pixel(32, 18), BLUE);
+        assert_eq!(renderer.framebuffer.pixel(1, 1), Vec4::ZERO);
+        let depth = renderer.framebuffer.depth_at(32, 32);
+        assert!(depth > 0.0 && depth < renderer.framebuffer.depth_at(32, 18));
+    }
+
+    // a square turned away from the camera is culled, unless culling is off or flipped
+    let away = Affine3::from_axis_angle(Vec3::Y, std::f32::consts::PI).to_matrix();
+    let facing = |renderer: &mut Renderer| {
+        renderer.clear(Vec4::ZERO);
+        renderer.draw_with(&square(1.0, 0.0, RED), &SQUARE, &away, |f| if f.front_facing { RED } else { BLUE });
+        renderer.framebuffer.pixel(32, 32)
+    };
+    assert_eq!(facing(&mut renderer), Vec4::ZERO);
+    renderer.cull = CullMode::None;
+    assert_eq!(facing(&mut renderer), BLUE);
+    renderer.cull = CullMode::Front;
+    assert_eq!(facing(&mut renderer), BLUE);
+}
+
+#[test]
+fn test_perspective_correct_interpolation() {
+    // a floor running away from the camera, with v going from 0 at the near edge to 1 at the far one
+    let projection = Matrix::perspective(FRAC_PI_2, 1.0, 0.5, 50.0);
+    let mut renderer = Renderer::new(64, 64).with_camera(identity(), projection);
+    let (near, far) = (-1.0, -21.0);
+    let vertices = [(-4.0, near, 0.0), (4.0, near, 0.0), (4.0, far, 1.0), (-4.0, far, 1.0)]
+        .map(|(x, z, v)| Vertex::new(Vec3(x, -1.0, z)).with_uv(Vec2(0.5, v)));
+    renderer.draw_with(&vertices, &SQUARE, &identity(), |f| Vec4(f.varyings.uv.x, f.varyings.uv.y, 0.0, 1.0));
+
+    let mut checked = 0;
+    for y in 33..64 {
+        let pixel = renderer.framebuffer.pixel(32, y);
+        if pixel.w == 0.0 {
+            continue
+        }
+        // the ray through the pixel center hits the floor at z = -1 / ndc y
+        let ndc_y = 1.0 - (y as f32 + 0.5) / 32.0;
+        let z = 1.0 / ndc_y;
+        let expected = (z - near) / (far - near);
+        assert!((pixel.y - expected).abs() < 5e-3, "{y}: {} != {expected}", pixel.y);
+        assert!((pixel.x - 0.5).abs() < 1e-4);
+        checked += 1;
+    }
+    assert!(checked > 20);
+}
+
+#[test]
+fn test_clipping() {
+    let projection = Matrix::perspective(FRAC_PI_2, 1.0, 1.0, 10.0);
+    let mut renderer = Renderer::new(32, 32).with_camera(identity(), projection);
+    renderer.cull = CullMode::None;
+
+    // a floor running from behind the camera, and a wall leaning back through the far plane
+    let floor = [(-0.5, 5.0), (0.5, 5.0), (0.5, -5.0), (-0.5, -5.0)]
+        .map(|(x, z)| Vertex::new(Vec3(x, -0.5, z)).with_color(RED));
+    renderer.draw(&floor, &SQUARE, &identity());
+    let beyond = [(-50.0, -50.0, -5.0), (50.0, -50.0, -5.0), (50.0, 50.0, -15.0), (-50.0, 50.0, -15.0)]
+        .map(|(x, y, z)| Vertex::new(Vec3(x, y, z)).with_color(BLUE));
+    renderer.draw(&beyond, &SQUARE, &identity());
+
+    let framebuffer = &renderer.framebuffer;
+    assert!(framebuffer.depth().iter().all(|&d| (0.0..=1.0).contains(&d)));
+    assert!(framebuffer.color().iter().all(|c| c.is_finite()));
+    // the floor is cut off at the near plane, short of the bottom of the screen
+    assert_eq!(framebuffer.pixel(16, 20), RED);
+    assert_ne!(framebuffer.pixel(16, 31), RED);
+    // and the wall is cut off at the far plane, which it crosses level with the camera
+    assert_eq!(framebuffer.pixel(2, 28), BLUE);
+    assert_eq!(framebuffer.pixel(16, 2), Vec4::ZERO);
+
+    // a triangle entirely behind the camera draws nothing
+    let mut renderer = Renderer::new(8, 8).with_camera(identity(), Matrix::perspective(FRAC_PI_2, 1.0, 1.0, 10.0));
+    renderer.draw(&square(1.0, 3.0, RED), &SQUARE, &identity());
+    assert!(renderer.framebuffer.color().iter().all(|&c| c == Vec4::ZERO));
+}
+
+#[test]
+fn test_image_output() {
+    assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    assert_eq!(adler32(b"Wikipedia"), 0x11E60398);
+    assert_eq!(adler32(&vec![0xFF; 100_000]), {
+        let (mut a, mut b) = (1u64, 0u64);
+        for _ in 0..100_000 {
+            a = (a + 0xFF) % 65521;
+            b = (b + a) % 65521;
+        }
+        ((b << 16) | a) as u32
+    });
+
+    let mut framebuffer = Framebuffer::new(300, 100);
+    framebuffer.clear_color(Vec4(1.0, 0.5, 0.0, 1.0));
+    let mut ppm = Vec::new();
+    framebuffer.write_ppm(&mut ppm).unwrap();
+    assert!(ppm.starts_with(b"P6\n300 100\n255\n"));
+    assert_eq!(ppm.len(), 15 + 300 * 100 * 3);
+    assert_eq!(&ppm[15..18], &[255, 128, 0]);
+
+    // walk the chunks, checking their crcs, then undo the stored deflate blocks
+    let mut png = Vec::new();
+    framebuffer.write_png(&mut png).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let (mut at, mut kinds, mut idat) = (8, Vec::new(), Vec::new());
+    while at < png.len() {
+        let len = u32::from_be_bytes(png[at..at + 4].try_into().unwrap()) as usize;
+        let body = &png[at + 4..at + 8 + len];
+        assert_eq!(crc32(body), u32::from_be_bytes(png[at + 8 + len..at + 12 + len].try_into().unwrap()));
+        kinds.push(String::from_utf8(body[..4].to_vec()).unwrap());
+        if &body[..4] == b"IDAT" {
+            idat.extend_from_slice(&body[4..]);
+        }
+        at += 12 + len;
+    }
+    assert_eq!(kinds, ["IHDR", "IDAT", "IEND"]);
+    assert_eq!(idat[0..2], [0x78, 0x01]);
+    let (mut at, mut raw) = (2, Vec::new());
+    loop {
+        let last = idat[at] & 1 == 1;
+        let len = u16::from_le_bytes([idat[at + 1], idat[at + 2]]);
+        assert_eq!(!len, u16::from_le_bytes([idat[at + 3], idat[at + 4]]));
+        raw.extend_from_slice(&idat[at + 5..at + 5 + len as usize]);
+        at += 5 + len as usize;
+        if last {
+            break
+        }
+    }
+    assert_eq!(u32::from_be_bytes(idat[at..at + 4].try_into().unwrap()), adler32(&raw));
+    assert_eq!(raw.len(), 100 * (1 + 300 * 4));
+    assert_eq!(&raw[..5], &[0, 255, 128, 0, 255]);
+}
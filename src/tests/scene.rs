@@ -0,0 +1,136 @@
+use std::f32::consts::FRAC_PI_2;
+use crate::math::quat::Quat;
+use crate::math::vec3::Vec3;
+use crate::scene::{NodeId, SceneGraph, Transform};
+
+fn assert_close(a: Vec3, b: Vec3) {
+    assert!((a - b).magnitude() < 1e-4, "{a:?} != {b:?}");
+}
+
+/// a ship turned a quarter turn about y, with a turret on its right and a barrel on that
+fn ship(graph: &mut SceneGraph) -> (NodeId, NodeId, NodeId) {
+    let ship = graph.insert(Transform {
+        translation: Vec3(10.0, 0.0, 0.0),
+        rotation: Quat::from_rotation_y(FRAC_PI_2),
+        scale: Vec3::ONE,
+    }, None);
+    let turret = graph.insert(Transform::from_translation(Vec3::X), Some(ship));
+    let barrel = graph.insert(Transform::from_translation(Vec3(0.0, 0.5, 2.0)), Some(turret));
+    (ship, turret, barrel)
+}
+
+#[test]
+fn test_propagate() {
+    let mut graph = SceneGraph::new();
+    let (ship, turret, barrel) = ship(&mut graph);
+    graph.propagate();
+    assert_close(graph.world_translation(turret).unwrap(), Vec3(10.0, 0.0, -1.0));
+    assert_close(graph.world_translation(barrel).unwrap(), Vec3(12.0, 0.5, -1.0));
+
+    // moving the ship moves what's on it, once propagated
+    graph.local_mut(ship).unwrap().translation = Vec3(0.0, 5.0, 0.0);
+    assert_close(graph.world_translation(barrel).unwrap(), Vec3(12.0, 0.5, -1.0));
+    graph.propagate();
+    assert_close(graph.world_translation(turret).unwrap(), Vec3(0.0, 5.0, -1.0));
+    assert_close(graph.world_translation(barrel).unwrap(), Vec3(2.0, 5.5, -1.0));
+
+    // scale carries down too
+    graph.local_mut(turret).unwrap().scale = Vec3::splat(2.0);
+    graph.propagate();
+    assert_close(graph.world_translation(barrel).unwrap(), Vec3(4.0, 6.0, -1.0));
+    let world = graph.compute_world(barrel).unwrap();
+    assert!(world.data.iter().zip(&graph.world(barrel).unwrap().data).all(|(a, b)| (a - b).abs() < 1e-5));
+}
+
+#[test]
+fn test_set_parent() {
+    let mut graph = SceneGraph::new();
+    let (ship, turret, barrel) = ship(&mut graph);
+    let dock = graph.insert(Transform {
+        translation: Vec3(-3.0, 1.0, 4.0),
+        rotation: Quat::from_axis_angle(Vec3(1.0, 1.0, 0.0).normalize(), 0.7),
+        scale: Vec3::splat(0.5),
+    }, None);
+    graph.propagate();
+    let before = graph.world_translation(barrel).unwrap();
+
+    assert!(graph.set_parent(turret, Some(dock)));
+    assert_eq!(graph.parent(turret), Some(dock));
+    assert!(graph.children(ship).is_empty());
+    graph.propagate();
+    assert_close(graph.world_translation(barrel).unwrap(), before);
+
+    // the dock now moves it instead of the ship
+    graph.local_mut(dock).unwrap().translation += Vec3::Y;
+    graph.local_mut(ship).unwrap().translation += Vec3::X;
+    graph.propagate();
+    assert_close(graph.world_translation(barrel).unwrap(), before + Vec3::Y);
+
+    assert!(graph.set_parent(barrel, None));
+    assert_eq!(graph.roots(), &[ship, dock, barrel]);
+    graph.propagate();
+    assert_close(graph.world_translation(barrel).unwrap(), before + Vec3::Y);
+
+    // a node can't go under itself or its own descendants
+    assert!(!graph.set_parent(dock, Some(turret)));
+    assert!(!graph.set_parent(dock, Some(dock)));
+}
+
+#[test]
+fn test_traversal() {
+    let mut graph = SceneGraph::new();
+    let (ship, turret, barrel) = ship(&mut graph);
+    let engine = graph.insert(Transform::IDENTITY, Some(ship));
+    let other = graph.insert(Transform::IDENTITY, None);
+
+    assert_eq!(graph.iter().collect::<Vec<_>>(), vec![ship, turret, barrel, engine, other]);
+    assert_eq!(graph.descendants(turret).collect::<Vec<_>>(), vec![turret, barrel]);
+    assert_eq!(graph.ancestors(barrel).collect::<Vec<_>>(), vec![turret, ship]);
+
+    assert!(graph.remove(turret));
+    assert!(!graph.remove(turret) && !graph.contains(barrel));
+    assert_eq!(graph.len(), 3);
+    assert_eq!(graph.children(ship), &[engine]);
+
+    // the slot comes back with a new generation
+    let again = graph.insert(Transform::IDENTITY, Some(barrel));
+    assert!(again.index() == barrel.index() || again.index() == turret.index());
+    assert_eq!(graph.parent(again), None);
+    assert_eq!(graph.roots(), &[ship, other, again]);
+}
+
+#[test]
+fn test_propagate_parallel() {
+    let mut graph = SceneGraph::new();
+    let mut nodes = Vec::new();
+    for i in 0..50 {
+        let root = graph.insert(Transform::from_rotation(Quat::from_rotation_z(i as f32 * 0.1)), None);
+        let mut parent = root;
+        for j in 0..20 {
+            parent = graph.insert(Transform {
+                translation: Vec3(1.0, j as f32 * 0.1, 0.0),
+                rotation: Quat::from_rotation_x(0.05),
+                scale: Vec3::splat(1.01),
+            }, Some(parent));
+            nodes.push(parent);
+        }
+    }
+    let mut parallel = graph.clone();
+    graph.propagate();
+    parallel.propagate_parallel();
+    for node in &nodes {
+        assert_eq!(graph.world(*node).unwrap().data, parallel.world(*node).unwrap().data);
+    }
+
+    // only the dirty subtrees change
+    let (moved, still) = (graph.world_translation(nodes[60]).unwrap(), graph.world_translation(nodes[0]).unwrap());
+    graph.local_mut(graph.roots()[3]).unwrap().translation = Vec3::Z;
+    parallel.local_mut(parallel.roots()[3]).unwrap().translation = Vec3::Z;
+    graph.propagate();
+    parallel.propagate_parallel();
+    for node in &nodes {
+        assert_eq!(graph.world(*node).unwrap().data, parallel.world(*node).unwrap().data);
+    }
+    assert_close(parallel.world_translation(nodes[60]).unwrap(), moved + Vec3::Z);
+    assert_eq!(parallel.world_translation(nodes[0]).unwrap(), still);
+}
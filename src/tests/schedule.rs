@@ -0,0 +1,141 @@
+use std::sync::{Arc, Mutex};
+use crate::ecs::entity::Entity;
+use crate::ecs::query::Changed;
+use crate::ecs::schedule::{Schedule, ScheduleError, System};
+use crate::ecs::world::World;
+use crate::math::vec2::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position(Vec2);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Velocity(Vec2);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Health(f32);
+
+#[derive(Debug, Default, PartialEq)]
+struct Time(f32);
+
+fn noop(name: &str) -> System {
+    System::new(name, |_| {})
+}
+
+#[test]
+fn test_stages() {
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(noop("move").query::<(&mut Position, &Velocity), ()>())
+        .add_system(noop("heal").write::<Health>().read_resource::<Time>())
+        .add_system(noop("render").read::<Position>().read::<Health>())
+        .add_system(noop("clock").write_resource::<Time>());
+    schedule.build().unwrap();
+
+    // readers share a stage, anything conflicting waits for the next one
+    assert_eq!(schedule.stages(), vec![vec!["move", "heal"], vec!["render", "clock"]]);
+    let conflicts: Vec<_> = schedule.ambiguities().iter().map(|a| (a.a.as_str(), a.b.as_str())).collect();
+    assert_eq!(conflicts, vec![("move", "render"), ("heal", "render"), ("heal", "clock")]);
+    assert!(schedule.ambiguities()[2].conflicts[0].ends_with("Time"));
+}
+
+#[test]
+fn test_ordering() {
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(noop("render").read::<Position>().after("move"))
+        .add_system(noop("move").write::<Position>())
+        .add_system(noop("input").write::<Velocity>().before("move"))
+        .add_system(noop("audio"));
+    schedule.build().unwrap();
+
+    assert_eq!(schedule.stages(), vec![vec!["input", "audio"], vec!["move"], vec!["render"]]);
+    assert!(schedule.ambiguities().is_empty());
+}
+
+#[test]
+fn test_errors() {
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(noop("a").after("c"))
+        .add_system(noop("b").after("a"))
+        .add_system(noop("c").after("b"))
+        .add_system(noop("d").after("a"));
+    let error = schedule.build().unwrap_err();
+    assert_eq!(error, ScheduleError::Cycle(vec!["a".into(), "b".into(), "c".into()]));
+    assert_eq!(error.to_string(), "ordering cycle: a -> b -> c -> a");
+
+    let mut schedule = Schedule::new();
+    schedule.add_system(noop("a").before("z"));
+    assert_eq!(schedule.build(), Err(ScheduleError::UnknownSystem { system: "a".into(), dependency: "z".into() }));
+
+    let mut schedule = Schedule::new();
+    schedule.add_system(noop("a")).add_system(noop("a"));
+    assert_eq!(schedule.build(), Err(ScheduleError::DuplicateName("a".into())));
+}
+
+#[test]
+fn test_run() {
+    let mut world = World::new();
+    world.insert_resource(Time(0.5));
+    for i in 0..100 {
+        world.spawn((Position(Vec2::ZERO), Velocity(Vec2(i as f32, 0.0)), Health(1.0)));
+    }
+
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(System::new("move", |cx| {
+            let dt = cx.resource::<Time>().unwrap().0;
+            cx.query::<(&mut Position, &Velocity)>().for_each(|(mut p, v)| p.0 += v.0 * dt);
+        }).query::<(&mut Position, &Velocity), ()>().read_resource::<Time>())
+        .add_system(System::new("damage", |cx| {
+            cx.query::<&mut Health>().for_each(|mut h| h.0 -= 0.25);
+        }).write::<Health>())
+        .add_system(System::new("reap", |cx| {
+            let dead = cx.query::<(Entity, &Health, &Position)>().entities().into_iter()
+                .filter(|e| cx.world().get::<Health>(*e).unwrap().0 <= 0.0 && cx.world().get::<Position>(*e).unwrap().0.x > 100.0)
+                .collect::<Vec<_>>();
+            for e in dead {
+                cx.commands().despawn(e);
+            }
+        }).read::<Health>().read::<Position>().after("damage").after("move"));
+
+    for _ in 0..4 {
+        schedule.run(&mut world);
+    }
+    assert_eq!(schedule.stages(), vec![vec!["move", "damage"], vec!["reap"]]);
+    // positions reach i * 2, so everything past 100 is gone once health runs out
+    assert_eq!(world.len(), 51);
+    world.query::<(&Position, &Velocity)>().for_each(|(p, v)| assert_eq!(p.0, v.0 * 2.0));
+}
+
+#[test]
+fn test_change_detection() {
+    let mut world = World::new();
+    let a = world.spawn((Position(Vec2::ZERO), Velocity(Vec2::X)));
+    world.spawn((Position(Vec2::ZERO), Velocity(Vec2::ZERO)));
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(System::new("track", move |cx| {
+            log.lock().unwrap().push(cx.query_filtered::<Entity, Changed<Position>>().count());
+        }).query::<Entity, Changed<Position>>())
+        .add_system(System::new("move", |cx| {
+            cx.query::<(&mut Position, &Velocity)>().for_each(|(mut p, v)| {
+                if v.0 != Vec2::ZERO {
+                    p.0 += v.0;
+                }
+            });
+        }).query::<(&mut Position, &Velocity), ()>().before("track"));
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    world.get_mut::<Position>(a).unwrap().0 = Vec2::ZERO;
+    world.increment_change_tick();
+    world.spawn((Position(Vec2::ONE),));
+    schedule.run(&mut world);
+
+    // everything is new on the first run, then only what moved or was spawned
+    assert_eq!(*seen.lock().unwrap(), vec![2, 1, 2]);
+}
@@ -0,0 +1,61 @@
+use crate::math::matrix::Matrix;
+use crate::math::strategy::MatmulStrategy;
+
+fn sample(rows: usize, cols: usize) -> Matrix<i64> {
+    Matrix::from_vec(rows, cols, (0..(rows * cols) as i64).map(|i| i % 7 - 3).collect())
+}
+
+#[test]
+fn test_strategies_agree() {
+    let a = sample(9, 13);
+    let b = sample(13, 6);
+    let expected = a.gemm(b.clone());
+
+    for strategy in [
+        MatmulStrategy::Auto,
+        MatmulStrategy::Naive,
+        MatmulStrategy::Blocked,
+        MatmulStrategy::Strassen { cutoff: 1 },
+        MatmulStrategy::Strassen { cutoff: 4 },
+        MatmulStrategy::Parallel,
+    ] {
+        assert_eq!(a.mul_with(&b, strategy).data, expected.data, "{strategy}");
+    }
+}
+
+#[test]
+fn test_simd_strategy() {
+    let a = sample(4, 4);
+    let b = sample(4, 4);
+
+    assert_eq!(
+        a.mul_with(&b, MatmulStrategy::Simd).data,
+        a.gemm(b.clone()).data
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_simd_strategy_wrong_shape() {
+    let a = sample(5, 5);
+    a.plan(&a, MatmulStrategy::Simd);
+}
+
+#[test]
+fn test_plan_auto() {
+    let small = sample(3, 3);
+    let expected = if cfg!(feature = "deterministic") {
+        MatmulStrategy::Naive
+    } else {
+        MatmulStrategy::Simd
+    };
+    assert_eq!(small.plan(&small, MatmulStrategy::Auto).strategy, expected);
+
+    let medium = sample(20, 20);
+    let plan = medium.plan(&medium, MatmulStrategy::Auto);
+    assert_eq!(plan.strategy, MatmulStrategy::Naive);
+    assert_eq!(plan.size, 32);
+
+    let pinned = medium.plan(&medium, MatmulStrategy::Strassen { cutoff: 8 });
+    assert_eq!(pinned.strategy, MatmulStrategy::Strassen { cutoff: 8 });
+}
@@ -0,0 +1,81 @@
+use std::marker::PhantomData;
+use crate::math::matrix::Matrix;
+use crate::math::strategy::MatmulStrategy;
+use crate::math::tuning::{MatmulTuning, ParseTuningError};
+
+#[test]
+fn test_round_trip() {
+    let tuning = MatmulTuning {
+        gemm_max: 64,
+        blocked_max: 1024,
+        block_size: 32,
+    };
+
+    let parsed = tuning.to_string().parse::<MatmulTuning>();
+    assert_eq!(parsed, Ok(tuning));
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        "gemm_max 64".parse::<MatmulTuning>(),
+        Err(ParseTuningError::Malformed("gemm_max 64".to_string()))
+    );
+    assert_eq!(
+        "cache = 64".parse::<MatmulTuning>(),
+        Err(ParseTuningError::UnknownKey("cache".to_string()))
+    );
+    assert_eq!(
+        "block_size = 0".parse::<MatmulTuning>(),
+        Err(ParseTuningError::InvalidValue("0".to_string()))
+    );
+}
+
+/// puts back the tuning installed for `T` when dropped, so a failing assert can't leak it into other tests
+struct Restore<T: 'static>(MatmulTuning, PhantomData<T>);
+
+impl<T: 'static> Restore<T> {
+    fn new() -> Restore<T> {
+        Restore(MatmulTuning::current::<T>(), PhantomData)
+    }
+}
+
+impl<T: 'static> Drop for Restore<T> {
+    fn drop(&mut self) {
+        self.0.install::<T>();
+    }
+}
+
+#[test]
+fn test_tuned() {
+    let a = Matrix::from_vec(5, 5, (0..25).collect::<Vec<i16>>());
+    let b = Matrix::from_vec(5, 5, (0..25).rev().collect::<Vec<i16>>());
+    let expected = a.gemm(b.clone());
+
+    let blocked = MatmulTuning { gemm_max: 0, blocked_max: 8, block_size: 2 };
+    let expected_strategy = |strategy| if cfg!(feature = "deterministic") { MatmulStrategy::Naive } else { strategy };
+    assert_eq!(a.plan_tuned(&b, MatmulStrategy::Auto, &blocked).strategy, expected_strategy(MatmulStrategy::Blocked));
+    assert_eq!(a.mul_tuned(&b, &blocked).data, expected.data);
+
+    let strassen = MatmulTuning { gemm_max: 0, blocked_max: 0, block_size: 2 };
+    let planned = a.plan_tuned(&b, MatmulStrategy::Auto, &strassen).strategy;
+    assert_eq!(planned, expected_strategy(MatmulStrategy::Strassen { cutoff: 1 }));
+    assert_eq!(a.mul_tuned(&b, &strassen).data, expected.data);
+}
+
+#[test]
+fn test_install() {
+    // no other test installs a tuning for i16, and the guard puts back what was there
+    let _restore = Restore::<i16>::new();
+    let blocked = MatmulTuning { gemm_max: 0, blocked_max: 8, block_size: 2 };
+    blocked.install::<i16>();
+    assert_eq!(MatmulTuning::current::<i16>(), blocked);
+    assert_eq!(MatmulTuning::current::<u32>(), MatmulTuning::DEFAULT);
+}
+
+#[test]
+fn test_calibrate() {
+    let tuning = MatmulTuning::calibrate_sizes::<f64>(&[4, 8, 16]);
+    assert!(tuning.gemm_max <= tuning.blocked_max);
+    assert!(tuning.blocked_max <= 16);
+}
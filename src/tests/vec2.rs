@@ -1,3 +1,4 @@
+use std::f32::consts::FRAC_PI_2;
 use crate::math::vec2::Vec2;
 
 #[test]
@@ -13,4 +14,47 @@ fn test_mag() {
 #[test]
 fn test_normalize() {
     assert_eq!(Vec2::X.normalize(), Vec2::X);
-}
\ No newline at end of file
+    assert_eq!(Vec2(3.0, 4.0).normalize(), Vec2(0.6, 0.8));
+    assert_eq!(Vec2::ZERO.try_normalize(), None);
+    assert_eq!(Vec2::ZERO.normalize_or_zero(), Vec2::ZERO);
+}
+
+#[test]
+fn test_ops() {
+    let a = Vec2(1.0, 2.0);
+    let b = Vec2(3.0, 5.0);
+
+    assert_eq!(a + b, Vec2(4.0, 7.0));
+    let (ra, rb) = (&a, &b);
+    assert_eq!(ra - rb, Vec2(-2.0, -3.0));
+    assert_eq!(a * b, Vec2(3.0, 10.0));
+    assert_eq!(b / a, Vec2(3.0, 2.5));
+    assert_eq!(a * 2.0, Vec2(2.0, 4.0));
+    assert_eq!(2.0 * a, Vec2(2.0, 4.0));
+    assert_eq!(b / 2.0, Vec2(1.5, 2.5));
+    assert_eq!(-a, Vec2(-1.0, -2.0));
+
+    let mut c = a;
+    c *= 3.0;
+    c -= a;
+    assert_eq!(c, Vec2(2.0, 4.0));
+
+    assert_eq!(a[0], 1.0);
+    assert_eq!(a[1], 2.0);
+    assert_eq!([a, b, a].iter().sum::<Vec2>(), Vec2(5.0, 9.0));
+}
+
+#[test]
+fn test_helpers() {
+    let a = Vec2(1.0, -2.0);
+    let b = Vec2(-3.0, 4.0);
+
+    assert_eq!(a.lerp(b, 0.5), Vec2(-1.0, 1.0));
+    assert_eq!(a.min(b), Vec2(-3.0, -2.0));
+    assert_eq!(a.max(b), Vec2(1.0, 4.0));
+    assert_eq!(b.abs(), Vec2(3.0, 4.0));
+    assert_eq!(b.clamp(Vec2::splat(-1.0), Vec2::ONE), Vec2(-1.0, 1.0));
+    assert_eq!(Vec2(1.0, -1.0).reflect(Vec2::Y), Vec2(1.0, 1.0));
+    assert_eq!(Vec2(2.0, 3.0).project_onto(Vec2::X), Vec2(2.0, 0.0));
+    assert_eq!(Vec2::X.angle_between(Vec2::Y), FRAC_PI_2);
+}
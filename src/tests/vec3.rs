@@ -1,3 +1,4 @@
+use std::f32::consts::FRAC_PI_2;
 use crate::math::vec3::Vec3;
 
 #[test]
@@ -18,4 +19,46 @@ fn test_mag() {
 #[test]
 fn test_normalize() {
     assert_eq!(Vec3::X.normalize(), Vec3::X);
-}
\ No newline at end of file
+    assert_eq!(Vec3(0.0, 3.0, 4.0).normalize(), Vec3(0.0, 0.6, 0.8));
+    assert_eq!(Vec3::ZERO.try_normalize(), None);
+    assert_eq!(Vec3(f32::INFINITY, 0.0, 0.0).normalize_or_zero(), Vec3::ZERO);
+}
+
+#[test]
+fn test_ops() {
+    let a = Vec3(1.0, 2.0, 3.0);
+    let b = Vec3(4.0, 6.0, 9.0);
+
+    assert_eq!(a + b, Vec3(5.0, 8.0, 12.0));
+    let (ra, rb) = (&a, &b);
+    assert_eq!(b - ra, Vec3(3.0, 4.0, 6.0));
+    assert_eq!(ra * rb, Vec3(4.0, 12.0, 27.0));
+    assert_eq!(b / a, Vec3(4.0, 3.0, 3.0));
+    assert_eq!(a * 2.0, Vec3(2.0, 4.0, 6.0));
+    assert_eq!(0.5 * b, Vec3(2.0, 3.0, 4.5));
+    assert_eq!(-a, Vec3(-1.0, -2.0, -3.0));
+
+    let mut c = a;
+    c += b;
+    c /= 2.0;
+    assert_eq!(c, Vec3(2.5, 4.0, 6.0));
+
+    c[2] = 1.0;
+    assert_eq!(c[2], 1.0);
+    assert_eq!(vec![a, b].into_iter().sum::<Vec3>(), a + b);
+}
+
+#[test]
+fn test_helpers() {
+    let a = Vec3(1.0, -2.0, 3.0);
+    let b = Vec3(-3.0, 4.0, 1.0);
+
+    assert_eq!(a.lerp(b, 0.5), Vec3(-1.0, 1.0, 2.0));
+    assert_eq!(a.min(b), Vec3(-3.0, -2.0, 1.0));
+    assert_eq!(a.max(b), Vec3(1.0, 4.0, 3.0));
+    assert_eq!(a.abs(), Vec3(1.0, 2.0, 3.0));
+    assert_eq!(Vec3(1.0, -1.0, 0.0).reflect(Vec3::Y), Vec3(1.0, 1.0, 0.0));
+    assert_eq!(a.reject_from(Vec3::Z), Vec3(1.0, -2.0, 0.0));
+    assert_eq!(Vec3::X.angle_between(Vec3::NEG_Z), FRAC_PI_2);
+    assert_eq!(Vec3::Y.any_orthonormal().dot(Vec3::Y), 0.0);
+}
@@ -0,0 +1,29 @@
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+
+#[test]
+fn test_dot() {
+    assert_eq!(Vec4::X.dot(Vec4::W), 0.0)
+}
+
+#[test]
+fn test_homogeneous() {
+    let p = Vec4::from_point(Vec3(1.0, 2.0, 3.0));
+    let d = Vec4::from_direction(Vec3(1.0, 2.0, 3.0));
+
+    assert_eq!(p.w, 1.0);
+    assert_eq!(d.w, 0.0);
+    assert_eq!(p.xyz(), d.xyz());
+}
+
+#[test]
+fn test_ops() {
+    let a = Vec4(1.0, 2.0, 3.0, 4.0);
+
+    assert_eq!(a + Vec4::ONE, Vec4(2.0, 3.0, 4.0, 5.0));
+    assert_eq!(a * 2.0 - a, a);
+    assert_eq!(-a, Vec4(-1.0, -2.0, -3.0, -4.0));
+    assert_eq!(a[3], 4.0);
+    assert_eq!(Vec4(2.0, 4.0, 6.0, 2.0).project(), Vec3(1.0, 2.0, 3.0));
+    assert_eq!(Vec4(0.0, 0.0, 3.0, 4.0).normalize(), Vec4(0.0, 0.0, 0.6, 0.8));
+}
@@ -0,0 +1,67 @@
+use crate::math::dvec::{DVec2, DVec3};
+use crate::math::ivec::{IVec2, IVec3};
+use crate::math::uvec::{UVec2, UVec3};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+#[test]
+fn test_dvec() {
+    let a = DVec3(1.0e12, 2.0, 3.0);
+    let b = DVec3(1.0, 0.0, 0.0);
+
+    assert_eq!(a - b, DVec3(1.0e12 - 1.0, 2.0, 3.0));
+    assert_eq!(DVec3::X.cross(DVec3::Y), DVec3::Z);
+    assert_eq!(DVec2(3.0, 4.0).magnitude(), 5.0);
+    assert_eq!(DVec2(3.0, 4.0).normalize(), DVec2(0.6, 0.8));
+    assert_eq!(DVec2::ZERO.try_normalize(), None);
+    assert_eq!(-DVec2::ONE, DVec2::splat(-1.0));
+
+    // the f64 vectors have everything the f32 ones do
+    assert_eq!(DVec2(1.0, 2.0).perp(), DVec2(-2.0, 1.0));
+    assert_eq!(DVec2::X.perp_dot(DVec2::Y), 1.0);
+    assert_eq!(DVec2(1.0, 2.0).extend(3.0).xy(), DVec2(1.0, 2.0));
+    assert_eq!(DVec3::NEG_Z, -DVec3::Z);
+    assert_eq!(DVec3::Z.any_orthonormal().dot(DVec3::Z), 0.0);
+    assert_eq!(Vec2(1.5, -0.5).floor().map(|v| v * 2.0), Vec2(2.0, -2.0));
+    assert_eq!(Vec3::default(), Vec3::ZERO);
+}
+
+#[test]
+fn test_ivec() {
+    let tile = IVec2(3, -4);
+
+    assert_eq!(tile + IVec2::X, IVec2(4, -4));
+    assert_eq!(tile * 2, IVec2(6, -8));
+    assert_eq!(tile.abs(), IVec2(3, 4));
+    assert_eq!(tile.dot(IVec2::Y), -4);
+    assert_eq!(tile[1], -4);
+    assert_eq!(IVec3::X.cross(IVec3::Y), IVec3::Z);
+    assert_eq!(tile.min(IVec2::ZERO), IVec2(0, -4));
+}
+
+#[test]
+fn test_uvec() {
+    let size = UVec2(16, 9);
+
+    assert_eq!(size / 2, UVec2(8, 4));
+    assert_eq!(size.max_element(), 16);
+    assert_eq!(UVec3::from([1, 2, 3]), UVec3(1, 2, 3));
+    assert_eq!([size, size].iter().sum::<UVec2>(), UVec2(32, 18));
+}
+
+#[test]
+fn test_conversions() {
+    // lossless
+    assert_eq!(DVec2::from(Vec2(0.5, -1.5)), DVec2(0.5, -1.5));
+    assert_eq!(DVec3::from(IVec3(1, -2, 3)), DVec3(1.0, -2.0, 3.0));
+    assert_eq!(DVec2::from(UVec2(u32::MAX, 0)), DVec2(u32::MAX as f64, 0.0));
+
+    // lossy
+    assert_eq!(Vec2(1.7, -1.7).as_ivec2(), IVec2(1, -1));
+    // integers changing sign wrap rather than saturate
+    assert_eq!(IVec2(-1, 5).as_uvec2(), UVec2(u32::MAX, 5));
+    assert_eq!(UVec2(u32::MAX, 7).as_ivec2(), IVec2(-1, 7));
+    assert_eq!(Vec3(-1.0, 2.5, 1.0e10).as_uvec3(), UVec3(0, 2, u32::MAX));
+    assert_eq!(DVec3(0.1, 0.2, 0.3).as_vec3(), Vec3(0.1, 0.2, 0.3));
+    assert_eq!(IVec3(1, 2, 3).as_vec3(), Vec3(1.0, 2.0, 3.0));
+}